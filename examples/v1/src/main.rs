@@ -1,4 +1,5 @@
-use openbook::v1::orders::OrderReturnType;
+use openbook::v1::orders::{ComboStep, OrderReturnType};
+use openbook::rpc::PriorityFeeStrategy;
 use openbook::v1::ob_client::OBClient;
 use openbook::matching::Side;
 use openbook::commitment_config::CommitmentConfig;
@@ -20,6 +21,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     println!("Initialized OpenBook V1 Client: {:?}", ob_client);
 
+    if !ob_client.open_orders.exists {
+        ob_client.init_open_orders().await?;
+    }
+
     println!("[*] Place Limit Order");
     if let Some(ord_ret_type) = ob_client
         .place_limit_order(
@@ -44,7 +49,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("[*] Cancel Orders");
     if let Some(ord_ret_type) = ob_client
         .cancel_orders(
-            true
+            true,
+            false
         )
         .await?
     {
@@ -82,19 +88,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             0.5,
             15.0,
             1.3,
+            ComboStep::default_order(),
+            false,
+            PriorityFeeStrategy::default(),
+            false,
         )
         .await?;
     println!("[*] Transaction successful, signature: {:?}", result);
 
     println!("[*] Cancel Settle Place Bid Order");
     let result = ob_client
-        .cancel_settle_place_bid(0.5, 15.0)
+        .cancel_settle_place_bid(0.5, 15.0, PriorityFeeStrategy::default(), false)
         .await?;
     println!("[*] Transaction successful, signature: {:?}", result);
 
     println!("[*] Cancel Settle Ask Order");
     let result = ob_client
-        .cancel_settle_place_ask(0.5, 15.0)
+        .cancel_settle_place_ask(0.5, 15.0, PriorityFeeStrategy::default(), false)
         .await?;
     println!("[*] Transaction successful, signature: {:?}", result);
 
@@ -3,7 +3,11 @@
 #[cfg(feature = "cli")]
 use clap::builder::styling::{AnsiColor, Effects, Styles};
 #[cfg(feature = "cli")]
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+#[cfg(feature = "cli")]
+use serde::Serialize;
+#[cfg(feature = "cli")]
+use solana_sdk::signature::Signature;
 
 #[cfg(feature = "cli")]
 fn styles() -> Styles {
@@ -85,11 +89,74 @@ pub struct Cli {
     #[arg(short, long, action = clap::ArgAction::Count)]
     pub debug: u8,
 
+    /// The format of the command results. `json` prints a single JSON object to stdout and
+    /// sends the logs to stderr.
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+
     /// The subcommand to execute.
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
 
+/// Represents the format of the command results.
+#[cfg(feature = "cli")]
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human readable logs.
+    #[default]
+    Text,
+    /// A machine-readable JSON object, for scripts.
+    Json,
+}
+
+/// Represents the JSON result of a command that sent a transaction.
+#[cfg(feature = "cli")]
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct TxReport {
+    /// The base58 signature of the transaction.
+    pub signature: String,
+    /// The id of the placed order, as a decimal string since it doesn't fit in a JSON number.
+    pub order_id: Option<String>,
+    /// The slot the transaction landed in, if known.
+    pub slot: Option<u64>,
+}
+
+#[cfg(feature = "cli")]
+impl TxReport {
+    /// Returns the report of the transaction `signature`.
+    ///
+    /// # Arguments
+    ///
+    /// * `signature` - The signature of the transaction.
+    /// * `order_id` - The id of the order placed by the transaction, if any.
+    /// * `slot` - The slot the transaction landed in, if known.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::cli::TxReport;
+    /// use openbook::signature::Signature;
+    ///
+    /// let report = TxReport::new(&Signature::default(), Some(u128::MAX), Some(42));
+    ///
+    /// assert_eq!(report.slot, Some(42));
+    /// assert!(report.to_json().contains(&u128::MAX.to_string()));
+    /// ```
+    pub fn new(signature: &Signature, order_id: Option<u128>, slot: Option<u64>) -> Self {
+        Self {
+            signature: signature.to_string(),
+            order_id: order_id.map(|id| id.to_string()),
+            slot,
+        }
+    }
+
+    /// Serializes the report to a single line of JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::json!(self).to_string()
+    }
+}
+
 /// Represents OpenBook-related subcommands.
 #[cfg(feature = "cli")]
 #[derive(Subcommand, Debug, Clone, PartialEq)]
@@ -106,6 +173,9 @@ pub struct V1 {
     /// The market id to trade on.
     #[arg(short, long, default_value_t = String::from("8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6"))]
     pub market_id: String,
+    /// Create the missing base and quote token accounts of the wallet before trading.
+    #[arg(long)]
+    pub create_atas: bool,
     /// The subcommand to execute.
     #[command(subcommand)]
     pub command: Option<V1ActionsCommands>,
@@ -150,6 +220,8 @@ pub enum V1ActionsCommands {
     Find(Find),
     /// Fetch Market Info.
     Info(Info),
+    /// Print an ASCII depth chart of the order book.
+    Depth(Depth),
 }
 
 /// Represents OpenBook V2 market actions subcommands.
@@ -202,6 +274,17 @@ pub struct Place {
     /// Target price for the order.
     #[arg(short, long)]
     pub price_target: f64,
+
+    /// Write the unsigned transaction, base64 encoded, to this file ("-" or no value for stdout)
+    /// instead of printing the instructions, to sign it on another machine.
+    #[arg(
+        long,
+        value_name = "PATH",
+        num_args = 0..=1,
+        default_missing_value = "-",
+        conflicts_with = "execute"
+    )]
+    pub export_tx: Option<String>,
 }
 
 /// Represents options for executing a combination of canceling all limit orders,
@@ -224,6 +307,14 @@ pub struct CancelSettlePlace {
     /// The ask price in JLP/USDC.
     #[arg(short, long)]
     pub ask_price_jlp_usdc: f64,
+
+    /// Flag indicating whether to create the missing token accounts before settling.
+    #[arg(short, long)]
+    pub ensure_atas: bool,
+
+    /// Flag indicating whether to only simulate the transaction and print its compute units and logs.
+    #[arg(long)]
+    pub dry_run: bool,
 }
 
 /// Represents options for executing a combination of canceling all limit orders,
@@ -238,6 +329,10 @@ pub struct CancelSettlePlaceBid {
     /// The bid price in JLP/USDC.
     #[arg(short, long)]
     pub bid_price_jlp_usdc: f64,
+
+    /// Flag indicating whether to only simulate the transaction and print its compute units and logs.
+    #[arg(long)]
+    pub dry_run: bool,
 }
 
 /// Represents options for executing a combination of canceling all limit orders,
@@ -252,6 +347,10 @@ pub struct CancelSettlePlaceAsk {
     /// The ask price in JLP/USDC.
     #[arg(short, long)]
     pub ask_price_jlp_usdc: f64,
+
+    /// Flag indicating whether to only simulate the transaction and print its compute units and logs.
+    #[arg(long)]
+    pub dry_run: bool,
 }
 
 /// Represents options for cancelling an order in the OpenBook market.
@@ -261,6 +360,21 @@ pub struct Cancel {
     /// Flag indicating whether to execute the order immediately.
     #[arg(short, long)]
     pub execute: bool,
+
+    /// Flag indicating whether to settle the freed balances in the same transaction.
+    #[arg(short, long)]
+    pub settle: bool,
+
+    /// Write the unsigned transaction, base64 encoded, to this file ("-" or no value for stdout)
+    /// instead of printing the instructions, to sign it on another machine.
+    #[arg(
+        long,
+        value_name = "PATH",
+        num_args = 0..=1,
+        default_missing_value = "-",
+        conflicts_with = "execute"
+    )]
+    pub export_tx: Option<String>,
 }
 
 /// Represents options for settling balances in the OpenBook market.
@@ -270,6 +384,17 @@ pub struct Settle {
     /// Flag indicating whether to execute the order immediately.
     #[arg(short, long)]
     pub execute: bool,
+
+    /// Write the unsigned transaction, base64 encoded, to this file ("-" or no value for stdout)
+    /// instead of printing the instructions, to sign it on another machine.
+    #[arg(
+        long,
+        value_name = "PATH",
+        num_args = 0..=1,
+        default_missing_value = "-",
+        conflicts_with = "execute"
+    )]
+    pub export_tx: Option<String>,
 }
 
 /// Represents options for match orders transactions in the OpenBook market.
@@ -288,6 +413,10 @@ pub struct Consume {
     /// Limit for consume events instruction.
     #[arg(short, long)]
     pub limit: u16,
+
+    /// Maximum number of consume events transactions to send while the queue isn't empty.
+    #[arg(long, default_value_t = 1)]
+    pub max_iterations: usize,
 }
 
 /// Represents options for consume events permissioned instructions in the OpenBook market.
@@ -313,3 +442,12 @@ pub struct Find {}
 #[cfg(feature = "cli")]
 #[derive(Args, Debug, Clone, PartialEq)]
 pub struct Info {}
+
+/// Represents options for printing the depth chart of the OpenBook market.
+#[cfg(feature = "cli")]
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct Depth {
+    /// The number of price levels shown per side.
+    #[arg(short, long, default_value_t = 10)]
+    pub levels: usize,
+}
@@ -0,0 +1,182 @@
+//! This module contains the error type returned by the OpenBook clients.
+
+use solana_client::client_error::ClientError;
+use solana_client::rpc_request::{RpcError, RpcResponseErrorData};
+use solana_rpc_client_api::client_error::ErrorKind;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::transaction::TransactionError;
+use solana_sdk::{program_error::ProgramError, pubkey::ParsePubkeyError, pubkey::Pubkey};
+use thiserror::Error;
+
+#[cfg(feature = "v1")]
+use crate::v1::market::MarketError;
+
+/// Errors returned by the v1 and v2 `OBClient` methods.
+///
+/// Callers can match on the variants for the failure modes they handle, such as retrying on
+/// `RpcError` or resizing on `OrderTooSmall`. Errors that don't have a variant yet are wrapped in
+/// `Other`.
+#[derive(Debug, Error)]
+pub enum OpenBookError {
+    /// The RPC node returned an error or couldn't be reached.
+    #[error("RPC error: {0}")]
+    RpcError(ClientError),
+
+    /// No market account exists at the given address.
+    #[error("Market {0} not found")]
+    MarketNotFound(Pubkey),
+
+    /// The wallet doesn't hold enough tokens or SOL for the transaction, as reported by the
+    /// preflight simulation of the node.
+    #[error("Insufficient balance: {0}")]
+    InsufficientBalance(TransactionError),
+
+    /// The market's bids or asks account is the default pubkey, so its book was never initialized.
+    #[error("The order book of market {0} is not initialized")]
+    MarketBookUninitialized(Pubkey),
+
+    /// The wallet's open orders account doesn't exist or isn't owned by the market program.
+    #[error("The open orders account {0} is not initialized")]
+    OpenOrdersNotInitialized(Pubkey),
+
+    /// The order size, in quote UI units, rounds down to zero base lots.
+    #[error("Order of {0} quote rounds down to zero base lots")]
+    OrderTooSmall(f64),
+
+    /// An on-chain account couldn't be deserialized.
+    #[error("Failed to deserialize account: {0}")]
+    AccountDeserialize(String),
+
+    /// No nonce yields a valid vault signer key for the market.
+    #[error("Failed to derive the vault signer key of market {0}")]
+    VaultSignerDerivation(Pubkey),
+
+    /// A saved client snapshot was taken on another market than the client's.
+    #[error("Snapshot of market {found} can't be restored on market {expected}")]
+    SnapshotMarketMismatch {
+        /// The market of the client.
+        expected: Pubkey,
+        /// The market of the snapshot.
+        found: Pubkey,
+    },
+
+    /// The order price isn't a positive number.
+    #[error("Invalid order price {0}")]
+    InvalidPrice(f64),
+
+    /// The order price is further from the mid price than the client allows.
+    #[error("Order price {price} is more than {max_deviation_percent}% away from the mid {mid}")]
+    PriceOffMarket {
+        /// The UI price of the order.
+        price: f64,
+        /// The mid UI price of the book.
+        mid: f64,
+        /// The largest deviation allowed, in percent of the mid.
+        max_deviation_percent: f64,
+    },
+
+    /// The account doesn't exist or isn't a mint of the SPL token program.
+    #[error("{0} is not an existing SPL token mint")]
+    InvalidMint(Pubkey),
+
+    /// Any other error.
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl From<anyhow::Error> for OpenBookError {
+    fn from(err: anyhow::Error) -> Self {
+        let err = match err.downcast::<OpenBookError>() {
+            Ok(err) => return err,
+            Err(err) => err,
+        };
+        let err = match err.downcast::<ClientError>() {
+            Ok(err) => return err.into(),
+            Err(err) => err,
+        };
+        #[cfg(feature = "v1")]
+        let err = match err.downcast::<MarketError>() {
+            Ok(err) => return err.into(),
+            Err(err) => err,
+        };
+        match err.downcast::<ProgramError>() {
+            Ok(err) => err.into(),
+            Err(err) => OpenBookError::Other(err),
+        }
+    }
+}
+
+impl From<ClientError> for OpenBookError {
+    fn from(err: ClientError) -> Self {
+        match insufficient_funds_error(&err) {
+            Some(err) => OpenBookError::InsufficientBalance(err),
+            None => OpenBookError::RpcError(err),
+        }
+    }
+}
+
+/// Log line of the token program when a transfer exceeds the balance of its source account.
+const TOKEN_INSUFFICIENT_FUNDS_LOG: &str = "Program log: Error: insufficient funds";
+
+/// Returns whether a transaction failed with `err` and `logs` for lack of funds.
+///
+/// The runtime reports missing SOL for the fees or the rent with dedicated errors. The token
+/// program reports a transfer exceeding its source balance with the custom error 0x1, which other
+/// programs use for their own errors, so its log line is checked too.
+pub fn is_insufficient_funds(err: &TransactionError, logs: &[String]) -> bool {
+    match err {
+        TransactionError::InsufficientFundsForFee
+        | TransactionError::InsufficientFundsForRent { .. } => true,
+        TransactionError::InstructionError(_, InstructionError::Custom(1)) => logs
+            .iter()
+            .any(|log| log.starts_with(TOKEN_INSUFFICIENT_FUNDS_LOG)),
+        _ => false,
+    }
+}
+
+/// Returns the transaction error of `err` if it's a preflight failure for lack of funds.
+pub(crate) fn insufficient_funds_error(err: &ClientError) -> Option<TransactionError> {
+    let transaction_error = err.get_transaction_error()?;
+    let logs = match err.kind() {
+        ErrorKind::RpcError(RpcError::RpcResponseError {
+            data: RpcResponseErrorData::SendTransactionPreflightFailure(result),
+            ..
+        }) => result.logs.clone().unwrap_or_default(),
+        _ => vec![],
+    };
+
+    is_insufficient_funds(&transaction_error, &logs).then_some(transaction_error)
+}
+
+impl From<ProgramError> for OpenBookError {
+    fn from(err: ProgramError) -> Self {
+        OpenBookError::AccountDeserialize(err.to_string())
+    }
+}
+
+impl From<ParsePubkeyError> for OpenBookError {
+    fn from(err: ParsePubkeyError) -> Self {
+        OpenBookError::Other(err.into())
+    }
+}
+
+#[cfg(feature = "v1")]
+impl From<MarketError> for OpenBookError {
+    fn from(err: MarketError) -> Self {
+        match err {
+            MarketError::VaultSignerDerivationFailed { market, .. } => {
+                OpenBookError::VaultSignerDerivation(market)
+            }
+            MarketError::MarketAddressMismatch { .. } => {
+                OpenBookError::AccountDeserialize(err.to_string())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "v1")]
+impl From<openbook_dex::error::DexError> for OpenBookError {
+    fn from(err: openbook_dex::error::DexError) -> Self {
+        OpenBookError::Other(err.into())
+    }
+}
@@ -0,0 +1,117 @@
+//! This module contains the readiness checks shared by the OpenBook clients.
+
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use crate::rpc::Rpc;
+
+/// The outcome of `health_check` on both clients.
+///
+/// Every check runs, so a single report lists all the configuration issues at once instead of
+/// failing on the first one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HealthReport {
+    /// Whether the RPC node answered. The other checks fail when it didn't.
+    pub rpc_reachable: bool,
+    /// Whether the market account exists, is owned by the program, and its state is loaded.
+    pub market_loaded: bool,
+    /// Whether the wallet's open orders account exists and is owned by the program.
+    pub open_orders_exists: bool,
+    /// Whether the base associated token account exists.
+    pub base_ata_exists: bool,
+    /// Whether the quote associated token account exists.
+    pub quote_ata_exists: bool,
+    /// The SOL balance of the wallet, in lamports.
+    pub sol_balance: u64,
+    /// Whether the SOL balance covers the requested minimum for fees.
+    pub sol_balance_ok: bool,
+    /// A description of every failed check.
+    pub errors: Vec<String>,
+}
+
+impl HealthReport {
+    /// Returns whether every check passed.
+    pub fn is_ready(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// The accounts a client needs to trade, as checked by `health_check`.
+pub(crate) struct HealthCheckAccounts {
+    pub program_id: Pubkey,
+    pub market: Pubkey,
+    /// Whether the client holds the decoded state of the market.
+    pub market_state_loaded: bool,
+    pub open_orders: Pubkey,
+    pub owner: Pubkey,
+    pub base_ata: Pubkey,
+    pub quote_ata: Pubkey,
+}
+
+impl HealthCheckAccounts {
+    /// Fetches the accounts in a single `getMultipleAccounts` call and runs every check.
+    pub(crate) async fn check(&self, rpc_client: &Rpc, min_sol_lamports: u64) -> HealthReport {
+        let keys = [
+            self.market,
+            self.open_orders,
+            self.owner,
+            self.base_ata,
+            self.quote_ata,
+        ];
+
+        let accounts = match rpc_client.fetch_multiple_accounts(&keys).await {
+            Ok(accounts) => accounts,
+            Err(err) => {
+                return HealthReport {
+                    errors: vec![format!("RPC node is unreachable: {}", err)],
+                    ..Default::default()
+                }
+            }
+        };
+        let account = |i: usize| accounts.get(i).and_then(Option::as_ref);
+        let owned_by_program =
+            |account: Option<&Account>| account.is_some_and(|a| a.owner == self.program_id);
+
+        let mut report = HealthReport {
+            rpc_reachable: true,
+            market_loaded: owned_by_program(account(0)) && self.market_state_loaded,
+            open_orders_exists: owned_by_program(account(1)),
+            base_ata_exists: account(3).is_some(),
+            quote_ata_exists: account(4).is_some(),
+            sol_balance: account(2).map_or(0, |a| a.lamports),
+            ..Default::default()
+        };
+        report.sol_balance_ok = report.sol_balance >= min_sol_lamports;
+
+        if !report.market_loaded {
+            report
+                .errors
+                .push(format!("Market {} is not loaded", self.market));
+        }
+        if !report.open_orders_exists {
+            report.errors.push(format!(
+                "Open orders account {} does not exist",
+                self.open_orders
+            ));
+        }
+        if !report.base_ata_exists {
+            report.errors.push(format!(
+                "Base token account {} does not exist",
+                self.base_ata
+            ));
+        }
+        if !report.quote_ata_exists {
+            report.errors.push(format!(
+                "Quote token account {} does not exist",
+                self.quote_ata
+            ));
+        }
+        if !report.sol_balance_ok {
+            report.errors.push(format!(
+                "Wallet {} holds {} lamports, below the {} required for fees",
+                self.owner, report.sol_balance, min_sol_lamports
+            ));
+        }
+
+        report
+    }
+}
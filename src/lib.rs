@@ -3,7 +3,16 @@
 
 #[cfg(feature = "cli")]
 pub mod cli;
+pub mod error;
+pub mod health;
+pub mod logs;
+pub mod metrics;
+pub mod orderbook;
+pub mod registry;
 pub mod rpc;
+pub mod subscription;
+pub mod tokens_and_markets;
+pub mod traits;
 #[cfg(feature = "cli")]
 pub mod tui;
 pub mod utils;
@@ -0,0 +1,159 @@
+//! This module contains a parser for the logs emitted by the OpenBook programs.
+
+use std::str::FromStr;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_sdk::{hash::hash, pubkey::Pubkey};
+
+/// Prefix of the log line emitted when a program is invoked.
+const INVOKE_PREFIX: &str = "Program ";
+
+/// Prefix of the instruction name logged by Anchor programs such as OpenBook V2.
+const INSTRUCTION_PREFIX: &str = "Program log: Instruction: ";
+
+/// Prefix of the base64 events emitted by Anchor programs.
+const DATA_PREFIX: &str = "Program data: ";
+
+/// Name of the event OpenBook V2 emits for every maker order matched.
+const FILL_EVENT_NAME: &str = "FillLog";
+
+/// An order event decoded from the logs of an OpenBook program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgramEvent {
+    /// An instruction placing one or more orders ran, such as `PlaceOrder` or `PlaceTakeOrder`.
+    OrderPlaced {
+        /// The program that logged the instruction.
+        program_id: Pubkey,
+        /// The logged instruction name.
+        instruction: String,
+    },
+    /// A maker order was filled.
+    OrderFilled {
+        /// The program that emitted the event.
+        program_id: Pubkey,
+        /// The market the fill happened on.
+        market: Pubkey,
+        /// The borsh-encoded `FillLog` event, without its discriminator.
+        data: Vec<u8>,
+    },
+    /// An instruction canceling one or more orders ran, such as `CancelOrder` or
+    /// `CancelAllOrders`.
+    OrderCanceled {
+        /// The program that logged the instruction.
+        program_id: Pubkey,
+        /// The logged instruction name.
+        instruction: String,
+    },
+}
+
+/// Parses the order events out of the log messages of a transaction.
+///
+/// The program invocation lines are tracked to attribute every event to the program that logged
+/// it, including through CPIs. Instruction names logged by Anchor programs yield `OrderPlaced` and
+/// `OrderCanceled`, and `FillLog` events yield `OrderFilled`; `CancelAllAndPlaceOrders` yields
+/// both a cancel and a placement. Other lines are ignored, as are logs truncated by the runtime.
+///
+/// The V1 program doesn't log its instruction names, so only the V2 program yields events.
+///
+/// # Arguments
+///
+/// * `logs` - The log messages of a transaction, as returned by the RPC node.
+///
+/// # Returns
+///
+/// The events, in log order.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::logs::{parse_program_logs, ProgramEvent};
+///
+/// let program_id = "opnb2LAfJYbRMAHHvqjCwQxanZn7ReEHp1k81EohpZb";
+/// let logs = vec![
+///     format!("Program {} invoke [1]", program_id),
+///     "Program log: Instruction: CancelOrder".to_string(),
+///     format!("Program {} success", program_id),
+/// ];
+///
+/// let events = parse_program_logs(&logs);
+///
+/// assert!(matches!(
+///     &events[..],
+///     [ProgramEvent::OrderCanceled { instruction, .. }] if instruction == "CancelOrder"
+/// ));
+/// ```
+pub fn parse_program_logs(logs: &[String]) -> Vec<ProgramEvent> {
+    let fill_discriminator = event_discriminator(FILL_EVENT_NAME);
+    let mut programs: Vec<Pubkey> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        if let Some(instruction) = line.strip_prefix(INSTRUCTION_PREFIX) {
+            let Some(&program_id) = programs.last() else {
+                continue;
+            };
+            let instruction = instruction.trim().to_string();
+            if instruction.contains("Cancel") {
+                events.push(ProgramEvent::OrderCanceled {
+                    program_id,
+                    instruction: instruction.clone(),
+                });
+            }
+            if instruction.contains("Place") {
+                events.push(ProgramEvent::OrderPlaced {
+                    program_id,
+                    instruction,
+                });
+            }
+        } else if let Some(encoded) = line.strip_prefix(DATA_PREFIX) {
+            let Some(&program_id) = programs.last() else {
+                continue;
+            };
+            let Ok(bytes) = STANDARD.decode(encoded.trim()) else {
+                continue;
+            };
+            if bytes.len() < 8 + 32 || bytes[..8] != fill_discriminator {
+                continue;
+            }
+            let data = bytes[8..].to_vec();
+            let market = Pubkey::try_from(&data[..32]).unwrap_or_default();
+            events.push(ProgramEvent::OrderFilled {
+                program_id,
+                market,
+                data,
+            });
+        } else if let Some(rest) = line.strip_prefix(INVOKE_PREFIX) {
+            let mut words = rest.split_whitespace();
+            let (Some(program), Some(status)) = (words.next(), words.next()) else {
+                continue;
+            };
+            let Ok(program_id) = Pubkey::from_str(program) else {
+                continue;
+            };
+            match status {
+                "invoke" => programs.push(program_id),
+                "success" | "failed:" => {
+                    programs.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    events
+}
+
+/// Returns the 8-byte discriminator Anchor prefixes the event `name` with.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::logs::event_discriminator;
+///
+/// assert_eq!(event_discriminator("FillLog").len(), 8);
+/// ```
+pub fn event_discriminator(name: &str) -> [u8; 8] {
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash(format!("event:{}", name).as_bytes()).to_bytes()[..8]);
+    discriminator
+}
@@ -10,24 +10,38 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     #[cfg(feature = "cli")]
     {
         use clap::Parser;
-        use openbook::cli::{Cli, Commands, V1ActionsCommands, V2ActionsCommands};
+        use openbook::cli::{
+            Cli, Commands, OutputFormat, TxReport, V1ActionsCommands, V2ActionsCommands,
+        };
         use openbook::commitment_config::CommitmentConfig;
         use openbook::matching::Side;
+        #[cfg(feature = "v1")]
+        use openbook::rpc::{PriorityFeeStrategy, TxOutcome};
+        use openbook::rpc_client::RpcClient;
+        #[cfg(feature = "v1")]
+        use openbook::signature::Signer;
+        use openbook::utils::{detect_version, get_rpc_url};
         use tokio::time::{sleep, Duration};
 
         use openbook::tui::run_tui;
-        #[cfg(feature = "v2")]
         use openbook::tui::SdkVersion;
         #[cfg(feature = "v1")]
-        use openbook::v1::{ob_client::OBClient as OBV1Client, orders::OrderReturnType};
+        use openbook::v1::{
+            ob_client::OBClient as OBV1Client,
+            orders::{ComboStep, LimitOrderParams, OrderReturnType},
+        };
         #[cfg(feature = "v2")]
         use openbook::v2::ob_client::OBClient as OBV2Client;
         use openbook::v2_state::Side as V2Side;
+        use serde_json::json;
         use solana_cli_output::display::println_transaction;
         use tracing::{error, info};
         use tracing_subscriber::{filter, fmt};
 
-        // Start configuring a `fmt` subscriber
+        let args = Cli::parse();
+        let json = args.output == OutputFormat::Json;
+
+        // Start configuring a `fmt` subscriber, logging to stderr when stdout carries JSON
         let filter = filter::LevelFilter::INFO;
         let subscriber = fmt()
             .compact()
@@ -36,44 +50,115 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .with_line_number(false)
             .with_thread_ids(false)
             .with_target(false)
+            .with_writer(move || -> Box<dyn std::io::Write> {
+                if json {
+                    Box::new(std::io::stderr())
+                } else {
+                    Box::new(std::io::stdout())
+                }
+            })
             .finish();
         tracing::subscriber::set_global_default(subscriber)?;
 
-        let args = Cli::parse();
-
         const CRANK_DELAY_MS: u64 = 50_000;
 
         match args.command {
             Some(Commands::V1(cmd)) => {
-                let mut ob_client_v1 = OBV1Client::new(
-                    CommitmentConfig::confirmed(),
-                    cmd.market_id.parse().unwrap(),
-                    true,
-                    123456789,
-                )
-                .await?;
-                match cmd.command {
-                    Some(V1ActionsCommands::Info(_)) => {
-                        info!("\n[*] {:?}", ob_client_v1);
+                let market_id = cmd.market_id.parse()?;
+                let version = detect_version(&RpcClient::new(get_rpc_url()), market_id).await?;
+                // Without an action, the TUI is opened on the version of the market.
+                let Some(command) = cmd.command else {
+                    let _ = run_tui(version).await;
+                    return Ok(());
+                };
+                if version != SdkVersion::V1 {
+                    return Err(format!(
+                        "Market {} is an OpenBook {:?} market, use the v2 subcommand",
+                        market_id, version
+                    )
+                    .into());
+                }
+                // Only placing orders creates the open orders account; cranking doesn't need one.
+                let places_orders = matches!(
+                    command,
+                    V1ActionsCommands::Place(_)
+                        | V1ActionsCommands::CancelSettlePlace(_)
+                        | V1ActionsCommands::CancelSettlePlaceBid(_)
+                        | V1ActionsCommands::CancelSettlePlaceAsk(_)
+                );
+                let settles = places_orders || matches!(command, V1ActionsCommands::Settle(_));
+                let mut ob_client_v1 = OBV1Client::builder(market_id)
+                    .commitment(CommitmentConfig::confirmed())
+                    .load(true)
+                    .cache_ts(123456789)
+                    .create_atas(settles && cmd.create_atas)
+                    .build()
+                    .await?;
+                if places_orders && !ob_client_v1.open_orders.exists {
+                    ob_client_v1.init_open_orders().await?;
+                }
+                match command {
+                    V1ActionsCommands::Info(_) => {
+                        if json {
+                            println!("{}", json!(ob_client_v1.market_info));
+                        } else {
+                            info!("\n[*] {:?}", ob_client_v1);
+                        }
                     }
-                    Some(V1ActionsCommands::Place(arg)) => {
+                    V1ActionsCommands::Place(arg) => {
                         let side = match arg.side.to_ascii_lowercase().as_str() {
                             "bid" => Side::Bid,
                             "ask" => Side::Ask,
                             _ => Side::Bid,
                         };
 
+                        // A client order id lets the JSON report look up the order id.
+                        let client_order_id = rand::random::<u64>();
                         if let Some(ord_ret_type) = ob_client_v1
-                            .place_limit_order(
+                            .place_limit_order_with_params(
                                 arg.target_amount_quote,
                                 side,
                                 arg.best_offset_usdc,
                                 arg.execute,
                                 arg.price_target,
+                                LimitOrderParams {
+                                    client_order_id: Some(client_order_id),
+                                    ..Default::default()
+                                },
                             )
                             .await?
                         {
                             match ord_ret_type {
+                                OrderReturnType::Instructions(insts) if arg.export_tx.is_some() => {
+                                    export_unsigned_transaction(
+                                        &ob_client_v1.rpc_client,
+                                        &ob_client_v1.owner.pubkey(),
+                                        insts,
+                                        arg.export_tx.as_deref().unwrap_or("-"),
+                                        json,
+                                    )
+                                    .await?;
+                                }
+                                OrderReturnType::Instructions(insts) if json => {
+                                    println!("{}", json!({ "instructions": insts }));
+                                }
+                                OrderReturnType::Signature(signature) if json => {
+                                    let order_id = ob_client_v1
+                                        .order_id_for_client_id(client_order_id)
+                                        .await
+                                        .ok()
+                                        .flatten();
+                                    let slot = ob_client_v1
+                                        .rpc_client
+                                        .fetch_transaction(&signature)
+                                        .await
+                                        .ok()
+                                        .map(|confirmed_transaction| confirmed_transaction.slot);
+                                    println!(
+                                        "{}",
+                                        TxReport::new(&signature, order_id, slot).to_json()
+                                    );
+                                }
                                 OrderReturnType::Instructions(insts) => {
                                     info!("\n[*] Got Instructions: {:?}", insts);
                                 }
@@ -109,11 +194,31 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                                     }
                                 }
                             }
+                        } else if json {
+                            println!("null");
                         }
                     }
-                    Some(V1ActionsCommands::Cancel(arg)) => {
-                        if let Some(ord_ret_type) = ob_client_v1.cancel_orders(arg.execute).await? {
+                    V1ActionsCommands::Cancel(arg) => {
+                        if let Some(ord_ret_type) =
+                            ob_client_v1.cancel_orders(arg.execute, arg.settle).await?
+                        {
                             match ord_ret_type {
+                                OrderReturnType::Instructions(insts) if arg.export_tx.is_some() => {
+                                    export_unsigned_transaction(
+                                        &ob_client_v1.rpc_client,
+                                        &ob_client_v1.owner.pubkey(),
+                                        insts,
+                                        arg.export_tx.as_deref().unwrap_or("-"),
+                                        json,
+                                    )
+                                    .await?;
+                                }
+                                OrderReturnType::Instructions(insts) if json => {
+                                    println!("{}", json!({ "instructions": insts }));
+                                }
+                                OrderReturnType::Signature(signature) if json => {
+                                    print_tx_report(&ob_client_v1.rpc_client, &signature).await;
+                                }
                                 OrderReturnType::Instructions(insts) => {
                                     info!("\n[*] Got Instructions: {:?}", insts);
                                 }
@@ -151,10 +256,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             }
                         }
                     }
-                    Some(V1ActionsCommands::Settle(arg)) => {
+                    V1ActionsCommands::Settle(arg) => {
                         if let Some(ord_ret_type) = ob_client_v1.settle_balance(arg.execute).await?
                         {
                             match ord_ret_type {
+                                OrderReturnType::Instructions(insts) if arg.export_tx.is_some() => {
+                                    export_unsigned_transaction(
+                                        &ob_client_v1.rpc_client,
+                                        &ob_client_v1.owner.pubkey(),
+                                        insts,
+                                        arg.export_tx.as_deref().unwrap_or("-"),
+                                        json,
+                                    )
+                                    .await?;
+                                }
+                                OrderReturnType::Instructions(insts) if json => {
+                                    println!("{}", json!({ "instructions": insts }));
+                                }
+                                OrderReturnType::Signature(signature) if json => {
+                                    print_tx_report(&ob_client_v1.rpc_client, &signature).await;
+                                }
                                 OrderReturnType::Instructions(insts) => {
                                     info!("\n[*] Got Instructions: {:?}", insts);
                                 }
@@ -192,10 +313,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             }
                         }
                     }
-                    Some(V1ActionsCommands::Match(arg)) => {
-                        let (_confirmed, signature) =
-                            ob_client_v1.match_orders_transaction(arg.limit).await?;
-                        info!("\n[*] Transaction successful, signature: {:?}", signature);
+                    V1ActionsCommands::Match(arg) => {
+                        let result = ob_client_v1.match_orders_transaction(arg.limit).await?;
+                        let signature = result.signature;
+                        if json {
+                            print_tx_report(&ob_client_v1.rpc_client, &signature).await;
+                            return Ok(());
+                        }
+                        info!(
+                            "\n[*] Transaction successful, signature: {:?}, matched: {}",
+                            signature, result.matched
+                        );
                         // wait for the tx to be cranked
                         sleep(Duration::from_millis(CRANK_DELAY_MS)).await;
                         match ob_client_v1.rpc_client.fetch_transaction(&signature).await {
@@ -218,129 +346,223 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             }
                         }
                     }
-                    Some(V1ActionsCommands::CancelSettlePlace(arg)) => {
-                        let (_confirmed, signature) = ob_client_v1
+                    V1ActionsCommands::CancelSettlePlace(arg) => {
+                        let outcome = ob_client_v1
                             .cancel_settle_place(
                                 arg.usdc_ask_target,
                                 arg.target_usdc_bid,
                                 arg.price_jlp_usdc_bid,
                                 arg.ask_price_jlp_usdc,
+                                ComboStep::default_order(),
+                                arg.ensure_atas,
+                                PriorityFeeStrategy::default(),
+                                arg.dry_run,
                             )
                             .await?;
-                        info!("\n[*] Transaction successful, signature: {:?}", signature);
-                        // wait for the tx to be cranked
-                        sleep(Duration::from_millis(CRANK_DELAY_MS)).await;
-                        match ob_client_v1.rpc_client.fetch_transaction(&signature).await {
-                            Ok(confirmed_transaction) => {
-                                info!("\n{:?}", confirmed_transaction);
-                                println_transaction(
-                                    &confirmed_transaction
-                                        .transaction
-                                        .transaction
-                                        .decode()
-                                        .expect("Successful decode"),
-                                    confirmed_transaction.transaction.meta.as_ref(),
-                                    "  ",
-                                    None,
-                                    None,
+                        match outcome {
+                            TxOutcome::Simulated(simulation) if json => {
+                                println!(
+                                    "{}",
+                                    json!({
+                                        "units_consumed": simulation.units_consumed,
+                                        "logs": simulation.logs,
+                                        "err": simulation.err.map(|err| err.to_string()),
+                                    })
                                 );
                             }
-                            Err(err) => {
-                                error!("[*] Unable to get confirmed transaction details: {}", err)
+                            TxOutcome::Sent { signature, .. } if json => {
+                                print_tx_report(&ob_client_v1.rpc_client, &signature).await;
+                            }
+                            TxOutcome::Simulated(simulation) => {
+                                info!("\n[*] Simulation: {:?}", simulation);
+                            }
+                            TxOutcome::Sent { signature, .. } => {
+                                info!("\n[*] Transaction successful, signature: {:?}", signature);
+                                // wait for the tx to be cranked
+                                sleep(Duration::from_millis(CRANK_DELAY_MS)).await;
+                                match ob_client_v1.rpc_client.fetch_transaction(&signature).await {
+                                    Ok(confirmed_transaction) => {
+                                        info!("\n{:?}", confirmed_transaction);
+                                        println_transaction(
+                                            &confirmed_transaction
+                                                .transaction
+                                                .transaction
+                                                .decode()
+                                                .expect("Successful decode"),
+                                            confirmed_transaction.transaction.meta.as_ref(),
+                                            "  ",
+                                            None,
+                                            None,
+                                        );
+                                    }
+                                    Err(err) => {
+                                        error!(
+                                            "[*] Unable to get confirmed transaction details: {}",
+                                            err
+                                        )
+                                    }
+                                }
                             }
                         }
                     }
-                    Some(V1ActionsCommands::CancelSettlePlaceBid(arg)) => {
-                        let (_confirmed, signature) = ob_client_v1
+                    V1ActionsCommands::CancelSettlePlaceBid(arg) => {
+                        let outcome = ob_client_v1
                             .cancel_settle_place_bid(
                                 arg.target_size_usdc_bid,
                                 arg.bid_price_jlp_usdc,
+                                PriorityFeeStrategy::default(),
+                                arg.dry_run,
                             )
                             .await?;
-                        info!("\n[*] Transaction successful, signature: {:?}", signature);
-                        // wait for the tx to be cranked
-                        sleep(Duration::from_millis(CRANK_DELAY_MS)).await;
-                        match ob_client_v1.rpc_client.fetch_transaction(&signature).await {
-                            Ok(confirmed_transaction) => {
-                                info!("\n{:?}", confirmed_transaction);
-                                println_transaction(
-                                    &confirmed_transaction
-                                        .transaction
-                                        .transaction
-                                        .decode()
-                                        .expect("Successful decode"),
-                                    confirmed_transaction.transaction.meta.as_ref(),
-                                    "  ",
-                                    None,
-                                    None,
+                        match outcome {
+                            TxOutcome::Simulated(simulation) if json => {
+                                println!(
+                                    "{}",
+                                    json!({
+                                        "units_consumed": simulation.units_consumed,
+                                        "logs": simulation.logs,
+                                        "err": simulation.err.map(|err| err.to_string()),
+                                    })
                                 );
                             }
-                            Err(err) => {
-                                error!("[*] Unable to get confirmed transaction details: {}", err)
+                            TxOutcome::Sent { signature, .. } if json => {
+                                print_tx_report(&ob_client_v1.rpc_client, &signature).await;
+                            }
+                            TxOutcome::Simulated(simulation) => {
+                                info!("\n[*] Simulation: {:?}", simulation);
+                            }
+                            TxOutcome::Sent { signature, .. } => {
+                                info!("\n[*] Transaction successful, signature: {:?}", signature);
+                                // wait for the tx to be cranked
+                                sleep(Duration::from_millis(CRANK_DELAY_MS)).await;
+                                match ob_client_v1.rpc_client.fetch_transaction(&signature).await {
+                                    Ok(confirmed_transaction) => {
+                                        info!("\n{:?}", confirmed_transaction);
+                                        println_transaction(
+                                            &confirmed_transaction
+                                                .transaction
+                                                .transaction
+                                                .decode()
+                                                .expect("Successful decode"),
+                                            confirmed_transaction.transaction.meta.as_ref(),
+                                            "  ",
+                                            None,
+                                            None,
+                                        );
+                                    }
+                                    Err(err) => {
+                                        error!(
+                                            "[*] Unable to get confirmed transaction details: {}",
+                                            err
+                                        )
+                                    }
+                                }
                             }
                         }
                     }
-                    Some(V1ActionsCommands::CancelSettlePlaceAsk(arg)) => {
-                        let (_confirmed, signature) = ob_client_v1
+                    V1ActionsCommands::CancelSettlePlaceAsk(arg) => {
+                        let outcome = ob_client_v1
                             .cancel_settle_place_ask(
                                 arg.target_size_usdc_ask,
                                 arg.ask_price_jlp_usdc,
+                                PriorityFeeStrategy::default(),
+                                arg.dry_run,
                             )
                             .await?;
-                        info!("\n[*] Transaction successful, signature: {:?}", signature);
-                        // wait for the tx to be cranked
-                        sleep(Duration::from_millis(CRANK_DELAY_MS)).await;
-                        match ob_client_v1.rpc_client.fetch_transaction(&signature).await {
-                            Ok(confirmed_transaction) => {
-                                info!("\n{:?}", confirmed_transaction);
-                                println_transaction(
-                                    &confirmed_transaction
-                                        .transaction
-                                        .transaction
-                                        .decode()
-                                        .expect("Successful decode"),
-                                    confirmed_transaction.transaction.meta.as_ref(),
-                                    "  ",
-                                    None,
-                                    None,
+                        match outcome {
+                            TxOutcome::Simulated(simulation) if json => {
+                                println!(
+                                    "{}",
+                                    json!({
+                                        "units_consumed": simulation.units_consumed,
+                                        "logs": simulation.logs,
+                                        "err": simulation.err.map(|err| err.to_string()),
+                                    })
                                 );
                             }
-                            Err(err) => {
-                                error!("[*] Unable to get confirmed transaction details: {}", err)
+                            TxOutcome::Sent { signature, .. } if json => {
+                                print_tx_report(&ob_client_v1.rpc_client, &signature).await;
+                            }
+                            TxOutcome::Simulated(simulation) => {
+                                info!("\n[*] Simulation: {:?}", simulation);
+                            }
+                            TxOutcome::Sent { signature, .. } => {
+                                info!("\n[*] Transaction successful, signature: {:?}", signature);
+                                // wait for the tx to be cranked
+                                sleep(Duration::from_millis(CRANK_DELAY_MS)).await;
+                                match ob_client_v1.rpc_client.fetch_transaction(&signature).await {
+                                    Ok(confirmed_transaction) => {
+                                        info!("\n{:?}", confirmed_transaction);
+                                        println_transaction(
+                                            &confirmed_transaction
+                                                .transaction
+                                                .transaction
+                                                .decode()
+                                                .expect("Successful decode"),
+                                            confirmed_transaction.transaction.meta.as_ref(),
+                                            "  ",
+                                            None,
+                                            None,
+                                        );
+                                    }
+                                    Err(err) => {
+                                        error!(
+                                            "[*] Unable to get confirmed transaction details: {}",
+                                            err
+                                        )
+                                    }
+                                }
                             }
                         }
                     }
-                    Some(V1ActionsCommands::Consume(arg)) => {
-                        let (_confirmed, signature) = ob_client_v1
-                            .consume_events_instruction(Vec::new(), arg.limit)
+                    V1ActionsCommands::Consume(arg) => {
+                        let signatures = ob_client_v1
+                            .crank_until_empty(arg.limit, arg.max_iterations)
                             .await?;
-                        info!("\n[*] Transaction successful, signature: {:?}", signature);
-                        // wait for the tx to be cranked
-                        sleep(Duration::from_millis(CRANK_DELAY_MS)).await;
-                        match ob_client_v1.rpc_client.fetch_transaction(&signature).await {
-                            Ok(confirmed_transaction) => {
-                                info!("\n{:?}", confirmed_transaction);
-                                println_transaction(
-                                    &confirmed_transaction
-                                        .transaction
-                                        .transaction
-                                        .decode()
-                                        .expect("Successful decode"),
-                                    confirmed_transaction.transaction.meta.as_ref(),
-                                    "  ",
-                                    None,
-                                    None,
-                                );
-                            }
-                            Err(err) => {
-                                error!("[*] Unable to get confirmed transaction details: {}", err)
+                        if json {
+                            let signatures: Vec<String> =
+                                signatures.iter().map(ToString::to_string).collect();
+                            println!("{}", json!({ "signatures": signatures }));
+                            return Ok(());
+                        }
+                        info!(
+                            "\n[*] Sent {} consume events transactions: {:?}",
+                            signatures.len(),
+                            signatures
+                        );
+                        if let Some(signature) = signatures.last() {
+                            // wait for the tx to be cranked
+                            sleep(Duration::from_millis(CRANK_DELAY_MS)).await;
+                            match ob_client_v1.rpc_client.fetch_transaction(signature).await {
+                                Ok(confirmed_transaction) => {
+                                    info!("\n{:?}", confirmed_transaction);
+                                    println_transaction(
+                                        &confirmed_transaction
+                                            .transaction
+                                            .transaction
+                                            .decode()
+                                            .expect("Successful decode"),
+                                        confirmed_transaction.transaction.meta.as_ref(),
+                                        "  ",
+                                        None,
+                                        None,
+                                    );
+                                }
+                                Err(err) => error!(
+                                    "[*] Unable to get confirmed transaction details: {}",
+                                    err
+                                ),
                             }
                         }
                     }
-                    Some(V1ActionsCommands::ConsumePermissioned(arg)) => {
+                    V1ActionsCommands::ConsumePermissioned(arg) => {
                         let (_confirmed, signature) = ob_client_v1
-                            .consume_events_permissioned_instruction(Vec::new(), arg.limit)
+                            .consume_events_permissioned_instruction(Vec::new(), arg.limit, None)
                             .await?;
+                        if json {
+                            print_tx_report(&ob_client_v1.rpc_client, &signature).await;
+                            return Ok(());
+                        }
                         info!("\n[*] Transaction successful, signature: {:?}", signature);
                         // wait for the tx to be cranked
                         sleep(Duration::from_millis(CRANK_DELAY_MS)).await;
@@ -364,51 +586,92 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             }
                         }
                     }
-                    Some(V1ActionsCommands::Load(_arg)) => {
+                    V1ActionsCommands::Load(_arg) => {
                         let l = ob_client_v1.load_orders_for_owner().await?;
-                        info!("\n[*] Found Program Accounts: {:?}", l);
+                        if json {
+                            let order_ids: Vec<String> =
+                                l.iter().map(ToString::to_string).collect();
+                            println!("{}", json!({ "order_ids": order_ids }));
+                        } else {
+                            info!("\n[*] Found Program Accounts: {:?}", l);
+                        }
                     }
-                    Some(V1ActionsCommands::Find(_arg)) => {
+                    V1ActionsCommands::Find(_arg) => {
                         let result = ob_client_v1
-                            .find_open_orders_accounts_for_owner(
-                                ob_client_v1.open_orders.oo_key,
-                                1000,
-                            )
+                            .find_open_orders_accounts_for_owner(ob_client_v1.owner.pubkey(), 1000)
                             .await?;
-                        info!("\n[*] Found Open Orders Accounts: {:?}", result);
+                        if json {
+                            println!(
+                                "{}",
+                                json!({
+                                    "open_orders_account": result.oo_key.to_string(),
+                                    "open_bids_prices": result.open_bids_prices,
+                                    "open_asks_prices": result.open_asks_prices,
+                                })
+                            );
+                        } else {
+                            info!("\n[*] Found Open Orders Accounts: {:?}", result);
+                        }
                     }
-                    None => {
-                        let _ = run_tui(SdkVersion::V1).await;
+                    V1ActionsCommands::Depth(arg) => {
+                        let book = ob_client_v1.load_orderbook_depth(arg.levels).await?;
+                        if json {
+                            println!("{}", json!({ "bids": book.bids, "asks": book.asks }));
+                            return Ok(());
+                        }
+                        let width = crossterm::terminal::size()
+                            .map(|(columns, _)| columns as usize)
+                            .unwrap_or(80);
+                        info!("\n{}", book.depth_chart(arg.levels, width));
                     }
                 }
             }
             Some(Commands::V2(cmd)) => {
-                let mut ob_client_v2 = OBV2Client::new(
-                    CommitmentConfig::confirmed(),
-                    cmd.market_id.parse().unwrap(),
-                    false,
-                    true,
-                )
-                .await?;
+                let market_id = cmd.market_id.parse()?;
+                let version = detect_version(&RpcClient::new(get_rpc_url()), market_id).await?;
+                // Without an action, the TUI is opened on the version of the market.
+                let Some(command) = cmd.command else {
+                    let _ = run_tui(version).await;
+                    return Ok(());
+                };
+                if version != SdkVersion::V2 {
+                    return Err(format!(
+                        "Market {} is an OpenBook {:?} market, use the v1 subcommand",
+                        market_id, version
+                    )
+                    .into());
+                }
+                let mut ob_client_v2 =
+                    OBV2Client::new(CommitmentConfig::confirmed(), market_id, false, true).await?;
 
-                match cmd.command {
-                    Some(V2ActionsCommands::Info(_)) => {
-                        info!("\n[*] {:?}", ob_client_v2);
+                match command {
+                    V2ActionsCommands::Info(_) => {
+                        if json {
+                            println!("{}", json!(ob_client_v2.market_info));
+                        } else {
+                            info!("\n[*] {:?}", ob_client_v2);
+                        }
                     }
-                    Some(V2ActionsCommands::Place(arg)) => {
+                    V2ActionsCommands::Place(arg) => {
                         let side = match arg.side.as_str() {
                             "bid" => V2Side::Bid,
                             "ask" => V2Side::Ask,
                             _ => V2Side::Bid,
                         };
 
-                        let (_confirmed, signature, _order_id, _slot) = ob_client_v2
+                        let (_confirmed, signature, order_id, slot) = ob_client_v2
                             .place_limit_order(
                                 arg.price_target,
                                 arg.target_amount_quote as u64,
                                 side,
                             )
                             .await?;
+                        if json {
+                            let report =
+                                TxReport::new(&signature, Some(u128::from(order_id)), Some(slot));
+                            println!("{}", report.to_json());
+                            return Ok(());
+                        }
                         info!("\n[*] Transaction successful, signature: {:?}", signature);
                         // wait for the tx to be cranked
                         sleep(Duration::from_millis(CRANK_DELAY_MS)).await;
@@ -432,16 +695,63 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             }
                         }
                     }
-                    None => {
-                        let _ = run_tui(SdkVersion::V2).await;
-                    }
                 }
             }
             None => {
-                // default is OpenBook V2
+                // Without a market id, the TUI defaults to OpenBook V2 until a market is entered.
                 let _ = run_tui(SdkVersion::V2).await;
             }
         };
     }
     Ok(())
 }
+
+/// Prints the JSON report of the transaction `signature`, reading its slot back from the node.
+#[cfg(feature = "cli")]
+async fn print_tx_report(
+    rpc_client: &openbook::rpc::Rpc,
+    signature: &openbook::signature::Signature,
+) {
+    let slot = rpc_client
+        .fetch_transaction(signature)
+        .await
+        .ok()
+        .map(|confirmed_transaction| confirmed_transaction.slot);
+    println!(
+        "{}",
+        openbook::cli::TxReport::new(signature, None, slot).to_json()
+    );
+}
+
+/// Writes the unsigned transaction of `instructions`, base64 encoded, to `path` or to stdout when
+/// `path` is "-".
+#[cfg(feature = "cli")]
+async fn export_unsigned_transaction(
+    rpc_client: &openbook::rpc::Rpc,
+    payer: &openbook::pubkey::Pubkey,
+    instructions: Vec<solana_sdk::instruction::Instruction>,
+    path: &str,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let transaction = rpc_client
+        .build_unsigned_transaction(payer, instructions)
+        .await?;
+    let encoded = openbook::utils::encode_transaction(&transaction)?;
+
+    if path == "-" {
+        if json {
+            println!("{}", serde_json::json!({ "transaction": encoded }));
+        } else {
+            println!("{}", encoded);
+        }
+    } else {
+        std::fs::write(path, &encoded)?;
+        if json {
+            println!("{}", serde_json::json!({ "path": path }));
+        } else {
+            tracing::info!("\n[*] Unsigned transaction written to {}", path);
+        }
+    }
+
+    Ok(())
+}
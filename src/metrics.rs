@@ -0,0 +1,353 @@
+//! This module contains the metrics hooks of the clients and a Prometheus exporter.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// The upper bounds, in seconds, of the RPC latency histogram buckets of `PrometheusMetrics`.
+pub const RPC_LATENCY_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Receives the events of the clients, to bridge them to a metrics system.
+///
+/// Every hook does nothing by default, so an implementation only overrides the events it
+/// records. Hooks are called from async tasks and must not block. The RPC hooks are called by
+/// `Rpc` once it's given an implementation with `Rpc::with_metrics`, and the order and settlement
+/// hooks by the v1 and v2 clients sending through that `Rpc`, once their transaction is confirmed.
+pub trait Metrics: Send + Sync {
+    /// Called after an RPC request completes, with the JSON-RPC method and the request latency.
+    ///
+    /// Each attempt of a retried request is reported.
+    fn on_rpc_call(&self, _method: &str, _duration: Duration) {}
+
+    /// Called before a request failing transiently is retried, with its JSON-RPC method.
+    fn on_retry(&self, _method: &str) {}
+
+    /// Called after a transaction is sent, with whether it was confirmed.
+    fn on_tx_sent(&self, _success: bool) {}
+
+    /// Called after `count` orders are placed.
+    fn on_orders_placed(&self, _count: u64) {}
+
+    /// Called after `count` orders are canceled.
+    fn on_orders_canceled(&self, _count: u64) {}
+
+    /// Called after the open orders balances are settled.
+    fn on_settled(&self) {}
+}
+
+/// `Metrics` recording nothing, the behavior of an `Rpc` without metrics.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopMetrics;
+
+impl Metrics for NoopMetrics {}
+
+/// The counts recorded by `CounterMetrics`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MetricsCounts {
+    /// The number of RPC requests, counting each attempt.
+    pub rpc_calls: u64,
+    /// The summed latency of the RPC requests.
+    pub rpc_latency: Duration,
+    /// The number of retried RPC requests.
+    pub retries: u64,
+    /// The number of confirmed transactions.
+    pub tx_confirmed: u64,
+    /// The number of transactions that failed to send or confirm.
+    pub tx_failed: u64,
+    /// The number of orders placed.
+    pub orders_placed: u64,
+    /// The number of orders canceled.
+    pub orders_canceled: u64,
+    /// The number of settlements.
+    pub settles: u64,
+}
+
+/// `Metrics` counting the events in memory, for logs or a quick health check.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::metrics::{CounterMetrics, Metrics};
+/// use std::time::Duration;
+///
+/// let metrics = CounterMetrics::new();
+/// metrics.on_rpc_call("getMultipleAccounts", Duration::from_millis(40));
+/// metrics.on_tx_sent(true);
+///
+/// let counts = metrics.counts();
+///
+/// assert_eq!(counts.rpc_calls, 1);
+/// assert_eq!(counts.tx_confirmed, 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct CounterMetrics {
+    rpc_calls: AtomicU64,
+    rpc_latency_micros: AtomicU64,
+    retries: AtomicU64,
+    tx_confirmed: AtomicU64,
+    tx_failed: AtomicU64,
+    orders_placed: AtomicU64,
+    orders_canceled: AtomicU64,
+    settles: AtomicU64,
+}
+
+impl CounterMetrics {
+    /// Returns metrics with every counter at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the current counts.
+    pub fn counts(&self) -> MetricsCounts {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        MetricsCounts {
+            rpc_calls: load(&self.rpc_calls),
+            rpc_latency: Duration::from_micros(load(&self.rpc_latency_micros)),
+            retries: load(&self.retries),
+            tx_confirmed: load(&self.tx_confirmed),
+            tx_failed: load(&self.tx_failed),
+            orders_placed: load(&self.orders_placed),
+            orders_canceled: load(&self.orders_canceled),
+            settles: load(&self.settles),
+        }
+    }
+}
+
+impl Metrics for CounterMetrics {
+    fn on_rpc_call(&self, _method: &str, duration: Duration) {
+        self.rpc_calls.fetch_add(1, Ordering::Relaxed);
+        self.rpc_latency_micros.fetch_add(
+            u64::try_from(duration.as_micros()).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+    }
+
+    fn on_retry(&self, _method: &str) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_tx_sent(&self, success: bool) {
+        let counter = if success {
+            &self.tx_confirmed
+        } else {
+            &self.tx_failed
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_orders_placed(&self, count: u64) {
+        self.orders_placed.fetch_add(count, Ordering::Relaxed);
+    }
+
+    fn on_orders_canceled(&self, count: u64) {
+        self.orders_canceled.fetch_add(count, Ordering::Relaxed);
+    }
+
+    fn on_settled(&self) {
+        self.settles.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// A latency histogram with the `RPC_LATENCY_BUCKETS` upper bounds.
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    /// The number of observations at or below each bucket bound.
+    buckets: [u64; RPC_LATENCY_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(RPC_LATENCY_BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+#[derive(Debug, Default)]
+struct PrometheusState {
+    orders_placed: u64,
+    orders_canceled: u64,
+    settles: u64,
+    tx_confirmed: u64,
+    tx_failed: u64,
+    retries: u64,
+    rpc_latency: BTreeMap<String, Histogram>,
+}
+
+/// `Metrics` keeping counters and an RPC latency histogram in memory, rendered in the Prometheus
+/// text exposition format.
+///
+/// Serve the output of `render` on a `/metrics` endpoint to scrape a bot. The exported metrics
+/// are:
+///
+/// * `openbook_orders_placed_total` - The number of orders placed.
+/// * `openbook_orders_canceled_total` - The number of orders canceled.
+/// * `openbook_settles_total` - The number of settlements.
+/// * `openbook_transactions_confirmed_total` - The number of confirmed transactions.
+/// * `openbook_transactions_failed_total` - The number of transactions that failed to send or
+///   confirm.
+/// * `openbook_rpc_retries_total` - The number of retried RPC requests.
+/// * `openbook_rpc_latency_seconds` - The latency of the RPC requests, by `method`.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::metrics::{Metrics, PrometheusMetrics};
+/// use std::time::Duration;
+///
+/// let metrics = PrometheusMetrics::new();
+/// metrics.on_orders_placed(2);
+/// metrics.on_rpc_call("getMultipleAccounts", Duration::from_millis(40));
+///
+/// let text = metrics.render();
+///
+/// assert!(text.contains("openbook_orders_placed_total 2"));
+/// assert!(text.contains("openbook_rpc_latency_seconds_count{method=\"getMultipleAccounts\"} 1"));
+/// ```
+#[derive(Debug, Default)]
+pub struct PrometheusMetrics {
+    state: Mutex<PrometheusState>,
+}
+
+impl PrometheusMetrics {
+    /// Returns metrics with every counter at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Renders the metrics in the Prometheus text exposition format.
+    ///
+    /// The RPC methods are rendered in alphabetical order, so the output is stable.
+    pub fn render(&self) -> String {
+        let state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        let mut out = String::new();
+
+        for (name, help, value) in [
+            (
+                "openbook_orders_placed_total",
+                "The number of orders placed.",
+                state.orders_placed,
+            ),
+            (
+                "openbook_orders_canceled_total",
+                "The number of orders canceled.",
+                state.orders_canceled,
+            ),
+            (
+                "openbook_settles_total",
+                "The number of settlements.",
+                state.settles,
+            ),
+            (
+                "openbook_transactions_confirmed_total",
+                "The number of confirmed transactions.",
+                state.tx_confirmed,
+            ),
+            (
+                "openbook_transactions_failed_total",
+                "The number of transactions that failed to send or confirm.",
+                state.tx_failed,
+            ),
+            (
+                "openbook_rpc_retries_total",
+                "The number of retried RPC requests.",
+                state.retries,
+            ),
+        ] {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} counter", name);
+            let _ = writeln!(out, "{} {}", name, value);
+        }
+
+        let name = "openbook_rpc_latency_seconds";
+        let _ = writeln!(out, "# HELP {} The latency of the RPC requests.", name);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        for (method, histogram) in &state.rpc_latency {
+            let method = escape_label_value(method);
+            for (bucket, bound) in histogram.buckets.iter().zip(RPC_LATENCY_BUCKETS) {
+                let _ = writeln!(
+                    out,
+                    "{}_bucket{{method=\"{}\",le=\"{}\"}} {}",
+                    name, method, bound, bucket
+                );
+            }
+            let _ = writeln!(
+                out,
+                "{}_bucket{{method=\"{}\",le=\"+Inf\"}} {}",
+                name, method, histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "{}_sum{{method=\"{}\"}} {}",
+                name, method, histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "{}_count{{method=\"{}\"}} {}",
+                name, method, histogram.count
+            );
+        }
+
+        out
+    }
+
+    fn update(&self, f: impl FnOnce(&mut PrometheusState)) {
+        f(&mut self.state.lock().unwrap_or_else(|err| err.into_inner()));
+    }
+}
+
+impl Metrics for PrometheusMetrics {
+    fn on_rpc_call(&self, method: &str, duration: Duration) {
+        self.update(|state| {
+            state
+                .rpc_latency
+                .entry(method.to_string())
+                .or_default()
+                .observe(duration.as_secs_f64())
+        });
+    }
+
+    fn on_retry(&self, _method: &str) {
+        self.update(|state| state.retries += 1);
+    }
+
+    fn on_tx_sent(&self, success: bool) {
+        self.update(|state| {
+            if success {
+                state.tx_confirmed += 1
+            } else {
+                state.tx_failed += 1
+            }
+        });
+    }
+
+    fn on_orders_placed(&self, count: u64) {
+        self.update(|state| state.orders_placed += count);
+    }
+
+    fn on_orders_canceled(&self, count: u64) {
+        self.update(|state| state.orders_canceled += count);
+    }
+
+    fn on_settled(&self) {
+        self.update(|state| state.settles += 1);
+    }
+}
+
+/// Escapes a label value for the text exposition format.
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
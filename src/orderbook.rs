@@ -0,0 +1,277 @@
+//! This module contains the order book depth shared by the OpenBook clients.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Best bid and ask UI prices of a book, as returned by `get_best_quotes` on both clients.
+///
+/// Each side is `None` when it has no orders, so a one-sided book still reports its best price.
+#[derive(Debug, Clone, Copy, Default, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct BestQuotes {
+    /// The highest bid price, if there are bids.
+    pub highest_bid: Option<f64>,
+    /// The lowest ask price, if there are asks.
+    pub lowest_ask: Option<f64>,
+}
+
+impl BestQuotes {
+    /// Returns the midpoint between the highest bid and the lowest ask, if both sides have orders.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::orderbook::BestQuotes;
+    ///
+    /// let quotes = BestQuotes {
+    ///     highest_bid: Some(1.99),
+    ///     lowest_ask: Some(2.01),
+    /// };
+    ///
+    /// assert!((quotes.mid().unwrap() - 2.0).abs() < 1e-9);
+    /// ```
+    pub fn mid(&self) -> Option<f64> {
+        self.highest_bid
+            .zip(self.lowest_ask)
+            .map(|(bid, ask)| (bid + ask) / 2.0)
+    }
+}
+
+/// Order book ladder, with the size aggregated per price level.
+///
+/// Levels are `(price, size)` pairs in UI units. Bids are sorted from the highest price down
+/// and asks from the lowest price up, so the first level of each side is the best price.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrderBook {
+    /// Bid levels, best (highest) price first.
+    pub bids: Vec<(f64, f64)>,
+    /// Ask levels, best (lowest) price first.
+    pub asks: Vec<(f64, f64)>,
+}
+
+impl OrderBook {
+    /// Merges orders into price levels, keeping at most `depth` levels.
+    ///
+    /// Orders must already be sorted from the best price to the worst, as they come out of a
+    /// book side. Consecutive orders at the same price are summed into a single level.
+    ///
+    /// # Arguments
+    ///
+    /// * `orders` - `(price, size)` of each order, best price first.
+    /// * `depth` - The maximum number of levels to return.
+    ///
+    /// # Returns
+    ///
+    /// The aggregated `(price, size)` levels.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::orderbook::OrderBook;
+    ///
+    /// let levels = OrderBook::aggregate_levels(vec![(2.0, 1.0), (2.0, 3.0), (1.9, 2.0)], 10);
+    ///
+    /// assert_eq!(levels, vec![(2.0, 4.0), (1.9, 2.0)]);
+    /// ```
+    pub fn aggregate_levels(
+        orders: impl IntoIterator<Item = (f64, f64)>,
+        depth: usize,
+    ) -> Vec<(f64, f64)> {
+        let mut levels: Vec<(f64, f64)> = Vec::new();
+
+        for (price, size) in orders {
+            match levels.last_mut() {
+                Some((level_price, level_size)) if *level_price == price => *level_size += size,
+                _ => {
+                    if levels.len() == depth {
+                        break;
+                    }
+                    levels.push((price, size));
+                }
+            }
+        }
+
+        levels
+    }
+
+    /// Returns the best bid level, if any.
+    pub fn best_bid(&self) -> Option<(f64, f64)> {
+        self.bids.first().copied()
+    }
+
+    /// Returns the best ask level, if any.
+    pub fn best_ask(&self) -> Option<(f64, f64)> {
+        self.asks.first().copied()
+    }
+
+    /// Returns the best bid and ask prices of the book.
+    pub fn best_quotes(&self) -> BestQuotes {
+        BestQuotes {
+            highest_bid: self.best_bid().map(|(price, _)| price),
+            lowest_ask: self.best_ask().map(|(price, _)| price),
+        }
+    }
+
+    /// Renders the cumulative size of the first `levels` levels of each side as an ASCII chart.
+    ///
+    /// Asks are printed above the spread from the worst price down, and bids below it from the
+    /// best price down. Each line holds the price, a bar scaled to fit in `width` columns and
+    /// the cumulative size.
+    ///
+    /// # Arguments
+    ///
+    /// * `levels` - The maximum number of levels shown per side.
+    /// * `width` - The width of the terminal, in columns.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::orderbook::OrderBook;
+    ///
+    /// let book = OrderBook {
+    ///     bids: vec![(1.99, 2.0), (1.98, 2.0)],
+    ///     asks: vec![(2.01, 1.0)],
+    /// };
+    ///
+    /// let chart = book.depth_chart(10, 80);
+    ///
+    /// assert_eq!(chart.lines().count(), 4);
+    /// ```
+    pub fn depth_chart(&self, levels: usize, width: usize) -> String {
+        let cumulative = |side: &[(f64, f64)]| -> Vec<(f64, f64)> {
+            side.iter()
+                .take(levels)
+                .scan(0.0, |total, &(price, size)| {
+                    *total += size;
+                    Some((price, *total))
+                })
+                .collect()
+        };
+        let asks = cumulative(&self.asks);
+        let bids = cumulative(&self.bids);
+
+        let sizes: Vec<f64> = asks.iter().chain(&bids).map(|&(_, size)| size).collect();
+        let bar_width = width.saturating_sub(DEPTH_CHART_LABELS_WIDTH).max(1);
+        let mut bars = scale_bars(&sizes, bar_width).into_iter();
+
+        let mut lines = Vec::with_capacity(asks.len() + bids.len() + 1);
+        let mut ask_lines: Vec<String> = asks
+            .iter()
+            .map(|&(price, size)| depth_line('-', price, size, bars.next().unwrap_or(0)))
+            .collect();
+        ask_lines.reverse();
+        lines.extend(ask_lines);
+        lines.push("-".repeat(width.min(DEPTH_CHART_LABELS_WIDTH + bar_width)));
+        lines.extend(
+            bids.iter()
+                .map(|&(price, size)| depth_line('+', price, size, bars.next().unwrap_or(0))),
+        );
+
+        lines.join("\n")
+    }
+}
+
+/// Expected outcome of a taker order walking one side of the book.
+///
+/// Prices are in UI units and quantities in base UI units.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FillEstimate {
+    /// The volume-weighted average price of the filled quantity, or `0.0` if nothing fills.
+    pub avg_price: f64,
+    /// The price of the last level reached, or `0.0` if nothing fills.
+    pub worst_price: f64,
+    /// The base quantity filled by the book.
+    pub filled_qty: f64,
+    /// Whether the book is too thin to fill the whole requested quantity.
+    pub insufficient_liquidity: bool,
+}
+
+impl FillEstimate {
+    /// Walks `levels` from the best price, filling `base_qty`.
+    ///
+    /// # Arguments
+    ///
+    /// * `levels` - The `(price, size)` levels taken by the order, best price first: the asks
+    ///   for a buy and the bids for a sell.
+    /// * `base_qty` - The base quantity of the order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::orderbook::FillEstimate;
+    ///
+    /// let asks = [(2.0, 1.0), (2.2, 1.0)];
+    ///
+    /// let estimate = FillEstimate::from_levels(&asks, 1.5);
+    ///
+    /// assert!((estimate.avg_price - 2.0666).abs() < 1e-3);
+    /// assert_eq!(estimate.worst_price, 2.2);
+    /// assert!(!estimate.insufficient_liquidity);
+    /// ```
+    pub fn from_levels(levels: &[(f64, f64)], base_qty: f64) -> Self {
+        let mut estimate = FillEstimate::default();
+        let mut notional = 0.0;
+
+        for &(price, size) in levels {
+            let remaining = base_qty - estimate.filled_qty;
+            if remaining <= 0.0 {
+                break;
+            }
+            let qty = size.min(remaining);
+            if qty <= 0.0 {
+                continue;
+            }
+            notional += price * qty;
+            estimate.filled_qty += qty;
+            estimate.worst_price = price;
+        }
+
+        if estimate.filled_qty > 0.0 {
+            estimate.avg_price = notional / estimate.filled_qty;
+        }
+        estimate.insufficient_liquidity = estimate.filled_qty < base_qty;
+        estimate
+    }
+}
+
+/// The columns taken by the price and size labels of a `depth_chart` line.
+const DEPTH_CHART_LABELS_WIDTH: usize = 32;
+
+/// Formats one `depth_chart` line.
+fn depth_line(symbol: char, price: f64, size: f64, bar: usize) -> String {
+    format!(
+        "{:>14.6} | {} {:.4}",
+        price,
+        symbol.to_string().repeat(bar),
+        size
+    )
+}
+
+/// Scales sizes into bar lengths that fit in `width` columns.
+///
+/// The largest size gets a bar of `width` columns and the others are scaled proportionally.
+/// Positive sizes always get at least one column, so thin levels stay visible on narrow
+/// terminals, while zero, negative or non-finite sizes get none.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::orderbook::scale_bars;
+///
+/// assert_eq!(scale_bars(&[1.0, 2.0, 4.0], 8), vec![2, 4, 8]);
+/// ```
+pub fn scale_bars(sizes: &[f64], width: usize) -> Vec<usize> {
+    let max = sizes
+        .iter()
+        .copied()
+        .filter(|size| size.is_finite())
+        .fold(0.0, f64::max);
+
+    sizes
+        .iter()
+        .map(|&size| {
+            if !size.is_finite() || size <= 0.0 || max <= 0.0 || width == 0 {
+                return 0;
+            }
+            ((size / max * width as f64).round() as usize).clamp(1, width)
+        })
+        .collect()
+}
@@ -0,0 +1,191 @@
+//! This module contains a registry of OpenBook markets loaded from JSON.
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+/// A market known to the registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistryEntry {
+    /// The symbol of the market (e.g., "JLP/USDC").
+    pub symbol: String,
+    /// The mint address of the base token.
+    pub base_mint: Pubkey,
+    /// The mint address of the quote token.
+    pub quote_mint: Pubkey,
+    /// The address of the market account.
+    pub market_address: Pubkey,
+    /// The dex program owning the market, when it isn't the default v1 program.
+    pub program_id: Option<Pubkey>,
+}
+
+/// The JSON shape of a registry entry, with addresses as base58 strings.
+#[derive(Debug, Deserialize)]
+struct RawRegistryEntry {
+    symbol: String,
+    base_mint: String,
+    quote_mint: String,
+    market_address: String,
+    #[serde(default)]
+    program_id: Option<String>,
+}
+
+impl TryFrom<RawRegistryEntry> for RegistryEntry {
+    type Error = anyhow::Error;
+
+    fn try_from(raw: RawRegistryEntry) -> Result<Self> {
+        let parse = |field: &str, value: &str| {
+            Pubkey::from_str(value)
+                .map_err(|e| anyhow!("Invalid {} for {}: {} ({})", field, raw.symbol, value, e))
+        };
+
+        Ok(Self {
+            base_mint: parse("base_mint", &raw.base_mint)?,
+            quote_mint: parse("quote_mint", &raw.quote_mint)?,
+            market_address: parse("market_address", &raw.market_address)?,
+            program_id: raw
+                .program_id
+                .as_deref()
+                .map(|program_id| parse("program_id", program_id))
+                .transpose()?,
+            symbol: raw.symbol,
+        })
+    }
+}
+
+/// A registry of markets, looked up by symbol or by base/quote mint pair.
+///
+/// Loading the registry at runtime lets a bot trade markets that aren't listed in
+/// `tokens_and_markets` without recompiling.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MarketRegistry {
+    entries: Vec<RegistryEntry>,
+}
+
+/// The former name of `MarketRegistry`.
+pub type TokenRegistry = MarketRegistry;
+
+impl MarketRegistry {
+    /// Builds a registry from a JSON array of entries.
+    ///
+    /// Each entry has a `symbol`, a `base_mint`, a `quote_mint`, a `market_address` and an
+    /// optional `program_id`. Two entries conflict when they share a symbol or a base/quote mint pair.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The JSON array of registry entries.
+    /// * `allow_duplicates` - When `true`, the first of conflicting entries is kept and the others
+    ///   are dropped; when `false`, conflicting entries are rejected.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `MarketRegistry`, or an error if the JSON is invalid.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the JSON can't be parsed, if an address isn't valid base58,
+    /// or if `allow_duplicates` is `false` and entries conflict. The error lists every conflicting
+    /// entry with its position in the array.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::registry::MarketRegistry;
+    ///
+    /// let json = r#"[{
+    ///     "symbol": "JLP/USDC",
+    ///     "base_mint": "27G8MtK7VtTcCHkpASjSDdkWWYfoqT6ggEuKidVJidD4",
+    ///     "quote_mint": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+    ///     "market_address": "ASUyMMNBpFzpW3zDSPYdDVggKajq1DMKFFPK1JS9hoSR"
+    /// }]"#;
+    ///
+    /// let registry = MarketRegistry::from_json(json, false).unwrap();
+    ///
+    /// assert!(registry.get("JLP/USDC").is_some());
+    /// ```
+    pub fn from_json(json: &str, allow_duplicates: bool) -> Result<Self> {
+        let raw_entries: Vec<RawRegistryEntry> = serde_json::from_str(json)?;
+
+        let mut entries: Vec<RegistryEntry> = Vec::with_capacity(raw_entries.len());
+        // Maps a symbol or a mint pair to the array position of the first entry using it.
+        let mut by_symbol: HashMap<String, usize> = HashMap::new();
+        let mut by_mints: HashMap<(Pubkey, Pubkey), usize> = HashMap::new();
+        let mut conflicts = Vec::new();
+
+        for (index, raw) in raw_entries.into_iter().enumerate() {
+            let entry = RegistryEntry::try_from(raw)?;
+            let mints = (entry.base_mint, entry.quote_mint);
+
+            if let Some(first) = by_symbol.get(&entry.symbol).or(by_mints.get(&mints)) {
+                conflicts.push(format!(
+                    "#{} {} ({}/{}) conflicts with #{}",
+                    index, entry.symbol, entry.base_mint, entry.quote_mint, first
+                ));
+                continue;
+            }
+
+            by_symbol.insert(entry.symbol.clone(), index);
+            by_mints.insert(mints, index);
+            entries.push(entry);
+        }
+
+        if !conflicts.is_empty() && !allow_duplicates {
+            return Err(anyhow!(
+                "Duplicate registry entries: {}",
+                conflicts.join("; ")
+            ));
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Builds a registry from a JSON array of entries, rejecting conflicting entries.
+    ///
+    /// Shorthand for `MarketRegistry::from_json(json, false)`.
+    pub fn from_json_str(json: &str) -> Result<Self> {
+        Self::from_json(json, false)
+    }
+
+    /// Reads a registry from a JSON file, rejecting conflicting entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the JSON file, in the format described in `MarketRegistry::from_json`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the file can't be read, or for any reason listed in
+    /// `MarketRegistry::from_json`.
+    pub fn from_json_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read registry {}: {}", path.display(), e))?;
+
+        Self::from_json_str(&json)
+    }
+
+    /// Returns a copy of the entry registered under `symbol`, if any.
+    pub fn resolve(&self, symbol: &str) -> Option<RegistryEntry> {
+        self.get(symbol).cloned()
+    }
+
+    /// Returns the entry registered under `symbol`, if any.
+    pub fn get(&self, symbol: &str) -> Option<&RegistryEntry> {
+        self.entries.iter().find(|entry| entry.symbol == symbol)
+    }
+
+    /// Returns the entry registered for the base/quote mint pair, if any.
+    pub fn find_by_mints(&self, base_mint: &Pubkey, quote_mint: &Pubkey) -> Option<&RegistryEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.base_mint == *base_mint && entry.quote_mint == *quote_mint)
+    }
+
+    /// Returns all entries, in the order they were loaded.
+    pub fn entries(&self) -> &[RegistryEntry] {
+        &self.entries
+    }
+}
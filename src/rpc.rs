@@ -1,13 +1,21 @@
 //! This module implements a thread safe client to interact with a remote Solana node.
 
+use std::collections::HashSet;
 use std::fmt;
-use std::sync::Arc;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use backon::ExponentialBuilder;
 use backon::Retryable;
-use solana_client::rpc_config::RpcSendTransactionConfig;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_config::{
+    RpcProgramAccountsConfig, RpcSendTransactionConfig, RpcSimulateTransactionConfig,
+};
 use solana_client::rpc_request::RpcError;
+use solana_client::rpc_response::{Response, RpcPrioritizationFee, RpcSimulateTransactionResult};
 use solana_client::{
     client_error::ClientError,
     nonblocking::rpc_client::RpcClient,
@@ -16,13 +24,25 @@ use solana_client::{
     rpc_response::RpcConfirmedTransactionStatusWithSignature,
 };
 use solana_rpc_client_api::client_error::ErrorKind;
+use solana_rpc_client_api::custom_error::{
+    JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE, JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED,
+    JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+};
+use solana_sdk::clock::DEFAULT_MS_PER_SLOT;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
 use solana_sdk::instruction::Instruction;
+use solana_sdk::message::Message;
 use solana_sdk::signature::Signer;
 use solana_sdk::signer::keypair::Keypair;
-use solana_sdk::transaction::Transaction;
+use solana_sdk::transaction::{Transaction, TransactionError};
 use solana_sdk::{account::Account, pubkey::Pubkey, signature::Signature};
 use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding};
 
+use crate::error::{insufficient_funds_error, is_insufficient_funds, OpenBookError};
+use crate::metrics::{Metrics, NoopMetrics};
+use crate::utils::prepend_compute_budget_parts;
+
 #[cfg(feature = "v2")]
 use anchor_lang::{AccountDeserialize, Discriminator};
 
@@ -30,54 +50,1323 @@ use anchor_lang::{AccountDeserialize, Discriminator};
 use openbookdex_v2::state::OpenOrdersAccount;
 
 #[cfg(feature = "v2")]
-use solana_client::{
-    rpc_config::RpcProgramAccountsConfig,
-    rpc_filter::{Memcmp, RpcFilterType},
-};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
 
-#[cfg(feature = "v2")]
-use solana_account_decoder::UiAccountEncoding;
+/// Policy used to send a transaction with `Rpc::send_and_confirm_with_config`.
+///
+/// The default runs preflight, adds no compute budget and leaves retries to the RPC node.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TxConfig {
+    /// Whether to skip the preflight simulation. Failing transactions are only caught by
+    /// preflight, so skipping it lets them land (and fail) silently.
+    pub skip_preflight: bool,
+    /// The compute unit limit to prepend, if any.
+    pub compute_unit_limit: Option<u32>,
+    /// The compute unit price to prepend, in micro-lamports, if any.
+    pub compute_unit_price: Option<u64>,
+    /// How many times the RPC node retries sending the transaction, if set.
+    pub max_retries: Option<usize>,
+    /// The commitment of the blockhash and the preflight simulation, overriding the one of the
+    /// `Rpc` if set.
+    pub commitment: Option<CommitmentConfig>,
+}
+
+impl TxConfig {
+    /// Returns the default config with a compute unit limit and price.
+    pub fn with_compute_budget(unit_limit: u32, unit_price: u64) -> Self {
+        Self {
+            compute_unit_limit: Some(unit_limit),
+            compute_unit_price: Some(unit_price),
+            ..Self::default()
+        }
+    }
+}
+
+/// How to pick a priority fee from the recent prioritization fees.
+///
+/// Taking the maximum overpays heavily during congestion, so the default is `Percentile(75)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriorityFeeStrategy {
+    /// The highest recent fee.
+    Max,
+    /// The given percentile (0 to 100) of the recent fees.
+    Percentile(u8),
+    /// The mean of the recent fees.
+    Mean,
+    /// A fixed fee, in micro-lamports per compute unit, without querying the RPC.
+    Fixed(u64),
+}
+
+impl Default for PriorityFeeStrategy {
+    fn default() -> Self {
+        PriorityFeeStrategy::Percentile(75)
+    }
+}
+
+impl PriorityFeeStrategy {
+    /// Picks the fee from the recent fees, never returning less than 1 micro-lamport.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::rpc::PriorityFeeStrategy;
+    ///
+    /// let fees = [10, 40, 20, 30];
+    ///
+    /// assert_eq!(PriorityFeeStrategy::Max.select(&fees), 40);
+    /// assert_eq!(PriorityFeeStrategy::Percentile(50).select(&fees), 20);
+    /// assert_eq!(PriorityFeeStrategy::Mean.select(&fees), 25);
+    /// ```
+    pub fn select(&self, fees: &[u64]) -> u64 {
+        let fee = match self {
+            PriorityFeeStrategy::Fixed(fee) => return *fee,
+            _ if fees.is_empty() => 0,
+            PriorityFeeStrategy::Max => fees.iter().copied().max().unwrap_or_default(),
+            PriorityFeeStrategy::Mean => {
+                (fees.iter().map(|&fee| fee as u128).sum::<u128>() / fees.len() as u128) as u64
+            }
+            PriorityFeeStrategy::Percentile(percentile) => {
+                let mut sorted = fees.to_vec();
+                sorted.sort_unstable();
+                // Nearest-rank percentile.
+                let rank = (f64::from((*percentile).min(100)) / 100. * sorted.len() as f64).ceil();
+                sorted[(rank as usize).saturating_sub(1)]
+            }
+        };
+
+        fee.max(1)
+    }
+}
+
+/// The outcome of simulating a transaction with `Rpc::simulate_transaction`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SimulationResult {
+    /// The compute units consumed, if reported by the node.
+    pub units_consumed: Option<u64>,
+    /// The program logs.
+    pub logs: Vec<String>,
+    /// The error the transaction would fail with, if any.
+    pub err: Option<TransactionError>,
+}
+
+impl SimulationResult {
+    /// Returns whether the transaction would fail.
+    pub fn is_err(&self) -> bool {
+        self.err.is_some()
+    }
+
+    /// Returns whether the transaction would fail for lack of tokens or SOL.
+    pub fn is_insufficient_funds(&self) -> bool {
+        self.err
+            .as_ref()
+            .is_some_and(|err| is_insufficient_funds(err, &self.logs))
+    }
+
+    /// Returns a compute unit limit covering the consumed units plus `margin_percent` percent.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::rpc::SimulationResult;
+    ///
+    /// let simulation = SimulationResult {
+    ///     units_consumed: Some(120_000),
+    ///     ..SimulationResult::default()
+    /// };
+    ///
+    /// assert_eq!(simulation.compute_unit_limit(10), Some(132_000));
+    /// ```
+    pub fn compute_unit_limit(&self, margin_percent: u32) -> Option<u32> {
+        self.units_consumed.map(|units| {
+            let limit = (units.saturating_mul(100 + u64::from(margin_percent)) + 99) / 100;
+            u32::try_from(limit).unwrap_or(u32::MAX)
+        })
+    }
+}
+
+impl From<RpcSimulateTransactionResult> for SimulationResult {
+    fn from(result: RpcSimulateTransactionResult) -> Self {
+        Self {
+            units_consumed: result.units_consumed,
+            logs: result.logs.unwrap_or_default(),
+            err: result.err,
+        }
+    }
+}
+
+/// The outcome of a transaction that was either sent or, on a dry run, only simulated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxOutcome {
+    /// The transaction was sent.
+    Sent {
+        /// Whether the transaction was confirmed.
+        confirmed: bool,
+        /// The signature of the transaction.
+        signature: Signature,
+    },
+    /// The transaction was only simulated.
+    Simulated(SimulationResult),
+}
+
+impl TxOutcome {
+    /// Returns the signature of a sent transaction, or `None` on a dry run.
+    pub fn signature(&self) -> Option<Signature> {
+        match self {
+            TxOutcome::Sent { signature, .. } => Some(*signature),
+            TxOutcome::Simulated(_) => None,
+        }
+    }
+
+    /// Returns whether the transaction was sent and confirmed.
+    pub fn is_confirmed(&self) -> bool {
+        matches!(
+            self,
+            TxOutcome::Sent {
+                confirmed: true,
+                ..
+            }
+        )
+    }
+}
+
+/// Policy used by `Rpc` to retry requests failing with a transient error.
+///
+/// Delays grow exponentially from `base_delay_ms` up to `max_delay_ms`, with a random jitter
+/// below `base_delay_ms` so that several bots hitting the same rate limit don't retry in lockstep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// The maximum number of attempts, including the first one. `1` disables retries.
+    pub max_attempts: usize,
+    /// The delay before the first retry, in milliseconds.
+    pub base_delay_ms: u64,
+    /// The maximum delay between two attempts, in milliseconds.
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            base_delay_ms: 500,
+            max_delay_ms: 10_000,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Returns a config sending every request once, without retries.
+    pub fn disabled() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Builds the exponential backoff with jitter described by this config.
+    pub fn backoff(&self) -> ExponentialBuilder {
+        ExponentialBuilder::default()
+            .with_jitter()
+            .with_min_delay(Duration::from_millis(self.base_delay_ms))
+            .with_max_delay(Duration::from_millis(
+                self.max_delay_ms.max(self.base_delay_ms),
+            ))
+            .with_max_times(self.max_attempts.saturating_sub(1))
+    }
+}
+
+/// Returns whether a request failing with `err` may succeed if sent again.
+///
+/// Timeouts, connection failures, rate limits (HTTP 429), gateway errors and unhealthy or lagging
+/// nodes are transient. Deterministic failures, such as a missing account or a transaction
+/// rejected by preflight, are not.
+pub fn is_transient(err: &ClientError) -> bool {
+    match err.kind() {
+        ErrorKind::Io(_) => true,
+        ErrorKind::Reqwest(reqwest_error) => {
+            reqwest_error.is_timeout()
+                || reqwest_error.is_connect()
+                || reqwest_error.status().map_or(false, |status| {
+                    matches!(status.as_u16(), 429 | 502 | 503 | 504)
+                })
+        }
+        ErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => matches!(
+            *code,
+            JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY
+                | JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE
+                | JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED
+        ),
+        _ => false,
+    }
+}
+
+/// Returns the commitment tried after `commitment` when a read falls back, if any.
+///
+/// `finalized` falls back to `confirmed`, and `confirmed` to `processed`.
+pub fn lower_commitment(commitment: CommitmentConfig) -> Option<CommitmentConfig> {
+    if commitment.is_finalized() {
+        Some(CommitmentConfig::confirmed())
+    } else if commitment.is_confirmed() {
+        Some(CommitmentConfig::processed())
+    } else {
+        None
+    }
+}
+
+/// Runs `read` at `commitment`, falling back to lower commitments when a level is too slow.
+///
+/// Each level but the last (`processed`) gets `per_level_timeout` to answer before the read is
+/// retried at the next lower level, and every downgrade is logged. Errors are returned as they
+/// are, without falling back.
+///
+/// # Arguments
+///
+/// * `commitment` - The commitment to read at first.
+/// * `per_level_timeout` - How long to wait at each level before falling back.
+/// * `read` - Performs the read at the given commitment.
+///
+/// # Returns
+///
+/// The value read and the commitment it was read at.
+pub async fn read_with_commitment_fallback<T, E, F, Fut>(
+    commitment: CommitmentConfig,
+    per_level_timeout: Duration,
+    mut read: F,
+) -> Result<(T, CommitmentConfig), E>
+where
+    F: FnMut(CommitmentConfig) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut commitment = commitment;
+
+    loop {
+        let Some(lower) = lower_commitment(commitment) else {
+            return read(commitment).await.map(|value| (value, commitment));
+        };
+
+        match tokio::time::timeout(per_level_timeout, read(commitment)).await {
+            Ok(result) => return result.map(|value| (value, commitment)),
+            Err(_) => {
+                tracing::warn!(
+                    "No {:?} read after {:?}, falling back to {:?}",
+                    commitment.commitment,
+                    per_level_timeout,
+                    lower.commitment
+                );
+                commitment = lower;
+            }
+        }
+    }
+}
+
+/// The RPC requests `Rpc` sends on behalf of the OpenBook clients.
+///
+/// `RpcClient` implements it by forwarding to the node. Tests can implement it with canned
+/// responses and build a client around it with `Rpc::with_provider`, so account decoding,
+/// order math and transaction building run without a validator.
+#[async_trait::async_trait]
+pub trait RpcProvider: Sync + Send {
+    /// The default commitment of the requests.
+    fn commitment(&self) -> CommitmentConfig;
+
+    /// The URL of the RPC node.
+    fn url(&self) -> String;
+
+    async fn get_multiple_accounts_with_config(
+        &self,
+        pubkeys: &[Pubkey],
+        config: RpcAccountInfoConfig,
+    ) -> Result<Response<Vec<Option<Account>>>, ClientError>;
+
+    async fn get_latest_blockhash_with_commitment(
+        &self,
+        commitment: CommitmentConfig,
+    ) -> Result<(Hash, u64), ClientError>;
+
+    async fn get_recent_prioritization_fees(
+        &self,
+        addresses: &[Pubkey],
+    ) -> Result<Vec<RpcPrioritizationFee>, ClientError>;
+
+    async fn send_transaction_with_config(
+        &self,
+        transaction: &Transaction,
+        config: RpcSendTransactionConfig,
+    ) -> Result<Signature, ClientError>;
+
+    async fn confirm_transaction(&self, signature: &Signature) -> Result<bool, ClientError>;
+
+    async fn get_signature_status(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<Result<(), TransactionError>>, ClientError>;
+
+    async fn is_blockhash_valid(
+        &self,
+        blockhash: &Hash,
+        commitment: CommitmentConfig,
+    ) -> Result<bool, ClientError>;
+
+    async fn simulate_transaction_with_config(
+        &self,
+        transaction: &Transaction,
+        config: RpcSimulateTransactionConfig,
+    ) -> Result<Response<RpcSimulateTransactionResult>, ClientError>;
+
+    async fn get_transaction_with_config(
+        &self,
+        signature: &Signature,
+        config: RpcTransactionConfig,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, ClientError>;
+
+    async fn get_signatures_for_address_with_config(
+        &self,
+        address: &Pubkey,
+        config: GetConfirmedSignaturesForAddress2Config,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, ClientError>;
+
+    async fn get_program_accounts_with_config(
+        &self,
+        program: &Pubkey,
+        config: RpcProgramAccountsConfig,
+    ) -> Result<Vec<(Pubkey, Account)>, ClientError>;
+
+    async fn get_minimum_balance_for_rent_exemption(
+        &self,
+        data_len: usize,
+    ) -> Result<u64, ClientError>;
+}
+
+#[async_trait::async_trait]
+impl RpcProvider for RpcClient {
+    fn commitment(&self) -> CommitmentConfig {
+        RpcClient::commitment(self)
+    }
+
+    fn url(&self) -> String {
+        RpcClient::url(self)
+    }
+
+    async fn get_multiple_accounts_with_config(
+        &self,
+        pubkeys: &[Pubkey],
+        config: RpcAccountInfoConfig,
+    ) -> Result<Response<Vec<Option<Account>>>, ClientError> {
+        RpcClient::get_multiple_accounts_with_config(self, pubkeys, config).await
+    }
+
+    async fn get_latest_blockhash_with_commitment(
+        &self,
+        commitment: CommitmentConfig,
+    ) -> Result<(Hash, u64), ClientError> {
+        RpcClient::get_latest_blockhash_with_commitment(self, commitment).await
+    }
+
+    async fn get_recent_prioritization_fees(
+        &self,
+        addresses: &[Pubkey],
+    ) -> Result<Vec<RpcPrioritizationFee>, ClientError> {
+        RpcClient::get_recent_prioritization_fees(self, addresses).await
+    }
+
+    async fn send_transaction_with_config(
+        &self,
+        transaction: &Transaction,
+        config: RpcSendTransactionConfig,
+    ) -> Result<Signature, ClientError> {
+        RpcClient::send_transaction_with_config(self, transaction, config).await
+    }
+
+    async fn confirm_transaction(&self, signature: &Signature) -> Result<bool, ClientError> {
+        RpcClient::confirm_transaction(self, signature).await
+    }
+
+    async fn get_signature_status(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<Result<(), TransactionError>>, ClientError> {
+        RpcClient::get_signature_status(self, signature).await
+    }
+
+    async fn is_blockhash_valid(
+        &self,
+        blockhash: &Hash,
+        commitment: CommitmentConfig,
+    ) -> Result<bool, ClientError> {
+        RpcClient::is_blockhash_valid(self, blockhash, commitment).await
+    }
+
+    async fn simulate_transaction_with_config(
+        &self,
+        transaction: &Transaction,
+        config: RpcSimulateTransactionConfig,
+    ) -> Result<Response<RpcSimulateTransactionResult>, ClientError> {
+        RpcClient::simulate_transaction_with_config(self, transaction, config).await
+    }
+
+    async fn get_transaction_with_config(
+        &self,
+        signature: &Signature,
+        config: RpcTransactionConfig,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, ClientError> {
+        RpcClient::get_transaction_with_config(self, signature, config).await
+    }
+
+    async fn get_signatures_for_address_with_config(
+        &self,
+        address: &Pubkey,
+        config: GetConfirmedSignaturesForAddress2Config,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, ClientError> {
+        RpcClient::get_signatures_for_address_with_config(self, address, config).await
+    }
+
+    async fn get_program_accounts_with_config(
+        &self,
+        program: &Pubkey,
+        config: RpcProgramAccountsConfig,
+    ) -> Result<Vec<(Pubkey, Account)>, ClientError> {
+        RpcClient::get_program_accounts_with_config(self, program, config).await
+    }
+
+    async fn get_minimum_balance_for_rent_exemption(
+        &self,
+        data_len: usize,
+    ) -> Result<u64, ClientError> {
+        RpcClient::get_minimum_balance_for_rent_exemption(self, data_len).await
+    }
+}
+
+/// How many consecutive transient errors mark an endpoint of a `FailoverProvider` unhealthy.
+pub const DEFAULT_UNHEALTHY_AFTER: u32 = 3;
+
+/// How long a `FailoverProvider` waits before probing an unhealthy endpoint again.
+pub const DEFAULT_REPROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The health of one endpoint of a `FailoverProvider`.
+#[derive(Debug, Default)]
+struct EndpointHealth {
+    consecutive_errors: u32,
+    unhealthy_since: Option<Instant>,
+}
+
+struct Endpoint {
+    client: RpcClient,
+    health: Mutex<EndpointHealth>,
+}
+
+impl Endpoint {
+    fn health(&self) -> std::sync::MutexGuard<'_, EndpointHealth> {
+        self.health.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// `RpcProvider` failing over across several RPC endpoints, so an outage of one provider
+/// doesn't take the client down.
+///
+/// Endpoints are listed in order of preference. Each request goes to the first healthy
+/// endpoint, and moves on to the next one when an endpoint is marked unhealthy. An endpoint is
+/// marked unhealthy after `unhealthy_after` consecutive transient errors (see `is_transient`),
+/// and is probed again by a request once `reprobe_interval` has elapsed, so traffic goes back to
+/// a preferred endpoint when it recovers. Unhealthy endpoints are still tried as a last resort
+/// when every endpoint is down. Deterministic errors are returned without failing over.
+///
+/// Build an `Rpc` around it with `Rpc::new_with_endpoints`.
+pub struct FailoverProvider {
+    endpoints: Vec<Endpoint>,
+    current: AtomicUsize,
+    unhealthy_after: u32,
+    reprobe_interval: Duration,
+}
+
+impl FailoverProvider {
+    /// Returns a provider failing over across `urls`, in order of preference.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `urls` is empty.
+    pub fn new(urls: Vec<String>, commitment: CommitmentConfig) -> Result<Self> {
+        if urls.is_empty() {
+            return Err(anyhow!("At least one RPC endpoint is required"));
+        }
+
+        Ok(Self {
+            endpoints: urls
+                .into_iter()
+                .map(|url| Endpoint {
+                    client: RpcClient::new_with_commitment(url, commitment),
+                    health: Mutex::default(),
+                })
+                .collect(),
+            current: AtomicUsize::new(0),
+            unhealthy_after: DEFAULT_UNHEALTHY_AFTER,
+            reprobe_interval: DEFAULT_REPROBE_INTERVAL,
+        })
+    }
+
+    /// Replaces the health check thresholds.
+    ///
+    /// # Arguments
+    ///
+    /// * `unhealthy_after` - The consecutive transient errors marking an endpoint unhealthy.
+    /// * `reprobe_interval` - How long an unhealthy endpoint is skipped before it's tried again.
+    pub fn with_health_check(mut self, unhealthy_after: u32, reprobe_interval: Duration) -> Self {
+        self.unhealthy_after = unhealthy_after.max(1);
+        self.reprobe_interval = reprobe_interval;
+        self
+    }
+
+    /// Returns the URL of the endpoint that answered last, or of the first one before any
+    /// request.
+    pub fn current_url(&self) -> String {
+        self.endpoints[self.current.load(Ordering::Relaxed)]
+            .client
+            .url()
+    }
+
+    /// Returns the URL of every endpoint, with whether it's currently healthy.
+    pub fn endpoint_health(&self) -> Vec<(String, bool)> {
+        self.endpoints
+            .iter()
+            .map(|endpoint| {
+                (
+                    endpoint.client.url(),
+                    endpoint.health().unhealthy_since.is_none(),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the indices of the endpoints to try, in order: the healthy endpoints and the
+    /// ones due for a probe by preference, then the other unhealthy ones.
+    fn candidates(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let (ready, waiting): (Vec<usize>, Vec<usize>) =
+            (0..self.endpoints.len()).partition(|&index| {
+                self.endpoints[index]
+                    .health()
+                    .unhealthy_since
+                    .map_or(true, |since| {
+                        now.duration_since(since) >= self.reprobe_interval
+                    })
+            });
+
+        ready.into_iter().chain(waiting).collect()
+    }
+
+    fn record_success(&self, index: usize) {
+        let endpoint = &self.endpoints[index];
+        let mut health = endpoint.health();
+        if health.unhealthy_since.take().is_some() {
+            tracing::info!("RPC endpoint {} recovered", endpoint.client.url());
+        }
+        health.consecutive_errors = 0;
+        self.current.store(index, Ordering::Relaxed);
+    }
+
+    /// Records a transient error, and returns whether the endpoint is now unhealthy.
+    fn record_error(&self, index: usize) -> bool {
+        let endpoint = &self.endpoints[index];
+        let mut health = endpoint.health();
+        health.consecutive_errors = health.consecutive_errors.saturating_add(1);
+        if health.consecutive_errors < self.unhealthy_after {
+            return false;
+        }
+
+        if health.unhealthy_since.is_none() {
+            tracing::warn!(
+                "RPC endpoint {} failed {} times in a row, marking it unhealthy",
+                endpoint.client.url(),
+                health.consecutive_errors
+            );
+        }
+        // A failed probe waits another interval.
+        health.unhealthy_since = Some(Instant::now());
+        true
+    }
+
+    /// Runs `request` against the endpoints, failing over while they are unhealthy.
+    async fn call<'a, T, F, Fut>(&'a self, request: F) -> Result<T, ClientError>
+    where
+        F: Fn(&'a RpcClient) -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        let candidates = self.candidates();
+        let last = candidates.len().saturating_sub(1);
+
+        for (attempt, index) in candidates.into_iter().enumerate() {
+            let result = request(&self.endpoints[index].client).await;
+            match &result {
+                Err(err) if is_transient(err) => {
+                    if self.record_error(index) && attempt < last {
+                        continue;
+                    }
+                }
+                _ => self.record_success(index),
+            }
+            return result;
+        }
+
+        Err(RpcError::ForUser("No RPC endpoint".to_string()).into())
+    }
+}
+
+#[async_trait::async_trait]
+impl RpcProvider for FailoverProvider {
+    fn commitment(&self) -> CommitmentConfig {
+        self.endpoints[0].client.commitment()
+    }
+
+    fn url(&self) -> String {
+        self.current_url()
+    }
+
+    async fn get_multiple_accounts_with_config(
+        &self,
+        pubkeys: &[Pubkey],
+        config: RpcAccountInfoConfig,
+    ) -> Result<Response<Vec<Option<Account>>>, ClientError> {
+        self.call(|client| client.get_multiple_accounts_with_config(pubkeys, config.clone()))
+            .await
+    }
+
+    async fn get_latest_blockhash_with_commitment(
+        &self,
+        commitment: CommitmentConfig,
+    ) -> Result<(Hash, u64), ClientError> {
+        self.call(|client| client.get_latest_blockhash_with_commitment(commitment))
+            .await
+    }
+
+    async fn get_recent_prioritization_fees(
+        &self,
+        addresses: &[Pubkey],
+    ) -> Result<Vec<RpcPrioritizationFee>, ClientError> {
+        self.call(|client| client.get_recent_prioritization_fees(addresses))
+            .await
+    }
+
+    async fn send_transaction_with_config(
+        &self,
+        transaction: &Transaction,
+        config: RpcSendTransactionConfig,
+    ) -> Result<Signature, ClientError> {
+        self.call(|client| client.send_transaction_with_config(transaction, config))
+            .await
+    }
+
+    async fn confirm_transaction(&self, signature: &Signature) -> Result<bool, ClientError> {
+        self.call(|client| client.confirm_transaction(signature))
+            .await
+    }
+
+    async fn get_signature_status(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<Result<(), TransactionError>>, ClientError> {
+        self.call(|client| client.get_signature_status(signature))
+            .await
+    }
+
+    async fn is_blockhash_valid(
+        &self,
+        blockhash: &Hash,
+        commitment: CommitmentConfig,
+    ) -> Result<bool, ClientError> {
+        self.call(|client| client.is_blockhash_valid(blockhash, commitment))
+            .await
+    }
+
+    async fn simulate_transaction_with_config(
+        &self,
+        transaction: &Transaction,
+        config: RpcSimulateTransactionConfig,
+    ) -> Result<Response<RpcSimulateTransactionResult>, ClientError> {
+        self.call(|client| client.simulate_transaction_with_config(transaction, config.clone()))
+            .await
+    }
+
+    async fn get_transaction_with_config(
+        &self,
+        signature: &Signature,
+        config: RpcTransactionConfig,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, ClientError> {
+        self.call(|client| client.get_transaction_with_config(signature, config))
+            .await
+    }
+
+    async fn get_signatures_for_address_with_config(
+        &self,
+        address: &Pubkey,
+        config: GetConfirmedSignaturesForAddress2Config,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, ClientError> {
+        self.call(|client| {
+            let config = GetConfirmedSignaturesForAddress2Config {
+                before: config.before,
+                until: config.until,
+                limit: config.limit,
+                commitment: config.commitment,
+            };
+            client.get_signatures_for_address_with_config(address, config)
+        })
+        .await
+    }
+
+    async fn get_program_accounts_with_config(
+        &self,
+        program: &Pubkey,
+        config: RpcProgramAccountsConfig,
+    ) -> Result<Vec<(Pubkey, Account)>, ClientError> {
+        self.call(|client| client.get_program_accounts_with_config(program, config.clone()))
+            .await
+    }
+
+    async fn get_minimum_balance_for_rent_exemption(
+        &self,
+        data_len: usize,
+    ) -> Result<u64, ClientError> {
+        self.call(|client| client.get_minimum_balance_for_rent_exemption(data_len))
+            .await
+    }
+}
+
+/// How often a `BlockhashCache` refreshes its blockhash by default, see `Rpc::with_blockhash_cache`.
+pub const DEFAULT_BLOCKHASH_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long a cached blockhash is used for.
+///
+/// A blockhash expires after 150 slots, about a minute; the margin leaves a transaction built with
+/// an old cached blockhash the time to land.
+pub const BLOCKHASH_MAX_AGE: Duration = Duration::from_secs(45);
+
+/// How many fresh blockhashes a send fetches before giving up on a transaction that the cached
+/// blockhash would make a duplicate of one already sent.
+const FRESH_BLOCKHASH_ATTEMPTS: u32 = 5;
+
+#[derive(Debug, Clone, Copy)]
+struct CachedBlockhash {
+    hash: Hash,
+    commitment: CommitmentConfig,
+    fetched_at: Instant,
+}
+
+/// The latest blockhash, shared by the clones of an `Rpc` built with `with_blockhash_cache`.
+#[derive(Debug)]
+struct BlockhashCache {
+    latest: Mutex<Option<CachedBlockhash>>,
+    /// The signatures of the transactions sent with the cached blockhash.
+    sent: Mutex<HashSet<Signature>>,
+}
+
+impl BlockhashCache {
+    fn latest(&self) -> std::sync::MutexGuard<'_, Option<CachedBlockhash>> {
+        self.latest.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Returns the cached blockhash if it was fetched with `commitment` and isn't too old.
+    fn get(&self, commitment: CommitmentConfig) -> Option<Hash> {
+        self.latest()
+            .filter(|cached| {
+                cached.commitment == commitment && cached.fetched_at.elapsed() < BLOCKHASH_MAX_AGE
+            })
+            .map(|cached| cached.hash)
+    }
+
+    fn store(&self, commitment: CommitmentConfig, hash: Hash) {
+        let mut latest = self.latest();
+        if latest.map_or(true, |cached| cached.hash != hash) {
+            self.sent
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .clear();
+        }
+        *latest = Some(CachedBlockhash {
+            hash,
+            commitment,
+            fetched_at: Instant::now(),
+        });
+    }
+
+    /// Records a transaction sent with the cached blockhash, returning `false` if a transaction
+    /// with the same signature was already sent.
+    fn mark_sent(&self, signature: Signature) -> bool {
+        self.sent
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .insert(signature)
+    }
+
+    /// Returns the commitment of the cached blockhash, to refresh it with the same one.
+    fn commitment(&self) -> Option<CommitmentConfig> {
+        self.latest().map(|cached| cached.commitment)
+    }
+}
 
 /// Wrapper type for RpcClient providing additional functionality and enabling Debug trait implementation.
 ///
-/// This struct holds an `Arc` of `RpcClient` to ensure thread safety and efficient resource sharing.
+/// This struct holds an `Arc` of `RpcClient` to ensure thread safety and efficient resource sharing,
+/// the `RpcProvider` its requests go through, and the `RetryConfig` applied to transient failures.
 #[derive(Clone)]
-pub struct Rpc(Arc<RpcClient>);
+pub struct Rpc {
+    client: Arc<RpcClient>,
+    provider: Arc<dyn RpcProvider>,
+    retry_config: RetryConfig,
+    commitment_fallback: Option<Duration>,
+    send_commitment: Option<CommitmentConfig>,
+    metrics: Option<Arc<dyn Metrics>>,
+    blockhash_cache: Option<Arc<BlockhashCache>>,
+}
+
+impl Rpc {
+    /// Constructs a new Rpc wrapper around the provided RpcClient instance.
+    ///
+    /// # Parameters
+    ///
+    /// - `rpc_client`: An instance of RpcClient to wrap.
+    ///
+    /// # Returns
+    ///
+    /// A new Rpc wrapper around the provided RpcClient.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::rpc_client::RpcClient;
+    /// use openbook::rpc::Rpc;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let rpc_url = std::env::var("RPC_URL").expect("RPC_URL is not set");
+    ///
+    ///     let connection = RpcClient::new(rpc_url);
+    ///     let rpc_client = Rpc::new(connection);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn new(rpc_client: RpcClient) -> Self {
+        let client = Arc::new(rpc_client);
+        Rpc {
+            provider: client.clone(),
+            client,
+            retry_config: RetryConfig::default(),
+            commitment_fallback: None,
+            send_commitment: None,
+            metrics: None,
+            blockhash_cache: None,
+        }
+    }
+
+    /// Constructs a new Rpc wrapper sending its requests through `provider`.
+    ///
+    /// Only the requests covered by `RpcProvider` go through the provider. `inner` returns a
+    /// client for the provider's URL and commitment, so requests sent through it directly still
+    /// hit that URL.
+    ///
+    /// # Parameters
+    ///
+    /// - `provider`: The `RpcProvider` answering the requests, such as a mock in tests.
+    ///
+    /// # Returns
+    ///
+    /// A new Rpc wrapper around `provider`.
+    pub fn with_provider(provider: impl RpcProvider + 'static) -> Self {
+        Rpc {
+            client: Arc::new(RpcClient::new_with_commitment(
+                provider.url(),
+                provider.commitment(),
+            )),
+            provider: Arc::new(provider),
+            retry_config: RetryConfig::default(),
+            commitment_fallback: None,
+            send_commitment: None,
+            metrics: None,
+            blockhash_cache: None,
+        }
+    }
+
+    /// Replaces the retry policy applied to transient RPC failures.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::rpc_client::RpcClient;
+    /// use openbook::rpc::{RetryConfig, Rpc};
+    ///
+    /// let rpc_client = Rpc::new(RpcClient::new("http://localhost:8899".to_string()))
+    ///     .with_retry_config(RetryConfig::disabled());
+    ///
+    /// assert_eq!(rpc_client.retry_config().max_attempts, 1);
+    /// ```
+    pub fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
+    /// Returns the retry policy applied to transient RPC failures.
+    pub fn retry_config(&self) -> RetryConfig {
+        self.retry_config
+    }
+
+    /// Lets account reads fall back from `finalized` to `confirmed` to `processed` when a level
+    /// doesn't answer within `per_level_timeout`.
+    ///
+    /// This keeps a monitor responsive on a node lagging behind the tip, at the cost of reading
+    /// less settled state. Downgrades are logged. See `read_with_commitment_fallback`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::rpc_client::RpcClient;
+    /// use openbook::rpc::Rpc;
+    /// use std::time::Duration;
+    ///
+    /// let rpc_client = Rpc::new(RpcClient::new_with_commitment(
+    ///     "http://localhost:8899".to_string(),
+    ///     CommitmentConfig::finalized(),
+    /// ))
+    /// .with_commitment_fallback(Duration::from_secs(2));
+    /// ```
+    pub fn with_commitment_fallback(mut self, per_level_timeout: Duration) -> Self {
+        self.commitment_fallback = Some(per_level_timeout);
+        self
+    }
+
+    /// Constructs a new Rpc wrapper failing over across several RPC endpoints.
+    ///
+    /// Requests go to the first healthy endpoint of `endpoints`, listed in order of preference,
+    /// see `FailoverProvider`. Requests sent through `inner` only target the first endpoint.
+    ///
+    /// # Parameters
+    ///
+    /// - `endpoints`: The URLs of the RPC endpoints, preferred first.
+    /// - `commitment`: The commitment of the requests.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `endpoints` is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::rpc::Rpc;
+    ///
+    /// let rpc_client = Rpc::new_with_endpoints(
+    ///     vec![
+    ///         "https://api.mainnet-beta.solana.com".to_string(),
+    ///         "https://solana-api.projectserum.com".to_string(),
+    ///     ],
+    ///     CommitmentConfig::confirmed(),
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(rpc_client.current_endpoint(), "https://api.mainnet-beta.solana.com");
+    /// ```
+    pub fn new_with_endpoints(
+        endpoints: Vec<String>,
+        commitment: CommitmentConfig,
+    ) -> Result<Self> {
+        Ok(Self::with_provider(FailoverProvider::new(
+            endpoints, commitment,
+        )?))
+    }
+
+    /// Returns the URL of the endpoint the requests currently go to, for logging.
+    pub fn current_endpoint(&self) -> String {
+        self.provider.url()
+    }
+
+    /// Sends transactions with `commitment` instead of the commitment of the provider.
+    ///
+    /// The commitment is used for the blockhash and the preflight simulation of the sent and
+    /// exported transactions. Reads keep the commitment of the provider. A `commitment` set in
+    /// the `TxConfig` of a send takes precedence.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::rpc_client::RpcClient;
+    /// use openbook::rpc::Rpc;
+    ///
+    /// let rpc_client = Rpc::new(RpcClient::new("http://localhost:8899".to_string()))
+    ///     .with_send_commitment(CommitmentConfig::processed());
+    ///
+    /// assert_eq!(rpc_client.send_commitment(), CommitmentConfig::processed());
+    /// ```
+    pub fn with_send_commitment(mut self, commitment: CommitmentConfig) -> Self {
+        self.send_commitment = Some(commitment);
+        self
+    }
+
+    /// Returns the commitment transactions are sent with, see `with_send_commitment`.
+    pub fn send_commitment(&self) -> CommitmentConfig {
+        self.send_commitment
+            .unwrap_or_else(|| self.provider.commitment())
+    }
+
+    /// Reports the RPC requests, retries and sent transactions to `metrics`.
+    ///
+    /// Requests are reported by JSON-RPC method, e.g. `getMultipleAccounts`. Without metrics,
+    /// nothing is recorded.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::metrics::PrometheusMetrics;
+    /// use openbook::rpc_client::RpcClient;
+    /// use openbook::rpc::Rpc;
+    /// use std::sync::Arc;
+    ///
+    /// let metrics = Arc::new(PrometheusMetrics::new());
+    /// let rpc_client = Rpc::new(RpcClient::new("http://localhost:8899".to_string()))
+    ///     .with_metrics(metrics.clone());
+    ///
+    /// println!("{}", metrics.render());
+    /// ```
+    pub fn with_metrics(mut self, metrics: Arc<dyn Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Returns the metrics given to `with_metrics`, or `NoopMetrics` without any.
+    ///
+    /// The clients report the orders they place and cancel and their settlements here.
+    pub fn metrics(&self) -> &dyn Metrics {
+        self.metrics.as_deref().unwrap_or(&NoopMetrics)
+    }
+
+    /// Caches the latest blockhash instead of fetching it before every transaction.
+    ///
+    /// A background task refreshes the cached blockhash every `refresh_interval`, and the sends
+    /// and `build_unsigned_transaction` use it. A blockhash is fetched on the spot when the cache
+    /// is empty, was filled with another commitment, or is older than `BLOCKHASH_MAX_AGE`. The
+    /// cache is shared by the clones of the returned `Rpc`, and the task stops once they're all
+    /// dropped. Outside a tokio runtime no task is spawned, and the blockhash is only refreshed
+    /// when it gets too old.
+    ///
+    /// Sending the same instructions twice with the same cached blockhash would sign the same
+    /// transaction, which the network only lands once. The sends fetch a fresh blockhash instead
+    /// when the cached one would repeat a signature already sent, e.g. for two `settle_balance`
+    /// calls in a row. `build_unsigned_transaction` doesn't, since it doesn't send.
+    ///
+    /// # Arguments
+    ///
+    /// * `refresh_interval` - How often the cached blockhash is refreshed, e.g.
+    ///   `DEFAULT_BLOCKHASH_REFRESH_INTERVAL`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::rpc::{Rpc, DEFAULT_BLOCKHASH_REFRESH_INTERVAL};
+    /// use openbook::rpc_client::RpcClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let rpc_url = std::env::var("RPC_URL").expect("RPC_URL is not set");
+    ///
+    ///     let rpc_client = Rpc::new(RpcClient::new(rpc_url))
+    ///         .with_blockhash_cache(DEFAULT_BLOCKHASH_REFRESH_INTERVAL);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn with_blockhash_cache(mut self, refresh_interval: Duration) -> Self {
+        let cache = Arc::new(BlockhashCache {
+            latest: Mutex::default(),
+            sent: Mutex::default(),
+        });
+
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let provider = self.provider.clone();
+            let weak_cache = Arc::downgrade(&cache);
+            runtime.spawn(async move {
+                loop {
+                    tokio::time::sleep(refresh_interval).await;
+                    let Some(cache) = weak_cache.upgrade() else {
+                        break;
+                    };
+                    // Nothing is cached until the first send picks the commitment.
+                    let Some(commitment) = cache.commitment() else {
+                        continue;
+                    };
+                    match provider
+                        .get_latest_blockhash_with_commitment(commitment)
+                        .await
+                    {
+                        Ok((hash, _)) => cache.store(commitment, hash),
+                        Err(err) => tracing::warn!("Failed to refresh the blockhash: {}", err),
+                    }
+                }
+            });
+        }
+
+        self.blockhash_cache = Some(cache);
+        self
+    }
+
+    /// Returns a reference to the inner RpcClient instance wrapped by this wrapper.
+    ///
+    /// Requests sent through the inner client directly aren't retried, and bypass the provider.
+    pub fn inner(&self) -> &RpcClient {
+        &self.client
+    }
+
+    /// Returns the `RpcProvider` the requests of this wrapper go through.
+    pub fn provider(&self) -> &dyn RpcProvider {
+        self.provider.as_ref()
+    }
+
+    /// Runs `request`, retrying it with the configured backoff while it fails transiently.
+    ///
+    /// Each attempt and retry is reported to the metrics under `method`.
+    async fn with_retry<T, F, Fut>(&self, method: &str, mut request: F) -> Result<T, ClientError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        (|| self.observe(method, request()))
+            .retry(&self.retry_config.backoff())
+            .when(is_transient)
+            .notify(|err, delay| {
+                tracing::warn!("Transient RPC error, retrying in {:?}: {}", delay, err);
+                if let Some(metrics) = &self.metrics {
+                    metrics.on_retry(method);
+                }
+            })
+            .await
+    }
+
+    /// Runs `request` once, reporting its latency to the metrics under `method`.
+    async fn observe<T, E>(
+        &self,
+        method: &str,
+        request: impl Future<Output = Result<T, E>>,
+    ) -> Result<T, E> {
+        let start = Instant::now();
+        let result = request.await;
+        if let Some(metrics) = &self.metrics {
+            metrics.on_rpc_call(method, start.elapsed());
+        }
+        result
+    }
+
+    /// Returns the latest blockhash with `commitment`, from the blockhash cache if it's enabled and
+    /// fresh.
+    async fn latest_blockhash(&self, commitment: CommitmentConfig) -> Result<Hash, ClientError> {
+        if let Some(hash) = self
+            .blockhash_cache
+            .as_ref()
+            .and_then(|cache| cache.get(commitment))
+        {
+            return Ok(hash);
+        }
+
+        self.fetch_blockhash(commitment).await
+    }
+
+    /// Fetches the latest blockhash with `commitment`, and caches it if the blockhash cache is
+    /// enabled.
+    async fn fetch_blockhash(&self, commitment: CommitmentConfig) -> Result<Hash, ClientError> {
+        let hash = self
+            .with_retry("getLatestBlockhash", || {
+                self.provider
+                    .get_latest_blockhash_with_commitment(commitment)
+            })
+            .await?
+            .0;
+        if let Some(cache) = &self.blockhash_cache {
+            cache.store(commitment, hash);
+        }
+
+        Ok(hash)
+    }
+
+    /// Fetches an account, retrying transient failures.
+    ///
+    /// Unlike `RpcClient::get_account`, which reports every failure as a missing account, the
+    /// transport error is kept so that it can be retried.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the account doesn't exist, or if the request still fails after the
+    /// configured attempts.
+    pub async fn get_account(&self, pubkey: &Pubkey) -> Result<Account, ClientError> {
+        self.fetch_multiple_accounts(std::slice::from_ref(pubkey))
+            .await?
+            .pop()
+            .flatten()
+            .ok_or_else(|| RpcError::ForUser(format!("AccountNotFound: pubkey={}", pubkey)).into())
+    }
 
-impl Rpc {
-    /// Constructs a new Rpc wrapper around the provided RpcClient instance.
+    /// Fetches the lamports an account of `data_len` bytes needs to be rent exempt, retrying
+    /// transient failures.
     ///
-    /// # Parameters
+    /// # Errors
     ///
-    /// - `rpc_client`: An instance of RpcClient to wrap.
+    /// Returns an error if the request still fails after the configured attempts.
+    pub async fn get_minimum_balance_for_rent_exemption(
+        &self,
+        data_len: usize,
+    ) -> Result<u64, ClientError> {
+        self.with_retry("getMinimumBalanceForRentExemption", || {
+            self.provider
+                .get_minimum_balance_for_rent_exemption(data_len)
+        })
+        .await
+    }
+
+    /// Fetches the status of a transaction, retrying transient failures.
     ///
     /// # Returns
     ///
-    /// A new Rpc wrapper around the provided RpcClient.
-    ///
-    /// # Examples
+    /// `None` if the node hasn't seen the transaction land, or its on-chain result otherwise.
     ///
-    /// ```rust
-    /// use openbook::rpc_client::RpcClient;
-    /// use openbook::rpc::Rpc;
+    /// # Errors
     ///
-    /// #[tokio::main]
-    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    ///     let rpc_url = std::env::var("RPC_URL").expect("RPC_URL is not set");
+    /// Returns an error if the request still fails after the configured attempts.
+    pub async fn get_signature_status(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<Result<(), TransactionError>>, ClientError> {
+        self.with_retry("getSignatureStatuses", || {
+            self.provider.get_signature_status(signature)
+        })
+        .await
+    }
+
+    /// Returns whether a transaction signed with `blockhash` can still land, retrying transient
+    /// failures.
     ///
-    ///     let connection = RpcClient::new(rpc_url);
-    ///     let rpc_client = Rpc::new(connection);
+    /// # Errors
     ///
-    ///     Ok(())
-    /// }
-    /// ```
-    pub fn new(rpc_client: RpcClient) -> Self {
-        Rpc(Arc::new(rpc_client))
+    /// Returns an error if the request still fails after the configured attempts.
+    pub async fn is_blockhash_valid(
+        &self,
+        blockhash: &Hash,
+        commitment: CommitmentConfig,
+    ) -> Result<bool, ClientError> {
+        self.with_retry("isBlockhashValid", || {
+            self.provider.is_blockhash_valid(blockhash, commitment)
+        })
+        .await
     }
 
-    /// Returns a reference to the inner RpcClient instance wrapped by this wrapper.
-    pub fn inner(&self) -> &RpcClient {
-        &self.0
+    /// Fetches the accounts owned by `program` matching `config`, retrying transient failures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request still fails after the configured attempts.
+    pub async fn fetch_program_accounts(
+        &self,
+        program: &Pubkey,
+        config: RpcProgramAccountsConfig,
+    ) -> Result<Vec<(Pubkey, Account)>, ClientError> {
+        self.with_retry("getProgramAccounts", || {
+            self.provider
+                .get_program_accounts_with_config(program, config.clone())
+        })
+        .await
     }
 
     /// Retrieves a transaction with the specified signature.
@@ -119,12 +1408,13 @@ impl Rpc {
         let config = RpcTransactionConfig {
             encoding: Some(UiTransactionEncoding::Base64),
             max_supported_transaction_version: Some(0),
-            commitment: Some(self.inner().commitment()),
+            commitment: Some(self.provider.commitment()),
         };
 
-        self.inner()
-            .get_transaction_with_config(signature, config)
-            .await
+        self.with_retry("getTransaction", || {
+            self.provider.get_transaction_with_config(signature, config)
+        })
+        .await
     }
 
     /// Retrieves confirmed transaction signatures associated with a specific address.
@@ -168,18 +1458,17 @@ impl Rpc {
         before: Option<Signature>,
         until: Option<Signature>,
     ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, ClientError> {
-        (|| async {
+        self.with_retry("getSignaturesForAddress", || async {
             let config = GetConfirmedSignaturesForAddress2Config {
                 before,
                 until,
-                commitment: Some(self.inner().commitment()),
+                commitment: Some(self.provider.commitment()),
                 ..GetConfirmedSignaturesForAddress2Config::default()
             };
-            self.inner()
+            self.provider
                 .get_signatures_for_address_with_config(pubkey, config)
                 .await
         })
-        .retry(&ExponentialBuilder::default())
         .await
     }
 
@@ -220,19 +1509,30 @@ impl Rpc {
         &self,
         pubkeys: &[Pubkey],
     ) -> Result<Vec<Option<Account>>, ClientError> {
-        Ok((|| async {
-            let config = RpcAccountInfoConfig {
-                commitment: Some(self.inner().commitment()),
-                ..RpcAccountInfoConfig::default()
-            };
+        let read = |commitment: CommitmentConfig| {
+            self.with_retry("getMultipleAccounts", move || async move {
+                let config = RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    commitment: Some(commitment),
+                    ..RpcAccountInfoConfig::default()
+                };
 
-            self.inner()
-                .get_multiple_accounts_with_config(pubkeys, config)
-                .await
-        })
-        .retry(&ExponentialBuilder::default())
-        .await?
-        .value)
+                self.provider
+                    .get_multiple_accounts_with_config(pubkeys, config)
+                    .await
+            })
+        };
+
+        let response = match self.commitment_fallback {
+            Some(per_level_timeout) => {
+                read_with_commitment_fallback(self.provider.commitment(), per_level_timeout, read)
+                    .await?
+                    .0
+            }
+            None => read(self.provider.commitment()).await?,
+        };
+
+        Ok(response.value)
     }
 
     #[cfg(feature = "v2")]
@@ -240,10 +1540,35 @@ impl Rpc {
         &self,
         address: &Pubkey,
     ) -> anyhow::Result<T> {
-        let account = self.inner().get_account(address).await?;
+        let account = self.get_account(address).await?;
         Ok(T::try_deserialize(&mut (&account.data as &[u8]))?)
     }
 
+    /// Fetches and deserializes several Anchor accounts in a single `getMultipleAccounts` call.
+    ///
+    /// The accounts are returned in the order of `addresses`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, if an account is missing, or if an account can't be
+    /// deserialized as `T`.
+    #[cfg(feature = "v2")]
+    pub async fn fetch_multiple_anchor_accounts<T: AccountDeserialize>(
+        &self,
+        addresses: &[Pubkey],
+    ) -> anyhow::Result<Vec<T>> {
+        self.fetch_multiple_accounts(addresses)
+            .await?
+            .into_iter()
+            .zip(addresses)
+            .map(|(account, address)| {
+                let account =
+                    account.ok_or_else(|| anyhow::anyhow!("Account {} not found", address))?;
+                Ok(T::try_deserialize(&mut (&account.data as &[u8]))?)
+            })
+            .collect()
+    }
+
     #[cfg(feature = "v2")]
     pub async fn fetch_openbook_accounts(
         &self,
@@ -264,17 +1589,19 @@ impl Rpc {
             },
             ..RpcProgramAccountsConfig::default()
         };
-        self.inner()
-            .get_program_accounts_with_config(&program, config)
-            .await?
-            .into_iter()
-            .map(|(key, account)| {
-                Ok((
-                    key,
-                    OpenOrdersAccount::try_deserialize(&mut (&account.data as &[u8]))?,
-                ))
-            })
-            .collect()
+        self.with_retry("getProgramAccounts", || {
+            self.provider
+                .get_program_accounts_with_config(&program, config.clone())
+        })
+        .await?
+        .into_iter()
+        .map(|(key, account)| {
+            Ok((
+                key,
+                OpenOrdersAccount::try_deserialize(&mut (&account.data as &[u8]))?,
+            ))
+        })
+        .collect()
     }
 
     #[cfg(feature = "v2")]
@@ -292,50 +1619,410 @@ impl Rpc {
             },
             ..RpcProgramAccountsConfig::default()
         };
-        self.inner()
-            .get_program_accounts_with_config(&program, config)
+        self.with_retry("getProgramAccounts", || {
+            self.provider
+                .get_program_accounts_with_config(&program, config.clone())
+        })
+        .await?
+        .into_iter()
+        .map(|(key, account)| Ok((key, T::try_deserialize(&mut (&account.data as &[u8]))?)))
+        .collect()
+    }
+
+    /// Estimates the priority fee of a transaction writing to `accounts`.
+    ///
+    /// # Arguments
+    ///
+    /// * `accounts` - The writable accounts of the transaction, to only consider fees paid to
+    ///   write them. Empty to consider all recent fees.
+    /// * `strategy` - How to pick the fee from the recent fees.
+    ///
+    /// # Returns
+    ///
+    /// The priority fee, in micro-lamports per compute unit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the recent prioritization fees can't be fetched.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::rpc::{PriorityFeeStrategy, Rpc};
+    /// use openbook::rpc_client::RpcClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let rpc_url = std::env::var("RPC_URL").expect("RPC_URL is not set");
+    ///     let rpc_client = Rpc::new(RpcClient::new(rpc_url));
+    ///
+    ///     let fee = rpc_client
+    ///         .estimate_priority_fee(&[], PriorityFeeStrategy::default())
+    ///         .await?;
+    ///
+    ///     println!("[*] Priority fee: {}", fee);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn estimate_priority_fee(
+        &self,
+        accounts: &[Pubkey],
+        strategy: PriorityFeeStrategy,
+    ) -> anyhow::Result<u64> {
+        if let PriorityFeeStrategy::Fixed(fee) = strategy {
+            return Ok(fee);
+        }
+
+        let fees: Vec<u64> = self
+            .observe(
+                "getRecentPrioritizationFees",
+                self.provider.get_recent_prioritization_fees(accounts),
+            )
             .await?
             .into_iter()
-            .map(|(key, account)| Ok((key, T::try_deserialize(&mut (&account.data as &[u8]))?)))
-            .collect()
+            .map(|fee| fee.prioritization_fee)
+            .collect();
+
+        Ok(strategy.select(&fees))
+    }
+
+    /// Simulates a transaction holding `instructions`, paid by `payer`, without sending it.
+    ///
+    /// Signatures aren't verified and the blockhash is replaced by the node, so no keypair is
+    /// needed. Include the compute budget instructions the transaction will be sent with, so the
+    /// consumed units match.
+    ///
+    /// # Parameters
+    ///
+    /// - `payer`: The fee payer of the transaction.
+    /// - `instructions`: The instructions to simulate.
+    ///
+    /// # Returns
+    ///
+    /// The consumed compute units, the program logs, and the error the transaction would fail
+    /// with, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::pubkey::Pubkey;
+    /// use openbook::rpc_client::RpcClient;
+    /// use openbook::rpc::Rpc;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let rpc_url = std::env::var("RPC_URL").expect("RPC_URL is not set");
+    ///
+    ///     let rpc_client = Rpc::new(RpcClient::new(rpc_url));
+    ///
+    ///     let simulation = rpc_client
+    ///         .simulate_transaction(&Pubkey::new_unique(), Vec::new())
+    ///         .await?;
+    ///
+    ///     println!("Units consumed: {:?}", simulation.units_consumed);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn simulate_transaction(
+        &self,
+        payer: &Pubkey,
+        instructions: Vec<Instruction>,
+    ) -> anyhow::Result<SimulationResult> {
+        let transaction = Transaction::new_unsigned(Message::new(&instructions, Some(payer)));
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(self.provider.commitment()),
+            ..RpcSimulateTransactionConfig::default()
+        };
+
+        let result = self
+            .with_retry("simulateTransaction", || {
+                self.provider
+                    .simulate_transaction_with_config(&transaction, config.clone())
+            })
+            .await?
+            .value;
+
+        Ok(result.into())
+    }
+
+    /// Builds an unsigned transaction for `instructions` with the latest blockhash.
+    ///
+    /// The transaction can be exported with `utils::encode_transaction` and signed on another
+    /// machine. It must be signed and sent before the blockhash expires, about a minute later.
+    ///
+    /// # Arguments
+    ///
+    /// * `payer` - The fee payer of the transaction.
+    /// * `instructions` - The instructions of the transaction.
+    ///
+    /// # Returns
+    ///
+    /// The transaction, with default signatures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the latest blockhash can't be fetched.
+    ///
+    /// # Examples
+    ///
+    /// ```rust , ignore
+    /// use openbook::pubkey::Pubkey;
+    /// use openbook::rpc::Rpc;
+    /// use openbook::rpc_client::RpcClient;
+    /// use openbook::utils::encode_transaction;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let rpc_url = std::env::var("RPC_URL").expect("RPC_URL is not set");
+    ///     let rpc_client = Rpc::new(RpcClient::new(rpc_url));
+    ///
+    ///     let transaction = rpc_client
+    ///         .build_unsigned_transaction(&Pubkey::new_unique(), Vec::new())
+    ///         .await?;
+    ///
+    ///     println!("{}", encode_transaction(&transaction)?);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn build_unsigned_transaction(
+        &self,
+        payer: &Pubkey,
+        instructions: Vec<Instruction>,
+    ) -> anyhow::Result<Transaction> {
+        let recent_hash = self.latest_blockhash(self.send_commitment()).await?;
+
+        Ok(Transaction::new_unsigned(Message::new_with_blockhash(
+            &instructions,
+            Some(payer),
+            &recent_hash,
+        )))
     }
 
+    /// Signs, sends and confirms a transaction with the default `TxConfig`.
+    ///
+    /// Preflight is run and no compute budget is added; see `send_and_confirm_with_config`.
     pub async fn send_and_confirm(
         &self,
         owner: Keypair,
         instructions: Vec<Instruction>,
     ) -> anyhow::Result<(bool, Signature)> {
-        let confirmed;
-        let mut sig = Signature::default();
-        let recent_hash = self
-            .inner()
-            .get_latest_blockhash_with_commitment(self.inner().commitment())
-            .await?
-            .0;
-        let txn = Transaction::new_signed_with_payer(
-            &instructions,
-            Some(&owner.pubkey()),
-            &[&owner],
-            recent_hash,
+        self.send_and_confirm_with_config(owner, instructions, &TxConfig::default())
+            .await
+    }
+
+    /// Signs, sends and confirms a transaction following `config`.
+    ///
+    /// The compute unit limit and price of `config` are prepended unless `instructions` already
+    /// set them. Send and confirmation failures are logged and reported as unconfirmed rather than
+    /// returned as errors, except a preflight failure for lack of funds.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The fee payer and signer of the transaction.
+    /// * `instructions` - The instructions of the transaction.
+    /// * `config` - The sending policy (preflight, compute budget, retries and commitment).
+    ///
+    /// # Returns
+    ///
+    /// Whether the transaction was confirmed, and its signature if it was.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the latest blockhash can't be fetched, or
+    /// `OpenBookError::InsufficientBalance` if the preflight simulation fails for lack of funds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust , ignore
+    /// use openbook::rpc::{Rpc, TxConfig};
+    /// use openbook::rpc_client::RpcClient;
+    /// use openbook::signature::Keypair;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let rpc_url = std::env::var("RPC_URL").expect("RPC_URL is not set");
+    ///     let rpc_client = Rpc::new(RpcClient::new(rpc_url));
+    ///
+    ///     let config = TxConfig::with_compute_budget(200_000, 1_000);
+    ///     let (confirmed, sig) = rpc_client
+    ///         .send_and_confirm_with_config(Keypair::new(), vec![], &config)
+    ///         .await?;
+    ///
+    ///     println!("[*] Confirmed: {}, signature: {:?}", confirmed, sig);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn send_and_confirm_with_config(
+        &self,
+        owner: Keypair,
+        instructions: Vec<Instruction>,
+        config: &TxConfig,
+    ) -> anyhow::Result<(bool, Signature)> {
+        self.send_and_confirm_with_signers(owner, &[], instructions, config)
+            .await
+    }
+
+    /// Signs and sends a transaction following `config`, without waiting for its confirmation.
+    ///
+    /// This is for callers that track the confirmation themselves, such as to re-send a dropped
+    /// transaction once its blockhash expires. The blockhash cache, the send commitment and the
+    /// retries apply as with `send_and_confirm_with_config`, but the transaction is not reported
+    /// to the metrics until the caller calls `Metrics::on_tx_sent`.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The fee payer and signer of the transaction.
+    /// * `instructions` - The instructions of the transaction.
+    /// * `config` - The sending policy (preflight, compute budget, retries and commitment).
+    ///
+    /// # Returns
+    ///
+    /// The signature of the transaction and the blockhash it was signed with, which can no longer
+    /// land once `is_blockhash_valid` returns `false`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the latest blockhash can't be fetched or the transaction can't be sent.
+    pub async fn send_with_config(
+        &self,
+        owner: &Keypair,
+        instructions: Vec<Instruction>,
+        config: &TxConfig,
+    ) -> anyhow::Result<(Signature, Hash)> {
+        let instructions = prepend_compute_budget_parts(
+            instructions,
+            config.compute_unit_limit,
+            config.compute_unit_price,
         );
+        let commitment = config.commitment.unwrap_or_else(|| self.send_commitment());
+        let txn = self
+            .sign_with_latest_blockhash(owner, &[], &instructions, commitment)
+            .await?;
+        let signature = self.send_signed(&txn, config, commitment).await?;
+
+        Ok((signature, txn.message.recent_blockhash))
+    }
+
+    /// Signs `instructions` with the latest blockhash with `commitment`.
+    ///
+    /// The same instructions signed with the same cached blockhash give the same signature, which
+    /// the network dedups to the transaction sent first. With the blockhash cache enabled, a
+    /// transaction already sent is signed with a fresh blockhash instead, waiting for the next slot
+    /// if the latest one is still the same.
+    async fn sign_with_latest_blockhash(
+        &self,
+        owner: &Keypair,
+        signers: &[&Keypair],
+        instructions: &[Instruction],
+        commitment: CommitmentConfig,
+    ) -> anyhow::Result<Transaction> {
+        let mut all_signers = vec![owner];
+        all_signers.extend_from_slice(signers);
+        let sign = |recent_hash| {
+            Transaction::new_signed_with_payer(
+                instructions,
+                Some(&owner.pubkey()),
+                all_signers.as_slice(),
+                recent_hash,
+            )
+        };
+        let mut txn = sign(self.latest_blockhash(commitment).await?);
+
+        if let Some(cache) = &self.blockhash_cache {
+            let mut attempts = 0;
+            while !cache.mark_sent(txn.signatures[0]) {
+                if attempts == FRESH_BLOCKHASH_ATTEMPTS {
+                    return Err(anyhow!(
+                        "Transaction {} was already sent with the latest blockhash",
+                        txn.signatures[0]
+                    ));
+                }
+                if attempts > 0 {
+                    tokio::time::sleep(Duration::from_millis(DEFAULT_MS_PER_SLOT)).await;
+                }
+                attempts += 1;
+                txn = sign(self.fetch_blockhash(commitment).await?);
+            }
+        }
+
+        Ok(txn)
+    }
 
-        match self
-            .inner()
-            .send_transaction_with_config(
-                &txn,
+    /// Sends a signed transaction following `config`, retrying transient failures.
+    ///
+    /// Resending the same signed transaction is safe: it can only land once.
+    async fn send_signed(
+        &self,
+        txn: &Transaction,
+        config: &TxConfig,
+        commitment: CommitmentConfig,
+    ) -> Result<Signature, ClientError> {
+        self.with_retry("sendTransaction", || {
+            self.provider.send_transaction_with_config(
+                txn,
                 RpcSendTransactionConfig {
-                    skip_preflight: false,
-                    max_retries: None,
-                    preflight_commitment: Some(self.inner().commitment().commitment),
+                    skip_preflight: config.skip_preflight,
+                    max_retries: config.max_retries,
+                    preflight_commitment: Some(commitment.commitment),
                     encoding: None,
                     min_context_slot: None,
                 },
             )
-            .await
-        {
+        })
+        .await
+    }
+
+    /// Signs, sends and confirms a transaction following `config`, with signers besides `owner`.
+    ///
+    /// This is `send_and_confirm_with_config` for instructions that also need the signature of
+    /// other accounts, such as accounts created by the transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - The fee payer and signer of the transaction.
+    /// * `signers` - The other signers of the transaction.
+    /// * `instructions` - The instructions of the transaction.
+    /// * `config` - The sending policy (preflight, compute budget, retries and commitment).
+    ///
+    /// # Returns
+    ///
+    /// Whether the transaction was confirmed, and its signature if it was.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the latest blockhash can't be fetched, or
+    /// `OpenBookError::InsufficientBalance` if the preflight simulation fails for lack of funds.
+    pub async fn send_and_confirm_with_signers(
+        &self,
+        owner: Keypair,
+        signers: &[&Keypair],
+        instructions: Vec<Instruction>,
+        config: &TxConfig,
+    ) -> anyhow::Result<(bool, Signature)> {
+        let instructions = prepend_compute_budget_parts(
+            instructions,
+            config.compute_unit_limit,
+            config.compute_unit_price,
+        );
+        let commitment = config.commitment.unwrap_or_else(|| self.send_commitment());
+        let confirmed;
+        let mut sig = Signature::default();
+        let txn = self
+            .sign_with_latest_blockhash(&owner, signers, &instructions, commitment)
+            .await?;
+
+        match self.send_signed(&txn, config, commitment).await {
             Ok(signature) => {
-                match (|| async { self.inner().confirm_transaction(&signature).await })
-                    .retry(&ExponentialBuilder::default())
+                match self
+                    .with_retry("getSignatureStatuses", || {
+                        self.provider.confirm_transaction(&signature)
+                    })
                     .await
                 {
                     Ok(_ret) => {
@@ -386,6 +2073,14 @@ impl Rpc {
                 }
             }
             Err(err) => {
+                // The caller can't do anything about the other failures, but can about a lack of
+                // funds, so it's returned rather than reported as unconfirmed.
+                if let Some(err) = insufficient_funds_error(&err) {
+                    if let Some(metrics) = &self.metrics {
+                        metrics.on_tx_sent(false);
+                    }
+                    return Err(OpenBookError::InsufficientBalance(err).into());
+                }
                 match err.kind() {
                     ErrorKind::Reqwest(reqwest_error) => {
                         if reqwest_error.is_timeout() {
@@ -426,6 +2121,10 @@ impl Rpc {
             }
         };
 
+        if let Some(metrics) = &self.metrics {
+            metrics.on_tx_sent(confirmed);
+        }
+
         Ok((confirmed, sig))
     }
 }
@@ -438,7 +2137,12 @@ impl fmt::Debug for Rpc {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // Include relevant information about RpcClient
         f.debug_struct("RpcClient")
-            .field("commitment", &self.inner().commitment())
+            .field("commitment", &self.provider.commitment())
+            .field("retry_config", &self.retry_config)
+            .field("commitment_fallback", &self.commitment_fallback)
+            .field("send_commitment", &self.send_commitment)
+            .field("metrics", &self.metrics.is_some())
+            .field("blockhash_cache", &self.blockhash_cache.is_some())
             .finish()
     }
 }
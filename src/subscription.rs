@@ -0,0 +1,138 @@
+//! This module contains the websocket account subscriptions shared by the OpenBook clients.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use futures::stream::{self, select_all, Stream, StreamExt};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{nonblocking::pubsub_client::PubsubClient, rpc_config::RpcAccountInfoConfig};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use tokio::sync::mpsc;
+
+use crate::rpc::Rpc;
+
+/// The delay before the first reconnection attempt after the websocket drops.
+pub const MIN_RECONNECT_DELAY: Duration = Duration::from_millis(500);
+
+/// The maximum delay between two reconnection attempts.
+pub const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// Subscribes to `keys` and streams the value decoded from their latest state on every change.
+///
+/// A background task owns the websocket. On every (re)connection it subscribes to all `keys`,
+/// fetches them over RPC to resync whatever was missed while disconnected, and yields a first
+/// value. If the websocket drops, it reconnects with an exponential backoff between
+/// `MIN_RECONNECT_DELAY` and `MAX_RECONNECT_DELAY`. The task stops once the stream is dropped.
+///
+/// # Arguments
+///
+/// * `rpc_client` - The RPC client used to resync the accounts; its commitment is reused for the
+///   subscriptions.
+/// * `ws_url` - The websocket URL of the RPC node.
+/// * `keys` - The accounts to watch.
+/// * `decode` - Decodes the accounts, in the order of `keys`, into the streamed value. Updates
+///   that fail to decode are logged and skipped.
+///
+/// # Returns
+///
+/// A stream of decoded values, one per account change.
+pub(crate) fn spawn_account_stream<T, F>(
+    rpc_client: Rpc,
+    ws_url: String,
+    keys: Vec<Pubkey>,
+    decode: F,
+) -> impl Stream<Item = T>
+where
+    T: Send + 'static,
+    F: Fn(&[Account]) -> Result<T> + Send + Sync + 'static,
+{
+    let (sender, receiver) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        let mut delay = MIN_RECONNECT_DELAY;
+
+        loop {
+            match watch_accounts(&rpc_client, &ws_url, &keys, &decode, &sender, &mut delay).await {
+                // The stream was dropped.
+                Ok(()) => return,
+                Err(err) => {
+                    if sender.is_closed() {
+                        return;
+                    }
+                    tracing::warn!("Subscription dropped, reconnecting in {:?}: {}", delay, err);
+                }
+            }
+
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+        }
+    });
+
+    stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|value| (value, receiver))
+    })
+}
+
+/// Watches `keys` over a single websocket connection, sending a decoded value on every change.
+///
+/// Returns `Ok(())` once the receiving stream is dropped, or an error when the connection fails
+/// or closes.
+async fn watch_accounts<T, F>(
+    rpc_client: &Rpc,
+    ws_url: &str,
+    keys: &[Pubkey],
+    decode: &F,
+    sender: &mpsc::UnboundedSender<T>,
+    delay: &mut Duration,
+) -> Result<()>
+where
+    F: Fn(&[Account]) -> Result<T>,
+{
+    let pubsub = PubsubClient::new(ws_url).await?;
+    let config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(rpc_client.provider().commitment()),
+        ..RpcAccountInfoConfig::default()
+    };
+
+    let mut subscriptions = Vec::with_capacity(keys.len());
+    for (index, key) in keys.iter().enumerate() {
+        let (notifications, _unsubscribe) =
+            pubsub.account_subscribe(key, Some(config.clone())).await?;
+        subscriptions.push(notifications.map(move |response| (index, response.value)));
+    }
+    let mut updates = select_all(subscriptions);
+
+    // Fetch after subscribing, so no change is missed between the two.
+    let mut accounts = rpc_client
+        .fetch_multiple_accounts(keys)
+        .await?
+        .into_iter()
+        .zip(keys)
+        .map(|(account, key)| account.ok_or_else(|| anyhow!("Account {} not found", key)))
+        .collect::<Result<Vec<Account>>>()?;
+    *delay = MIN_RECONNECT_DELAY;
+
+    if sender.send(decode(&accounts)?).is_err() {
+        return Ok(());
+    }
+
+    while let Some((index, ui_account)) = updates.next().await {
+        let Some(account) = ui_account.decode::<Account>() else {
+            tracing::warn!("Failed to decode an update of account {}", keys[index]);
+            continue;
+        };
+        accounts[index] = account;
+
+        match decode(&accounts) {
+            Ok(value) => {
+                if sender.send(value).is_err() {
+                    return Ok(());
+                }
+            }
+            Err(err) => tracing::warn!("Skipping an undecodable update: {}", err),
+        }
+    }
+
+    Err(anyhow!("Websocket closed"))
+}
@@ -0,0 +1,86 @@
+//! This module contains the tokens known to the SDK, with their mints and display symbols.
+
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+/// A token known to the SDK.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Token {
+    /// Wrapped SOL.
+    SOL,
+    /// USD Coin.
+    USDC,
+    /// Tether USD.
+    USDT,
+    /// Jupiter Perps LP.
+    JLP,
+}
+
+impl Token {
+    /// All known tokens.
+    pub const ALL: [Token; 4] = [Token::SOL, Token::USDC, Token::USDT, Token::JLP];
+
+    /// Returns the base58 mint address of the token.
+    pub fn mint_address(&self) -> &'static str {
+        match self {
+            Token::SOL => "So11111111111111111111111111111111111111112",
+            Token::USDC => "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+            Token::USDT => "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB",
+            Token::JLP => "27G8MtK7VtTcCHkpASjSDdkWWYfoqT6ggEuKidVJidD4",
+        }
+    }
+
+    /// Returns the mint of the token.
+    pub fn mint(&self) -> Pubkey {
+        Pubkey::from_str(self.mint_address()).unwrap()
+    }
+
+    /// Returns the display symbol of the token (e.g., "USDC").
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::tokens_and_markets::Token;
+    ///
+    /// assert_eq!(Token::JLP.symbol(), "JLP");
+    /// ```
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Token::SOL => "SOL",
+            Token::USDC => "USDC",
+            Token::USDT => "USDT",
+            Token::JLP => "JLP",
+        }
+    }
+
+    /// Returns the known token with the given mint, if any.
+    pub fn from_mint(mint: &Pubkey) -> Option<Token> {
+        Token::ALL.into_iter().find(|token| token.mint() == *mint)
+    }
+}
+
+/// Returns the display symbol of a mint, if the token is known.
+///
+/// Use it to show "JLP" or "USDC" instead of a base58 mint, falling back to the address otherwise.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::pubkey::Pubkey;
+/// use openbook::tokens_and_markets::symbol_for_mint;
+///
+/// let usdc: Pubkey = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".parse().unwrap();
+///
+/// assert_eq!(symbol_for_mint(&usdc), Some("USDC"));
+/// assert_eq!(symbol_for_mint(&Pubkey::default()), None);
+/// ```
+pub fn symbol_for_mint(mint: &Pubkey) -> Option<&'static str> {
+    Token::from_mint(mint).map(|token| token.symbol())
+}
+
+/// Returns the display symbol of a mint, or its base58 address if the token is unknown.
+pub fn display_mint(mint: &Pubkey) -> String {
+    symbol_for_mint(mint)
+        .map(str::to_string)
+        .unwrap_or_else(|| mint.to_string())
+}
@@ -0,0 +1,120 @@
+//! This module contains the traits implemented by both the v1 and v2 clients.
+
+use async_trait::async_trait;
+use solana_sdk::signature::Signature;
+
+use crate::error::OpenBookError;
+
+/// The side of an order, shared by the v1 and v2 clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OrderSide {
+    /// A buy order.
+    Bid,
+    /// A sell order.
+    Ask,
+}
+
+#[cfg(feature = "v1")]
+impl From<OrderSide> for openbook_dex::matching::Side {
+    fn from(side: OrderSide) -> Self {
+        match side {
+            OrderSide::Bid => Self::Bid,
+            OrderSide::Ask => Self::Ask,
+        }
+    }
+}
+
+#[cfg(feature = "v2")]
+impl From<OrderSide> for openbookdex_v2::state::Side {
+    fn from(side: OrderSide) -> Self {
+        match side {
+            OrderSide::Bid => Self::Bid,
+            OrderSide::Ask => Self::Ask,
+        }
+    }
+}
+
+/// An order placed with `OrderPlacer::place_limit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderHandle {
+    /// The signature of the confirmed transaction placing the order.
+    pub signature: Signature,
+    /// The order id assigned by the program, or `None` if it isn't known: the v1 client reads it
+    /// back unless the order was filled right away, the v2 client doesn't read it back.
+    pub order_id: Option<u128>,
+    /// The client order id the order was placed with, to cancel or look it up by.
+    pub client_order_id: u64,
+}
+
+/// Places, cancels and settles orders on a market, whatever its OpenBook version.
+///
+/// Strategy code written against this trait runs on both `v1::ob_client::OBClient` and
+/// `v2::ob_client::OBClient`, e.g. as a `Box<dyn OrderPlacer>`. Sizes are in quote UI units,
+/// like the `place_limit_order` methods of both clients.
+///
+/// # Examples
+///
+/// ```rust , ignore
+/// use openbook::error::OpenBookError;
+/// use openbook::traits::{OrderPlacer, OrderSide};
+///
+/// async fn quote_around_mid(
+///     client: &dyn OrderPlacer,
+///     half_spread: f64,
+/// ) -> Result<(), OpenBookError> {
+///     let mid = client.mid_price().await?;
+///
+///     client.cancel_all().await?;
+///     client.place_limit(OrderSide::Bid, mid - half_spread, 10.0).await?;
+///     client.place_limit(OrderSide::Ask, mid + half_spread, 10.0).await?;
+///
+///     Ok(())
+/// }
+/// ```
+#[async_trait]
+pub trait OrderPlacer: Send + Sync {
+    /// Places a post-only limit order of `size` quote at `price`, and waits for its confirmation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the size rounds down to zero base lots, or if the transaction fails
+    /// or isn't confirmed.
+    async fn place_limit(
+        &self,
+        side: OrderSide,
+        price: f64,
+        size: f64,
+    ) -> Result<OrderHandle, OpenBookError>;
+
+    /// Cancels all the open orders of the wallet on the market.
+    ///
+    /// # Returns
+    ///
+    /// The signature of the confirmed transaction, or `None` if there was nothing to cancel.
+    async fn cancel_all(&self) -> Result<Option<Signature>, OpenBookError>;
+
+    /// Settles the free balances of the wallet's open orders account back to its token accounts.
+    ///
+    /// # Returns
+    ///
+    /// The signature of the confirmed transaction.
+    async fn settle(&self) -> Result<Signature, OpenBookError>;
+
+    /// Fetches the book and returns the mid price between the best bid and the best ask.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the book can't be fetched or one of its sides is empty.
+    async fn mid_price(&self) -> Result<f64, OpenBookError>;
+}
+
+/// Returns the signature of a sent transaction, or an error if it wasn't confirmed.
+pub(crate) fn confirmed_signature(
+    (confirmed, signature): (bool, Signature),
+) -> Result<Signature, OpenBookError> {
+    if !confirmed {
+        return Err(anyhow::anyhow!("Transaction {} was not confirmed", signature).into());
+    }
+
+    Ok(signature)
+}
@@ -1,4 +1,5 @@
 use crossterm::{
+    cursor::Show,
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
@@ -27,25 +28,21 @@ use tui_input::Input;
 use crate::commitment_config::CommitmentConfig;
 #[cfg(feature = "v1")]
 use crate::matching::Side as OBV1Side;
-use crate::rpc::Rpc;
+use crate::pubkey::Pubkey;
 use crate::rpc_client::RpcClient;
-use crate::utils::read_keypair;
+use crate::tokens_and_markets::display_mint;
+use crate::utils::{detect_version, read_keypair};
+pub use crate::utils::SdkVersion;
 #[cfg(feature = "v1")]
 use crate::v1::{ob_client::OBClient as OBClientV1, orders::OrderReturnType};
 #[cfg(feature = "v2")]
 use crate::v2::market::CreateMarketArgs;
 #[cfg(feature = "v2")]
-use crate::v2::ob_client::OBClient as OBClientV2;
-
-use anyhow::{Error, Result};
+use crate::v2::ob_client::{get_base_price, OBClient as OBClientV2, OpenOrderNode};
+#[cfg(feature = "v2")]
+use openbookdex_v2::state::Side as OBV2Side;
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum SdkVersion {
-    /// OpenBook v1.
-    V1,
-    /// OpenBook v2.
-    V2,
-}
+use anyhow::{anyhow, Error, Result};
 
 #[derive(Clone)]
 pub enum SdkClient {
@@ -78,6 +75,20 @@ enum CurrentInput {
     ConsumeEventsAdmin,
     CloseMarketAdmin,
     TimeExpiry,
+    SwapFromMint,
+    SwapToMint,
+    SwapAmount,
+}
+
+/// The most the swap price may be worse than the quoted price, in percent.
+const SWAP_MAX_SLIPPAGE_PERCENT: f64 = 1.0;
+
+/// A swap quote, kept until the user confirms it with a second Enter.
+struct SwapQuote {
+    from_mint: Pubkey,
+    to_mint: Pubkey,
+    amount: f64,
+    limit_price: f64,
 }
 
 #[derive(Default, Clone, Copy, Display, FromRepr, EnumIter)]
@@ -157,6 +168,10 @@ struct App {
     market_consume_events_admin: Input,
     market_close_market_admin: Input,
     market_time_expiry: Input,
+    swap_from_mint: Input,
+    swap_to_mint: Input,
+    swap_amount: Input,
+    swap_quote: Option<SwapQuote>,
 }
 
 impl Default for App {
@@ -188,11 +203,111 @@ impl Default for App {
             market_consume_events_admin: Input::default(),
             market_close_market_admin: Input::default(),
             market_time_expiry: Input::default(),
+            swap_from_mint: Input::default(),
+            swap_to_mint: Input::default(),
+            swap_amount: Input::default(),
+            swap_quote: None,
+        }
+    }
+}
+
+/// Leaves raw mode and the alternate screen, and shows the cursor again.
+pub fn restore_terminal() -> Result<(), Error> {
+    disable_raw_mode()?;
+    execute!(
+        std::io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        Show
+    )?;
+    Ok(())
+}
+
+/// Installs a panic hook that runs `cleanup` before the previously installed hook prints the panic.
+///
+/// `run_tui` installs it with `restore_terminal`, so a panic inside the app doesn't leave the
+/// terminal in raw mode with the alternate screen active.
+pub fn install_panic_hook<F>(cleanup: F)
+where
+    F: Fn() + Send + Sync + 'static,
+{
+    let previous_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic_info| {
+        cleanup();
+        previous_hook(panic_info);
+    }));
+}
+
+/// Formats the v2 open orders of one side for the wallet info panel, as `price x amount` pairs.
+///
+/// Bids are the orders with `is_buy` set and asks the others.
+#[cfg(feature = "v2")]
+pub fn format_open_orders(orders: &[OpenOrderNode], is_buy: bool) -> String {
+    orders
+        .iter()
+        .filter(|order| order.is_buy == is_buy)
+        .map(|order| format!("{} x {}", order.price, order.amount))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Parses the market creation form into validated `CreateMarketArgs`.
+///
+/// Empty optional fields are left unset, and an empty time expiry means the market never
+/// expires. Unparsable or invalid values are returned as an error to show in the status bar,
+/// instead of panicking.
+#[cfg(feature = "v2")]
+fn create_market_args(app: &App) -> Result<CreateMarketArgs> {
+    fn parse<T: std::str::FromStr>(field: &str, input: &Input) -> Result<T> {
+        input
+            .value()
+            .trim()
+            .parse()
+            .map_err(|_| anyhow!("Invalid {}: {:?}", field, input.value()))
+    }
+    fn parse_optional(field: &str, input: &Input) -> Result<Option<Pubkey>> {
+        match input.value().trim() {
+            "" => Ok(None),
+            _ => parse(field, input).map(Some),
         }
     }
+    let time_expiry = match app.market_time_expiry.value().trim() {
+        "" => 0,
+        _ => parse("time expiry", &app.market_time_expiry)?,
+    };
+
+    Ok(CreateMarketArgs::builder()
+        .name(app.market_name.value().trim())
+        .base_mint(parse("base mint", &app.market_base_mint)?)
+        .quote_mint(parse("quote mint", &app.market_quote_mint)?)
+        .base_lot_size(parse("base lot size", &app.market_base_lot_size)?)
+        .quote_lot_size(parse("quote lot size", &app.market_quote_lot_size)?)
+        .maker_fee(parse("maker fee", &app.market_maker_fee)?)
+        .taker_fee(parse("taker fee", &app.market_taker_fee)?)
+        .oracle_a(parse_optional("oracle A", &app.market_oracle_a)?)
+        .oracle_b(parse_optional("oracle B", &app.market_oracle_b)?)
+        .open_orders_admin(parse_optional(
+            "open orders admin",
+            &app.market_open_orders_admin,
+        )?)
+        .collect_fee_admin(parse("collect fee admin", &app.market_collect_fee_admin)?)
+        .consume_events_admin(parse_optional(
+            "consume events admin",
+            &app.market_consume_events_admin,
+        )?)
+        .close_market_admin(parse_optional(
+            "close market admin",
+            &app.market_close_market_admin,
+        )?)
+        .time_expiry(time_expiry)
+        .build()?)
 }
 
 pub async fn run_tui(version: SdkVersion) -> Result<(), Error> {
+    install_panic_hook(|| {
+        let _ = restore_terminal();
+    });
+
     // setup terminal
     enable_raw_mode()?;
     let mut stdout = std::io::stdout();
@@ -223,7 +338,7 @@ pub async fn run_tui(version: SdkVersion) -> Result<(), Error> {
 async fn run_app<B: Backend>(
     terminal: &mut Terminal<B>,
     mut app: App,
-    version: SdkVersion,
+    mut version: SdkVersion,
 ) -> Result<(), Error> {
     loop {
         terminal.draw(|f| ui(f, &mut app, version.clone()))?;
@@ -259,25 +374,26 @@ async fn run_app<B: Backend>(
                         let market_id = app.market_id_input.value().parse()?;
 
                         if app.ob_client.is_none() {
+                            // Fall back to the version the TUI was opened on if detection fails.
+                            if let Ok(detected) = detect_version(&rpc_client, market_id).await {
+                                version = detected;
+                            }
                             match version {
                                 SdkVersion::V1 => {
-                                    let mut ob_client = OBClientV1::new(
-                                        commitment_config,
-                                        market_id,
-                                        true,
-                                        123456789,
+                                    let mut ob_client = OBClientV1::new_with_rpc(
+                                        rpc_client, owner, market_id, true, 123456789,
                                     )
                                     .await?;
-                                    ob_client.rpc_client = Rpc::new(rpc_client);
-                                    ob_client.owner = owner.into();
+                                    if !ob_client.open_orders.exists {
+                                        ob_client.init_open_orders().await?;
+                                    }
                                     app.ob_client = Some(SdkClient::OBClientV1(ob_client));
                                 }
                                 SdkVersion::V2 => {
-                                    let mut ob_client =
-                                        OBClientV2::new(commitment_config, market_id, false, true)
-                                            .await?;
-                                    ob_client.rpc_client = Rpc::new(rpc_client);
-                                    ob_client.owner = owner.into();
+                                    let ob_client = OBClientV2::new_with_rpc(
+                                        rpc_client, owner, market_id, false, true,
+                                    )
+                                    .await?;
                                     app.ob_client = Some(SdkClient::OBClientV2(ob_client));
                                 }
                             }
@@ -292,11 +408,11 @@ async fn run_app<B: Backend>(
                                     );
                                     app.market_info.insert(
                                         "Base Mint".to_string(),
-                                        ob_client.market_info.base_mint.to_string(),
+                                        display_mint(&ob_client.market_info.base_mint),
                                     );
                                     app.market_info.insert(
                                         "Quote Mint".to_string(),
-                                        ob_client.market_info.quote_mint.to_string(),
+                                        display_mint(&ob_client.market_info.quote_mint),
                                     );
                                     app.market_info.insert(
                                         "Coin Vault".to_string(),
@@ -445,7 +561,7 @@ async fn run_app<B: Backend>(
                                     );
                                     app.market_info.insert(
                                         "Quote Mint".to_string(),
-                                        ob_client.market_info.quote_mint.to_string(),
+                                        display_mint(&ob_client.market_info.quote_mint),
                                     );
                                     app.market_info.insert(
                                         "Base Vault".to_string(),
@@ -517,97 +633,42 @@ async fn run_app<B: Backend>(
                                         ob_client.ata_balances.price.to_string(),
                                     );
 
-                                    let open_asks: String = ob_client
-                                        .open_orders
-                                        .iter()
-                                        .filter(|x| x.is_buy)
-                                        .map(|x| x.price.to_string())
-                                        .collect::<Vec<String>>()
-                                        .join(", ");
-                                    app.wallet_info.insert("Open Asks".to_string(), open_asks);
-
-                                    let open_bids: String = ob_client
-                                        .open_orders
-                                        .iter()
-                                        .filter(|x| x.is_buy)
-                                        .map(|x| x.price.to_string())
-                                        .collect::<Vec<String>>()
-                                        .join(", ");
-                                    app.wallet_info.insert("Open Bids".to_string(), open_bids);
+                                    app.wallet_info.insert(
+                                        "Open Asks".to_string(),
+                                        format_open_orders(&ob_client.open_orders, false),
+                                    );
+                                    app.wallet_info.insert(
+                                        "Open Bids".to_string(),
+                                        format_open_orders(&ob_client.open_orders, true),
+                                    );
                                 }
                             },
                             SelectedTab::Tab2 => match app.ob_client.clone().unwrap() {
                                 SdkClient::OBClientV1(_ob_client) => {}
                                 SdkClient::OBClientV2(ob_client) => {
-                                    let market_args = CreateMarketArgs {
-                                        name: app.market_name.to_string(),
-                                        base_mint: app
-                                            .market_base_mint
-                                            .to_string()
-                                            .parse()
-                                            .expect("Invalid Pubkey"),
-                                        quote_mint: app
-                                            .market_quote_mint
-                                            .to_string()
-                                            .parse()
-                                            .expect("Invalid Pubkey"),
-                                        base_lot_size: app
-                                            .market_base_lot_size
-                                            .to_string()
-                                            .parse()
-                                            .expect("Invalid i64"),
-                                        quote_lot_size: app
-                                            .market_quote_lot_size
-                                            .to_string()
-                                            .parse()
-                                            .expect("Invalid i64"),
-                                        maker_fee: app
-                                            .market_maker_fee
-                                            .to_string()
-                                            .parse()
-                                            .expect("Invalid i64"),
-                                        taker_fee: app
-                                            .market_taker_fee
-                                            .to_string()
-                                            .parse()
-                                            .expect("Invalid i64"),
-                                        oracle_a: app.market_oracle_a.to_string().parse().ok(),
-                                        oracle_b: app.market_oracle_b.to_string().parse().ok(),
-                                        open_orders_admin: app
-                                            .market_open_orders_admin
-                                            .to_string()
-                                            .parse()
-                                            .ok(),
-                                        collect_fee_admin: app
-                                            .market_collect_fee_admin
-                                            .to_string()
-                                            .parse()
-                                            .expect("Invalid Pubkey"),
-                                        consume_events_admin: app
-                                            .market_consume_events_admin
-                                            .to_string()
-                                            .parse()
-                                            .ok(),
-                                        close_market_admin: app
-                                            .market_close_market_admin
-                                            .to_string()
-                                            .parse()
-                                            .ok(),
-                                        time_expiry: app
-                                            .market_time_expiry
-                                            .to_string()
-                                            .parse()
-                                            .expect("Invalid i64"),
-                                    };
-
-                                    let (_confirmed, _sig, market_id) =
-                                        ob_client.create_market(market_args).await?;
-
-                                    app.transaction_status = format!(
-                                        "Transaction successful, got market id: {:?}",
-                                        market_id
-                                    )
-                                    .to_string();
+                                    match create_market_args(&app) {
+                                        Ok(market_args) => {
+                                            app.transaction_status =
+                                                match ob_client.create_market(market_args).await {
+                                                    Ok((true, sig, created)) => format!(
+                                                        "Created market {}, signature: {:?}",
+                                                        created.market, sig
+                                                    ),
+                                                    Ok((false, _, created)) => format!(
+                                                        "Market {} creation not confirmed",
+                                                        created.market
+                                                    ),
+                                                    Err(err) => format!(
+                                                        "Failed to create the market: {}",
+                                                        err
+                                                    ),
+                                                };
+                                        }
+                                        Err(err) => {
+                                            app.transaction_status =
+                                                format!("Invalid market: {}", err);
+                                        }
+                                    }
                                 }
                             },
                             SelectedTab::Tab3 => {}
@@ -638,7 +699,123 @@ async fn run_app<B: Backend>(
                                 }
                                 SdkClient::OBClientV2(_ob_client) => {}
                             },
-                            SelectedTab::Tab5 => {}
+                            SelectedTab::Tab5 => match app.ob_client.clone().unwrap() {
+                                SdkClient::OBClientV1(_ob_client) => {
+                                    app.transaction_status =
+                                        "Swaps are only supported on OpenBook v2".to_string();
+                                }
+                                SdkClient::OBClientV2(ob_client) => {
+                                    let parsed = (
+                                        app.swap_from_mint.value().trim().parse::<Pubkey>(),
+                                        app.swap_to_mint.value().trim().parse::<Pubkey>(),
+                                        app.swap_amount.value().trim().parse::<f64>(),
+                                    );
+                                    let (from_mint, to_mint, amount) = match parsed {
+                                        (Ok(from_mint), Ok(to_mint), Ok(amount))
+                                            if amount > 0.0 =>
+                                        {
+                                            (from_mint, to_mint, amount)
+                                        }
+                                        (Err(err), _, _) | (_, Err(err), _) => {
+                                            app.transaction_status =
+                                                format!("Invalid mint: {}", err);
+                                            app.swap_quote = None;
+                                            continue;
+                                        }
+                                        _ => {
+                                            app.transaction_status = format!(
+                                                "Invalid amount: {:?}",
+                                                app.swap_amount.value()
+                                            );
+                                            app.swap_quote = None;
+                                            continue;
+                                        }
+                                    };
+                                    let base_mint = ob_client.market_info.base_mint;
+                                    let quote_mint = ob_client.market_info.quote_mint;
+
+                                    let side = if from_mint == quote_mint && to_mint == base_mint {
+                                        OBV2Side::Bid
+                                    } else if from_mint == base_mint && to_mint == quote_mint {
+                                        OBV2Side::Ask
+                                    } else {
+                                        app.transaction_status = format!(
+                                            "This market doesn't swap {} for {}",
+                                            display_mint(&from_mint),
+                                            display_mint(&to_mint)
+                                        );
+                                        app.swap_quote = None;
+                                        continue;
+                                    };
+
+                                    // The first Enter fetches a quote, the second one executes it.
+                                    let quote = match app.swap_quote.take() {
+                                        Some(quote)
+                                            if quote.from_mint == from_mint
+                                                && quote.to_mint == to_mint
+                                                && quote.amount == amount =>
+                                        {
+                                            quote
+                                        }
+                                        _ => {
+                                            let prices = (
+                                                get_base_price(&base_mint.to_string()).await,
+                                                get_base_price(&quote_mint.to_string()).await,
+                                            );
+                                            let price = match prices {
+                                                (Ok(base_price), Ok(quote_price)) => {
+                                                    base_price / quote_price
+                                                }
+                                                (Err(err), _) | (_, Err(err)) => {
+                                                    app.transaction_status =
+                                                        format!("Failed to fetch a quote: {}", err);
+                                                    continue;
+                                                }
+                                            };
+                                            // The order fills up to this price, so it bounds the
+                                            // slippage from the quoted price.
+                                            let slippage = SWAP_MAX_SLIPPAGE_PERCENT / 100.0;
+                                            let (limit_price, expected_out) = match side {
+                                                OBV2Side::Bid => {
+                                                    (price * (1.0 + slippage), amount / price)
+                                                }
+                                                OBV2Side::Ask => {
+                                                    (price * (1.0 - slippage), amount * price)
+                                                }
+                                            };
+                                            app.transaction_status = format!(
+                                                "Quote: {} {} for ~{:.6} {} at {:.6} \
+                                                 (limit {:.6}), press Enter again to swap",
+                                                amount,
+                                                display_mint(&from_mint),
+                                                expected_out,
+                                                display_mint(&to_mint),
+                                                price,
+                                                limit_price
+                                            );
+                                            app.swap_quote = Some(SwapQuote {
+                                                from_mint,
+                                                to_mint,
+                                                amount,
+                                                limit_price,
+                                            });
+                                            continue;
+                                        }
+                                    };
+
+                                    app.transaction_status = match ob_client
+                                        .place_swap_order(side, quote.amount, quote.limit_price)
+                                        .await
+                                    {
+                                        Ok((true, signature)) => format!(
+                                            "Swap successful, signature: {:?}",
+                                            signature
+                                        ),
+                                        Ok((false, _)) => "Swap not confirmed".to_string(),
+                                        Err(err) => format!("Swap failed: {}", err),
+                                    };
+                                }
+                            },
                         }
                     }
                     KeyCode::Tab => {
@@ -675,7 +852,9 @@ async fn run_app<B: Backend>(
                                     SelectedTab::Tab4 => {
                                         app.current_input = Some(CurrentInput::OBV1Side);
                                     }
-                                    SelectedTab::Tab5 => {}
+                                    SelectedTab::Tab5 => {
+                                        app.current_input = Some(CurrentInput::SwapFromMint);
+                                    }
                                 },
                                 CurrentInput::OBV1Side => match app.selected_tab {
                                     SelectedTab::Tab1 => {}
@@ -823,6 +1002,15 @@ async fn run_app<B: Backend>(
                                     SelectedTab::Tab4 => {}
                                     SelectedTab::Tab5 => {}
                                 },
+                                CurrentInput::SwapFromMint => {
+                                    app.current_input = Some(CurrentInput::SwapToMint);
+                                }
+                                CurrentInput::SwapToMint => {
+                                    app.current_input = Some(CurrentInput::SwapAmount);
+                                }
+                                CurrentInput::SwapAmount => {
+                                    app.current_input = Some(CurrentInput::RpcUrl);
+                                }
                             }
                         }
                     }
@@ -890,6 +1078,15 @@ async fn run_app<B: Backend>(
                                 CurrentInput::TimeExpiry => {
                                     app.market_time_expiry.handle_event(&Event::Key(key));
                                 }
+                                CurrentInput::SwapFromMint => {
+                                    app.swap_from_mint.handle_event(&Event::Key(key));
+                                }
+                                CurrentInput::SwapToMint => {
+                                    app.swap_to_mint.handle_event(&Event::Key(key));
+                                }
+                                CurrentInput::SwapAmount => {
+                                    app.swap_amount.handle_event(&Event::Key(key));
+                                }
                             }
                         }
                     }
@@ -1086,6 +1283,12 @@ fn ui(frame: &mut Frame, app: &mut App, version: SdkVersion) {
     let order_row_layout = Layout::new(Direction::Horizontal, [Percentage(50), Percentage(50)])
         .split(order_input_row_layout[0]);
 
+    let swap_row_layout = Layout::new(
+        Direction::Horizontal,
+        [Percentage(40), Percentage(40), Percentage(20)],
+    )
+    .split(order_input_row_layout[0]);
+
     let first_row_market =
         Layout::new(Direction::Horizontal, [Percentage(50), Percentage(50)]).split(market_rows[0]);
 
@@ -1268,6 +1471,31 @@ fn ui(frame: &mut Frame, app: &mut App, version: SdkVersion) {
                             seventh_row_market[1].y + 1,
                         );
                     }
+                    CurrentInput::SwapFromMint => {
+                        frame.set_cursor(
+                            swap_row_layout[0].x
+                                + ((app.swap_from_mint.visual_cursor()).max(scroll) - scroll)
+                                    as u16
+                                + 1,
+                            swap_row_layout[0].y + 1,
+                        );
+                    }
+                    CurrentInput::SwapToMint => {
+                        frame.set_cursor(
+                            swap_row_layout[1].x
+                                + ((app.swap_to_mint.visual_cursor()).max(scroll) - scroll) as u16
+                                + 1,
+                            swap_row_layout[1].y + 1,
+                        );
+                    }
+                    CurrentInput::SwapAmount => {
+                        frame.set_cursor(
+                            swap_row_layout[2].x
+                                + ((app.swap_amount.visual_cursor()).max(scroll) - scroll) as u16
+                                + 1,
+                            swap_row_layout[2].y + 1,
+                        );
+                    }
                 }
             }
         }
@@ -1733,9 +1961,68 @@ fn ui(frame: &mut Frame, app: &mut App, version: SdkVersion) {
                 .block(Block::default().borders(Borders::ALL).title("#️ Market ID"));
 
             frame.render_widget(market_id_input, chunks[2]);
-            let inner = Paragraph::new("Coming Soon!")
-                .block(Block::default().borders(Borders::ALL).title("Soon"));
-            frame.render_widget(inner, third_row_layout[0]);
+
+            let swap_from_mint = Paragraph::new(app.swap_from_mint.value())
+                .style(match app.input_mode {
+                    InputMode::Normal => Style::default(),
+                    InputMode::Editing => {
+                        let mut style = Style::default().fg(Color::Green);
+                        if let Some(current_input) = &app.current_input {
+                            match current_input {
+                                CurrentInput::SwapFromMint => {
+                                    style = Style::default().white().on_black();
+                                }
+                                _ => {}
+                            }
+                        }
+                        style
+                    }
+                })
+                .scroll((0, scroll as u16))
+                .block(Block::default().borders(Borders::ALL).title("📤 From Mint"));
+
+            let swap_to_mint = Paragraph::new(app.swap_to_mint.value())
+                .style(match app.input_mode {
+                    InputMode::Normal => Style::default(),
+                    InputMode::Editing => {
+                        let mut style = Style::default().fg(Color::Green);
+                        if let Some(current_input) = &app.current_input {
+                            match current_input {
+                                CurrentInput::SwapToMint => {
+                                    style = Style::default().white().on_black();
+                                }
+                                _ => {}
+                            }
+                        }
+                        style
+                    }
+                })
+                .scroll((0, scroll as u16))
+                .block(Block::default().borders(Borders::ALL).title("📥 To Mint"));
+
+            let swap_amount = Paragraph::new(app.swap_amount.value())
+                .style(match app.input_mode {
+                    InputMode::Normal => Style::default(),
+                    InputMode::Editing => {
+                        let mut style = Style::default().fg(Color::Green);
+                        if let Some(current_input) = &app.current_input {
+                            match current_input {
+                                CurrentInput::SwapAmount => {
+                                    style = Style::default().white().on_black();
+                                }
+                                _ => {}
+                            }
+                        }
+                        style
+                    }
+                })
+                .scroll((0, scroll as u16))
+                .block(Block::default().borders(Borders::ALL).title("💰 Amount"));
+
+            frame.render_widget(swap_from_mint, swap_row_layout[0]);
+            frame.render_widget(swap_to_mint, swap_row_layout[1]);
+            frame.render_widget(swap_amount, swap_row_layout[2]);
+            frame.render_widget(transaction_status, order_input_row_layout[1]);
         }
     }
 
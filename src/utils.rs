@@ -1,8 +1,218 @@
 //! This module contains utility functions related openbook.
 
-use crate::{bs58, keypair::Keypair};
-use solana_sdk::{account::Account, account_info::AccountInfo, pubkey::Pubkey};
-use std::{fs, time::SystemTime, time::UNIX_EPOCH};
+use crate::{bs58, keypair::Keypair, rpc_client::RpcClient};
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use borsh::BorshDeserialize;
+use solana_sdk::{
+    account::Account,
+    account_info::AccountInfo,
+    compute_budget::{self, ComputeBudgetInstruction},
+    instruction::Instruction,
+    message::Message,
+    packet::PACKET_DATA_SIZE,
+    pubkey::Pubkey,
+    transaction::Transaction,
+};
+use std::{
+    fs,
+    sync::{Once, RwLock},
+    time::SystemTime,
+    time::UNIX_EPOCH,
+};
+
+/// The public mainnet endpoint used when neither `RPC_URL` nor a default RPC URL is set.
+pub const MAINNET_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
+
+/// The program id of OpenBook v1, the Serum dex fork.
+pub const OPENBOOK_V1_PROGRAM_ID: &str = "srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX";
+
+/// The program id of OpenBook v2.
+pub const OPENBOOK_V2_PROGRAM_ID: &str = "opnb2LAfJYbRMAHHvqjCwQxanZn7ReEHp1k81EohpZb";
+
+/// The length of the keypair bytes: the secret key followed by the public key.
+const KEYPAIR_LENGTH: usize = 64;
+
+static DEFAULT_RPC_URL: RwLock<String> = RwLock::new(String::new());
+static WARN_DEFAULT_RPC_URL: Once = Once::new();
+
+/// The version of the OpenBook program, and of the client, used for a market.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SdkVersion {
+    /// OpenBook v1.
+    V1,
+    /// OpenBook v2.
+    V2,
+}
+
+impl SdkVersion {
+    /// Returns the version of the OpenBook program `program_id`, or `None` if it is neither.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::utils::{SdkVersion, OPENBOOK_V2_PROGRAM_ID};
+    ///
+    /// let program_id = OPENBOOK_V2_PROGRAM_ID.parse().unwrap();
+    ///
+    /// assert_eq!(SdkVersion::from_program_id(&program_id), Some(SdkVersion::V2));
+    /// ```
+    pub fn from_program_id(program_id: &Pubkey) -> Option<Self> {
+        Self::from_program_ids(
+            program_id,
+            &OPENBOOK_V1_PROGRAM_ID.parse().ok()?,
+            &OPENBOOK_V2_PROGRAM_ID.parse().ok()?,
+        )
+    }
+
+    /// Returns the version of `program_id` among the v1 and v2 deployments `v1_program_id` and
+    /// `v2_program_id`, or `None` if it is neither.
+    ///
+    /// This is `from_program_id` for deployments other than mainnet, such as a v1 fork targeted
+    /// with `OBClient::new_with_program_id`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::pubkey::Pubkey;
+    /// use openbook::utils::{SdkVersion, OPENBOOK_V2_PROGRAM_ID};
+    ///
+    /// let fork = Pubkey::new_unique();
+    /// let v2 = OPENBOOK_V2_PROGRAM_ID.parse().unwrap();
+    ///
+    /// assert_eq!(SdkVersion::from_program_ids(&fork, &fork, &v2), Some(SdkVersion::V1));
+    /// ```
+    pub fn from_program_ids(
+        program_id: &Pubkey,
+        v1_program_id: &Pubkey,
+        v2_program_id: &Pubkey,
+    ) -> Option<Self> {
+        if program_id == v1_program_id {
+            Some(Self::V1)
+        } else if program_id == v2_program_id {
+            Some(Self::V2)
+        } else {
+            None
+        }
+    }
+}
+
+/// Detects the OpenBook version of a market from the program owning its account.
+///
+/// Opening a market with the client of the wrong version fails with deserialization errors, so
+/// this lets the caller pick the right client from the market address alone.
+///
+/// # Arguments
+///
+/// * `rpc_client` - The RPC client used to fetch the market account.
+/// * `market_id` - The address of the market.
+///
+/// # Returns
+///
+/// The `SdkVersion` of the program owning the market account.
+///
+/// # Errors
+///
+/// Returns an error if the account can't be fetched, or if neither the v1 nor the v2 program
+/// owns it.
+///
+/// # Examples
+///
+/// ```rust , ignore
+/// use openbook::rpc_client::RpcClient;
+/// use openbook::utils::{detect_version, get_rpc_url};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let rpc_client = RpcClient::new(get_rpc_url());
+///
+///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+///
+///     let version = detect_version(&rpc_client, market_id).await?;
+///     println!("[*] Market {} is an OpenBook {:?} market", market_id, version);
+///
+///     Ok(())
+/// }
+/// ```
+pub async fn detect_version(rpc_client: &RpcClient, market_id: Pubkey) -> Result<SdkVersion> {
+    detect_version_with_program_ids(
+        rpc_client,
+        market_id,
+        &OPENBOOK_V1_PROGRAM_ID.parse()?,
+        &OPENBOOK_V2_PROGRAM_ID.parse()?,
+    )
+    .await
+}
+
+/// Detects the OpenBook version of a market owned by the deployments `v1_program_id` or
+/// `v2_program_id`.
+///
+/// This is `detect_version` for clients targeting another deployment than mainnet, such as a v1
+/// client built with `OBClient::new_with_program_id`.
+///
+/// # Arguments
+///
+/// * `rpc_client` - The RPC client used to fetch the market account.
+/// * `market_id` - The address of the market.
+/// * `v1_program_id` - The OpenBook v1 program.
+/// * `v2_program_id` - The OpenBook v2 program.
+///
+/// # Returns
+///
+/// The `SdkVersion` of the program owning the market account.
+///
+/// # Errors
+///
+/// Returns an error if the account can't be fetched, or if neither program owns it.
+///
+/// # Examples
+///
+/// ```rust , ignore
+/// use openbook::pubkey::Pubkey;
+/// use openbook::rpc_client::RpcClient;
+/// use openbook::utils::{detect_version_with_program_ids, get_rpc_url, OPENBOOK_V2_PROGRAM_ID};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let rpc_client = RpcClient::new(get_rpc_url());
+///
+///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+///     let v1_program_id: Pubkey = std::env::var("V1_PROGRAM_ID")?.parse()?;
+///
+///     let version = detect_version_with_program_ids(
+///         &rpc_client,
+///         market_id,
+///         &v1_program_id,
+///         &OPENBOOK_V2_PROGRAM_ID.parse()?,
+///     )
+///     .await?;
+///     println!("[*] Market {} is an OpenBook {:?} market", market_id, version);
+///
+///     Ok(())
+/// }
+/// ```
+pub async fn detect_version_with_program_ids(
+    rpc_client: &RpcClient,
+    market_id: Pubkey,
+    v1_program_id: &Pubkey,
+    v2_program_id: &Pubkey,
+) -> Result<SdkVersion> {
+    let account = rpc_client
+        .get_account(&market_id)
+        .await
+        .with_context(|| format!("Failed to fetch market {}", market_id))?;
+
+    SdkVersion::from_program_ids(&account.owner, v1_program_id, v2_program_id).ok_or_else(|| {
+        anyhow!(
+            "Market {} is owned by {}, which is neither the OpenBook v1 program {} nor the v2 \
+             program {}",
+            market_id,
+            account.owner,
+            v1_program_id,
+            v2_program_id
+        )
+    })
+}
 
 /// Converts a slice of `u64` values into a fixed-size byte array.
 ///
@@ -66,6 +276,119 @@ pub fn read_keypair(path: &String) -> Keypair {
     keypair
 }
 
+/// Reads a keypair from its 64 secret bytes, the secret key followed by the public key.
+///
+/// # Arguments
+///
+/// * `bytes` - The keypair bytes, as found in a Solana CLI keypair file.
+///
+/// # Returns
+///
+/// The keypair.
+///
+/// # Errors
+///
+/// Returns an error if `bytes` isn't 64 bytes long or doesn't hold a valid keypair. The error
+/// never includes the secret bytes.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::keypair::Keypair;
+/// use openbook::utils::read_keypair_from_bytes;
+///
+/// let keypair = Keypair::new();
+///
+/// assert_eq!(read_keypair_from_bytes(&keypair.to_bytes()).unwrap(), keypair);
+/// assert!(read_keypair_from_bytes(&[0; 32]).is_err());
+/// ```
+pub fn read_keypair_from_bytes(bytes: &[u8]) -> Result<Keypair> {
+    if bytes.len() != KEYPAIR_LENGTH {
+        return Err(anyhow!(
+            "Invalid keypair length: expected {} bytes, got {}",
+            KEYPAIR_LENGTH,
+            bytes.len()
+        ));
+    }
+
+    Keypair::from_bytes(bytes).map_err(|_| anyhow!("Invalid keypair bytes"))
+}
+
+/// Reads a keypair from its base58 encoding, as exported by most wallets.
+///
+/// # Arguments
+///
+/// * `s` - The base58 encoded keypair bytes. Surrounding whitespace is ignored.
+///
+/// # Returns
+///
+/// The keypair.
+///
+/// # Errors
+///
+/// Returns an error if `s` isn't valid base58 or doesn't decode to a valid keypair.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::keypair::Keypair;
+/// use openbook::utils::read_keypair_from_base58;
+///
+/// let keypair = Keypair::new();
+///
+/// assert_eq!(read_keypair_from_base58(&keypair.to_base58_string()).unwrap(), keypair);
+/// assert!(read_keypair_from_base58("not base58!").is_err());
+/// ```
+pub fn read_keypair_from_base58(s: &str) -> Result<Keypair> {
+    let bytes = bs58::decode(s.trim())
+        .into_vec()
+        .map_err(|_| anyhow!("Invalid base58 keypair"))?;
+
+    read_keypair_from_bytes(&bytes)
+}
+
+/// Reads the keypair of the clients from the environment.
+///
+/// The first variable set wins:
+///
+/// * `KEY_BASE58` - The base58 encoded keypair.
+/// * `KEY_BYTES` - The keypair bytes as a JSON array, e.g. `[12,34,...]`, like a keypair file.
+/// * `KEY_PATH` - The path of a keypair file, read with `read_keypair`.
+///
+/// Injecting the key through `KEY_BASE58` or `KEY_BYTES` keeps it off the disk, e.g. in
+/// containers or CI where secrets are passed as env vars.
+///
+/// # Returns
+///
+/// The keypair. Like `read_keypair`, a new random keypair is returned when none of the variables
+/// is set.
+///
+/// # Errors
+///
+/// Returns an error if `KEY_BASE58` or `KEY_BYTES` is set but malformed.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::utils::read_keypair_from_env;
+///
+/// let keypair = read_keypair_from_env();
+/// ```
+pub fn read_keypair_from_env() -> Result<Keypair> {
+    if let Ok(key) = std::env::var("KEY_BASE58") {
+        return read_keypair_from_base58(&key).context("Failed to read KEY_BASE58");
+    }
+
+    if let Ok(key) = std::env::var("KEY_BYTES") {
+        let bytes: Vec<u8> = serde_json::from_str(key.trim())
+            .map_err(|_| anyhow!("Failed to read KEY_BYTES: expected a JSON array of bytes"))?;
+        return read_keypair_from_bytes(&bytes).context("Failed to read KEY_BYTES");
+    }
+
+    let key_path = std::env::var("KEY_PATH").unwrap_or_default();
+    Ok(read_keypair(&key_path))
+}
+
 /// Gets the current UNIX timestamp in seconds.
 ///
 /// # Returns
@@ -86,6 +409,58 @@ pub fn get_unix_secs() -> u64 {
         .as_secs()
 }
 
+/// Returns the premium of `perp_mid` over `spot_mid`, in basis points.
+///
+/// This is `(perp_mid - spot_mid) / spot_mid * 10_000`: positive when the perp trades at a
+/// premium to spot, negative when it trades at a discount. Hedgers can read it as the basis
+/// implied by two venues' mids. A non-positive `spot_mid` has no meaningful premium and yields
+/// `NaN`.
+///
+/// # Arguments
+///
+/// * `spot_mid` - The mid price of the reference (spot) market.
+/// * `perp_mid` - The mid price of the other (perp) market.
+///
+/// # Returns
+///
+/// The premium in basis points.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::utils::premium_bps;
+///
+/// assert!((premium_bps(100.0, 100.5) - 50.0).abs() < 1e-9);
+/// assert!((premium_bps(100.0, 99.0) + 100.0).abs() < 1e-9);
+/// ```
+pub fn premium_bps(spot_mid: f64, perp_mid: f64) -> f64 {
+    if spot_mid <= 0. {
+        return f64::NAN;
+    }
+    (perp_mid - spot_mid) / spot_mid * 10_000.
+}
+
+/// Serializes a `Pubkey` as a base58 string instead of a byte array.
+///
+/// Use it with `#[serde(with = "crate::utils::serde_pubkey")]` on `Pubkey` fields, so JSON
+/// snapshots stay readable and can be edited by hand.
+pub mod serde_pubkey {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use solana_sdk::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    /// Serializes `pubkey` as its base58 string.
+    pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(pubkey)
+    }
+
+    /// Deserializes a `Pubkey` from its base58 string.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let pubkey = String::deserialize(deserializer)?;
+        Pubkey::from_str(&pubkey).map_err(|e| D::Error::custom(format!("{}: {}", pubkey, e)))
+    }
+}
+
 /// Creates an `AccountInfo` instance from an `Account`.
 ///
 /// # Arguments
@@ -152,3 +527,293 @@ pub fn create_account_info_from_account<'a>(
         account.rent_epoch,
     )
 }
+
+/// Reads the raw token amount from the data of an SPL token account.
+///
+/// # Arguments
+///
+/// * `data` - The data of the token account.
+///
+/// # Returns
+///
+/// The amount held by the token account in native units, or `None` if the data is too short
+/// to be a token account.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::utils::token_account_amount;
+///
+/// let mut data = vec![0u8; 165];
+/// data[64..72].copy_from_slice(&42u64.to_le_bytes());
+///
+/// assert_eq!(token_account_amount(&data), Some(42));
+/// ```
+pub fn token_account_amount(data: &[u8]) -> Option<u64> {
+    // mint (32) + owner (32) precede the amount.
+    let bytes = data.get(64..72)?;
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// Sets the RPC URL used by the clients when the `RPC_URL` env var is unset.
+///
+/// # Arguments
+///
+/// * `url` - The RPC URL to fall back to (e.g., a devnet endpoint).
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::utils::set_default_rpc_url;
+///
+/// set_default_rpc_url("https://api.devnet.solana.com");
+/// ```
+pub fn set_default_rpc_url(url: &str) {
+    *DEFAULT_RPC_URL.write().unwrap() = url.to_string();
+}
+
+/// Gets the RPC URL from the `RPC_URL` env var, falling back to the default RPC URL.
+///
+/// The default is `MAINNET_RPC_URL` unless changed with `set_default_rpc_url`. The public mainnet
+/// endpoint rate-limits heavily, so falling back to it logs a warning, once per process.
+///
+/// # Returns
+///
+/// The RPC URL to connect to.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::utils::get_rpc_url;
+///
+/// let rpc_url = get_rpc_url();
+/// ```
+pub fn get_rpc_url() -> String {
+    if let Ok(rpc_url) = std::env::var("RPC_URL") {
+        return rpc_url;
+    }
+
+    let default_rpc_url = DEFAULT_RPC_URL.read().unwrap().clone();
+    if !default_rpc_url.is_empty() {
+        return default_rpc_url;
+    }
+
+    WARN_DEFAULT_RPC_URL.call_once(|| {
+        tracing::warn!(
+            "RPC_URL is not set, using the public mainnet endpoint {}",
+            MAINNET_RPC_URL
+        );
+    });
+
+    MAINNET_RPC_URL.to_string()
+}
+
+/// Derives the websocket URL of an RPC node from its HTTP URL.
+///
+/// `http` becomes `ws` and `https` becomes `wss`; URLs that already use a websocket scheme are
+/// returned as is. Nodes serving the websocket on another port (e.g., a local validator on 8900)
+/// need the `WS_URL` env var instead.
+///
+/// # Arguments
+///
+/// * `rpc_url` - The HTTP URL of the RPC node.
+///
+/// # Returns
+///
+/// The websocket URL of the RPC node.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::utils::websocket_url;
+///
+/// assert_eq!(
+///     websocket_url("https://api.mainnet-beta.solana.com"),
+///     "wss://api.mainnet-beta.solana.com"
+/// );
+/// ```
+pub fn websocket_url(rpc_url: &str) -> String {
+    if let Some(rest) = rpc_url.strip_prefix("https://") {
+        format!("wss://{rest}")
+    } else if let Some(rest) = rpc_url.strip_prefix("http://") {
+        format!("ws://{rest}")
+    } else {
+        rpc_url.to_string()
+    }
+}
+
+/// Gets the websocket URL from the `WS_URL` env var, falling back to the one derived from
+/// `rpc_url` by `websocket_url`.
+pub fn get_ws_url(rpc_url: &str) -> String {
+    std::env::var("WS_URL").unwrap_or_else(|_| websocket_url(rpc_url))
+}
+
+/// Prepends compute budget instructions to `instructions`, unless they are already present.
+///
+/// A transaction only honors one compute unit limit and one compute unit price, so each is
+/// prepended only when `instructions` doesn't already set it.
+///
+/// # Arguments
+///
+/// * `instructions` - The instructions of the transaction, possibly with their own budget.
+/// * `unit_limit` - The compute unit limit to prepend.
+/// * `unit_price` - The compute unit price to prepend, in micro-lamports.
+///
+/// # Returns
+///
+/// The instructions, with a single compute unit limit and compute unit price.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::utils::prepend_compute_budget;
+/// use solana_sdk::compute_budget::ComputeBudgetInstruction;
+///
+/// let instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(200_000)];
+///
+/// let instructions = prepend_compute_budget(instructions, 800_000, 1);
+///
+/// assert_eq!(instructions.len(), 2);
+/// ```
+pub fn prepend_compute_budget(
+    instructions: Vec<Instruction>,
+    unit_limit: u32,
+    unit_price: u64,
+) -> Vec<Instruction> {
+    prepend_compute_budget_parts(instructions, Some(unit_limit), Some(unit_price))
+}
+
+/// Like `prepend_compute_budget`, but only prepends the limit and price that are set.
+pub(crate) fn prepend_compute_budget_parts(
+    instructions: Vec<Instruction>,
+    unit_limit: Option<u32>,
+    unit_price: Option<u64>,
+) -> Vec<Instruction> {
+    let budget: Vec<ComputeBudgetInstruction> = instructions
+        .iter()
+        .filter(|ix| ix.program_id == compute_budget::id())
+        .filter_map(|ix| ComputeBudgetInstruction::try_from_slice(&ix.data).ok())
+        .collect();
+    let has_limit = budget
+        .iter()
+        .any(|ix| matches!(ix, ComputeBudgetInstruction::SetComputeUnitLimit(_)));
+    let has_price = budget
+        .iter()
+        .any(|ix| matches!(ix, ComputeBudgetInstruction::SetComputeUnitPrice(_)));
+
+    let mut prepended = Vec::with_capacity(instructions.len() + 2);
+    if let Some(unit_limit) = unit_limit.filter(|_| !has_limit) {
+        prepended.push(ComputeBudgetInstruction::set_compute_unit_limit(unit_limit));
+    }
+    if let Some(unit_price) = unit_price.filter(|_| !has_price) {
+        prepended.push(ComputeBudgetInstruction::set_compute_unit_price(unit_price));
+    }
+    prepended.extend(instructions);
+    prepended
+}
+
+/// The default maximum number of instructions packed in one transaction by `pack_instructions`.
+///
+/// It keeps a batch of order instructions, plus the two compute budget instructions prepended
+/// when sending, within the compute limit of a transaction.
+pub const DEFAULT_MAX_IX_PER_TX: usize = 10;
+
+/// Splits instructions into batches that each fit in a single transaction.
+///
+/// Instructions keep their order. A batch is closed once it holds `max_ix_per_tx` instructions,
+/// or as soon as the next instruction would push the signed transaction past `PACKET_DATA_SIZE`
+/// (1232 bytes). An instruction too large to fit on its own is returned in a batch of its own.
+///
+/// # Arguments
+///
+/// * `instructions` - The instructions to split.
+/// * `payer` - The fee payer and signer of the transactions.
+/// * `max_ix_per_tx` - The maximum number of instructions per batch, usually
+///   `DEFAULT_MAX_IX_PER_TX`. A value of 0 is treated as 1.
+///
+/// # Returns
+///
+/// The batches of instructions, one per transaction.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::pubkey::Pubkey;
+/// use openbook::utils::{pack_instructions, DEFAULT_MAX_IX_PER_TX};
+/// use solana_sdk::instruction::Instruction;
+///
+/// let program_id = Pubkey::new_unique();
+/// let instructions = vec![Instruction::new_with_bytes(program_id, &[0; 400], vec![]); 3];
+///
+/// let batches = pack_instructions(instructions, &Pubkey::new_unique(), DEFAULT_MAX_IX_PER_TX);
+///
+/// assert_eq!(batches.len(), 2);
+/// ```
+pub fn pack_instructions(
+    instructions: Vec<Instruction>,
+    payer: &Pubkey,
+    max_ix_per_tx: usize,
+) -> Vec<Vec<Instruction>> {
+    let max_ix_per_tx = max_ix_per_tx.max(1);
+    let mut batches: Vec<Vec<Instruction>> = Vec::new();
+    let mut batch: Vec<Instruction> = Vec::new();
+
+    for ix in instructions {
+        if batch.len() == max_ix_per_tx {
+            batches.push(std::mem::take(&mut batch));
+        }
+        batch.push(ix);
+        if batch.len() > 1 && transaction_size(&batch, payer) > PACKET_DATA_SIZE {
+            let ix = batch.pop().unwrap();
+            batches.push(std::mem::replace(&mut batch, vec![ix]));
+        }
+    }
+
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+
+    batches
+}
+
+/// Returns the size in bytes of a signed transaction holding `instructions`.
+fn transaction_size(instructions: &[Instruction], payer: &Pubkey) -> usize {
+    let message = Message::new(instructions, Some(payer));
+    let signatures = message.header.num_required_signatures as usize;
+
+    // The compact-u16 signature count takes a single byte below 128 signatures.
+    1 + signatures * 64 + message.serialize().len()
+}
+
+/// Serializes a transaction to base64, the wire format accepted by `sendTransaction` and most
+/// wallets.
+///
+/// # Arguments
+///
+/// * `transaction` - The transaction to serialize, signed or not.
+///
+/// # Returns
+///
+/// The base64 encoding of the bincode serialized transaction.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::pubkey::Pubkey;
+/// use openbook::utils::{decode_transaction, encode_transaction};
+/// use solana_sdk::{message::Message, transaction::Transaction};
+///
+/// let transaction = Transaction::new_unsigned(Message::new(&[], Some(&Pubkey::new_unique())));
+///
+/// let encoded = encode_transaction(&transaction).unwrap();
+///
+/// assert_eq!(decode_transaction(&encoded).unwrap(), transaction);
+/// ```
+pub fn encode_transaction(transaction: &Transaction) -> Result<String> {
+    Ok(STANDARD.encode(bincode::serialize(transaction)?))
+}
+
+/// Deserializes a transaction encoded by `encode_transaction`.
+pub fn decode_transaction(encoded: &str) -> Result<Transaction> {
+    Ok(bincode::deserialize(&STANDARD.decode(encoded.trim())?)?)
+}
@@ -1,3 +1,4 @@
+pub mod events;
 pub mod market;
 pub mod ob_client;
 pub mod orders;
@@ -0,0 +1,412 @@
+//! This module contains a decoder for the event queue of an OpenBook v1 market.
+
+use crate::v1::{
+    market::Market,
+    orders::{read_u128, read_u64},
+};
+use anyhow::{anyhow, Result};
+use openbook_dex::matching::Side;
+use solana_sdk::pubkey::Pubkey;
+
+/// The size of the "serum" head padding and the event queue header.
+const EVENT_QUEUE_HEAD_SIZE: usize = 5 + 32;
+
+/// The size of the "padding" tail of the event queue account.
+const EVENT_QUEUE_TAIL_SIZE: usize = 7;
+
+/// The size of an event in the event queue.
+pub const EVENT_SIZE: usize = 88;
+
+/// Set on fill events.
+pub const EVENT_FLAG_FILL: u8 = 0x1;
+/// Set on out events, emitted when an order leaves the book.
+pub const EVENT_FLAG_OUT: u8 = 0x2;
+/// Set on events of bids.
+pub const EVENT_FLAG_BID: u8 = 0x4;
+/// Set on events of the maker side of a fill.
+pub const EVENT_FLAG_MAKER: u8 = 0x8;
+
+/// The maximum number of open orders accounts passed to a single consume events instruction.
+pub const MAX_CONSUME_EVENTS_ACCOUNTS: usize = 10;
+
+/// An event of the event queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Event {
+    /// The `EVENT_FLAG_*` bits of the event.
+    pub flags: u8,
+    /// The slot of the order in the owner's open orders account.
+    pub owner_slot: u8,
+    /// Tokens (native units) released to the owner.
+    pub native_qty_released: u64,
+    /// Tokens (native units) paid by the owner.
+    pub native_qty_paid: u64,
+    /// Quote tokens (native units) paid as fee by a taker, or earned as rebate by a maker.
+    pub native_fee_or_rebate: u64,
+    /// The id of the order.
+    pub order_id: u128,
+    /// The open orders account of the order.
+    pub owner: Pubkey,
+    /// The client order id of the order.
+    pub client_order_id: u64,
+}
+
+impl Event {
+    /// Decodes an event from its 88 bytes.
+    fn from_bytes(data: &[u8]) -> Result<Self> {
+        Ok(Self {
+            flags: data[0],
+            owner_slot: data[1],
+            // fee_tier (1) and 5 bytes of padding precede the quantities.
+            native_qty_released: read_u64(data, 8),
+            native_qty_paid: read_u64(data, 16),
+            native_fee_or_rebate: read_u64(data, 24),
+            order_id: read_u128(data, 32),
+            owner: Pubkey::try_from(&data[48..80])?,
+            client_order_id: read_u64(data, 80),
+        })
+    }
+
+    /// Returns whether the event is a fill.
+    pub fn is_fill(&self) -> bool {
+        self.flags & EVENT_FLAG_FILL != 0
+    }
+
+    /// Returns whether the event is the maker side of a fill.
+    pub fn is_maker_fill(&self) -> bool {
+        self.is_fill() && self.flags & EVENT_FLAG_MAKER != 0
+    }
+
+    /// Returns the side of the order of the event.
+    pub fn side(&self) -> Side {
+        if self.flags & EVENT_FLAG_BID != 0 {
+            Side::Bid
+        } else {
+            Side::Ask
+        }
+    }
+
+    /// Converts a fill event into a `FillEvent` with UI price and quantity.
+    ///
+    /// The price is computed from the traded quantities before fees, so a taker gets its actual
+    /// execution price rather than the limit price of its order.
+    ///
+    /// # Arguments
+    ///
+    /// * `market` - The market of the event queue, for the token decimals.
+    ///
+    /// # Returns
+    ///
+    /// The fill, or `None` if the event isn't a fill or traded no base tokens.
+    pub fn to_fill(&self, market: &Market) -> Option<FillEvent> {
+        if !self.is_fill() {
+            return None;
+        }
+
+        let maker = self.is_maker_fill();
+        let side = self.side();
+        // Bids receive base and pay quote, asks the opposite.
+        let (native_base, native_quote) = match side {
+            Side::Bid => (self.native_qty_released, self.native_qty_paid),
+            Side::Ask => (self.native_qty_paid, self.native_qty_released),
+        };
+        if native_base == 0 {
+            return None;
+        }
+        // Makers earn a rebate and takers pay a fee on top of the traded quote.
+        let quote_before_fees = match (side, maker) {
+            (Side::Bid, true) | (Side::Ask, false) => native_quote + self.native_fee_or_rebate,
+            (Side::Bid, false) | (Side::Ask, true) => {
+                native_quote.saturating_sub(self.native_fee_or_rebate)
+            }
+        };
+
+        let base_factor = 10u64.pow(market.coin_decimals as u32) as f64;
+        let quote_factor = 10u64.pow(market.pc_decimals as u32) as f64;
+        let quantity = native_base as f64 / base_factor;
+
+        Some(FillEvent {
+            side,
+            price: quote_before_fees as f64 / quote_factor / quantity,
+            quantity,
+            owner: self.owner,
+            order_id: self.order_id,
+            client_order_id: self.client_order_id,
+            maker,
+        })
+    }
+}
+
+/// A fill of the event queue, in UI units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FillEvent {
+    /// The side of the filled order.
+    pub side: Side,
+    /// The execution price, in quote per base, before fees.
+    pub price: f64,
+    /// The filled quantity, in base.
+    pub quantity: f64,
+    /// The open orders account of the filled order.
+    pub owner: Pubkey,
+    /// The id of the filled order.
+    pub order_id: u128,
+    /// The client order id of the filled order.
+    pub client_order_id: u64,
+    /// Whether the order was resting on the book (maker) or took liquidity (taker).
+    pub maker: bool,
+}
+
+/// The events of an event queue, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventQueue {
+    /// The number of events ever pushed to the queue.
+    pub seq_num: u64,
+    /// The events not consumed yet, oldest first.
+    pub events: Vec<Event>,
+}
+
+impl EventQueue {
+    /// Decodes an event queue from its raw account data.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The data of the event queue account, as returned by the RPC.
+    ///
+    /// # Returns
+    ///
+    /// The decoded `EventQueue`, with the unconsumed events in the order they were pushed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is too short for an event queue or if the header doesn't
+    /// match the size of the ring buffer.
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        let buffer_size = data
+            .len()
+            .checked_sub(EVENT_QUEUE_HEAD_SIZE + EVENT_QUEUE_TAIL_SIZE)
+            .ok_or_else(|| anyhow!("Invalid event queue account size: {}", data.len()))?;
+        let capacity = buffer_size / EVENT_SIZE;
+
+        // Skip the 5 bytes "serum" head padding and the 8 bytes account flags.
+        let head = read_u64(data, 13) as usize;
+        let count = read_u64(data, 21) as usize;
+        let seq_num = read_u64(data, 29);
+
+        if capacity == 0 || head >= capacity || count > capacity {
+            return Err(anyhow!(
+                "Invalid event queue header: head {}, count {} (capacity {})",
+                head,
+                count,
+                capacity
+            ));
+        }
+
+        let events = (0..count)
+            .map(|i| {
+                let offset = EVENT_QUEUE_HEAD_SIZE + (head + i) % capacity * EVENT_SIZE;
+                Event::from_bytes(&data[offset..offset + EVENT_SIZE])
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { seq_num, events })
+    }
+
+    /// Returns the events pushed since the queue was at `seq_num`, oldest first.
+    ///
+    /// Events already consumed by a crank are no longer in the queue, so fewer events than were
+    /// pushed may be returned.
+    pub fn events_since(&self, seq_num: u64) -> &[Event] {
+        let pushed = self.seq_num.saturating_sub(seq_num) as usize;
+        &self.events[self.events.len().saturating_sub(pushed)..]
+    }
+
+    /// Returns the number of matches among the events pushed since the queue was at `seq_num`.
+    ///
+    /// Each match pushes a fill for the maker and one for the taker, so the maker fills are
+    /// counted. With `owner` set, only the matches of that open orders account are counted,
+    /// from its fills as maker or taker, leaving out the matches of other traders pushed in the
+    /// meantime.
+    ///
+    /// # Arguments
+    ///
+    /// * `seq_num` - The sequence number of the queue before the events to count.
+    /// * `owner` - If set, only the matches of this open orders account are counted.
+    pub fn matches_since(&self, seq_num: u64, owner: Option<&Pubkey>) -> usize {
+        self.events_since(seq_num)
+            .iter()
+            .filter(|event| match owner {
+                Some(owner) => event.is_fill() && event.owner == *owner,
+                None => event.is_maker_fill(),
+            })
+            .count()
+    }
+
+    /// Returns the fills of the queue, oldest first, skipping the other events.
+    ///
+    /// # Arguments
+    ///
+    /// * `market` - The market of the event queue, for the token decimals.
+    /// * `owner` - If set, only the fills of this open orders account are returned.
+    pub fn fills(&self, market: &Market, owner: Option<&Pubkey>) -> Vec<FillEvent> {
+        self.events
+            .iter()
+            .filter(|event| owner.map_or(true, |owner| event.owner == *owner))
+            .filter_map(|event| event.to_fill(market))
+            .collect()
+    }
+
+    /// Returns the open orders accounts needed to consume the oldest events of the queue.
+    ///
+    /// The owners of up to `max_events` events are collected, stopping before an event whose owner
+    /// would exceed `max_accounts`. The accounts are deduplicated and sorted the way the program
+    /// looks them up: by their key read as four little-endian `u64` words.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_events` - The maximum number of events to consume.
+    /// * `max_accounts` - The maximum number of accounts to return.
+    pub fn open_orders_accounts(&self, max_events: usize, max_accounts: usize) -> Vec<Pubkey> {
+        let mut accounts: Vec<Pubkey> = Vec::new();
+        for event in self.events.iter().take(max_events) {
+            if accounts.contains(&event.owner) {
+                continue;
+            }
+            if accounts.len() == max_accounts {
+                break;
+            }
+            accounts.push(event.owner);
+        }
+
+        accounts.sort_by_key(|key| {
+            let bytes = key.to_bytes();
+            [0, 8, 16, 24].map(|offset| read_u64(&bytes, offset))
+        });
+        accounts
+    }
+}
+
+/// The status of an order watched with `ReadOnlyClient::watch_order`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrderStatus {
+    /// The order left the book after filling, in base, at the quantity-weighted price.
+    Filled {
+        /// The filled quantity, in base.
+        qty: f64,
+        /// The average execution price, in quote per base, before fees.
+        avg_price: f64,
+    },
+    /// The order was still open when the watch timed out, after filling `qty` base.
+    PartiallyFilled {
+        /// The filled quantity, in base.
+        qty: f64,
+    },
+    /// The order left the book with its remaining quantity unlocked.
+    Cancelled,
+    /// The order was still open and unfilled when the watch timed out.
+    Timeout,
+}
+
+/// Follows an order across snapshots of the event queue and of its open orders account.
+///
+/// The fills of the order are summed as they appear in the event queue, each event being
+/// counted once thanks to the queue sequence number. Fills consumed by a crank between two
+/// snapshots are missed, so observe the queue more often than it is cranked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderTracker {
+    /// The id of the order.
+    pub order_id: u128,
+    /// The open orders account of the order.
+    pub owner: Pubkey,
+    /// The sequence number of the queue at the last snapshot.
+    seq_num: Option<u64>,
+    filled_qty: f64,
+    filled_quote: f64,
+}
+
+impl OrderTracker {
+    /// Returns a tracker of `order_id`, placed by the open orders account `owner`.
+    pub fn new(order_id: u128, owner: Pubkey) -> Self {
+        Self {
+            order_id,
+            owner,
+            seq_num: None,
+            filled_qty: 0.0,
+            filled_quote: 0.0,
+        }
+    }
+
+    /// Returns the base quantity filled so far.
+    pub fn filled_qty(&self) -> f64 {
+        self.filled_qty
+    }
+
+    /// Records the events pushed since the last snapshot and tells whether the order is done.
+    ///
+    /// The first snapshot records every event still in the queue, so fills that happened
+    /// between placing the order and watching it are counted.
+    ///
+    /// # Arguments
+    ///
+    /// * `queue` - The event queue of the market.
+    /// * `market` - The market of the event queue, for the token decimals.
+    /// * `open` - Whether the order is still in its open orders account.
+    ///
+    /// # Returns
+    ///
+    /// The final status of the order, or `None` while it may still fill.
+    pub fn observe(
+        &mut self,
+        queue: &EventQueue,
+        market: &Market,
+        open: bool,
+    ) -> Option<OrderStatus> {
+        let events = match self.seq_num {
+            Some(seq_num) => queue.events_since(seq_num),
+            None => &queue.events[..],
+        };
+        self.seq_num = Some(queue.seq_num);
+
+        let mut out = None;
+        for event in events
+            .iter()
+            .filter(|event| event.order_id == self.order_id && event.owner == self.owner)
+        {
+            if let Some(fill) = event.to_fill(market) {
+                self.filled_qty += fill.quantity;
+                self.filled_quote += fill.quantity * fill.price;
+            } else if event.flags & EVENT_FLAG_OUT != 0 {
+                // A canceled order unlocks its remaining funds, a filled one has none left.
+                out = Some(event.native_qty_released > 0);
+            }
+        }
+
+        match out {
+            Some(true) => Some(OrderStatus::Cancelled),
+            Some(false) => Some(self.closed_status()),
+            None if !open => Some(self.closed_status()),
+            None => None,
+        }
+    }
+
+    /// Returns the status of the order when the watch times out before it is done.
+    pub fn timeout_status(&self) -> OrderStatus {
+        if self.filled_qty > 0.0 {
+            OrderStatus::PartiallyFilled {
+                qty: self.filled_qty,
+            }
+        } else {
+            OrderStatus::Timeout
+        }
+    }
+
+    /// Returns the status of an order that left the book.
+    fn closed_status(&self) -> OrderStatus {
+        if self.filled_qty > 0.0 {
+            OrderStatus::Filled {
+                qty: self.filled_qty,
+                avg_price: self.filled_quote / self.filled_qty,
+            }
+        } else {
+            OrderStatus::Cancelled
+        }
+    }
+}
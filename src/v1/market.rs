@@ -1,26 +1,67 @@
 //! This module contains structs and functions related to the openbook market.
 use crate::{
+    error::OpenBookError,
+    orderbook::OrderBook,
+    registry::MarketRegistry,
     rpc::Rpc,
-    utils::{create_account_info_from_account, u64_slice_to_pubkey},
-    v1::traits::MarketInfo,
+    utils::{create_account_info_from_account, serde_pubkey, u64_slice_to_pubkey},
+    v1::{ob_client::SRM_PROGRAM_ID, orders::read_u64, traits::MarketInfo},
 };
-use anyhow::{Error, Result};
+use anyhow::{anyhow, Error, Result};
 use borsh::{BorshDeserialize, BorshSerialize};
-use openbook_dex::state::{gen_vault_signer_key, MarketState};
+use openbook_dex::critbit::{AnyNode, LeafNode, Slab, SlabView};
+use openbook_dex::fees::FeeTier;
+use openbook_dex::matching::Side;
+use openbook_dex::state::{gen_vault_signer_key, Market as MarketAuth, MarketState};
+use serde::{Deserialize, Serialize};
+use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::rpc_config::RpcAccountInfoConfig;
 use solana_sdk::{
+    account::Account,
     pubkey::Pubkey,
     sysvar::slot_history::{AccountInfo, ProgramError},
 };
 
+use std::cmp::Reverse;
 use std::fmt::{Debug, Formatter};
 
+/// The number of nonces tried when deriving the vault signer key of a market.
+pub const MAX_VAULT_SIGNER_NONCE: u64 = 100;
+
+/// Size of a request or event queue header: the 5 bytes "serum" padding, then the account
+/// flags, head, count and sequence number.
+pub const QUEUE_HEADER_SIZE: usize = 37;
+
+/// Errors raised while initializing a market from on-chain data.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MarketError {
+    /// No nonce below `MAX_VAULT_SIGNER_NONCE` yields a valid vault signer key.
+    #[error("Failed to derive the vault signer key of market {market} after {attempts} nonces")]
+    VaultSignerDerivationFailed {
+        /// The address of the market.
+        market: Pubkey,
+        /// The number of nonces tried.
+        attempts: u64,
+    },
+    /// The market state was loaded from an account belonging to another market.
+    #[error("Market state belongs to {found} instead of {expected}")]
+    MarketAddressMismatch {
+        /// The address the market was loaded from.
+        expected: Pubkey,
+        /// The address stored in the market state.
+        found: Pubkey,
+    },
+}
+
 /// Struct representing a market with associated state and information.
-#[derive(Clone, Default, BorshSerialize, BorshDeserialize)]
+#[derive(Clone, Default, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
 pub struct Market {
     /// The public key of the program associated with the market.
+    #[serde(with = "serde_pubkey")]
     pub program_id: Pubkey,
 
     /// The public key of the market.
+    #[serde(with = "serde_pubkey")]
     pub market_address: Pubkey,
 
     /// The number of decimal places for the base currency (coin) in the market.
@@ -39,34 +80,53 @@ pub struct Market {
     pub pc_lot_size: u64,
 
     /// The public key of the market quote mint.
+    #[serde(with = "serde_pubkey")]
     pub quote_mint: Pubkey,
 
     /// The public key of the market base mint.
+    #[serde(with = "serde_pubkey")]
     pub base_mint: Pubkey,
 
     /// The public key of the vault holding base currency (coin) tokens.
+    #[serde(with = "serde_pubkey")]
     pub coin_vault: Pubkey,
 
     /// The public key of the vault holding quote currency (pc) tokens.
+    #[serde(with = "serde_pubkey")]
     pub pc_vault: Pubkey,
 
     /// The public key of the vault signer key associated with the market.
+    #[serde(with = "serde_pubkey")]
     pub vault_signer_key: Pubkey,
 
     /// The public key of the event queue associated with the market.
+    #[serde(with = "serde_pubkey")]
     pub event_queue: Pubkey,
 
     /// The public key of the request queue associated with the market.
+    #[serde(with = "serde_pubkey")]
     pub request_queue: Pubkey,
 
     /// The public key of the bids associated with the market.
+    #[serde(with = "serde_pubkey")]
     pub bids_address: Pubkey,
 
     /// The public key of the asks associated with the market.
+    #[serde(with = "serde_pubkey")]
     pub asks_address: Pubkey,
 
     /// The public key of the events authority used for consume transactions.
+    #[serde(with = "serde_pubkey")]
     pub events_authority: Pubkey,
+
+    /// Change counter of the book, as of the last `load_seq_num`.
+    #[serde(default)]
+    pub seq_num: u64,
+
+    /// The taker fee of the market's base fee tier, in basis points. Markets saved without it
+    /// load with no fee.
+    #[serde(default)]
+    pub taker_fee_bps: f64,
 }
 
 impl Debug for Market {
@@ -89,6 +149,8 @@ impl Debug for Market {
         writeln!(f, "        bids_address: {:?}", self.bids_address)?;
         writeln!(f, "        asks_address: {:?}", self.asks_address)?;
         writeln!(f, "        events_authority: {:?}", self.events_authority)?;
+        writeln!(f, "        seq_num: {:?}", self.seq_num)?;
+        writeln!(f, "        taker_fee_bps: {:?}", self.taker_fee_bps)?;
         writeln!(f, "    }}")
     }
 }
@@ -192,6 +254,8 @@ impl MarketInfo for Market {
             event_queue: Default::default(),
             request_queue: Default::default(),
             account_flags: 0,
+            seq_num: 0,
+            taker_fee_bps: 0.0,
         };
 
         if load {
@@ -217,7 +281,8 @@ impl MarketInfo for Market {
     /// # Errors
     ///
     /// This function returns an error if loading the market fails for any reason,
-    /// such as the absence of the market account or permission issues.
+    /// such as permission issues, and `OpenBookError::MarketNotFound` if the market account
+    /// doesn't exist.
     ///
     /// # Examples
     ///
@@ -261,7 +326,12 @@ impl MarketInfo for Market {
     /// }
     /// ```
     async fn load(&mut self, rpc_client: &Rpc) -> Result<(), Error> {
-        let mut account = rpc_client.inner().get_account(&self.market_address).await?;
+        let mut account = rpc_client
+            .fetch_multiple_accounts(&[self.market_address])
+            .await?
+            .pop()
+            .flatten()
+            .ok_or(OpenBookError::MarketNotFound(self.market_address))?;
         let owner = account.owner;
         let program_id_binding = self.program_id;
         let market_account_binding = self.market_address;
@@ -297,7 +367,8 @@ impl MarketInfo for Market {
     /// # Errors
     ///
     /// This function returns an error if loading the market state fails for any reason,
-    /// such as invalid account data or parsing issues.
+    /// such as invalid account data or parsing issues, and `MarketError::MarketAddressMismatch`
+    /// if the state belongs to another market.
     ///
     /// # Examples
     ///
@@ -379,12 +450,23 @@ impl MarketInfo for Market {
         self.asks_address = Pubkey::new_from_array(asks_array);
 
         let own_address = Pubkey::new_from_array(own_address_array);
-        assert_eq!(self.market_address, own_address);
+        if self.market_address != own_address {
+            return Err(MarketError::MarketAddressMismatch {
+                expected: self.market_address,
+                found: own_address,
+            }
+            .into());
+        }
 
         self.account_flags = market_state.account_flags;
         self.coin_lot_size = market_state.coin_lot_size;
         self.pc_lot_size = market_state.pc_lot_size;
 
+        // Orders are placed without a fee discount account, so they pay the base tier, or the
+        // stable tier on stable markets. The fee on a million native units gives its rate.
+        let fee_tier = FeeTier::from_srm_and_msrm_balances(&self.market_address, 0, 0);
+        self.taker_fee_bps = fee_tier.taker_fee(1_000_000) as f64 / 100.0;
+
         Ok(())
     }
 
@@ -396,8 +478,8 @@ impl MarketInfo for Market {
     ///
     /// # Errors
     ///
-    /// This function returns an error if generating the vault signer key fails,
-    /// such as reaching the maximum number of attempts or encountering cryptographic errors.
+    /// This function returns `MarketError::VaultSignerDerivationFailed` if no nonce below
+    /// `MAX_VAULT_SIGNER_NONCE` yields a valid vault signer key.
     ///
     /// # Examples
     ///
@@ -439,12 +521,507 @@ impl MarketInfo for Market {
     /// }
     /// ```
     async fn init_vault_signer_key(&mut self) -> Result<(), Error> {
-        for i in 0..100 {
-            if let Ok(pk) = gen_vault_signer_key(i, &self.market_address, &self.program_id) {
+        for nonce in 0..MAX_VAULT_SIGNER_NONCE {
+            if let Ok(pk) = gen_vault_signer_key(nonce, &self.market_address, &self.program_id) {
                 self.vault_signer_key = pk;
                 return Ok(());
             }
         }
-        Ok(())
+        Err(MarketError::VaultSignerDerivationFailed {
+            market: self.market_address,
+            attempts: MAX_VAULT_SIGNER_NONCE,
+        }
+        .into())
+    }
+}
+
+impl Market {
+    /// Initializes a market from its address alone, for markets whose tokens aren't listed in
+    /// `tokens_and_markets`.
+    ///
+    /// The market account is fetched once and the base and quote mints, as well as the consume
+    /// events authority, are read from its state. When `load` is set, the rest of the market state
+    /// is loaded from the same account data instead of fetching it again.
+    ///
+    /// # Arguments
+    ///
+    /// * `rpc_client` - RPC client for interacting with the Solana blockchain.
+    /// * `program_id` - The dex program owning the market.
+    /// * `market_id` - The public key of the market.
+    /// * `load` - A boolean indicating whether to load the rest of the market data immediately.
+    ///
+    /// # Returns
+    ///
+    /// Returns a new instance of the `Market` struct on success.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the market account can't be fetched or isn't a market of `program_id`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::rpc_client::RpcClient;
+    /// use openbook::v1::market::Market;
+    /// use openbook::rpc::Rpc;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let rpc_url = std::env::var("RPC_URL").expect("RPC_URL is not set in .env file");
+    ///
+    ///     let rpc_client = Rpc::new(RpcClient::new(rpc_url));
+    ///
+    ///     let program_id = "srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX".parse()?;
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let market = Market::new_from_market_id(rpc_client, program_id, market_id, true).await?;
+    ///
+    ///     println!("Base mint: {}, quote mint: {}", market.base_mint, market.quote_mint);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn new_from_market_id(
+        rpc_client: Rpc,
+        program_id: Pubkey,
+        market_id: Pubkey,
+        load: bool,
+    ) -> Result<Self> {
+        let mut account = rpc_client.get_account(&market_id).await?;
+        let owner = account.owner;
+
+        // Both views borrow the account data, so each is dropped before the next is loaded.
+        let (base_mint, quote_mint, events_authority) = {
+            let account_info = create_account_info_from_account(
+                &mut account,
+                &market_id,
+                &program_id,
+                false,
+                false,
+            );
+            let (base_mint, quote_mint) = {
+                let market = MarketState::load(&account_info, &program_id, false)?;
+                (
+                    Pubkey::from(u64_slice_to_pubkey(market.coin_mint)),
+                    Pubkey::from(u64_slice_to_pubkey(market.pc_mint)),
+                )
+            };
+            let market_auth = MarketAuth::load(&account_info, &program_id, false)?;
+            let events_authority = market_auth
+                .consume_events_authority()
+                .copied()
+                .unwrap_or_default();
+
+            (base_mint, quote_mint, events_authority)
+        };
+
+        let mut market = Market::new(
+            rpc_client,
+            program_id,
+            market_id,
+            base_mint,
+            quote_mint,
+            events_authority,
+            false,
+        )
+        .await?;
+
+        if load {
+            if owner != program_id {
+                return Err(ProgramError::InvalidArgument.into());
+            }
+            let account_info = create_account_info_from_account(
+                &mut account,
+                &market_id,
+                &program_id,
+                false,
+                false,
+            );
+            market.load_market_state_info(&account_info).await?;
+        }
+
+        Ok(market)
+    }
+
+    /// Initializes the market registered under `symbol` in a runtime `MarketRegistry`.
+    ///
+    /// The market is loaded with `Market::new_from_market_id`, using the entry's program, or the
+    /// default v1 program when the entry has none, and its mints are checked against the entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `rpc_client` - RPC client for interacting with the Solana blockchain.
+    /// * `registry` - The registry to resolve `symbol` in.
+    /// * `symbol` - The symbol of the market (e.g., "JLP/USDC").
+    /// * `load` - A boolean indicating whether to load the rest of the market data immediately.
+    ///
+    /// # Returns
+    ///
+    /// Returns a new instance of the `Market` struct on success.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `symbol` isn't registered, if the market can't be loaded, or if its
+    /// mints differ from the registry entry.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::registry::MarketRegistry;
+    /// use openbook::rpc_client::RpcClient;
+    /// use openbook::v1::market::Market;
+    /// use openbook::rpc::Rpc;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let rpc_url = std::env::var("RPC_URL").expect("RPC_URL is not set in .env file");
+    ///
+    ///     let rpc_client = Rpc::new(RpcClient::new(rpc_url));
+    ///
+    ///     let registry = MarketRegistry::from_json_file("markets.json")?;
+    ///
+    ///     let market = Market::new_from_registry(rpc_client, &registry, "JLP/USDC", true).await?;
+    ///
+    ///     println!("Market: {}", market.market_address);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn new_from_registry(
+        rpc_client: Rpc,
+        registry: &MarketRegistry,
+        symbol: &str,
+        load: bool,
+    ) -> Result<Self> {
+        let entry = registry
+            .resolve(symbol)
+            .ok_or_else(|| anyhow!("Market {} is not in the registry", symbol))?;
+        let program_id = match entry.program_id {
+            Some(program_id) => program_id,
+            None => SRM_PROGRAM_ID.parse()?,
+        };
+
+        let market =
+            Market::new_from_market_id(rpc_client, program_id, entry.market_address, load).await?;
+
+        if market.base_mint != entry.base_mint || market.quote_mint != entry.quote_mint {
+            return Err(anyhow!(
+                "Market {} trades {}/{}, but the registry lists {} as {}/{}",
+                entry.market_address,
+                market.base_mint,
+                market.quote_mint,
+                symbol,
+                entry.base_mint,
+                entry.quote_mint
+            ));
+        }
+
+        Ok(market)
+    }
+
+    /// Serializes the market definition to JSON, with pubkeys as base58 strings.
+    ///
+    /// The snapshot holds no keypair; the owner of an `OBClient` is read from `KEY_PATH` when
+    /// the client is created, so a cached market can be shared between wallets.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::v1::market::Market;
+    ///
+    /// let market = Market::default();
+    ///
+    /// let json = market.to_json().unwrap();
+    /// let restored = Market::from_json(&json).unwrap();
+    ///
+    /// assert_eq!(restored.market_address, market.market_address);
+    /// ```
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Deserializes a market definition written by `Market::to_json`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is invalid or a pubkey isn't valid base58.
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
     }
+
+    /// Returns the factor converting a UI price (quote per base) into a price in lots.
+    ///
+    /// Multiplying a UI price by this factor yields the `limit_price` expected by the
+    /// `new_order` instruction, while dividing a price in lots by it gives back the UI price.
+    pub fn price_factor(&self) -> f64 {
+        let base_d_factor = 10u32.pow(self.coin_decimals as u32) as f64;
+        let quote_d_factor = 10u32.pow(self.pc_decimals as u32) as f64;
+        let base_lot_factor = self.coin_lot_size as f64;
+        let quote_lot_factor = self.pc_lot_size as f64;
+
+        quote_d_factor * base_lot_factor / base_d_factor / quote_lot_factor
+    }
+
+    /// Returns the taker fee charged on `native_pc` quote, rounded up like the program does.
+    ///
+    /// A bid crossing the book pays it on top of the quote it buys, so it's added to the quote a
+    /// bid may lock.
+    pub fn taker_fee(&self, native_pc: u64) -> u64 {
+        (native_pc as f64 * self.taker_fee_bps / 10_000.0).ceil() as u64
+    }
+
+    /// Computes the change counter of the book from the request and event queue headers.
+    ///
+    /// Unlike v2, the v1 market state has no sequence number of its own. The request queue
+    /// counts every order the market accepted (`next_seq_num`, which order ids derive from), and
+    /// the event queue every fill and cancel it pushed (`seq_num`). Both only grow and every book
+    /// change bumps at least one of them, so their sum changes whenever the book does.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_queue` - The data of the request queue account, or at least its header.
+    /// * `event_queue` - The data of the event queue account, or at least its header.
+    ///
+    /// # Returns
+    ///
+    /// The change counter of the book.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either header is shorter than `QUEUE_HEADER_SIZE`.
+    pub fn seq_num_from_queue_headers(request_queue: &[u8], event_queue: &[u8]) -> Result<u64> {
+        if request_queue.len() < QUEUE_HEADER_SIZE || event_queue.len() < QUEUE_HEADER_SIZE {
+            return Err(anyhow!(
+                "Queue headers are {} and {} bytes, expected at least {}",
+                request_queue.len(),
+                event_queue.len(),
+                QUEUE_HEADER_SIZE
+            ));
+        }
+
+        Ok(read_u64(request_queue, 29).wrapping_add(read_u64(event_queue, 29)))
+    }
+
+    /// Fetches the request and event queue headers and updates `seq_num`.
+    ///
+    /// Only the headers are requested, so polling it is much cheaper than loading the book.
+    ///
+    /// # Arguments
+    ///
+    /// * `&mut self` - A mutable reference to the `Market` struct.
+    /// * `rpc_client` - A reference to the RPC client used to fetch the queue headers.
+    ///
+    /// # Returns
+    ///
+    /// The new `seq_num`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or either queue account is missing.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::ReadOnlyClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let mut client = ReadOnlyClient::new(commitment, market_id, true).await?;
+    ///     let rpc_client = client.rpc_client.clone();
+    ///
+    ///     let prev = client.market_info.load_seq_num(&rpc_client).await?;
+    ///     client.market_info.load_seq_num(&rpc_client).await?;
+    ///
+    ///     if client.market_info.seq_changed(prev) {
+    ///         client.load_bids_asks_info().await?;
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn load_seq_num(&mut self, rpc_client: &Rpc) -> Result<u64> {
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice: Some(UiDataSliceConfig {
+                offset: 0,
+                length: QUEUE_HEADER_SIZE,
+            }),
+            commitment: Some(rpc_client.provider().commitment()),
+            min_context_slot: None,
+        };
+        let accounts = rpc_client
+            .provider()
+            .get_multiple_accounts_with_config(&[self.request_queue, self.event_queue], config)
+            .await?
+            .value;
+
+        match accounts.as_slice() {
+            [Some(request_queue), Some(event_queue)] => {
+                self.seq_num =
+                    Self::seq_num_from_queue_headers(&request_queue.data, &event_queue.data)?;
+                Ok(self.seq_num)
+            }
+            _ => Err(anyhow!("Missing request or event queue account")),
+        }
+    }
+
+    /// Returns whether the book changed since `seq_num` was `prev`.
+    ///
+    /// Pollers can compare it before reloading and decoding the whole book.
+    pub fn seq_changed(&self, prev: u64) -> bool {
+        self.seq_num != prev
+    }
+
+    /// Loads the order book ladder, up to `depth` price levels per side.
+    ///
+    /// Unlike `OBClient::load_bids_asks_info`, the whole ladder is returned with the size of every
+    /// level, not only the best bid and ask.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `Market` struct.
+    /// * `rpc_client` - A reference to the RPC client used to fetch the book accounts.
+    /// * `depth` - The maximum number of price levels per side.
+    ///
+    /// # Returns
+    ///
+    /// An `OrderBook` with the aggregated bid and ask levels.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the accounts can't be fetched or the book can't be decoded.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::ReadOnlyClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let client = ReadOnlyClient::new(commitment, market_id, true).await?;
+    ///
+    ///     let book = client.market_info.load_orderbook_depth(&client.rpc_client, 10).await?;
+    ///
+    ///     println!("{:?}", book);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn load_orderbook_depth(&self, rpc_client: &Rpc, depth: usize) -> Result<OrderBook> {
+        let accounts = rpc_client
+            .fetch_multiple_accounts(&[self.market_address, self.bids_address, self.asks_address])
+            .await?;
+
+        match accounts.as_slice() {
+            [Some(market), Some(bids), Some(asks)] => {
+                self.orderbook_depth_from_accounts(market, bids, asks, depth)
+            }
+            _ => Err(anyhow!("Missing market, bids or asks account")),
+        }
+    }
+
+    /// Decodes the order book ladder from already fetched market, bids and asks accounts.
+    ///
+    /// The slabs are read in place, without removing any node, so the given accounts are left
+    /// untouched and can be decoded again.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `Market` struct.
+    /// * `market` - The market account.
+    /// * `bids` - The bids account.
+    /// * `asks` - The asks account.
+    /// * `depth` - The maximum number of price levels per side.
+    ///
+    /// # Returns
+    ///
+    /// An `OrderBook` with the aggregated bid and ask levels.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the market state or the slabs can't be decoded.
+    pub fn orderbook_depth_from_accounts(
+        &self,
+        market: &Account,
+        bids: &Account,
+        asks: &Account,
+        depth: usize,
+    ) -> Result<OrderBook> {
+        let (bids_len, asks_len) = (bids.data.len(), asks.data.len());
+        let (mut market, mut bids, mut asks) = (market.clone(), bids.clone(), asks.clone());
+        let price_factor = self.price_factor();
+        let size_factor = self.coin_lot_size as f64 / 10u64.pow(self.coin_decimals as u32) as f64;
+
+        let market_info = create_account_info_from_account(
+            &mut market,
+            &self.market_address,
+            &self.program_id,
+            false,
+            false,
+        );
+        let market_state = MarketState::load(&market_info, &self.program_id, false)?;
+
+        let bids_info = create_account_info_from_account(
+            &mut bids,
+            &self.bids_address,
+            &self.program_id,
+            false,
+            false,
+        );
+        let bids_slab = market_state.load_bids_mut(&bids_info)?;
+        let to_level = |node: &LeafNode| {
+            (
+                node.price().get() as f64 / price_factor,
+                node.quantity() as f64 * size_factor,
+            )
+        };
+        let bid_orders = sorted_leaves(&bids_slab, bids_len, Side::Bid);
+        let bids = OrderBook::aggregate_levels(bid_orders.into_iter().map(to_level), depth);
+
+        let asks_info = create_account_info_from_account(
+            &mut asks,
+            &self.asks_address,
+            &self.program_id,
+            false,
+            false,
+        );
+        let asks_slab = market_state.load_asks_mut(&asks_info)?;
+        let ask_orders = sorted_leaves(&asks_slab, asks_len, Side::Ask);
+        let asks = OrderBook::aggregate_levels(ask_orders.into_iter().map(to_level), depth);
+
+        Ok(OrderBook { bids, asks })
+    }
+}
+
+/// Returns the orders of one side of the book, best price first, reading the slab nodes in place.
+///
+/// Only the node handles fitting in the `data_len` bytes of the account are scanned, so a corrupt
+/// leaf count can't make the scan run past the slab.
+fn sorted_leaves(slab: &Slab, data_len: usize, side: Side) -> Vec<&LeafNode> {
+    let max_nodes = (data_len / std::mem::size_of::<AnyNode>()) as u32;
+    let mut leaves: Vec<&LeafNode> = (0..max_nodes)
+        .filter_map(|handle| slab.get(handle).and_then(AnyNode::as_leaf))
+        .take(slab.len() as usize)
+        .collect();
+
+    match side {
+        Side::Bid => leaves.sort_by_key(|leaf| Reverse(leaf.price())),
+        Side::Ask => leaves.sort_by_key(|leaf| leaf.price()),
+    }
+
+    leaves
 }
@@ -1,63 +1,370 @@
 use crate::v1::{
+    events::{EventQueue, FillEvent, OrderStatus, OrderTracker, MAX_CONSUME_EVENTS_ACCOUNTS},
     market::Market,
-    orders::{OpenOrders, OpenOrdersCacheEntry, OrderReturnType},
+    orders::{
+        open_orders_initialized, order_combo_instructions, prepend_step_instructions,
+        resend_until_confirmed, resolve_open_orders, ComboInstructionKind, ComboPreview, ComboStep,
+        LimitOrderParams, LimitOrderSpec, OpenOrderInfo, OpenOrders, OpenOrdersAccount,
+        OpenOrdersCacheEntry, OrderReturnType, PlaceOrderOutcome, PlaceOutcome, PlacedOrder,
+        SendAttempt, OPEN_ORDERS_ACCOUNT_SIZE,
+    },
 };
 use crate::{
-    rpc::Rpc,
+    error::OpenBookError,
+    health::{HealthCheckAccounts, HealthReport},
+    orderbook::{BestQuotes, FillEstimate, OrderBook},
+    rpc::{PriorityFeeStrategy, Rpc, TxConfig, TxOutcome},
     rpc_client::RpcClient,
-    utils::{create_account_info_from_account, get_unix_secs, read_keypair, u64_slice_to_pubkey},
+    subscription::spawn_account_stream,
+    traits::{OrderHandle, OrderPlacer, OrderSide},
+    utils::{
+        create_account_info_from_account, get_rpc_url, get_unix_secs, get_ws_url,
+        pack_instructions, premium_bps, prepend_compute_budget_parts, read_keypair_from_env,
+        token_account_amount, u64_slice_to_pubkey, OPENBOOK_V1_PROGRAM_ID,
+    },
     v1::traits::{MarketInfo, OpenOrdersT},
 };
 
-use anyhow::{Error, Result};
+use anyhow::{anyhow, Error, Result};
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
 use openbook_dex::{
     critbit::Slab,
-    instruction::SelfTradeBehavior,
+    instruction::MarketInstruction,
     matching::{OrderType, Side},
-    state::{Market as MarketAuth, MarketState},
+    state::MarketState,
 };
 use rand::random;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_config::RpcAccountInfoConfig;
 use solana_sdk::{
+    account::Account,
+    clock::DEFAULT_MS_PER_SLOT,
     commitment_config::CommitmentConfig,
-    compute_budget::ComputeBudgetInstruction,
+    instruction::Instruction,
     pubkey::Pubkey,
     signature::{Signature, Signer},
     signer::keypair::Keypair,
+    system_instruction,
     sysvar::{rent, slot_history::ProgramError},
+    transaction::TransactionError,
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account_idempotent,
 };
-use spl_associated_token_account::get_associated_token_address;
 use std::{
     cell::RefMut,
     collections::HashMap,
     fmt::{Debug, Formatter},
     num::NonZeroU64,
+    ops::{Deref, DerefMut},
     str::FromStr,
     sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
+use tokio::time::sleep;
 
 use tracing::debug;
 
 pub static SPL_TOKEN_ID: &'static str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
-pub static SRM_PROGRAM_ID: &'static str = "srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX";
+pub static SRM_PROGRAM_ID: &'static str = OPENBOOK_V1_PROGRAM_ID;
+
+/// Compute unit limit of the combo transactions placing a single order, used when their
+/// simulation fails.
+pub const COMBO_COMPUTE_UNIT_LIMIT: u32 = 800_000;
+
+/// Compute unit limit of `cancel_settle_place`, which places both a bid and an ask, used when its
+/// simulation fails.
+pub const FULL_COMBO_COMPUTE_UNIT_LIMIT: u32 = 1_000_000;
+
+/// Default margin, in percent, added to the simulated compute units of a combo transaction.
+pub const DEFAULT_COMPUTE_UNIT_MARGIN_PERCENT: u32 = 15;
+
+/// Default of `OBClient::max_price_deviation_percent`, the furthest a limit order may be priced
+/// from the mid before it's rejected.
+pub const DEFAULT_MAX_PRICE_DEVIATION_PERCENT: f64 = 50.0;
+
+/// Lamports charged per transaction signature.
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Size of an SPL token account.
+pub const TOKEN_ACCOUNT_SIZE: usize = 165;
+
+/// How long `watch_order` waits for a websocket update before polling the accounts instead.
+pub const WATCH_ORDER_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long `place_limit_order_tracked` waits for its transaction to land, about the lifetime of
+/// a blockhash.
+pub const TRACKED_ORDER_LANDING_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Read-only OpenBook v1 client that loads and queries market data without a signer.
+///
+/// Monitoring and explorer code can use it to read the book without holding a keypair, so it
+/// cannot place or cancel orders by accident. `OBClient` wraps it and dereferences to it.
+#[derive(Clone)]
+pub struct ReadOnlyClient {
+    /// The RPC client for interacting with the Solana blockchain.
+    pub rpc_client: Rpc,
+    /// Information about the OpenBook market.
+    pub market_info: Market,
+    /// Book state loaded from the market (e.g., best bid and ask, open orders).
+    pub open_orders: OpenOrders,
+}
 
 /// OpenBook v1 Client to interact with the OpenBook market and perform actions.
 #[derive(Clone)]
 pub struct OBClient {
     /// The keypair of the owner used for signing transactions related to the market.
     pub owner: Arc<Keypair>,
-    /// The RPC client for interacting with the Solana blockchain.
-    pub rpc_client: Rpc,
+    /// The read-only client holding the RPC client, market info and the wallet's open orders.
+    pub reader: ReadOnlyClient,
     /// The public key of the associated account holding the quote tokens.
     pub quote_ata: Pubkey,
     /// The public key of the associated account holding the base tokens.
     pub base_ata: Pubkey,
-    /// Account info of the wallet on the market (e.g., open orders).
-    pub open_orders: OpenOrders,
-    /// Information about the OpenBook market.
-    pub market_info: Market,
     /// A HashMap containing open orders cache entries associated with their public keys.
     pub open_orders_cache: HashMap<Pubkey, OpenOrdersCacheEntry>,
+    /// The mid price at the time of the last quote, used by `should_requote`.
+    pub last_quote_mid: Option<f64>,
+    /// Margin, in percent, added to the simulated compute units when setting the compute unit
+    /// limit of the combo transactions.
+    pub compute_unit_margin_percent: u32,
+    /// Whether the combo transactions close the wrapped SOL account after settling on markets
+    /// trading native SOL, unwrapping its balance back to SOL.
+    pub unwrap_sol: bool,
+    /// Whether `place_limit_order` wraps the SOL paid by an order on markets trading native SOL,
+    /// and unwraps what is left after placing it, in the same transaction.
+    pub wrap_sol: bool,
+    /// Whether the limit orders snap their price to the market tick, up for bids and down for
+    /// asks, instead of truncating it. See `round_price_to_tick`.
+    pub round_to_tick: bool,
+    /// The quote token account receiving the referrer rebate when settling, as a front-end
+    /// operator. `None` settles without a referrer.
+    pub referrer: Option<Pubkey>,
+    /// The furthest, in percent of the mid price, a limit order may be priced from the mid of the
+    /// loaded book. Orders priced further are rejected unless `LimitOrderParams::allow_off_market`
+    /// is set.
+    pub max_price_deviation_percent: f64,
+}
+
+impl Deref for OBClient {
+    type Target = ReadOnlyClient;
+
+    fn deref(&self) -> &Self::Target {
+        &self.reader
+    }
+}
+
+impl DerefMut for OBClient {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.reader
+    }
+}
+
+/// A wallet view combining token account balances and the balances held by the open orders account.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WalletSnapshot {
+    /// Base tokens held by the base associated token account.
+    pub base_balance: f64,
+    /// Quote tokens held by the quote associated token account.
+    pub quote_balance: f64,
+    /// Base tokens that can be settled from the open orders account.
+    pub base_free: f64,
+    /// Quote tokens that can be settled from the open orders account.
+    pub quote_free: f64,
+    /// Base tokens locked in open asks.
+    pub base_in_orders: f64,
+    /// Quote tokens locked in open bids.
+    pub quote_in_orders: f64,
+}
+
+impl WalletSnapshot {
+    /// Builds a snapshot from the raw base ATA, quote ATA and open orders accounts.
+    ///
+    /// Missing accounts are treated as empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_ata` - The base associated token account, if it exists.
+    /// * `quote_ata` - The quote associated token account, if it exists.
+    /// * `open_orders` - The open orders account, if it exists.
+    /// * `base_decimals` - Decimals of the base mint.
+    /// * `quote_decimals` - Decimals of the quote mint.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the open orders account data cannot be decoded.
+    pub fn from_accounts(
+        base_ata: Option<&Account>,
+        quote_ata: Option<&Account>,
+        open_orders: Option<&Account>,
+        base_decimals: u8,
+        quote_decimals: u8,
+    ) -> Result<Self> {
+        let base_d_factor = 10u64.pow(base_decimals as u32) as f64;
+        let quote_d_factor = 10u64.pow(quote_decimals as u32) as f64;
+        let token_amount = |account: Option<&Account>| {
+            account
+                .and_then(|a| token_account_amount(&a.data))
+                .unwrap_or_default()
+        };
+
+        let mut snapshot = Self {
+            base_balance: token_amount(base_ata) as f64 / base_d_factor,
+            quote_balance: token_amount(quote_ata) as f64 / quote_d_factor,
+            ..Default::default()
+        };
+
+        if let Some(account) = open_orders {
+            let oo = OpenOrdersAccount::from_account_data(&account.data)?;
+            snapshot.base_free = oo.native_coin_free as f64 / base_d_factor;
+            snapshot.quote_free = oo.native_pc_free as f64 / quote_d_factor;
+            snapshot.base_in_orders =
+                oo.native_coin_total.saturating_sub(oo.native_coin_free) as f64 / base_d_factor;
+            snapshot.quote_in_orders =
+                oo.native_pc_total.saturating_sub(oo.native_pc_free) as f64 / quote_d_factor;
+        }
+
+        Ok(snapshot)
+    }
+}
+
+/// Wallet and open orders balances, valued in quote at the market price.
+///
+/// This is the v1 counterpart of the v2 `AtaBalances`, with the open orders balances added on top
+/// of the token account balances.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Balances {
+    /// Base tokens held by the base associated token account.
+    pub base_balance: f64,
+    /// Quote tokens held by the quote associated token account.
+    pub quote_balance: f64,
+    /// Base tokens held by the open orders account, free and locked in asks.
+    pub base_in_open_orders: f64,
+    /// Quote tokens held by the open orders account, free and locked in bids.
+    pub quote_in_open_orders: f64,
+    /// The UI price used to value the base tokens.
+    pub price: f64,
+    /// All base and quote tokens, valued in quote.
+    pub total_balance: f64,
+}
+
+impl Balances {
+    /// Builds the balances from a wallet snapshot, valuing the base tokens at `price`.
+    pub fn from_snapshot(snapshot: &WalletSnapshot, price: f64) -> Self {
+        let base_in_open_orders = snapshot.base_free + snapshot.base_in_orders;
+        let quote_in_open_orders = snapshot.quote_free + snapshot.quote_in_orders;
+        let base_total = snapshot.base_balance + base_in_open_orders;
+        let quote_total = snapshot.quote_balance + quote_in_open_orders;
+
+        Self {
+            base_balance: snapshot.base_balance,
+            quote_balance: snapshot.quote_balance,
+            base_in_open_orders,
+            quote_in_open_orders,
+            price,
+            total_balance: quote_total + price * base_total,
+        }
+    }
+}
+
+/// Best prices of the loaded book.
+///
+/// Each field is `None` when its side of the book is empty, so callers can branch on an empty or
+/// one-sided market instead of getting a zero or infinite price.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MarketPrice {
+    /// The best bid UI price, if there are bids.
+    pub best_bid: Option<f64>,
+    /// The best ask UI price, if there are asks.
+    pub best_ask: Option<f64>,
+    /// The mid between the best bid and ask, if both sides have orders.
+    pub mid: Option<f64>,
+    /// Whether both sides of the book have orders.
+    pub has_liquidity: bool,
+}
+
+impl MarketPrice {
+    /// Returns the best ask minus the best bid in quote UI units, if both sides have orders.
+    pub fn spread(&self) -> Option<f64> {
+        self.best_bid.zip(self.best_ask).map(|(bid, ask)| ask - bid)
+    }
+}
+
+/// The outcome of a `match_orders_transaction`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchResult {
+    /// The signature of the match transaction.
+    pub signature: Signature,
+    /// Whether the transaction was confirmed.
+    pub confirmed: bool,
+    /// The number of matches made, counted from the maker fills pushed to the event queue. Fills
+    /// consumed by a crank before the queue is read back are not counted.
+    pub matched: usize,
+}
+
+/// The all-in cost of placing a bid: the quote locked by the order plus the SOL for rent and fees.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CostEstimate {
+    /// Quote tokens locked by the order, after rounding to lots.
+    pub quote_needed: f64,
+    /// Lamports for the rent of accounts that don't exist yet.
+    pub sol_for_rent: u64,
+    /// Lamports for the base signature fees, excluding priority fees.
+    pub sol_for_fees: u64,
+}
+
+impl CostEstimate {
+    /// Builds an estimate from the accounts a bid needs, created when missing.
+    ///
+    /// A missing open orders account is created in its own transaction, so it adds one signature.
+    /// Missing ATAs are created alongside the order and only add their rent.
+    ///
+    /// # Arguments
+    ///
+    /// * `quote_needed` - Quote tokens locked by the order.
+    /// * `open_orders` - The open orders account, if it exists.
+    /// * `base_ata` - The base associated token account, if it exists.
+    /// * `quote_ata` - The quote associated token account, if it exists.
+    /// * `open_orders_rent` - Rent-exempt minimum of an open orders account, in lamports.
+    /// * `token_account_rent` - Rent-exempt minimum of a token account, in lamports.
+    pub fn from_accounts(
+        quote_needed: f64,
+        open_orders: Option<&Account>,
+        base_ata: Option<&Account>,
+        quote_ata: Option<&Account>,
+        open_orders_rent: u64,
+        token_account_rent: u64,
+    ) -> Self {
+        let mut sol_for_rent = 0;
+        let mut signatures = 1;
+
+        if open_orders.is_none() {
+            sol_for_rent += open_orders_rent;
+            signatures += 1;
+        }
+        for ata in [base_ata, quote_ata] {
+            if ata.is_none() {
+                sol_for_rent += token_account_rent;
+            }
+        }
+
+        Self {
+            quote_needed,
+            sol_for_rent,
+            sol_for_fees: signatures * LAMPORTS_PER_SIGNATURE,
+        }
+    }
+}
+
+impl Debug for ReadOnlyClient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        writeln!(f, "OB_V1_ReadOnlyClient {{")?;
+        writeln!(f, "    rpc_client: {:?}", self.rpc_client)?;
+        writeln!(f, "    market_info: {:?}", self.market_info)?;
+        writeln!(f, "    open_orders: {:?}", self.open_orders)?;
+        writeln!(f, "}}")
+    }
 }
 
 impl Debug for OBClient {
@@ -69,34 +376,157 @@ impl Debug for OBClient {
         writeln!(f, "    base_ata: {:?}", self.base_ata)?;
         writeln!(f, "    open_orders: {:?}", self.open_orders)?;
         writeln!(f, "    market_info: {:?}", self.market_info)?;
+        writeln!(f, "    last_quote_mid: {:?}", self.last_quote_mid)?;
+        writeln!(
+            f,
+            "    compute_unit_margin_percent: {:?}",
+            self.compute_unit_margin_percent
+        )?;
+        writeln!(f, "    unwrap_sol: {:?}", self.unwrap_sol)?;
+        writeln!(f, "    wrap_sol: {:?}", self.wrap_sol)?;
+        writeln!(f, "    round_to_tick: {:?}", self.round_to_tick)?;
+        writeln!(f, "    referrer: {:?}", self.referrer)?;
+        writeln!(
+            f,
+            "    max_price_deviation_percent: {:?}",
+            self.max_price_deviation_percent
+        )?;
         writeln!(f, "}}")
     }
 }
 
-impl OBClient {
-    /// Initializes a new instance of the `OBClient` struct, representing an OpenBook V1 program client.
+/// Builds an `OBClient` with the options `OBClient::new` doesn't take, see `OBClient::builder`.
+///
+/// Unless set, the RPC client and owner are read from the environment like `OBClient::new`
+/// does, the program is `SRM_PROGRAM_ID` and the market isn't loaded. With `create_atas`, the
+/// missing base and quote token accounts of the wallet are created before the client is returned.
+pub struct OBClientBuilder {
+    market_id: Pubkey,
+    commitment: CommitmentConfig,
+    program_id: Option<Pubkey>,
+    rpc_client: Option<Rpc>,
+    owner: Option<Keypair>,
+    load: bool,
+    cache_ts: u128,
+    create_atas: bool,
+}
+
+impl OBClientBuilder {
+    /// Sets the commitment of the RPC client read from `RPC_URL`. Ignored with `rpc_client`.
+    pub fn commitment(mut self, commitment: CommitmentConfig) -> Self {
+        self.commitment = commitment;
+        self
+    }
+
+    /// Sets the dex program owning the market, such as a fork deployed on a local validator.
+    pub fn program_id(mut self, program_id: Pubkey) -> Self {
+        self.program_id = Some(program_id);
+        self
+    }
+
+    /// Sets the RPC client, instead of the one read from `RPC_URL`. Its commitment is used for
+    /// every request.
+    pub fn rpc_client(mut self, rpc_client: Rpc) -> Self {
+        self.rpc_client = Some(rpc_client);
+        self
+    }
+
+    /// Sets the keypair of the wallet trading on the market, instead of the one read from the
+    /// environment. The open orders account is then the one derived from the owner, `OOS_KEY` is
+    /// ignored.
+    pub fn owner(mut self, owner: Keypair) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    /// Sets whether the market data and the book are loaded immediately.
+    pub fn load(mut self, load: bool) -> Self {
+        self.load = load;
+        self
+    }
+
+    /// Sets the timestamp of the open orders cache entry.
+    pub fn cache_ts(mut self, cache_ts: u128) -> Self {
+        self.cache_ts = cache_ts;
+        self
+    }
+
+    /// Sets whether the missing base and quote token accounts of the wallet are created, with
+    /// `OBClient::ensure_token_accounts`, once the client is built.
+    pub fn create_atas(mut self, create_atas: bool) -> Self {
+        self.create_atas = create_atas;
+        self
+    }
+
+    /// Builds the client, creating the missing token accounts of the wallet with `create_atas`.
     ///
-    /// This method initializes the `OBClient` struct, containing information about the requested market id.
-    /// It fetches and stores all data about this OpenBook market. Additionally, it includes information about
-    /// the account associated with the wallet on the OpenBook market (e.g., open orders, bids, asks, etc.).
+    /// # Errors
+    ///
+    /// Returns an error if the keypair can't be read, the market can't be fetched, or the token
+    /// accounts creation transaction doesn't confirm.
+    pub async fn build(self) -> Result<OBClient, OpenBookError> {
+        let program_id = match self.program_id {
+            Some(program_id) => program_id,
+            None => SRM_PROGRAM_ID.parse()?,
+        };
+        let rpc_client = self.rpc_client.unwrap_or_else(|| {
+            Rpc::new(RpcClient::new_with_commitment(
+                get_rpc_url(),
+                self.commitment,
+            ))
+        });
+        let (owner, oos_key) = match self.owner {
+            Some(owner) => (owner, None),
+            None => (
+                read_keypair_from_env()?,
+                std::env::var("OOS_KEY")
+                    .ok()
+                    .and_then(|key| Pubkey::from_str(&key).ok()),
+            ),
+        };
+
+        let ob_client = OBClient::from_parts(
+            rpc_client,
+            owner,
+            self.market_id,
+            program_id,
+            oos_key,
+            self.load,
+            self.cache_ts,
+        )
+        .await?;
+
+        if self.create_atas {
+            if let Some(signature) = ob_client.ensure_token_accounts().await? {
+                debug!("[*] Created the token accounts: {:?}", signature);
+            }
+        }
+
+        Ok(ob_client)
+    }
+}
+
+impl ReadOnlyClient {
+    /// Initializes a new instance of the `ReadOnlyClient` struct for the requested market id.
+    ///
+    /// Unlike `OBClient::new`, no keypair is read: only `RPC_URL` is used.
     ///
     /// # Arguments
     ///
-    /// * `commitment` - Commitment configuration for transactions, determining the level of finality required.
+    /// * `commitment` - Commitment configuration for RPC requests.
     /// * `market_id` - Public key (ID) of the market to fetch information about.
-    /// * `load` - Boolean indicating whether to load market data immediately after initialization.
-    /// * `cache_ts` - Timestamp for caching current open orders, used to manage the cache validity.
+    /// * `load` - Boolean indicating whether to load the market data and the book immediately.
     ///
     /// # Returns
     ///
-    /// Returns a `Result` wrapping a new instance of the `OBClient` struct initialized with the provided parameters,
-    /// or an `Error` if the initialization process fails.
+    /// Returns a `Result` wrapping a new instance of the `ReadOnlyClient` struct,
+    /// or an `Error` if fetching the market fails.
     ///
     /// # Example
     ///
     /// ```rust
     /// use openbook::commitment_config::CommitmentConfig;
-    /// use openbook::v1::ob_client::OBClient;
+    /// use openbook::v1::ob_client::ReadOnlyClient;
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -104,134 +534,64 @@ impl OBClient {
     ///
     ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
     ///
-    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///     let client = ReadOnlyClient::new(commitment, market_id, true).await?;
     ///
-    ///     println!("Initialized OBClient: {:?}", ob_client);
+    ///     println!("Best bid: {}, best ask: {}", client.open_orders.max_bid, client.open_orders.min_ask);
     ///
     ///     Ok(())
     /// }
     /// ```
+    pub async fn new(commitment: CommitmentConfig, market_id: Pubkey, load: bool) -> Result<Self> {
+        Self::new_with_program_id(commitment, market_id, SRM_PROGRAM_ID.parse()?, load).await
+    }
+
+    /// Initializes a new instance of the `ReadOnlyClient` struct for a market of a custom dex
+    /// deployment, such as a fork deployed on a local validator.
     ///
-    /// # Business Logic
+    /// # Arguments
     ///
-    /// 1. Retrieve necessary env vars, such as the `RPC_URL` and `KEY_PATH` path.
-    /// 2. Read the owner's keypair from the specified key path.
-    /// 3. Initialize the RPC client with the given commitment configuration.
-    /// 4. Fetch the market account information on chain.
-    /// 5. Load the market state and extract base and quote mints.
-    /// 6. Initialize the `Market` struct with fetched market information.
-    /// 7. Fetche associated token accounts (ATA) for the base and quote tokens.
-    /// 8. Initialize the open orders for the client.
-    /// 9. Populate the open orders cache.
-    /// 10. Load bids and asks information if the `load` parameter is set to `true`.
+    /// * `commitment` - Commitment configuration for RPC requests.
+    /// * `market_id` - Public key (ID) of the market to fetch information about.
+    /// * `program_id` - The dex program owning the market, used instead of `SRM_PROGRAM_ID`.
+    /// * `load` - Boolean indicating whether to load the market data and the book immediately.
     ///
-    pub async fn new(
+    /// # Returns
+    ///
+    /// Returns a `Result` wrapping a new instance of the `ReadOnlyClient` struct,
+    /// or an `Error` if fetching the market fails.
+    pub async fn new_with_program_id(
         commitment: CommitmentConfig,
         market_id: Pubkey,
+        program_id: Pubkey,
         load: bool,
-        cache_ts: u128,
-    ) -> Result<Self, Error> {
-        let rpc_url =
-            std::env::var("RPC_URL").unwrap_or("https://api.mainnet-beta.solana.com".to_string());
-        let key_path = std::env::var("KEY_PATH").unwrap_or("".to_string());
-
-        let owner = read_keypair(&key_path);
-        let rpc_client = RpcClient::new_with_commitment(rpc_url, commitment);
-        let oos_key_str = std::env::var("OOS_KEY").unwrap_or("".to_string());
-
-        let orders_key = Pubkey::from_str(oos_key_str.as_str());
-
-        let pub_owner_key = owner.pubkey().clone();
-
-        let rpc_client = Rpc::new(rpc_client);
-
-        let mut account_1 = rpc_client.inner().get_account(&market_id).await?;
-        let mut account_2 = rpc_client.inner().get_account(&market_id).await?;
-        let account_info_1;
-        let account_info_2;
-        let program_id = SRM_PROGRAM_ID.parse().unwrap();
-        {
-            account_info_1 = create_account_info_from_account(
-                &mut account_1,
-                &market_id,
-                &program_id,
-                false,
-                false,
-            );
-            account_info_2 = create_account_info_from_account(
-                &mut account_2,
-                &market_id,
-                &program_id,
-                false,
-                false,
-            );
-        }
-        let market = MarketState::load(&account_info_1, &SRM_PROGRAM_ID.parse().unwrap(), false)?;
-        let market_auth =
-            MarketAuth::load(&account_info_2, &SRM_PROGRAM_ID.parse().unwrap(), false)?;
-        let default_auth = Default::default();
-        let events_authority = market_auth
-            .consume_events_authority()
-            .unwrap_or(&default_auth);
-
-        let base_mint = Pubkey::from(u64_slice_to_pubkey(market.coin_mint));
-        let quote_mint = Pubkey::from(u64_slice_to_pubkey(market.pc_mint));
-
-        let market_info = Market::new(
-            rpc_client.clone(),
-            SRM_PROGRAM_ID.parse().unwrap(),
-            market_id,
-            base_mint,
-            quote_mint,
-            *events_authority,
-            load,
-        )
-        .await?;
-
-        let base_ata = get_associated_token_address(&pub_owner_key.clone(), &market_info.base_mint);
-        let quote_ata =
-            get_associated_token_address(&pub_owner_key.clone(), &market_info.quote_mint);
-
-        let cloned_owner = owner.insecure_clone();
-        let open_orders = OpenOrders::new(
-            rpc_client.clone(),
-            SRM_PROGRAM_ID.parse().unwrap(),
-            cloned_owner,
-            market_info.market_address,
-        )
-        .await?;
-        let mut open_orders_cache = HashMap::new();
+    ) -> Result<Self> {
+        let rpc_url = get_rpc_url();
+        let rpc_client = Rpc::new(RpcClient::new_with_commitment(rpc_url, commitment));
 
-        let open_orders_cache_entry = OpenOrdersCacheEntry {
-            open_orders: open_orders.clone(),
-            ts: cache_ts,
-        };
+        let market_info =
+            Self::load_market(rpc_client.clone(), market_id, program_id, load).await?;
 
-        open_orders_cache.insert(pub_owner_key, open_orders_cache_entry.clone());
-
-        let mut ob_client = Self {
+        let mut client = Self {
             rpc_client,
             market_info,
-            owner: owner.into(),
-            quote_ata,
-            base_ata,
-            open_orders,
-            open_orders_cache,
+            open_orders: Default::default(),
         };
 
-        if !orders_key.is_err() {
-            ob_client.open_orders.oo_key = orders_key.unwrap();
-        }
-
         if load {
-            ob_client.load_bids_asks_info().await?;
+            client.load_bids_asks_info().await?;
         }
 
-        if let Some(entry) = ob_client.open_orders_cache.get_mut(&pub_owner_key) {
-            entry.open_orders = ob_client.open_orders.clone();
-        }
+        Ok(client)
+    }
 
-        Ok(ob_client)
+    /// Fetches the market account and builds the `Market` struct from its state.
+    pub(crate) async fn load_market(
+        rpc_client: Rpc,
+        market_id: Pubkey,
+        program_id: Pubkey,
+        load: bool,
+    ) -> Result<Market> {
+        Market::new_from_market_id(rpc_client, program_id, market_id, load).await
     }
 
     /// Loads information about bids, asks, and the open orders associated with the wallet from the market state.
@@ -239,9 +599,11 @@ impl OBClient {
     /// This function fetches and processes bids information, including extracting the bids and asks addresses
     /// and loading the open orders. It also determines the maximum bid price and minimum ask price.
     ///
+    /// The market, bids and asks accounts are fetched in a single `getMultipleAccounts` call.
+    ///
     /// # Arguments
     ///
-    /// * `&mut self` - A mutable reference to the `OBClient` struct.
+    /// * `&mut self` - A mutable reference to the `ReadOnlyClient` struct.
     ///
     /// # Returns
     ///
@@ -251,118 +613,253 @@ impl OBClient {
     /// # Errors
     ///
     /// This function may return an error if there is an issue with fetching accounts
-    /// or processing the bids information.
+    /// or processing the bids information. If the market's bids or asks address is the default
+    /// pubkey, it returns `OpenBookError::MarketBookUninitialized` without querying the RPC.
     pub async fn load_bids_asks_info(&mut self) -> Result<(Pubkey, Pubkey, OpenOrders)> {
-        let mut account = self
-            .rpc_client
-            .inner()
-            .get_account(&self.market_info.market_address)
-            .await?;
-        let program_id_binding = self.market_info.program_id;
-        let market_account_binding = self.market_info.market_address;
-        let account_info;
-        {
-            account_info = create_account_info_from_account(
-                &mut account,
-                &market_account_binding,
-                &program_id_binding,
-                false,
-                false,
-            );
-        }
-        let market_state = MarketState::load(&account_info, &self.market_info.program_id, false)?;
-
-        let bids_address = self.market_info.bids_address;
-        let asks_address = self.market_info.asks_address;
-
-        let mut bids_account = self.rpc_client.inner().get_account(&bids_address).await?;
-        let bids_info = create_account_info_from_account(
-            &mut bids_account,
-            &bids_address,
-            &self.market_info.program_id,
-            false,
-            false,
-        );
-        let mut bids = market_state.load_bids_mut(&bids_info)?;
-        let (open_bids, open_bids_prices, max_bid) = self.process_bids(&mut bids)?;
+        self.ensure_book_initialized()?;
 
-        let mut asks_account = self.rpc_client.inner().get_account(&asks_address).await?;
-        let asks_info = create_account_info_from_account(
-            &mut asks_account,
-            &asks_address,
-            &self.market_info.program_id,
-            false,
-            false,
-        );
-        let mut asks = market_state.load_asks_mut(&asks_info)?;
-        let (open_asks, open_asks_prices, min_ask) = self.process_asks(&mut asks)?;
+        let keys = [
+            self.market_info.market_address,
+            self.market_info.bids_address,
+            self.market_info.asks_address,
+        ];
 
-        self.open_orders = OpenOrders {
-            oo_key: self.open_orders.oo_key,
-            min_ask,
-            max_bid,
-            open_asks,
-            open_bids,
-            bids_address,
-            asks_address,
-            open_asks_prices,
-            open_bids_prices,
-            base_total: 0.,
-            quote_total: 0.,
+        let mut accounts = self
+            .rpc_client
+            .fetch_multiple_accounts(&keys)
+            .await?
+            .into_iter();
+        let mut next_account = |key: &Pubkey| {
+            accounts
+                .next()
+                .flatten()
+                .ok_or_else(|| anyhow!("Account {} not found", key))
         };
+        let market_account = next_account(&keys[0])?;
+        let bids_account = next_account(&keys[1])?;
+        let asks_account = next_account(&keys[2])?;
 
-        Ok((bids_address, asks_address, self.open_orders.clone()))
+        self.process_book_accounts(market_account, bids_account, asks_account)
     }
 
-    /// Processes bids information to find the maximum bid price.
+    /// Loads bids, asks, and the wallet's open orders as they were at a specific slot.
     ///
-    /// This function removes bids from the provided `Slab` to find the maximum bid price.
+    /// The market, bids and asks accounts are fetched in a single `getMultipleAccounts` call
+    /// with `min_context_slot` set to `slot`, so all three accounts come from the same context slot.
+    /// If the node answers from any slot other than the requested one, an error is returned instead
+    /// of silently loading a newer book.
+    ///
+    /// # RPC Requirements
+    ///
+    /// Standard Solana RPC nodes only keep the latest account state; `min_context_slot` merely
+    /// guarantees that the node has caught up to `slot`. Reading a book as of a past slot therefore
+    /// requires an archival RPC provider that pins account reads to the requested slot. Against a
+    /// regular node this method only succeeds when `slot` is the node's current slot.
     ///
     /// # Arguments
     ///
-    /// * `&self` - A reference to the `OBClient` struct.
-    /// * `bids` - A mutable reference to the `Slab` containing bids information.
+    /// * `&mut self` - A mutable reference to the `ReadOnlyClient` struct.
+    /// * `slot` - The slot at which the market state should be read.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the maximum bid price if successful, or an error if processing bids fails.
+    /// `Ok(())` once `self.open_orders` reflects the book at `slot`.
     ///
     /// # Errors
     ///
-    /// This function may return an error if there is an issue with processing the bids information.
-    pub fn process_bids(&self, bids: &mut RefMut<Slab>) -> Result<(Vec<u128>, Vec<f64>, u64)> {
-        let mut max_bid = 0;
-        let mut open_bids = Vec::new();
-        let mut open_bids_prices = Vec::new();
-        loop {
-            let node = bids.remove_max();
-            match node {
-                Some(node) => {
-                    let owner = node.owner();
-                    let bytes = u64_slice_to_pubkey(owner);
-                    let owner_address = Pubkey::from(bytes);
-
-                    let order_id = node.order_id();
-                    let price_raw = node.price().get();
-                    let ui_price = price_raw as f64 / 1e4;
-
-                    debug!("[*] Bid: {price_raw}");
-
-                    if max_bid == 0 {
-                        max_bid = price_raw;
-                    }
-
-                    if owner_address == self.open_orders.oo_key {
-                        open_bids.push(order_id);
-                        open_bids_prices.push(ui_price);
-                    }
-                }
-                None => {
-                    break;
-                }
-            }
+    /// Returns an error if the RPC request fails, if one of the accounts is missing, or if the
+    /// node served the accounts from a slot other than `slot`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust , ignore
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::ReadOnlyClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let mut client = ReadOnlyClient::new(commitment, market_id, false).await?;
+    ///
+    ///     client.load_at_slot(250_000_000).await?;
+    ///
+    ///     println!("{:?}", client.open_orders);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn load_at_slot(&mut self, slot: u64) -> Result<()> {
+        self.ensure_book_initialized()?;
+
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(self.rpc_client.provider().commitment()),
+            min_context_slot: Some(slot),
+            data_slice: None,
+        };
+        let keys = [
+            self.market_info.market_address,
+            self.market_info.bids_address,
+            self.market_info.asks_address,
+        ];
+
+        let response = self
+            .rpc_client
+            .provider()
+            .get_multiple_accounts_with_config(&keys, config)
+            .await?;
+
+        if response.context.slot != slot {
+            return Err(anyhow!(
+                "RPC node served slot {} instead of {}; historical reads require an archival node",
+                response.context.slot,
+                slot
+            ));
+        }
+
+        let mut accounts = response.value.into_iter();
+        let mut next_account = |key: &Pubkey| {
+            accounts
+                .next()
+                .flatten()
+                .ok_or_else(|| anyhow!("Account {} not found at slot {}", key, slot))
+        };
+        let market_account = next_account(&keys[0])?;
+        let bids_account = next_account(&keys[1])?;
+        let asks_account = next_account(&keys[2])?;
+
+        self.process_book_accounts(market_account, bids_account, asks_account)?;
+
+        Ok(())
+    }
+
+    /// Fails with `OpenBookError::MarketBookUninitialized` if the market has no bids or asks account.
+    fn ensure_book_initialized(&self) -> Result<(), OpenBookError> {
+        if self.market_info.bids_address == Pubkey::default()
+            || self.market_info.asks_address == Pubkey::default()
+        {
+            return Err(OpenBookError::MarketBookUninitialized(
+                self.market_info.market_address,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Fails with `OpenBookError::OpenOrdersNotInitialized` if the wallet's open orders account
+    /// doesn't exist, as loaded in `open_orders.exists`.
+    fn ensure_open_orders_initialized(&self) -> Result<(), OpenBookError> {
+        if !self.open_orders.exists {
+            return Err(OpenBookError::OpenOrdersNotInitialized(
+                self.open_orders.oo_key,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether the wallet's open orders account exists and is owned by the market program.
+    ///
+    /// Settling and canceling need the account; when it's missing they fail with
+    /// `OpenBookError::OpenOrdersNotInitialized` instead of sending a transaction. Create it with
+    /// `OBClient::init_open_orders`.
+    pub fn open_orders_initialized(&self) -> bool {
+        self.open_orders.exists
+    }
+
+    /// Parses already fetched market, bids and asks accounts into `self.open_orders`.
+    fn process_book_accounts(
+        &mut self,
+        mut account: Account,
+        mut bids_account: Account,
+        mut asks_account: Account,
+    ) -> Result<(Pubkey, Pubkey, OpenOrders)> {
+        let program_id_binding = self.market_info.program_id;
+        let market_account_binding = self.market_info.market_address;
+        let account_info;
+        {
+            account_info = create_account_info_from_account(
+                &mut account,
+                &market_account_binding,
+                &program_id_binding,
+                false,
+                false,
+            );
         }
-        Ok((open_bids, open_bids_prices, max_bid))
+        let market_state = MarketState::load(&account_info, &self.market_info.program_id, false)?;
+
+        let bids_address = self.market_info.bids_address;
+        let asks_address = self.market_info.asks_address;
+
+        let bids_info = create_account_info_from_account(
+            &mut bids_account,
+            &bids_address,
+            &self.market_info.program_id,
+            false,
+            false,
+        );
+        let mut bids = market_state.load_bids_mut(&bids_info)?;
+        let bid_orders = self.walk_side(&mut bids, Side::Bid);
+
+        let asks_info = create_account_info_from_account(
+            &mut asks_account,
+            &asks_address,
+            &self.market_info.program_id,
+            false,
+            false,
+        );
+        let mut asks = market_state.load_asks_mut(&asks_info)?;
+        let ask_orders = self.walk_side(&mut asks, Side::Ask);
+
+        let mut order_quantities = bid_orders.quantities;
+        order_quantities.extend(ask_orders.quantities);
+        let mut client_order_ids = bid_orders.client_ids;
+        client_order_ids.extend(ask_orders.client_ids);
+
+        self.open_orders = OpenOrders {
+            oo_key: self.open_orders.oo_key,
+            min_ask: ask_orders.best_price,
+            max_bid: bid_orders.best_price,
+            open_asks: ask_orders.order_ids,
+            open_bids: bid_orders.order_ids,
+            bids_address,
+            asks_address,
+            open_asks_prices: ask_orders.prices,
+            open_bids_prices: bid_orders.prices,
+            base_total: self.open_orders.base_total,
+            quote_total: self.open_orders.quote_total,
+            exists: self.open_orders.exists,
+            order_timestamps: std::mem::take(&mut self.open_orders.order_timestamps),
+            order_quantities,
+            client_order_ids,
+        };
+        self.open_orders.stamp_orders(get_unix_secs());
+
+        Ok((bids_address, asks_address, self.open_orders.clone()))
+    }
+
+    /// Processes bids information to find the maximum bid price.
+    ///
+    /// This function removes bids from the provided `Slab` to find the maximum bid price.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `ReadOnlyClient` struct.
+    /// * `bids` - A mutable reference to the `Slab` containing bids information.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the maximum bid price if successful, or an error if processing bids fails.
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if there is an issue with processing the bids information.
+    pub fn process_bids(&self, bids: &mut RefMut<Slab>) -> Result<(Vec<u128>, Vec<f64>, u64)> {
+        let orders = self.walk_side(bids, Side::Bid);
+        Ok((orders.order_ids, orders.prices, orders.best_price))
     }
 
     /// Processes asks information to fetch asks info.
@@ -372,7 +869,7 @@ impl OBClient {
     ///
     /// # Arguments
     ///
-    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `&self` - A reference to the `ReadOnlyClient` struct.
     /// * `asks` - A mutable reference to the `Slab` containing asks information.
     ///
     /// # Returns
@@ -380,38 +877,99 @@ impl OBClient {
     /// A `Result` containing a tuple of `(open_asks, open_asks_prices, min_ask)` if successful,
     /// or an error if processing asks fails.
     pub fn process_asks(&self, asks: &mut RefMut<Slab>) -> Result<(Vec<u128>, Vec<f64>, u64)> {
-        let mut min_ask = 0;
-        let mut open_asks = Vec::new();
-        let mut open_asks_prices = Vec::new();
-        loop {
-            let node = asks.remove_min();
-            match node {
-                Some(node) => {
-                    let owner = node.owner();
-                    let bytes = u64_slice_to_pubkey(owner);
-                    let owner_address = Pubkey::from(bytes);
+        let orders = self.walk_side(asks, Side::Ask);
+        Ok((orders.order_ids, orders.prices, orders.best_price))
+    }
+
+    /// Removes every order of one side of the book from `slab`, best price first, keeping the
+    /// wallet's orders and the best price.
+    fn walk_side(&self, slab: &mut RefMut<Slab>, side: Side) -> SideOrders {
+        let mut orders = SideOrders::default();
+        let mut next = || match side {
+            Side::Bid => slab.remove_max(),
+            Side::Ask => slab.remove_min(),
+        };
 
-                    let order_id = node.order_id();
-                    let price_raw = node.price().get();
-                    let ui_price = price_raw as f64 / 1e4;
+        while let Some(node) = next() {
+            let owner = node.owner();
+            let bytes = u64_slice_to_pubkey(owner);
+            let owner_address = Pubkey::from(bytes);
 
-                    debug!("[*] Ask: {price_raw}");
+            let order_id = node.order_id();
+            let price_raw = node.price().get();
+            let ui_price = price_raw as f64 / 1e4;
 
-                    if min_ask == 0 {
-                        min_ask = price_raw;
-                    }
+            debug!("[*] {:?}: {price_raw}", side);
 
-                    if owner_address == self.open_orders.oo_key {
-                        open_asks.push(order_id);
-                        open_asks_prices.push(ui_price);
-                    }
-                }
-                None => {
-                    break;
-                }
+            if orders.best_price == 0 {
+                orders.best_price = price_raw;
+            }
+
+            if owner_address == self.open_orders.oo_key {
+                orders.order_ids.push(order_id);
+                orders.prices.push(ui_price);
+                orders.quantities.insert(order_id, node.quantity());
+                orders.client_ids.insert(order_id, node.client_order_id());
             }
         }
-        Ok((open_asks, open_asks_prices, min_ask))
+
+        orders
+    }
+
+    /// Returns the wallet's open orders with their side, price, size and client order id.
+    ///
+    /// Prices are decoded from the order ids, and sizes and client order ids come from the
+    /// last book load (see `load_bids_asks_info`). Bids come first, best price first, then asks.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::ReadOnlyClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let client = ReadOnlyClient::new(commitment, market_id, true).await?;
+    ///
+    ///     for order in client.open_orders_detailed() {
+    ///         println!("[*] {:?} {} @ {}", order.side, order.size, order.price);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn open_orders_detailed(&self) -> Vec<OpenOrderInfo> {
+        let price_factor = self.market_info.price_factor();
+        let size_factor = self.market_info.coin_lot_size as f64
+            / 10u64.pow(self.market_info.coin_decimals as u32) as f64;
+
+        [
+            (Side::Bid, &self.open_orders.open_bids),
+            (Side::Ask, &self.open_orders.open_asks),
+        ]
+        .into_iter()
+        .flat_map(|(side, order_ids)| order_ids.iter().map(move |&order_id| (side, order_id)))
+        .map(|(side, order_id)| OpenOrderInfo {
+            order_id,
+            side,
+            price: (order_id >> 64) as u64 as f64 / price_factor,
+            size: self
+                .open_orders
+                .order_quantities
+                .get(&order_id)
+                .map_or(0.0, |&lots| lots as f64 * size_factor),
+            client_id: self
+                .open_orders
+                .client_order_ids
+                .get(&order_id)
+                .copied()
+                .unwrap_or_default(),
+        })
+        .collect()
     }
 
     /// Loads the open bids from the market.
@@ -428,7 +986,7 @@ impl OBClient {
     ///
     /// ```rust
     /// use openbook::commitment_config::CommitmentConfig;
-    /// use openbook::v1::ob_client::OBClient;
+    /// use openbook::v1::ob_client::ReadOnlyClient;
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -436,9 +994,9 @@ impl OBClient {
     ///
     ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
     ///
-    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///     let mut client = ReadOnlyClient::new(commitment, market_id, true).await?;
     ///
-    ///     let result = ob_client.load_bids()?;
+    ///     let result = client.load_bids()?;
     ///
     ///     println!("{:?}", result);
     ///
@@ -463,7 +1021,7 @@ impl OBClient {
     ///
     /// ```rust
     /// use openbook::commitment_config::CommitmentConfig;
-    /// use openbook::v1::ob_client::OBClient;
+    /// use openbook::v1::ob_client::ReadOnlyClient;
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -471,9 +1029,9 @@ impl OBClient {
     ///
     ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
     ///
-    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///     let mut client = ReadOnlyClient::new(commitment, market_id, true).await?;
     ///
-    ///     let result = ob_client.load_asks()?;
+    ///     let result = client.load_asks()?;
     ///
     ///     println!("{:?}", result);
     ///
@@ -483,31 +1041,28 @@ impl OBClient {
     pub fn load_asks(&mut self) -> Result<Vec<u128>, ProgramError> {
         Ok(self.open_orders.open_asks.clone())
     }
-    /// Places a limit order on the market.
-    ///
-    /// # Arguments
+
+    /// Re-reads the market state, the bids and asks, and the open orders account in place.
     ///
-    /// * `&self` - A reference to the `OBClient` struct.
-    /// * `target_amount_quote` - The target amount in quote currency for the order.
-    /// * `side` - The side of the order (buy or sell).
-    /// * `best_offset_usdc` - The best offset in USDC for the order.
-    /// * `execute` - A boolean indicating whether to execute the order immediately.
-    /// * `target_price` - The target price for the order.
+    /// A long-lived client calls this in its loop instead of being rebuilt. The four accounts are
+    /// fetched in a single `getMultipleAccounts` call, then the market state (vaults, queues, lot
+    /// sizes), the best bid and ask, and the wallet's orders and open orders balances are updated.
+    /// Prices and open orders are only as fresh as the last `refresh`; token account balances
+    /// aren't cached and are always fetched by `OBClient::get_balances`.
     ///
-    /// # Returns
+    /// # Arguments
     ///
-    /// A `Result` containing the transaction signature if successful,
-    /// or an error if placing the limit order fails.
+    /// * `&mut self` - A mutable reference to the `ReadOnlyClient` struct.
     ///
     /// # Errors
     ///
-    /// This function may return an error if there is an issue with creating or sending the transaction.
+    /// Returns an error if the RPC request fails, if the market, bids or asks account is missing,
+    /// or if an account can't be decoded. A missing open orders account isn't an error; the
+    /// wallet's orders and balances are zeroed and `open_orders.exists` is set to `false`.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use openbook::matching::Side;
-    /// use openbook::v1::orders::OrderReturnType;
     /// use openbook::commitment_config::CommitmentConfig;
     /// use openbook::v1::ob_client::OBClient;
     ///
@@ -519,137 +1074,3130 @@ impl OBClient {
     ///
     ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
     ///
-    ///     let target_amount_quote = 5.0;
-    ///     let side = Side::Bid; // or Side::Ask
-    ///     let best_offset_usdc = 5.0;
-    ///     let execute = true;
-    ///     let target_price = 2.1;
+    ///     loop {
+    ///         ob_client.refresh().await?;
     ///
-    ///     if let Some(ord_ret_type) = ob_client.place_limit_order(
-    ///         target_amount_quote,
-    ///         side,
-    ///         best_offset_usdc,
-    ///         execute,
-    ///         target_price
-    ///     ).await?
-    ///     {
-    ///         match ord_ret_type {
-    ///             OrderReturnType::Instructions(insts) => {
-    ///                 println!("[*] Got Instructions: {:?}", insts);
-    ///             }
-    ///             OrderReturnType::Signature(sign) => {
-    ///                 println!("[*] Transaction successful, signature: {:?}", sign);
-    ///             }
-    ///         }
-    ///     }
+    ///         println!("[*] Best bid: {}", ob_client.open_orders.max_bid);
     ///
-    ///     Ok(())
+    ///         tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    ///     }
     /// }
     /// ```
-    pub async fn place_limit_order(
-        &self,
-        target_amount_quote: f64,
-        side: Side,
-        best_offset_usdc: f64,
-        execute: bool,
-        target_price: f64,
-    ) -> Result<Option<OrderReturnType>, Error> {
-        // coin: base
-        // pc: quote
-        let base_d_factor = 10u32.pow(self.market_info.coin_decimals as u32) as f64;
-        let quote_d_factor = 10u32.pow(self.market_info.pc_decimals as u32) as f64;
-        let base_lot_factor = self.market_info.coin_lot_size as f64;
-        let quote_lot_factor = self.market_info.pc_lot_size as f64;
-
-        let price_factor = quote_d_factor * base_lot_factor / base_d_factor / quote_lot_factor;
-
-        let (input_ata, price) = match side {
-            Side::Bid => {
-                let mut price = self.open_orders.max_bid as f64 / price_factor - best_offset_usdc;
-                if execute {
-                    price = target_price;
-                }
+    pub async fn refresh(&mut self) -> Result<()> {
+        self.ensure_book_initialized()?;
 
-                (&self.quote_ata, price)
-            }
-            Side::Ask => {
-                let mut price = self.open_orders.min_ask as f64 / price_factor + best_offset_usdc;
-                if execute {
-                    price = target_price;
-                }
+        let keys = [
+            self.market_info.market_address,
+            self.market_info.bids_address,
+            self.market_info.asks_address,
+            self.open_orders.oo_key,
+        ];
 
-                (&self.base_ata, price)
-            }
+        let mut accounts = self
+            .rpc_client
+            .fetch_multiple_accounts(&keys)
+            .await?
+            .into_iter();
+        let mut next_account = |key: &Pubkey| {
+            accounts
+                .next()
+                .flatten()
+                .ok_or_else(|| anyhow!("Account {} not found", key))
         };
+        let market_account = next_account(&keys[0])?;
+        let bids_account = next_account(&keys[1])?;
+        let asks_account = next_account(&keys[2])?;
+        let open_orders_account = accounts.next().flatten();
 
-        let limit_price_lots = (price * price_factor) as u64;
-        let target_amount_base = target_amount_quote / price;
-
-        let target_base_lots = (target_amount_base * base_d_factor / base_lot_factor) as u64;
-        let target_quote_lots_w_fee =
-            (target_base_lots as f64 * quote_lot_factor * limit_price_lots as f64) as u64;
-
-        debug!("[*] Using limit price lots: {:?}", limit_price_lots);
-        debug!("[*] Using target base lots: {:?}", target_base_lots);
-
-        if target_base_lots == 0 {
-            debug!(
-                "[*] Got zero base lots, and quote: {:?}",
-                target_amount_quote
+        {
+            let mut market_account = market_account.clone();
+            let market_address = self.market_info.market_address;
+            let program_id = self.market_info.program_id;
+            let account_info = create_account_info_from_account(
+                &mut market_account,
+                &market_address,
+                &program_id,
+                false,
+                false,
             );
-            return Ok(None);
+            self.market_info
+                .load_market_state_info(&account_info)
+                .await?;
         }
 
-        let limit_price = NonZeroU64::new(limit_price_lots).unwrap();
-        let max_coin_qty = NonZeroU64::new(target_base_lots).unwrap();
-        let max_native_pc_qty_including_fees = NonZeroU64::new(target_quote_lots_w_fee).unwrap();
+        // The balances come from the open orders account, the orders from the book walk.
+        match open_orders_account {
+            Some(account) => self.apply_open_orders_account(&account.data)?,
+            None => self.open_orders.mark_missing(),
+        }
+        self.process_book_accounts(market_account, bids_account, asks_account)?;
 
-        let place_order_ix = openbook_dex::instruction::new_order(
-            &self.market_info.market_address,
-            &self.open_orders.oo_key,
-            &self.market_info.request_queue,
-            &self.market_info.event_queue,
-            &self.market_info.bids_address,
-            &self.market_info.asks_address,
-            input_ata,
-            &self.owner.pubkey(),
-            &self.market_info.coin_vault,
-            &self.market_info.pc_vault,
-            &SPL_TOKEN_ID.parse()?,
-            &rent::ID,
-            None,
-            &self.market_info.program_id,
-            side,
-            limit_price,
-            max_coin_qty,
-            OrderType::PostOnly,
-            random::<u64>(),
-            SelfTradeBehavior::AbortTransaction,
-            u16::MAX,
-            max_native_pc_qty_including_fees,
-            (get_unix_secs() + 30) as i64,
-        )?;
+        Ok(())
+    }
 
-        let instructions = vec![place_order_ix];
+    /// Re-reads only the open orders account, without walking the bids and asks.
+    ///
+    /// Updates the wallet's open orders and balances in `self.open_orders`, keeping the best bid
+    /// and ask from the last book load. This is much cheaper than `load_bids_asks_info` after
+    /// placing or cancelling orders. If the open orders account doesn't exist, the wallet's orders
+    /// and balances are zeroed and `open_orders.exists` is set to `false`.
+    ///
+    /// # Arguments
+    ///
+    /// * `&mut self` - A mutable reference to the `ReadOnlyClient` struct.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC request fails or the open orders account data can't be decoded.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     ob_client.refresh_open_orders().await?;
+    ///
+    ///     println!("{:?}", ob_client.open_orders);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn refresh_open_orders(&mut self) -> Result<()> {
+        let account = self
+            .rpc_client
+            .fetch_multiple_accounts(&[self.open_orders.oo_key])
+            .await?
+            .pop()
+            .flatten();
+
+        match account {
+            Some(account) => self.apply_open_orders_account(&account.data),
+            None => {
+                self.open_orders.mark_missing();
+                Ok(())
+            }
+        }
+    }
+
+    /// Applies the raw data of the open orders account to `self.open_orders`.
+    ///
+    /// This is the parsing half of `refresh_open_orders`, usable with account data fetched
+    /// elsewhere (e.g., from a subscription).
+    ///
+    /// # Arguments
+    ///
+    /// * `&mut self` - A mutable reference to the `ReadOnlyClient` struct.
+    /// * `data` - The data of the open orders account.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data can't be decoded as an open orders account.
+    pub fn apply_open_orders_account(&mut self, data: &[u8]) -> Result<()> {
+        let account = OpenOrdersAccount::from_account_data(data)?;
+        let (base_decimals, quote_decimals) =
+            (self.market_info.coin_decimals, self.market_info.pc_decimals);
+
+        self.open_orders
+            .apply_account(&account, base_decimals, quote_decimals);
+        self.open_orders.stamp_orders(get_unix_secs());
+
+        Ok(())
+    }
+
+    /// Loads the order book ladder, up to `depth` price levels per side.
+    ///
+    /// The book is read without touching `self.open_orders`, which keeps the best bid and ask
+    /// from the last `load_bids_asks_info`.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `ReadOnlyClient` struct.
+    /// * `depth` - The maximum number of price levels per side.
+    ///
+    /// # Returns
+    ///
+    /// An `OrderBook` with the aggregated bid and ask levels.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the accounts can't be fetched or the book can't be decoded.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, false, 1000).await?;
+    ///
+    ///     let book = ob_client.load_orderbook_depth(10).await?;
+    ///
+    ///     for (price, size) in book.bids {
+    ///         println!("[*] Bid {} x {}", price, size);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn load_orderbook_depth(&self, depth: usize) -> Result<OrderBook> {
+        self.market_info
+            .load_orderbook_depth(&self.rpc_client, depth)
+            .await
+    }
+
+    /// Estimates the fill of a taker order of `base_qty` against the current book.
+    ///
+    /// The whole book is read with `load_orderbook_depth` and walked from the best price until
+    /// the size is filled, so the expected slippage can be checked before crossing the book. No
+    /// transaction is sent. When the book can't fill the whole size, the estimate of the partial
+    /// fill is returned with `insufficient_liquidity` set.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `ReadOnlyClient` struct.
+    /// * `side` - The side of the taker order: a bid walks the asks and an ask walks the bids.
+    /// * `base_qty` - The base quantity of the order, in UI units.
+    ///
+    /// # Returns
+    ///
+    /// The `FillEstimate` of the order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the accounts can't be fetched or the book can't be decoded.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::matching::Side;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, false, 1000).await?;
+    ///
+    ///     let estimate = ob_client.estimate_fill(Side::Bid, 10.0).await?;
+    ///
+    ///     println!(
+    ///         "[*] Avg price: {}, worst price: {}, filled: {}",
+    ///         estimate.avg_price, estimate.worst_price, estimate.filled_qty
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn estimate_fill(&self, side: Side, base_qty: f64) -> Result<FillEstimate> {
+        let book = self.load_orderbook_depth(usize::MAX).await?;
+        let levels = match side {
+            Side::Bid => &book.asks,
+            Side::Ask => &book.bids,
+        };
+
+        Ok(FillEstimate::from_levels(levels, base_qty))
+    }
+
+    /// Subscribes to the bids and asks accounts and streams the order book on every change.
+    ///
+    /// Updates are pushed by the RPC websocket instead of being polled, and each one is decoded
+    /// with the same slab parsing as `load_orderbook_depth`, keeping the whole ladder. The
+    /// websocket URL comes from the `WS_URL` env var, or is derived from the RPC URL. If the
+    /// websocket drops, the subscription reconnects with a backoff and resyncs the book, so the
+    /// stream can be consumed for days.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `ReadOnlyClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// A stream yielding the current `OrderBook`, then a fresh one on every bids or asks change.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the market account can't be fetched. Later connection errors are
+    /// retried instead of ending the stream.
+    ///
+    /// # Examples
+    ///
+    /// ```rust , ignore
+    /// use futures::StreamExt;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, false, 1000).await?;
+    ///
+    ///     let mut books = Box::pin(ob_client.subscribe_orderbook().await?);
+    ///
+    ///     while let Some(book) = books.next().await {
+    ///         println!("[*] Best bid: {:?}, best ask: {:?}", book.best_bid(), book.best_ask());
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn subscribe_orderbook(&self) -> Result<impl Stream<Item = OrderBook>> {
+        let market_info = self.market_info.clone();
+        let market = self
+            .rpc_client
+            .get_account(&market_info.market_address)
+            .await?;
+        let keys = vec![market_info.bids_address, market_info.asks_address];
+        let ws_url = get_ws_url(&self.rpc_client.inner().url());
+
+        Ok(spawn_account_stream(
+            self.rpc_client.clone(),
+            ws_url,
+            keys,
+            move |accounts| {
+                market_info.orderbook_depth_from_accounts(
+                    &market,
+                    &accounts[0],
+                    &accounts[1],
+                    usize::MAX,
+                )
+            },
+        ))
+    }
+
+    /// Returns the best bid, best ask and mid prices of the loaded book.
+    ///
+    /// Prices come from the book loaded by `load_bids_asks_info`. An empty side yields `None`
+    /// instead of a zero price, and `has_liquidity` is `false` unless both sides have orders.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `ReadOnlyClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// A `MarketPrice` with the UI prices of the book.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::ReadOnlyClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let client = ReadOnlyClient::new(commitment, market_id, true).await?;
+    ///
+    ///     match client.market_price().mid {
+    ///         Some(mid) => println!("[*] Mid: {}", mid),
+    ///         None => println!("[*] No two-sided market"),
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn market_price(&self) -> MarketPrice {
+        let price_factor = self.market_info.price_factor();
+        let to_price = |lots: u64| (lots != 0).then_some(lots as f64 / price_factor);
+
+        let best_bid = to_price(self.open_orders.max_bid);
+        let best_ask = to_price(self.open_orders.min_ask);
+        let mid = best_bid.zip(best_ask).map(|(bid, ask)| (bid + ask) / 2.0);
+
+        MarketPrice {
+            best_bid,
+            best_ask,
+            mid,
+            has_liquidity: mid.is_some(),
+        }
+    }
+
+    /// Returns the best bid and ask prices of the loaded book.
+    ///
+    /// This is the `max_bid` and `min_ask` of `open_orders`, converted with the market's
+    /// `price_factor`, so call `load_bids_asks_info` first to read the current book. An empty
+    /// side is reported as `None`. It mirrors the v2 `get_best_quotes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `ReadOnlyClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// The `BestQuotes` of the loaded book.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::ReadOnlyClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let client = ReadOnlyClient::new(commitment, market_id, true).await?;
+    ///
+    ///     let quotes = client.get_best_quotes().await?;
+    ///
+    ///     println!("[*] Bid: {:?}, ask: {:?}", quotes.highest_bid, quotes.lowest_ask);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn get_best_quotes(&self) -> Result<BestQuotes> {
+        let market_price = self.market_price();
+        Ok(BestQuotes {
+            highest_bid: market_price.best_bid,
+            lowest_ask: market_price.best_ask,
+        })
+    }
+
+    /// Returns the midpoint between the best bid and ask of the loaded book.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `ReadOnlyClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// The mid UI price of the loaded book.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either side of the book is empty; use `get_best_quotes` to handle a
+    /// one-sided book.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::ReadOnlyClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let client = ReadOnlyClient::new(commitment, market_id, true).await?;
+    ///
+    ///     println!("[*] Mid: {}", client.get_mid_price().await?);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn get_mid_price(&self) -> Result<f64> {
+        self.get_best_quotes().await?.mid().ok_or_else(|| {
+            anyhow!(
+                "Market {} has no two-sided book",
+                self.market_info.market_address
+            )
+        })
+    }
+
+    /// Returns the premium of `other`'s mid over this market's mid, in basis points.
+    ///
+    /// This market is the reference (spot) leg; see `utils::premium_bps`. Both books must be
+    /// loaded, and the premium is `None` unless both have a mid.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `ReadOnlyClient` of the reference market.
+    /// * `other` - The client of the other market, such as a perp or another venue.
+    ///
+    /// # Returns
+    ///
+    /// The premium in basis points, negative at a discount.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::ReadOnlyClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let spot = ReadOnlyClient::new(commitment, "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?, true).await?;
+    ///     let other = ReadOnlyClient::new(commitment, "ASUyMMNBpFzpW3zDSPYdDVggKajq1DMKFFPK1JS9hoSR".parse()?, true).await?;
+    ///
+    ///     if let Some(premium) = spot.premium_bps(&other) {
+    ///         println!("[*] Premium: {:.2} bps", premium);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn premium_bps(&self, other: &ReadOnlyClient) -> Option<f64> {
+        let spot_mid = self.market_price().mid?;
+        let other_mid = other.market_price().mid?;
+        Some(premium_bps(spot_mid, other_mid))
+    }
+
+    /// Returns the absolute spread of the loaded book, in quote UI units.
+    ///
+    /// This is `best_ask - best_bid` of `market_price`, so an empty side yields `None`.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `ReadOnlyClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// The spread in quote UI units, or `None` if either side of the book is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::ReadOnlyClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let client = ReadOnlyClient::new(commitment, market_id, true).await?;
+    ///
+    ///     if let Some(spread) = client.spread() {
+    ///         println!("[*] Spread: {}", spread);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn spread(&self) -> Option<f64> {
+        self.market_price().spread()
+    }
+
+    /// Checks whether a PostOnly order at `price` would cross the loaded book.
+    ///
+    /// A bid crosses when its price is at or above the best ask, and an ask crosses when its
+    /// price is at or below the best bid. This is a pure check against the book loaded by
+    /// `load_bids_asks_info`; an empty opposite side never crosses.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `ReadOnlyClient` struct.
+    /// * `side` - The side of the order to check.
+    /// * `price` - The UI price of the order.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the order would be rejected for crossing, `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::matching::Side;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::ReadOnlyClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let client = ReadOnlyClient::new(commitment, market_id, true).await?;
+    ///
+    ///     if client.would_cross(Side::Bid, 2.1) {
+    ///         println!("[*] Bid at 2.1 would cross the book");
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn would_cross(&self, side: Side, price: f64) -> bool {
+        let price_lots = (price * self.market_info.price_factor()) as u64;
+
+        match side {
+            Side::Bid => self.open_orders.min_ask != 0 && price_lots >= self.open_orders.min_ask,
+            Side::Ask => self.open_orders.max_bid != 0 && price_lots <= self.open_orders.max_bid,
+        }
+    }
+
+    /// Lists the valid tick-aligned prices between `low` and `high`, both inclusive.
+    ///
+    /// A tick is one price lot, the smallest price increment the market accepts. The first
+    /// level is the lowest tick at or above `low`, and every following level is `step_ticks`
+    /// ticks above the previous one, up to the highest tick at or below `high`.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `ReadOnlyClient` struct.
+    /// * `low` - The lowest UI price of the range.
+    /// * `high` - The highest UI price of the range.
+    /// * `step_ticks` - The number of ticks between two consecutive levels.
+    ///
+    /// # Returns
+    ///
+    /// The UI prices of the levels, lowest first. The list is empty if `step_ticks` is zero or
+    /// no tick falls within the range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::ReadOnlyClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let client = ReadOnlyClient::new(commitment, market_id, true).await?;
+    ///
+    ///     for price in client.price_levels(2.0, 2.1, 10) {
+    ///         println!("[*] Level: {price}");
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn price_levels(&self, low: f64, high: f64, step_ticks: u64) -> Vec<f64> {
+        let price_factor = self.market_info.price_factor();
+        if step_ticks == 0 || low > high || price_factor <= 0.0 {
+            return Vec::new();
+        }
+
+        // Absorb the float error of `price * price_factor` so exact ticks stay in range.
+        let low_lots = (low * price_factor - 1e-6).ceil().max(1.0) as u64;
+        let high_lots = (high * price_factor + 1e-6).floor().max(0.0) as u64;
+
+        (low_lots..=high_lots)
+            .step_by(step_ticks as usize)
+            .map(|lots| lots as f64 / price_factor)
+            .collect()
+    }
+
+    /// Snaps a UI price to the market tick, the price of one quote lot per base lot.
+    ///
+    /// The `new_order` instruction takes the price in lots, so a price between two ticks is
+    /// truncated when the order is built. This rounds it up for bids and down for asks instead,
+    /// never below one tick, so the caller knows the exact price the order rests at.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `ReadOnlyClient` struct.
+    /// * `price` - The UI price to round.
+    /// * `side` - The side of the order, choosing the rounding direction.
+    ///
+    /// # Returns
+    ///
+    /// The UI price of the tick. A price already on a tick is returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::matching::Side;
+    /// use openbook::v1::ob_client::ReadOnlyClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let client = ReadOnlyClient::new(commitment, market_id, true).await?;
+    ///
+    ///     let price = client.round_price_to_tick(2.00017, Side::Ask);
+    ///     println!("[*] Ask price on the tick: {price}");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn round_price_to_tick(&self, price: f64, side: Side) -> f64 {
+        let price_factor = self.market_info.price_factor();
+        if price_factor <= 0.0 {
+            return price;
+        }
+
+        // Absorb the float error of `price * price_factor` so exact ticks stay unchanged.
+        let lots = price * price_factor;
+        let lots = match side {
+            Side::Bid => (lots - 1e-6).ceil(),
+            Side::Ask => (lots + 1e-6).floor(),
+        };
+
+        lots.max(1.0) / price_factor
+    }
+
+    /// Fetches and decodes the event queue of the market.
+    async fn load_event_queue(&self) -> Result<EventQueue> {
+        let account = self
+            .rpc_client
+            .get_account(&self.market_info.event_queue)
+            .await?;
+
+        EventQueue::from_account_data(&account.data)
+    }
+
+    /// Loads the fills waiting in the event queue of the market.
+    ///
+    /// Fills stay in the queue until a crank consumes them, so this only returns the fills that
+    /// happened since the last `consume_events`. No transaction is sent.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `ReadOnlyClient` struct.
+    /// * `only_own` - Whether to return only the fills of `self.open_orders.oo_key`.
+    ///
+    /// # Returns
+    ///
+    /// The fills with their side, UI price and quantity, owner, order id and maker flag, oldest
+    /// first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC request fails or the event queue can't be decoded.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, false, 1000).await?;
+    ///
+    ///     for fill in ob_client.load_fill_events(true).await? {
+    ///         println!("{:?} {} @ {}", fill.side, fill.quantity, fill.price);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn load_fill_events(&self, only_own: bool) -> Result<Vec<FillEvent>> {
+        let event_queue = self.load_event_queue().await?;
+        let owner = only_own.then_some(&self.open_orders.oo_key);
+
+        Ok(event_queue.fills(&self.market_info, owner))
+    }
+
+    /// Waits until an order of `self.open_orders.oo_key` fills or is canceled.
+    ///
+    /// The open orders and event queue accounts are watched over the RPC websocket (see
+    /// `subscribe_orderbook`), and polled whenever no update arrives for
+    /// `WATCH_ORDER_POLL_INTERVAL`, so the watch keeps working when the websocket is down. The
+    /// fills of the order are summed from the event queue, see `OrderTracker`.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `ReadOnlyClient` struct.
+    /// * `order_id` - The id of the order, as returned by `place_limit_order_tracked`.
+    /// * `timeout` - How long to wait for the order to be done.
+    ///
+    /// # Returns
+    ///
+    /// `Filled` or `Cancelled` once the order leaves the book, `PartiallyFilled` or `Timeout`
+    /// if it is still open after `timeout`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the websocket stream ends. Failed polls are logged and retried.
+    ///
+    /// # Examples
+    ///
+    /// ```rust , ignore
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::events::OrderStatus;
+    /// use openbook::v1::ob_client::OBClient;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     let order_id = 42;
+    ///     match ob_client.watch_order(order_id, Duration::from_secs(60)).await? {
+    ///         OrderStatus::Filled { qty, avg_price } => println!("[*] Filled {qty} @ {avg_price}"),
+    ///         status => println!("[*] Order status: {:?}", status),
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn watch_order(&self, order_id: u128, timeout: Duration) -> Result<OrderStatus> {
+        let deadline = Instant::now() + timeout;
+        let keys = vec![self.open_orders.oo_key, self.market_info.event_queue];
+        let ws_url = get_ws_url(&self.rpc_client.inner().url());
+        let mut tracker = OrderTracker::new(order_id, self.open_orders.oo_key);
+
+        let mut updates = Box::pin(spawn_account_stream(
+            self.rpc_client.clone(),
+            ws_url,
+            keys.clone(),
+            move |accounts| order_observation(order_id, Some(&accounts[0]), &accounts[1]),
+        ));
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(tracker.timeout_status());
+            }
+
+            let wait = remaining.min(WATCH_ORDER_POLL_INTERVAL);
+            let (open, event_queue) = match tokio::time::timeout(wait, updates.next()).await {
+                Ok(Some(observation)) => observation,
+                Ok(None) => return Err(anyhow!("Order subscription closed")),
+                // No update in a while, the websocket may be down: poll instead.
+                Err(_) => match self.poll_order(order_id, &keys).await {
+                    Ok(observation) => observation,
+                    Err(err) => {
+                        debug!("[*] Failed to poll order {}: {}", order_id, err);
+                        continue;
+                    }
+                },
+            };
+
+            if let Some(status) = tracker.observe(&event_queue, &self.market_info, open) {
+                return Ok(status);
+            }
+        }
+    }
+
+    /// Fetches the open orders and event queue accounts watched by `watch_order`.
+    async fn poll_order(&self, order_id: u128, keys: &[Pubkey]) -> Result<(bool, EventQueue)> {
+        let accounts = self.rpc_client.fetch_multiple_accounts(keys).await?;
+        let event_queue = accounts[1]
+            .as_ref()
+            .ok_or_else(|| anyhow!("Event queue {} not found", keys[1]))?;
+
+        order_observation(order_id, accounts[0].as_ref(), event_queue)
+    }
+}
+
+impl OBClient {
+    /// Initializes a new instance of the `OBClient` struct, representing an OpenBook V1 program client.
+    ///
+    /// This method initializes the `OBClient` struct, containing information about the requested market id.
+    /// It fetches and stores all data about this OpenBook market. Additionally, it includes information about
+    /// the account associated with the wallet on the OpenBook market (e.g., open orders, bids, asks, etc.).
+    ///
+    /// # Arguments
+    ///
+    /// * `commitment` - Commitment configuration for transactions, determining the level of finality required.
+    /// * `market_id` - Public key (ID) of the market to fetch information about.
+    /// * `load` - Boolean indicating whether to load market data immediately after initialization.
+    /// * `cache_ts` - Timestamp for caching current open orders, used to manage the cache validity.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` wrapping a new instance of the `OBClient` struct initialized with the provided parameters,
+    /// or an `OpenBookError` if the initialization process fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     println!("Initialized OBClient: {:?}", ob_client);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Business Logic
+    ///
+    /// 1. Retrieve necessary env vars, such as the `RPC_URL` and `KEY_PATH` path.
+    /// 2. Read the owner's keypair with `read_keypair_from_env` (`KEY_BASE58`, `KEY_BYTES`, then `KEY_PATH`).
+    /// 3. Initialize the RPC client with the given commitment configuration.
+    /// 4. Fetch the market account information on chain.
+    /// 5. Load the market state and extract base and quote mints.
+    /// 6. Initialize the `Market` struct with fetched market information (shared with `ReadOnlyClient`).
+    /// 7. Fetche associated token accounts (ATA) for the base and quote tokens. They are only derived, call
+    ///    `ensure_token_accounts`, or build the client with `OBClient::builder` and `create_atas`, to
+    ///    create the missing ones.
+    /// 8. Resolve the open orders account with `resolve_open_orders` (`OOS_KEY` override, or the derived address).
+    ///    The account is not created: if the wallet never traded the market, `open_orders.exists` is `false`
+    ///    and `init_open_orders` must be called before placing orders.
+    /// 9. Populate the open orders cache.
+    /// 10. Load bids and asks information if the `load` parameter is set to `true`. Without an open orders
+    ///    account, the wallet's orders and balances are left zeroed.
+    ///
+    pub async fn new(
+        commitment: CommitmentConfig,
+        market_id: Pubkey,
+        load: bool,
+        cache_ts: u128,
+    ) -> Result<Self, OpenBookError> {
+        Self::new_with_program_id(
+            commitment,
+            market_id,
+            SRM_PROGRAM_ID.parse()?,
+            load,
+            cache_ts,
+        )
+        .await
+    }
+
+    /// Initializes a new instance of the `OBClient` struct for a market of a custom dex deployment,
+    /// such as a fork deployed on a local validator.
+    ///
+    /// The program id is used everywhere `OBClient::new` uses `SRM_PROGRAM_ID`: to check the owner
+    /// of the market account, to derive the open orders account and vault signer, and as the
+    /// program of every instruction built by the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `commitment` - Commitment configuration for RPC requests.
+    /// * `market_id` - Public key (ID) of the market to fetch information about.
+    /// * `program_id` - The dex program owning the market.
+    /// * `load` - Boolean indicating whether to load market data immediately.
+    /// * `cache_ts` - Timestamp for caching current open orders.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` wrapping a new instance of the `OBClient` struct,
+    /// or an `OpenBookError` if the initialization process fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///     let program_id = "srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX".parse()?;
+    ///
+    ///     let ob_client =
+    ///         OBClient::new_with_program_id(commitment, market_id, program_id, true, 1000).await?;
+    ///
+    ///     println!("Initialized OBClient: {:?}", ob_client);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn new_with_program_id(
+        commitment: CommitmentConfig,
+        market_id: Pubkey,
+        program_id: Pubkey,
+        load: bool,
+        cache_ts: u128,
+    ) -> Result<Self, OpenBookError> {
+        let rpc_url = get_rpc_url();
+        let owner = read_keypair_from_env()?;
+        let rpc_client = RpcClient::new_with_commitment(rpc_url, commitment);
+        let oos_key = std::env::var("OOS_KEY")
+            .ok()
+            .and_then(|key| Pubkey::from_str(&key).ok());
+
+        Self::from_parts(
+            Rpc::new(rpc_client),
+            owner,
+            market_id,
+            program_id,
+            oos_key,
+            load,
+            cache_ts,
+        )
+        .await
+    }
+
+    /// Initializes a new instance of the `OBClient` struct from an explicit RPC client and owner,
+    /// without reading any env var.
+    ///
+    /// `OBClient::new` reads the RPC URL and keypair from the environment and is a thin wrapper
+    /// around this method. Clients built with `new_with_rpc` can talk to different RPC nodes, with
+    /// different wallets, in the same process. The open orders account is the one derived from
+    /// the owner, `OOS_KEY` is ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `rpc_client` - The RPC client, whose commitment is used for every request.
+    /// * `owner` - The keypair of the wallet trading on the market.
+    /// * `market_id` - Public key (ID) of the market to fetch information about.
+    /// * `load` - Boolean indicating whether to load market data immediately.
+    /// * `cache_ts` - Timestamp for caching current open orders.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` wrapping a new instance of the `OBClient` struct,
+    /// or an `OpenBookError` if the initialization process fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::keypair::Keypair;
+    /// use openbook::rpc_client::RpcClient;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let rpc_client = RpcClient::new_with_commitment(
+    ///         "https://api.mainnet-beta.solana.com".to_string(),
+    ///         CommitmentConfig::confirmed(),
+    ///     );
+    ///     let owner = Keypair::new();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new_with_rpc(rpc_client, owner, market_id, true, 1000).await?;
+    ///
+    ///     println!("Initialized OBClient: {:?}", ob_client);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn new_with_rpc(
+        rpc_client: RpcClient,
+        owner: Keypair,
+        market_id: Pubkey,
+        load: bool,
+        cache_ts: u128,
+    ) -> Result<Self, OpenBookError> {
+        Self::from_parts(
+            Rpc::new(rpc_client),
+            owner,
+            market_id,
+            SRM_PROGRAM_ID.parse()?,
+            None,
+            load,
+            cache_ts,
+        )
+        .await
+    }
+
+    /// Returns an `OBClientBuilder` for the market, to build a client with options
+    /// `OBClient::new` doesn't take, such as creating the wallet's token accounts on startup.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::builder(market_id)
+    ///         .commitment(CommitmentConfig::confirmed())
+    ///         .load(true)
+    ///         .cache_ts(1000)
+    ///         .create_atas(true)
+    ///         .build()
+    ///         .await?;
+    ///
+    ///     println!("Initialized OBClient: {:?}", ob_client);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn builder(market_id: Pubkey) -> OBClientBuilder {
+        OBClientBuilder {
+            market_id,
+            commitment: CommitmentConfig::confirmed(),
+            program_id: None,
+            rpc_client: None,
+            owner: None,
+            load: false,
+            cache_ts: 0,
+            create_atas: false,
+        }
+    }
+
+    /// Builds the client shared by `new_with_program_id`, `new_with_rpc` and `OBClientBuilder`,
+    /// from explicit configuration.
+    async fn from_parts(
+        rpc_client: Rpc,
+        owner: Keypair,
+        market_id: Pubkey,
+        program_id: Pubkey,
+        oos_key: Option<Pubkey>,
+        load: bool,
+        cache_ts: u128,
+    ) -> Result<Self, OpenBookError> {
+        let pub_owner_key = owner.pubkey().clone();
+
+        let market_info =
+            ReadOnlyClient::load_market(rpc_client.clone(), market_id, program_id, load).await?;
+
+        let base_ata = get_associated_token_address(&pub_owner_key.clone(), &market_info.base_mint);
+        let quote_ata =
+            get_associated_token_address(&pub_owner_key.clone(), &market_info.quote_mint);
+
+        let open_orders = match oos_key {
+            Some(_) => {
+                let oo_key = resolve_open_orders(
+                    &pub_owner_key,
+                    &market_info.market_address,
+                    &market_info.program_id,
+                    oos_key,
+                );
+                OpenOrders {
+                    oo_key,
+                    exists: open_orders_initialized(&rpc_client, &oo_key, &program_id).await?,
+                    ..Default::default()
+                }
+            }
+            None => {
+                let cloned_owner = owner.insecure_clone();
+                OpenOrders::new(
+                    rpc_client.clone(),
+                    program_id,
+                    cloned_owner,
+                    market_info.market_address,
+                )
+                .await?
+            }
+        };
+        let mut open_orders_cache = HashMap::new();
+
+        let open_orders_cache_entry = OpenOrdersCacheEntry {
+            open_orders: open_orders.clone(),
+            ts: cache_ts,
+        };
+
+        open_orders_cache.insert(pub_owner_key, open_orders_cache_entry.clone());
+
+        let mut ob_client = Self {
+            owner: owner.into(),
+            reader: ReadOnlyClient {
+                rpc_client,
+                market_info,
+                open_orders,
+            },
+            quote_ata,
+            base_ata,
+            open_orders_cache,
+            last_quote_mid: None,
+            compute_unit_margin_percent: DEFAULT_COMPUTE_UNIT_MARGIN_PERCENT,
+            unwrap_sol: false,
+            wrap_sol: false,
+            round_to_tick: false,
+            referrer: None,
+            max_price_deviation_percent: DEFAULT_MAX_PRICE_DEVIATION_PERCENT,
+        };
+
+        if load {
+            ob_client.load_bids_asks_info().await?;
+            if !ob_client.open_orders.exists {
+                ob_client.open_orders.mark_missing();
+            }
+        }
+
+        let open_orders = ob_client.open_orders.clone();
+        if let Some(entry) = ob_client.open_orders_cache.get_mut(&pub_owner_key) {
+            entry.open_orders = open_orders;
+        }
+
+        Ok(ob_client)
+    }
+
+    /// Returns a copy of the client sending its transactions with `commitment`.
+    ///
+    /// This overrides the commitment of the client for one operation, such as placing orders with
+    /// `processed` for speed and settling with `finalized` for safety. The commitment is used for
+    /// the blockhash and the preflight simulation; reads keep the commitment of the client. See
+    /// `Rpc::with_send_commitment`.
+    ///
+    /// # Arguments
+    ///
+    /// * `commitment` - The commitment of the transactions sent by the returned client.
+    ///
+    /// # Returns
+    ///
+    /// A clone of the client, with its own open orders state.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     let result = ob_client
+    ///         .with_commitment(CommitmentConfig::finalized())
+    ///         .settle_balance(true)
+    ///         .await?;
+    ///
+    ///     println!("Settle result: {:?}", result);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn with_commitment(&self, commitment: CommitmentConfig) -> Self {
+        let mut ob_client = self.clone();
+        ob_client.rpc_client = ob_client
+            .rpc_client
+            .clone()
+            .with_send_commitment(commitment);
+        ob_client
+    }
+
+    /// Returns a copy of the client targeting the dex deployment `program_id`, such as a fork
+    /// deployed on a local validator.
+    ///
+    /// The market is reloaded with `program_id`, which must own the market account, and the open
+    /// orders account and vault signer are derived again from it. Every instruction built by the
+    /// returned client uses `program_id`. An open orders account given explicitly (e.g. with
+    /// `OOS_KEY`) is kept. The other settings of the client are carried over.
+    ///
+    /// # Arguments
+    ///
+    /// * `program_id` - The dex program owning the market.
+    ///
+    /// # Returns
+    ///
+    /// A new client on the same market and wallet, loaded from the `program_id` deployment.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the market isn't owned by `program_id` or if any RPC request fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///     let program_id = "srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true, 1000)
+    ///         .await?
+    ///         .with_program_id(program_id)
+    ///         .await?;
+    ///
+    ///     println!("Initialized OBClient: {:?}", ob_client);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn with_program_id(&self, program_id: Pubkey) -> Result<Self, OpenBookError> {
+        let owner = self.owner.pubkey();
+        let market_address = self.market_info.market_address;
+        let derived =
+            resolve_open_orders(&owner, &market_address, &self.market_info.program_id, None);
+        let oos_key = (self.open_orders.oo_key != derived).then_some(self.open_orders.oo_key);
+        let cache_ts = self
+            .open_orders_cache
+            .get(&owner)
+            .map_or(0, |entry| entry.ts);
+
+        let ob_client = Self::from_parts(
+            self.rpc_client.clone(),
+            self.owner.insecure_clone(),
+            market_address,
+            program_id,
+            oos_key,
+            true,
+            cache_ts,
+        )
+        .await?;
+
+        Ok(Self {
+            last_quote_mid: self.last_quote_mid,
+            compute_unit_margin_percent: self.compute_unit_margin_percent,
+            unwrap_sol: self.unwrap_sol,
+            wrap_sol: self.wrap_sol,
+            round_to_tick: self.round_to_tick,
+            referrer: self.referrer,
+            max_price_deviation_percent: self.max_price_deviation_percent,
+            ..ob_client
+        })
+    }
+
+    /// Creates the wallet's open orders account on the market if it doesn't exist yet.
+    ///
+    /// A wallet that never traded the market has no open orders account after `OBClient::new`,
+    /// as reported by `open_orders.exists`. Orders can only be placed once it exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `&mut self` - A mutable reference to the `OBClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// The public key of the open orders account.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC request fails or the creation transaction doesn't confirm.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     if !ob_client.open_orders.exists {
+    ///         let oo_key = ob_client.init_open_orders().await?;
+    ///         println!("Created open orders account {}", oo_key);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn init_open_orders(&mut self) -> Result<Pubkey, OpenBookError> {
+        if self.open_orders.exists {
+            return Ok(self.open_orders.oo_key);
+        }
+
+        let rpc_client = self.rpc_client.clone();
+        let (program_id, market_address) =
+            (self.market_info.program_id, self.market_info.market_address);
+        let owner = self.owner.clone();
+        let oo_key = self
+            .open_orders
+            .make_create_account_transaction(&rpc_client, program_id, &owner, market_address)
+            .await?;
+
+        if !self.open_orders.exists {
+            return Err(anyhow!("Failed to create open orders account {}", oo_key).into());
+        }
+
+        let open_orders = self.open_orders.clone();
+        if let Some(entry) = self.open_orders_cache.get_mut(&self.owner.pubkey()) {
+            entry.open_orders = open_orders;
+        }
+
+        Ok(oo_key)
+    }
+
+    /// Creates the wallet's open orders account on the market only if it's missing on chain.
+    ///
+    /// Unlike `init_open_orders`, which trusts `open_orders.exists` as loaded by `OBClient::new`,
+    /// the account is looked up on chain first, so calling this at every startup is safe: it
+    /// sends nothing once the account exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `&mut self` - A mutable reference to the `OBClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// The signatures of the creation transactions, empty if the account already existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC request fails or the creation transaction doesn't confirm.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     let signatures = ob_client.ensure_accounts().await?;
+    ///     println!("Created {} accounts", signatures.len());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn ensure_accounts(&mut self) -> Result<Vec<Signature>, OpenBookError> {
+        let oo_key = self.open_orders.oo_key;
+        let exists =
+            open_orders_initialized(&self.rpc_client, &oo_key, &self.market_info.program_id)
+                .await?;
+
+        let mut signatures = Vec::new();
+        if exists {
+            self.open_orders.exists = true;
+        } else {
+            let rpc_client = self.rpc_client.clone();
+            let (program_id, market_address) =
+                (self.market_info.program_id, self.market_info.market_address);
+            let owner = self.owner.clone();
+            let (oo_key, signature) = self
+                .open_orders
+                .create_account(&rpc_client, program_id, &owner, market_address)
+                .await?;
+            match signature {
+                Some(signature) => signatures.push(signature),
+                None => {
+                    return Err(anyhow!("Failed to create open orders account {}", oo_key).into())
+                }
+            }
+        }
+
+        let open_orders = self.open_orders.clone();
+        if let Some(entry) = self.open_orders_cache.get_mut(&self.owner.pubkey()) {
+            entry.open_orders = open_orders;
+        }
+
+        Ok(signatures)
+    }
+
+    /// Creates the wallet's base and quote associated token accounts if they are missing on chain.
+    ///
+    /// `base_ata` and `quote_ata` are derived by `OBClient::new` but never created, while settling
+    /// and placing orders need them, so a fresh wallet should call this once at startup. Both
+    /// accounts are looked up with a single request, and nothing is sent when they exist, so it is
+    /// cheap to call every time.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// The signature of the creation transaction, or `None` if both accounts already existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC request fails or the creation transaction doesn't confirm.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     if let Some(signature) = ob_client.ensure_token_accounts().await? {
+    ///         println!("[*] Created the token accounts: {:?}", signature);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn ensure_token_accounts(&self) -> Result<Option<Signature>, OpenBookError> {
+        let accounts = self
+            .rpc_client
+            .fetch_multiple_accounts(&[self.base_ata, self.quote_ata])
+            .await?;
+        let instructions =
+            self.build_create_atas_instructions(accounts[0].is_some(), accounts[1].is_some())?;
+        if instructions.is_empty() {
+            return Ok(None);
+        }
+
+        let (confirmed, signature) = self
+            .rpc_client
+            .send_and_confirm((*self.owner).insecure_clone(), instructions)
+            .await?;
+        if !confirmed {
+            return Err(anyhow!("Failed to create the token accounts").into());
+        }
+
+        Ok(Some(signature))
+    }
+
+    /// Fetches the wallet's token balances and open orders balances in a single batch.
+    ///
+    /// The base ATA, quote ATA and open orders account are fetched with one `getMultipleAccounts`
+    /// call, so all balances come from the same slot.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// A `WalletSnapshot` with the ATA balances, the settleable (free) balances and the balances
+    /// locked in open orders.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC request fails or the open orders account cannot be decoded.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     let snapshot = ob_client.wallet_snapshot().await?;
+    ///
+    ///     println!("{:?}", snapshot);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn wallet_snapshot(&self) -> Result<WalletSnapshot, OpenBookError> {
+        let accounts = self
+            .rpc_client
+            .fetch_multiple_accounts(&[self.base_ata, self.quote_ata, self.open_orders.oo_key])
+            .await?;
+
+        Ok(WalletSnapshot::from_accounts(
+            accounts.first().and_then(Option::as_ref),
+            accounts.get(1).and_then(Option::as_ref),
+            accounts.get(2).and_then(Option::as_ref),
+            self.market_info.coin_decimals,
+            self.market_info.pc_decimals,
+        )?)
+    }
+
+    /// Fetches the wallet's token and open orders balances, valued at the market price.
+    ///
+    /// The balances come from `wallet_snapshot`, and the base tokens are valued at the mid price
+    /// of the loaded book, or at its only side if the book is one-sided.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    ///
+    /// # Returns
+    ///
+    /// A `Balances` with the ATA balances, the open orders balances and their total value in
+    /// quote. The total only counts quote tokens if the book is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC request fails or the open orders account cannot be decoded.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     let balances = ob_client.get_balances().await?;
+    ///
+    ///     println!("[*] Portfolio value: {}", balances.total_balance);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn get_balances(&self) -> Result<Balances, OpenBookError> {
+        let snapshot = self.wallet_snapshot().await?;
+        let market_price = self.market_price();
+        let price = market_price
+            .mid
+            .or(market_price.best_bid)
+            .or(market_price.best_ask)
+            .unwrap_or_default();
+
+        Ok(Balances::from_snapshot(&snapshot, price))
+    }
+
+    /// Checks that the client is ready to trade, running every check instead of failing on the
+    /// first problem.
+    ///
+    /// The market, open orders, wallet and token accounts are fetched in a single
+    /// `getMultipleAccounts` call, which also tells whether the RPC node is reachable.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `min_sol_lamports` - The minimum SOL balance of the wallet, in lamports, to pay fees.
+    ///
+    /// # Returns
+    ///
+    /// A `HealthReport` with the outcome of each check and a description of the failed ones.
+    ///
+    /// # Errors
+    ///
+    /// Failed checks, including an unreachable RPC node, are reported in the `HealthReport`
+    /// rather than as an error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     let report = ob_client.health_check(10_000_000).await?;
+    ///
+    ///     for error in &report.errors {
+    ///         println!("[!] {}", error);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn health_check(&self, min_sol_lamports: u64) -> Result<HealthReport, OpenBookError> {
+        let accounts = HealthCheckAccounts {
+            program_id: self.market_info.program_id,
+            market: self.market_info.market_address,
+            market_state_loaded: self.market_info.bids_address != Pubkey::default()
+                && self.market_info.asks_address != Pubkey::default(),
+            open_orders: self.open_orders.oo_key,
+            owner: self.owner.pubkey(),
+            base_ata: self.base_ata,
+            quote_ata: self.quote_ata,
+        };
+
+        Ok(accounts.check(&self.rpc_client, min_sol_lamports).await)
+    }
+
+    /// Estimates how much quote and SOL is needed to place a bid of `amount` base tokens at `price`.
+    ///
+    /// The quote needed is the price times the size, both rounded down to lots, plus the market's
+    /// taker fee on it. That's what a bid that may cross the book locks when placed with
+    /// `place_limit_order`; a `PostOnly` bid locks the same quote without the fee. Rent is
+    /// included for the open orders account and ATAs that don't exist yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `price` - The UI price of the bid.
+    /// * `amount` - The amount of base tokens to buy.
+    ///
+    /// # Returns
+    ///
+    /// A `CostEstimate` with the quote needed and the lamports for rent and fees.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the RPC requests fail.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     let estimate = ob_client.bid_cost_estimate(2.0, 10.0).await?;
+    ///
+    ///     println!("{:?}", estimate);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn bid_cost_estimate(
+        &self,
+        price: f64,
+        amount: f64,
+    ) -> Result<CostEstimate, OpenBookError> {
+        let base_d_factor = 10u64.pow(self.market_info.coin_decimals as u32) as f64;
+        let quote_d_factor = 10u64.pow(self.market_info.pc_decimals as u32) as f64;
+
+        let limit_price_lots = (price * self.market_info.price_factor()) as u64;
+        let base_lots = (amount * base_d_factor / self.market_info.coin_lot_size as f64) as u64;
+        let native_quote = base_lots * limit_price_lots * self.market_info.pc_lot_size;
+        let native_quote = native_quote + self.market_info.taker_fee(native_quote);
+
+        let accounts = self
+            .rpc_client
+            .fetch_multiple_accounts(&[self.open_orders.oo_key, self.base_ata, self.quote_ata])
+            .await?;
+        let open_orders_rent = self
+            .rpc_client
+            .get_minimum_balance_for_rent_exemption(OPEN_ORDERS_ACCOUNT_SIZE)
+            .await?;
+        let token_account_rent = self
+            .rpc_client
+            .get_minimum_balance_for_rent_exemption(TOKEN_ACCOUNT_SIZE)
+            .await?;
+
+        Ok(CostEstimate::from_accounts(
+            native_quote as f64 / quote_d_factor,
+            accounts.first().and_then(Option::as_ref),
+            accounts.get(1).and_then(Option::as_ref),
+            accounts.get(2).and_then(Option::as_ref),
+            open_orders_rent,
+            token_account_rent,
+        ))
+    }
+
+    /// Records the mid price of the quote that was just placed, for use by `should_requote`.
+    ///
+    /// `cancel_settle_place` records it automatically once its transaction is confirmed.
+    ///
+    /// # Arguments
+    ///
+    /// * `&mut self` - A mutable reference to the `OBClient` struct.
+    /// * `mid` - The mid price of the last quote.
+    pub fn record_quote_mid(&mut self, mid: f64) {
+        self.last_quote_mid = Some(mid);
+    }
+
+    /// Decides whether quotes should be refreshed given the current mid price.
+    ///
+    /// Returns `true` when no quote has been recorded yet, or when `current_mid` moved by more
+    /// than `threshold_bps` basis points from the mid recorded at the last quote.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `current_mid` - The current mid price of the market.
+    /// * `threshold_bps` - The minimum move, in basis points, that triggers a requote.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the client should requote, `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     ob_client.record_quote_mid(2.0);
+    ///
+    ///     if ob_client.should_requote(2.01, 25.0) {
+    ///         println!("[*] Mid moved more than 25 bps, requoting");
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn should_requote(&self, current_mid: f64, threshold_bps: f64) -> bool {
+        match self.last_quote_mid {
+            Some(last_mid) if last_mid > 0. => {
+                let move_bps = (current_mid - last_mid).abs() / last_mid * 10_000.;
+                move_bps > threshold_bps
+            }
+            _ => true,
+        }
+    }
+
+    /// Places a limit order on the market.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `target_amount_quote` - The target amount in quote currency for the order.
+    /// * `side` - The side of the order (buy or sell).
+    /// * `best_offset_usdc` - The best offset in USDC for the order.
+    /// * `execute` - A boolean indicating whether to execute the order immediately.
+    /// * `target_price` - The target price for the order.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the transaction signature if successful,
+    /// or an error if placing the limit order fails. No order is placed when pricing off the book
+    /// (`use_target_price` is `false`) and `market_price` reports no liquidity.
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if there is an issue with creating or sending the transaction.
+    /// Returns `OpenBookError::PriceOffMarket` if the price is further than
+    /// `max_price_deviation_percent` from the mid; use `place_limit_order_with_params` with
+    /// `allow_off_market` to place it anyway.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::matching::Side;
+    /// use openbook::v1::orders::OrderReturnType;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     let target_amount_quote = 5.0;
+    ///     let side = Side::Bid; // or Side::Ask
+    ///     let best_offset_usdc = 5.0;
+    ///     let execute = true;
+    ///     let target_price = 2.1;
+    ///
+    ///     if let Some(ord_ret_type) = ob_client.place_limit_order(
+    ///         target_amount_quote,
+    ///         side,
+    ///         best_offset_usdc,
+    ///         execute,
+    ///         target_price
+    ///     ).await?
+    ///     {
+    ///         match ord_ret_type {
+    ///             OrderReturnType::Instructions(insts) => {
+    ///                 println!("[*] Got Instructions: {:?}", insts);
+    ///             }
+    ///             OrderReturnType::Signature(sign) => {
+    ///                 println!("[*] Transaction successful, signature: {:?}", sign);
+    ///             }
+    ///         }
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn place_limit_order(
+        &self,
+        target_amount_quote: f64,
+        side: Side,
+        best_offset_usdc: f64,
+        execute: bool,
+        target_price: f64,
+    ) -> Result<Option<OrderReturnType>, OpenBookError> {
+        self.place_limit_order_with_params(
+            target_amount_quote,
+            side,
+            best_offset_usdc,
+            execute,
+            target_price,
+            LimitOrderParams::default(),
+        )
+        .await
+    }
+
+    /// Places a limit order on the market with a custom order type, self trade behavior, client
+    /// order id and expiry.
+    ///
+    /// `place_limit_order` is this method with `LimitOrderParams::default()`: a `PostOnly` order
+    /// aborting on self trades, with a random client order id, expiring after 30 seconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `target_amount_quote` - The target amount in quote currency for the order.
+    /// * `side` - The side of the order (buy or sell).
+    /// * `best_offset_usdc` - The best offset in USDC for the order.
+    /// * `execute` - A boolean indicating whether to execute the order immediately.
+    /// * `target_price` - The target price for the order.
+    /// * `params` - The order type, self trade behavior, client order id and expiry of the order.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the transaction signature if successful,
+    /// or an error if placing the limit order fails.
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if there is an issue with creating or sending the transaction.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::matching::{OrderType, Side};
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    /// use openbook::v1::orders::{Expiry, LimitOrderParams};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     let params = LimitOrderParams {
+    ///         order_type: OrderType::Limit,
+    ///         client_order_id: Some(42),
+    ///         expiry: Expiry::Seconds(120),
+    ///         ..Default::default()
+    ///     };
+    ///
+    ///     let result = ob_client
+    ///         .place_limit_order_with_params(5.0, Side::Bid, 0.0, true, 2.1, params)
+    ///         .await?;
+    ///
+    ///     println!("{:?}", result);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn place_limit_order_with_params(
+        &self,
+        target_amount_quote: f64,
+        side: Side,
+        best_offset_usdc: f64,
+        execute: bool,
+        target_price: f64,
+        params: LimitOrderParams,
+    ) -> Result<Option<OrderReturnType>, OpenBookError> {
+        Ok(self
+            .place_limit_order_outcome(
+                target_amount_quote,
+                side,
+                best_offset_usdc,
+                execute,
+                target_price,
+                params,
+            )
+            .await?
+            .into())
+    }
+
+    /// Places a limit order like `place_limit_order_with_params`, telling apart the reasons the
+    /// order isn't placed.
+    ///
+    /// `place_limit_order` returns `Ok(None)` both when the size rounds down to zero base lots and
+    /// when the book is empty; this method returns `BelowMinimumSize` and `EmptyBook` instead, so
+    /// the caller can e.g. increase the size and retry.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `target_amount_quote` - The target amount in quote currency for the order.
+    /// * `side` - The side of the order (buy or sell).
+    /// * `best_offset_usdc` - The best offset in USDC for the order.
+    /// * `execute` - A boolean indicating whether to execute the order immediately.
+    /// * `target_price` - The target price for the order.
+    /// * `params` - The order type, self trade behavior, client order id and expiry of the order.
+    ///
+    /// # Returns
+    ///
+    /// The signature of the sent order, its instructions when not executed, or why no order was
+    /// built.
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if there is an issue with creating or sending the transaction.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::matching::Side;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    /// use openbook::v1::orders::{LimitOrderParams, PlaceOrderOutcome};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     match ob_client
+    ///         .place_limit_order_outcome(0.001, Side::Bid, 0.0, true, 2.1, LimitOrderParams::default())
+    ///         .await?
+    ///     {
+    ///         PlaceOrderOutcome::Submitted(sign) => println!("[*] Order sent: {:?}", sign),
+    ///         PlaceOrderOutcome::BelowMinimumSize { .. } => println!("[*] Order too small"),
+    ///         outcome => println!("[*] {:?}", outcome),
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn place_limit_order_outcome(
+        &self,
+        target_amount_quote: f64,
+        side: Side,
+        best_offset_usdc: f64,
+        execute: bool,
+        target_price: f64,
+        params: LimitOrderParams,
+    ) -> Result<PlaceOrderOutcome, OpenBookError> {
+        let price = match self.limit_order_price(side, best_offset_usdc, execute, target_price) {
+            Some(price) => price,
+            None => return Ok(PlaceOrderOutcome::EmptyBook),
+        };
+        let place_order_ix =
+            match self.build_new_order_instruction(target_amount_quote, side, price, &params)? {
+                Some(ix) => ix,
+                None => {
+                    return Ok(PlaceOrderOutcome::BelowMinimumSize {
+                        computed_base_lots: self.target_base_lots(target_amount_quote, price),
+                    })
+                }
+            };
+
+        let instructions = self.with_sol_wrapping(side, place_order_ix).await?;
+
+        if !execute {
+            return Ok(PlaceOrderOutcome::Instructions(instructions));
+        }
+
+        let (_, signature) = self
+            .send_and_report(instructions, &TxConfig::default())
+            .await?;
+
+        Ok(PlaceOrderOutcome::Submitted(signature))
+    }
+
+    /// Places several limit orders with as few transactions as possible.
+    ///
+    /// The `new_order` instructions of all orders are packed into transactions up to the
+    /// transaction size limit, so a grid of orders is sent with one or a few transactions instead
+    /// of one per order. Orders whose size rounds down to zero base lots are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `orders` - The orders to place, each with its side, size, price and params.
+    /// * `max_ix_per_tx` - The maximum number of orders per transaction, usually
+    ///   `DEFAULT_MAX_IX_PER_TX`.
+    ///
+    /// # Returns
+    ///
+    /// The confirmation status and signature of each transaction sent, in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an instruction can't be built or a transaction fails to send. The
+    /// transactions sent before the failure are not rolled back.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::matching::Side;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    /// use openbook::v1::orders::{LimitOrderParams, LimitOrderSpec};
+    /// use openbook::utils::DEFAULT_MAX_IX_PER_TX;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     let grid = (1..=10)
+    ///         .map(|level| LimitOrderSpec {
+    ///             side: Side::Bid,
+    ///             target_amount_quote: 5.0,
+    ///             price: 2.0 - level as f64 * 0.01,
+    ///             params: LimitOrderParams::default(),
+    ///         })
+    ///         .collect();
+    ///
+    ///     for (confirmed, signature) in ob_client
+    ///         .place_orders_batch(grid, DEFAULT_MAX_IX_PER_TX)
+    ///         .await?
+    ///     {
+    ///         println!("[*] Batch {} confirmed: {}", signature, confirmed);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn place_orders_batch(
+        &self,
+        orders: Vec<LimitOrderSpec>,
+        max_ix_per_tx: usize,
+    ) -> Result<Vec<(bool, Signature)>, OpenBookError> {
+        let mut results = Vec::new();
+
+        for instructions in self.build_orders_batch(&orders, max_ix_per_tx)? {
+            let result = self
+                .send_and_report(instructions, &TxConfig::default())
+                .await?;
+            results.push(result);
+        }
+
+        Ok(results)
+    }
+
+    /// Builds the `new_order` instructions of `orders`, split into transaction-sized batches.
+    ///
+    /// Orders whose size rounds down to zero base lots are skipped. Use `place_orders_batch` to
+    /// also send the batches.
+    pub fn build_orders_batch(
+        &self,
+        orders: &[LimitOrderSpec],
+        max_ix_per_tx: usize,
+    ) -> Result<Vec<Vec<Instruction>>, OpenBookError> {
+        let mut instructions = Vec::with_capacity(orders.len());
+
+        for order in orders {
+            match self.build_new_order_instruction(
+                order.target_amount_quote,
+                order.side,
+                order.price,
+                &order.params,
+            )? {
+                Some(ix) => instructions.push(ix),
+                None => debug!("[*] Skipping order with zero base lots: {:?}", order),
+            }
+        }
+
+        Ok(pack_instructions(
+            instructions,
+            &self.owner.pubkey(),
+            max_ix_per_tx,
+        ))
+    }
+
+    /// Places a limit order and returns its signature together with the order id assigned by the
+    /// program and the slot it landed in.
+    ///
+    /// The order is placed with a random client order id. The v1 program doesn't log the order
+    /// ids it assigns, so once the transaction has landed, the open orders account is read as of
+    /// the slot of the transaction or later, and the order with that client order id is looked up
+    /// in it, without re-walking the book. An order that was filled as it was placed, or canceled
+    /// before the read, is no longer there: it is still reported as placed, without an order id,
+    /// so callers don't place it again.
+    ///
+    /// Like `place_limit_order`, native SOL is wrapped around the order when `wrap_sol` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `target_amount_quote` - The target amount in quote currency for the order.
+    /// * `side` - The side of the order (buy or sell).
+    /// * `target_price` - The limit price of the order.
+    ///
+    /// # Returns
+    ///
+    /// A `PlacedOrder` with the signature, order id, client order id and slot, or `None` if the
+    /// order size rounds down to zero base lots.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction doesn't land within `TRACKED_ORDER_LANDING_TIMEOUT` or
+    /// fails on chain, or if the transaction or the open orders account can't be read back.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::matching::Side;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     if let Some(placed) = ob_client.place_limit_order_tracked(5.0, Side::Bid, 2.1).await? {
+    ///         match placed.order_id {
+    ///             Some(order_id) => println!("[*] Order {} rests on the book", order_id),
+    ///             None => println!("[*] Order filled in slot {}", placed.slot),
+    ///         }
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn place_limit_order_tracked(
+        &self,
+        target_amount_quote: f64,
+        side: Side,
+        target_price: f64,
+    ) -> Result<Option<PlacedOrder>, OpenBookError> {
+        let client_order_id = random::<u64>();
+        let params = LimitOrderParams {
+            client_order_id: Some(client_order_id),
+            ..Default::default()
+        };
+        let place_order_ix = match self.build_limit_order_instruction(
+            target_amount_quote,
+            side,
+            0.,
+            true,
+            target_price,
+            &params,
+        )? {
+            Some(ix) => ix,
+            None => return Ok(None),
+        };
+
+        let instructions = self.with_sol_wrapping(side, place_order_ix).await?;
+
+        let (confirmed, signature) = self
+            .send_and_report(instructions, &TxConfig::default())
+            .await?;
+        if !confirmed {
+            return Err(anyhow!("Transaction {} was not confirmed", signature).into());
+        }
+
+        // Confirmation is only reported once the transaction is sent, so wait for it to land
+        // before reading the open orders account.
+        let deadline = Instant::now() + TRACKED_ORDER_LANDING_TIMEOUT;
+        while self
+            .rpc_client
+            .get_signature_status(&signature)
+            .await?
+            .is_none()
+        {
+            if Instant::now() >= deadline {
+                return Err(anyhow!("Transaction {} did not land", signature).into());
+            }
+            sleep(Duration::from_millis(DEFAULT_MS_PER_SLOT)).await;
+        }
+        let transaction = self.rpc_client.fetch_transaction(&signature).await?;
+        if let Some(err) = transaction.transaction.meta.and_then(|meta| meta.err) {
+            return Err(anyhow!("Transaction {} failed: {}", signature, err).into());
+        }
+        let slot = transaction.slot;
+        let order_id = self
+            .order_id_for_client_id_since(client_order_id, slot)
+            .await?;
+        if order_id.is_none() {
+            debug!(
+                "[*] Order with client order id {} no longer rests in the open orders account",
+                client_order_id
+            );
+        }
+
+        Ok(Some(PlacedOrder {
+            signature,
+            order_id,
+            client_order_id,
+            slot,
+        }))
+    }
+
+    /// Places a limit order and returns an `OrderGuard` canceling it when dropped.
+    ///
+    /// This is meant for scoped quoting, where orders must never outlive the code (or the
+    /// process) that placed them. The guard and the on-chain expiry of `params.expiry` are
+    /// independent safety nets: the expiry holds even if the process dies before the guard can
+    /// cancel, and the guard cancels orders that would otherwise rest until they expire. The
+    /// order is tracked by its client order id, generated when `params.client_order_id` is `None`.
+    ///
+    /// Like `place_limit_order`, native SOL is wrapped around the order when `wrap_sol` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `target_amount_quote` - The target amount in quote currency for the order.
+    /// * `side` - The side of the order (buy or sell).
+    /// * `target_price` - The limit price of the order.
+    /// * `params` - The order type, self trade behavior, client order id and expiry of the order.
+    ///
+    /// # Returns
+    ///
+    /// The guard of the placed order, or `None` if the order size rounds down to zero base lots.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction fails or is not confirmed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::matching::Side;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    /// use openbook::v1::orders::{Expiry, LimitOrderParams};
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     let params = LimitOrderParams {
+    ///         expiry: Expiry::from(Duration::from_secs(3_600)),
+    ///         ..Default::default()
+    ///     };
+    ///     if let Some(guard) = ob_client
+    ///         .place_limit_order_guarded(5.0, Side::Bid, 2.1, params)
+    ///         .await?
+    ///     {
+    ///         // Quote while the guard is alive, then cancel and wait for the cancel to land.
+    ///         guard.cancel().await?;
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn place_limit_order_guarded(
+        &self,
+        target_amount_quote: f64,
+        side: Side,
+        target_price: f64,
+        params: LimitOrderParams,
+    ) -> Result<Option<OrderGuard>, OpenBookError> {
+        let client_order_id = params.client_order_id.unwrap_or_else(random::<u64>);
+        let params = LimitOrderParams {
+            client_order_id: Some(client_order_id),
+            ..params
+        };
+        let place_order_ix = match self.build_limit_order_instruction(
+            target_amount_quote,
+            side,
+            0.,
+            true,
+            target_price,
+            &params,
+        )? {
+            Some(ix) => ix,
+            None => return Ok(None),
+        };
+
+        let instructions = self.with_sol_wrapping(side, place_order_ix).await?;
+
+        let (confirmed, signature) = self
+            .send_and_report(instructions, &TxConfig::default())
+            .await?;
+        if !confirmed {
+            return Err(anyhow!("Transaction {} was not confirmed", signature).into());
+        }
+
+        Ok(Some(OrderGuard {
+            client: self.clone(),
+            client_order_id,
+            signature,
+            armed: true,
+        }))
+    }
+
+    /// Places an immediate-or-cancel order that takes liquidity from the top of the book.
+    ///
+    /// The limit price is the best price on the opposite side of the loaded book, moved by
+    /// `max_slippage_bps` against the order: above the best ask for a bid, below the best bid for
+    /// an ask. Whatever can't be filled within that price is cancelled instead of resting.
+    ///
+    /// Like `place_limit_order`, native SOL is wrapped around the order when `wrap_sol` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `side` - The side of the order (buy or sell).
+    /// * `target_amount_quote` - The target amount in quote currency for the order.
+    /// * `max_slippage_bps` - The maximum price slippage from the top of the book, in basis points.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the transaction signature if successful. Returns `Ok(None)` when the
+    /// opposite side of the book is empty or the size rounds down to zero base lots.
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if there is an issue with creating or sending the transaction.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::matching::Side;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     let result = ob_client.place_market_order(Side::Bid, 5.0, 50).await?;
+    ///
+    ///     println!("{:?}", result);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn place_market_order(
+        &self,
+        side: Side,
+        target_amount_quote: f64,
+        max_slippage_bps: u16,
+    ) -> Result<Option<OrderReturnType>, OpenBookError> {
+        let place_order_ix = match self.build_market_order_instruction(
+            side,
+            target_amount_quote,
+            max_slippage_bps,
+            random::<u64>(),
+        )? {
+            Some(ix) => ix,
+            None => return Ok(None),
+        };
+
+        let instructions = self.with_sol_wrapping(side, place_order_ix).await?;
+
+        let (_, signature) = self
+            .send_and_report(instructions, &TxConfig::default())
+            .await?;
+
+        Ok(Some(OrderReturnType::Signature(signature)))
+    }
+
+    /// Builds the immediate-or-cancel `new_order` instruction used by `place_market_order`.
+    ///
+    /// The order is priced off the top of the book and bounded by `max_slippage_bps`, so it isn't
+    /// checked against `max_price_deviation_percent`.
+    ///
+    /// Returns `Ok(None)` when the opposite side of the book is empty or the size rounds down to
+    /// zero base lots.
+    pub fn build_market_order_instruction(
+        &self,
+        side: Side,
+        target_amount_quote: f64,
+        max_slippage_bps: u16,
+        client_order_id: u64,
+    ) -> Result<Option<Instruction>, OpenBookError> {
+        let market_price = self.market_price();
+        let slippage = max_slippage_bps as f64 / 10_000.0;
+
+        let price = match side {
+            Side::Bid => market_price.best_ask.map(|ask| ask * (1.0 + slippage)),
+            Side::Ask => market_price.best_bid.map(|bid| bid * (1.0 - slippage)),
+        };
+
+        match price {
+            Some(price) => Ok(self.build_new_order_instruction(
+                target_amount_quote,
+                side,
+                price,
+                &LimitOrderParams {
+                    order_type: OrderType::ImmediateOrCancel,
+                    client_order_id: Some(client_order_id),
+                    allow_off_market: true,
+                    ..Default::default()
+                },
+            )?),
+            None => {
+                debug!("[*] Empty book on the opposite side, skipping the market order");
+                Ok(None)
+            }
+        }
+    }
+
+    /// Builds the `new_order` instruction used by `place_limit_order`.
+    ///
+    /// Returns `Ok(None)` when the requested size rounds down to zero base lots.
+    fn build_limit_order_instruction(
+        &self,
+        target_amount_quote: f64,
+        side: Side,
+        best_offset_usdc: f64,
+        use_target_price: bool,
+        target_price: f64,
+        params: &LimitOrderParams,
+    ) -> Result<Option<Instruction>, Error> {
+        match self.limit_order_price(side, best_offset_usdc, use_target_price, target_price) {
+            Some(price) => {
+                self.build_new_order_instruction(target_amount_quote, side, price, params)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the price of a limit order: `target_price`, or the best price of `side` moved
+    /// away from the book by `best_offset_usdc`.
+    ///
+    /// Returns `None` when the book is empty and no target price is used.
+    fn limit_order_price(
+        &self,
+        side: Side,
+        best_offset_usdc: f64,
+        use_target_price: bool,
+        target_price: f64,
+    ) -> Option<f64> {
+        let market_price = self.market_price();
+
+        if !use_target_price && !market_price.has_liquidity {
+            debug!("[*] No liquidity on the book, skipping the order");
+            return None;
+        }
+
+        let price = match side {
+            Side::Bid => {
+                let mut price = market_price.best_bid.unwrap_or_default() - best_offset_usdc;
+                if use_target_price {
+                    price = target_price;
+                }
+
+                price
+            }
+            Side::Ask => {
+                let mut price = market_price.best_ask.unwrap_or_default() + best_offset_usdc;
+                if use_target_price {
+                    price = target_price;
+                }
+
+                price
+            }
+        };
+
+        Some(price)
+    }
+
+    /// Returns the base lots bought or sold by an order of `target_amount_quote` at `price`,
+    /// rounded down.
+    fn target_base_lots(&self, target_amount_quote: f64, price: f64) -> u64 {
+        let base_d_factor = 10u32.pow(self.market_info.coin_decimals as u32) as f64;
+        let base_lot_factor = self.market_info.coin_lot_size as f64;
+        let target_amount_base = target_amount_quote / price;
+
+        (target_amount_base * base_d_factor / base_lot_factor) as u64
+    }
+
+    /// Rejects an order price that is obviously wrong.
+    ///
+    /// The price must be a positive number, and within `max_price_deviation_percent` of the mid of
+    /// the loaded book unless `params` allows off-market orders. The deviation isn't checked when
+    /// the book has no mid.
+    fn check_price_sanity(
+        &self,
+        price: f64,
+        params: &LimitOrderParams,
+    ) -> Result<(), OpenBookError> {
+        if !price.is_finite() || price <= 0.0 {
+            return Err(OpenBookError::InvalidPrice(price));
+        }
+        if params.allow_off_market {
+            return Ok(());
+        }
+        if let Some(mid) = self.market_price().mid {
+            if (price - mid).abs() / mid * 100.0 > self.max_price_deviation_percent {
+                return Err(OpenBookError::PriceOffMarket {
+                    price,
+                    mid,
+                    max_deviation_percent: self.max_price_deviation_percent,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Builds a `new_order` instruction for `target_amount_quote` worth of base at `price`, with
+    /// the order type, self trade behavior, client order id and expiry of `params`.
+    ///
+    /// Returns `Ok(None)` when the requested size rounds down to zero base lots, and an error when
+    /// the price fails `check_price_sanity` or is below one price lot.
+    fn build_new_order_instruction(
+        &self,
+        target_amount_quote: f64,
+        side: Side,
+        price: f64,
+        params: &LimitOrderParams,
+    ) -> Result<Option<Instruction>, Error> {
+        // coin: base
+        // pc: quote
+        let input_ata = match side {
+            Side::Bid => &self.quote_ata,
+            Side::Ask => &self.base_ata,
+        };
+        let quote_lot_factor = self.market_info.pc_lot_size as f64;
+        let price_factor = self.market_info.price_factor();
+
+        self.check_price_sanity(price, params)?;
+
+        let (price, limit_price_lots) = if self.round_to_tick {
+            let rounded = self.round_price_to_tick(price, side);
+            if rounded != price {
+                debug!("[*] Adjusted price {} to the tick {}", price, rounded);
+            }
+            (rounded, (rounded * price_factor).round() as u64)
+        } else {
+            (price, (price * price_factor) as u64)
+        };
+        if limit_price_lots == 0 {
+            return Err(OpenBookError::InvalidPrice(price).into());
+        }
+        let target_base_lots = self.target_base_lots(target_amount_quote, price);
+        let target_native_pc =
+            (target_base_lots as f64 * quote_lot_factor * limit_price_lots as f64) as u64;
+        // A post-only order never takes, so it never pays the taker fee.
+        let taker_fee = match params.order_type {
+            OrderType::PostOnly => 0,
+            _ => self.market_info.taker_fee(target_native_pc),
+        };
+        let target_quote_lots_w_fee = target_native_pc + taker_fee;
+
+        debug!("[*] Using limit price lots: {:?}", limit_price_lots);
+        debug!("[*] Using target base lots: {:?}", target_base_lots);
+
+        if target_base_lots == 0 {
+            debug!(
+                "[*] Got zero base lots, and quote: {:?}",
+                target_amount_quote
+            );
+            return Ok(None);
+        }
+
+        let limit_price = NonZeroU64::new(limit_price_lots).unwrap();
+        let max_coin_qty = NonZeroU64::new(target_base_lots).unwrap();
+        let max_native_pc_qty_including_fees = NonZeroU64::new(target_quote_lots_w_fee).unwrap();
+
+        let place_order_ix = openbook_dex::instruction::new_order(
+            &self.market_info.market_address,
+            &self.open_orders.oo_key,
+            &self.market_info.request_queue,
+            &self.market_info.event_queue,
+            &self.market_info.bids_address,
+            &self.market_info.asks_address,
+            input_ata,
+            &self.owner.pubkey(),
+            &self.market_info.coin_vault,
+            &self.market_info.pc_vault,
+            &SPL_TOKEN_ID.parse()?,
+            &rent::ID,
+            None,
+            &self.market_info.program_id,
+            side,
+            limit_price,
+            max_coin_qty,
+            params.order_type,
+            params.client_order_id.unwrap_or_else(random::<u64>),
+            params.self_trade_behavior,
+            u16::MAX,
+            max_native_pc_qty_including_fees,
+            params
+                .expiry
+                .max_ts(get_unix_secs(), Duration::from_millis(DEFAULT_MS_PER_SLOT)),
+        )?;
+
+        Ok(Some(place_order_ix))
+    }
+
+    /// Places a limit order and re-sends it until it lands, without ever double-placing.
+    ///
+    /// Every attempt uses the same client order id, and rebuilds the order so that its expiry
+    /// counts from that attempt, then signs it with a fresh blockhash and waits up to
+    /// `confirm_timeout` for confirmation. When an attempt is not
+    /// confirmed in time, the client waits for that blockhash to expire (so the dropped transaction
+    /// can no longer land) and checks the open orders account for the client order id before
+    /// re-sending, so a late-landing transaction is reported as placed instead of being duplicated.
+    ///
+    /// Like `place_limit_order`, native SOL is wrapped around the order when `wrap_sol` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `target_amount_quote` - The target amount in quote currency for the order.
+    /// * `side` - The side of the order (buy or sell).
+    /// * `target_price` - The limit price of the order.
+    /// * `max_attempts` - The maximum number of times the transaction is sent.
+    /// * `confirm_timeout` - How long each attempt waits for confirmation.
+    ///
+    /// # Returns
+    ///
+    /// A `PlaceOutcome` with the client order id, the last signature sent, the number of attempts
+    /// and whether the order was confirmed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the order size rounds down to zero, if an RPC request fails, or if the
+    /// transaction lands but fails on chain.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::matching::Side;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     let outcome = ob_client
+    ///         .place_limit_order_durable(5.0, Side::Bid, 2.1, 3, Duration::from_secs(30))
+    ///         .await?;
+    ///
+    ///     println!("{:?}", outcome);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn place_limit_order_durable(
+        &self,
+        target_amount_quote: f64,
+        side: Side,
+        target_price: f64,
+        max_attempts: u32,
+        confirm_timeout: Duration,
+    ) -> Result<PlaceOutcome, OpenBookError> {
+        let client_order_id = random::<u64>();
+        let params = LimitOrderParams {
+            client_order_id: Some(client_order_id),
+            ..Default::default()
+        };
+
+        Ok(
+            resend_until_confirmed(client_order_id, max_attempts, |attempt| async move {
+                if attempt > 1 && self.has_order_with_client_id(client_order_id).await? {
+                    return Ok(SendAttempt::AlreadyPlaced);
+                }
+                // Rebuilt on every attempt, so the expiry counts from this send rather than
+                // from the first one.
+                let place_order_ix = self
+                    .build_limit_order_instruction(
+                        target_amount_quote,
+                        side,
+                        0.,
+                        true,
+                        target_price,
+                        &params,
+                    )?
+                    .ok_or(OpenBookError::OrderTooSmall(target_amount_quote))?;
+                let instructions = self.with_sol_wrapping(side, place_order_ix).await?;
+                self.send_once(instructions, confirm_timeout).await
+            })
+            .await?,
+        )
+    }
+
+    /// Sends `instructions` like `Rpc::send_and_confirm_with_config`, and reports the orders they
+    /// place and cancel and their settlements to the metrics of the RPC client once confirmed.
+    async fn send_and_report(
+        &self,
+        instructions: Vec<Instruction>,
+        config: &TxConfig,
+    ) -> Result<(bool, Signature)> {
+        let kinds = self.instruction_kinds(&instructions);
+        let (confirmed, signature) = self
+            .rpc_client
+            .send_and_confirm_with_config((*self.owner).insecure_clone(), instructions, config)
+            .await?;
+        if confirmed {
+            self.report_confirmed(&kinds);
+        }
+
+        Ok((confirmed, signature))
+    }
+
+    /// Decodes the kind of each of `instructions`, for `report_confirmed`.
+    fn instruction_kinds(&self, instructions: &[Instruction]) -> Vec<ComboInstructionKind> {
+        instructions
+            .iter()
+            .map(|ix| ComboInstructionKind::of(ix, &self.market_info.program_id))
+            .collect()
+    }
+
+    /// Reports the orders placed and canceled and the settlements of a confirmed transaction made
+    /// of instructions of `kinds` to the metrics of the RPC client.
+    fn report_confirmed(&self, kinds: &[ComboInstructionKind]) {
+        let metrics = self.rpc_client.metrics();
+        let count = |kind: ComboInstructionKind| kinds.iter().filter(|k| **k == kind).count();
+
+        let placed = count(ComboInstructionKind::NewOrder);
+        if placed > 0 {
+            metrics.on_orders_placed(placed as u64);
+        }
+        let canceled = count(ComboInstructionKind::CancelOrder);
+        if canceled > 0 {
+            metrics.on_orders_canceled(canceled as u64);
+        }
+        for _ in 0..count(ComboInstructionKind::SettleFunds) {
+            metrics.on_settled();
+        }
+    }
+
+    /// Sends a transaction once through the RPC client and waits up to `timeout` for it to be
+    /// confirmed.
+    ///
+    /// If it is not confirmed in time, waits for its blockhash to expire so the transaction can
+    /// no longer land, and reports it as dropped.
+    async fn send_once(
+        &self,
+        instructions: Vec<Instruction>,
+        timeout: Duration,
+    ) -> Result<SendAttempt> {
+        let kinds = self.instruction_kinds(&instructions);
+        let rpc = &self.rpc_client;
+        let (signature, blockhash) = rpc
+            .send_with_config(&self.owner, instructions, &TxConfig::default())
+            .await?;
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(status) = rpc.get_signature_status(&signature).await? {
+                return self.confirmed_attempt(signature, status, &kinds);
+            }
+            if Instant::now() >= deadline {
+                break;
+            }
+            sleep(Duration::from_millis(500)).await;
+        }
+
+        while rpc
+            .is_blockhash_valid(&blockhash, CommitmentConfig::processed())
+            .await?
+        {
+            sleep(Duration::from_secs(2)).await;
+        }
+
+        if let Some(status) = rpc.get_signature_status(&signature).await? {
+            return self.confirmed_attempt(signature, status, &kinds);
+        }
+
+        debug!("[*] Transaction dropped: {:?}", signature);
+        rpc.metrics().on_tx_sent(false);
+        Ok(SendAttempt::Dropped(signature))
+    }
+
+    /// Reports a landed attempt of `send_once` to the metrics, failing if it failed on chain.
+    fn confirmed_attempt(
+        &self,
+        signature: Signature,
+        status: std::result::Result<(), TransactionError>,
+        kinds: &[ComboInstructionKind],
+    ) -> Result<SendAttempt> {
+        self.rpc_client.metrics().on_tx_sent(status.is_ok());
+        status?;
+        self.report_confirmed(kinds);
+        Ok(SendAttempt::Confirmed(signature))
+    }
+
+    /// Cancels a single open order by its order id, leaving the rest of the book untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `side` - The side of the order.
+    /// * `order_id` - The order id assigned by the program.
+    /// * `execute` - A boolean indicating whether to execute the order immediately.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the transaction signature or the cancel instruction, or `None` if
+    /// the order isn't in the loaded open orders.
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if there is an issue with creating or sending the transaction.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::v1::orders::OrderReturnType;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    /// use openbook::matching::Side;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     if let Some(&order_id) = ob_client.open_orders.open_bids.first() {
+    ///         if let Some(OrderReturnType::Signature(sign)) = ob_client
+    ///             .cancel_order_by_id(Side::Bid, order_id, true)
+    ///             .await?
+    ///         {
+    ///             println!("[*] Canceled order {}, signature: {:?}", order_id, sign);
+    ///         }
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn cancel_order_by_id(
+        &self,
+        side: Side,
+        order_id: u128,
+        execute: bool,
+    ) -> Result<Option<OrderReturnType>, OpenBookError> {
+        let open_ids = match side {
+            Side::Bid => &self.open_orders.open_bids,
+            Side::Ask => &self.open_orders.open_asks,
+        };
+        if !open_ids.contains(&order_id) {
+            return Ok(None);
+        }
+
+        let ix = openbook_dex::instruction::cancel_order(
+            &self.market_info.program_id,
+            &self.market_info.market_address,
+            &self.market_info.bids_address,
+            &self.market_info.asks_address,
+            &self.open_orders.oo_key,
+            &self.owner.pubkey(),
+            &self.market_info.event_queue,
+            side,
+            order_id,
+        )?;
+
+        self.send_cancel(vec![ix], execute).await
+    }
+
+    /// Cancels a single open order by the client order id it was placed with.
+    ///
+    /// The open orders account is read to check the order is still open, since the loaded open
+    /// orders only track the order ids.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `client_id` - The client order id the order was placed with.
+    /// * `execute` - A boolean indicating whether to execute the order immediately.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the transaction signature or the cancel instruction, or `None` if
+    /// no open order has this client order id.
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if the open orders account can't be read, or if there
+    /// is an issue with creating or sending the transaction.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::v1::orders::OrderReturnType;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     match ob_client.cancel_order_by_client_id(42, true).await? {
+    ///         Some(OrderReturnType::Signature(sign)) => {
+    ///             println!("[*] Canceled order 42, signature: {:?}", sign);
+    ///         }
+    ///         Some(OrderReturnType::Instructions(_)) => {}
+    ///         None => println!("[*] Order 42 isn't open"),
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn cancel_order_by_client_id(
+        &self,
+        client_id: u64,
+        execute: bool,
+    ) -> Result<Option<OrderReturnType>, OpenBookError> {
+        if self.order_id_for_client_id(client_id).await?.is_none() {
+            return Ok(None);
+        }
+
+        let ix = openbook_dex::instruction::cancel_order_by_client_order_id(
+            &self.market_info.program_id,
+            &self.market_info.market_address,
+            &self.market_info.bids_address,
+            &self.market_info.asks_address,
+            &self.open_orders.oo_key,
+            &self.owner.pubkey(),
+            &self.market_info.event_queue,
+            client_id,
+        )?;
+
+        self.send_cancel(vec![ix], execute).await
+    }
+
+    /// Returns the cancel instructions, or sends them when `execute` is set.
+    async fn send_cancel(
+        &self,
+        ixs: Vec<Instruction>,
+        execute: bool,
+    ) -> Result<Option<OrderReturnType>, OpenBookError> {
+        if !execute {
+            return Ok(Some(OrderReturnType::Instructions(ixs)));
+        }
+        self.ensure_open_orders_initialized()?;
+
+        let (_, signature) = self.send_and_report(ixs, &TxConfig::default()).await?;
+
+        Ok(Some(OrderReturnType::Signature(signature)))
+    }
+
+    /// Checks whether the open orders account holds an order with the given client order id.
+    async fn has_order_with_client_id(&self, client_order_id: u64) -> Result<bool> {
+        Ok(self
+            .order_id_for_client_id(client_order_id)
+            .await?
+            .is_some())
+    }
+
+    /// Reads the open orders account and returns the order id assigned to `client_order_id`.
+    ///
+    /// # Returns
+    ///
+    /// The order id, or `None` if no order with `client_order_id` rests in the open orders
+    /// account, e.g. because it was filled or canceled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the open orders account can't be fetched or decoded.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     if let Some(order_id) = ob_client.order_id_for_client_id(42).await? {
+    ///         println!("[*] Order 42 rests with order id {}", order_id);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn order_id_for_client_id(
+        &self,
+        client_order_id: u64,
+    ) -> Result<Option<u128>, OpenBookError> {
+        let accounts = self
+            .rpc_client
+            .fetch_multiple_accounts(&[self.open_orders.oo_key])
+            .await?;
 
-        if !execute {
-            return Ok(Some(OrderReturnType::Instructions(instructions)));
+        match accounts.into_iter().next().flatten() {
+            Some(account) => Ok(OpenOrdersAccount::from_account_data(&account.data)?
+                .order_id_for_client_id(client_order_id)),
+            None => Ok(None),
         }
+    }
 
-        let (_, signature) = self
+    /// Reads the open orders account as of `slot` or later and returns the order id assigned to
+    /// `client_order_id`, like `order_id_for_client_id`.
+    async fn order_id_for_client_id_since(
+        &self,
+        client_order_id: u64,
+        slot: u64,
+    ) -> Result<Option<u128>> {
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(self.rpc_client.provider().commitment()),
+            min_context_slot: Some(slot),
+            data_slice: None,
+        };
+        let response = self
             .rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), instructions)
+            .provider()
+            .get_multiple_accounts_with_config(&[self.open_orders.oo_key], config)
             .await?;
 
-        Ok(Some(OrderReturnType::Signature(signature)))
+        match response.value.into_iter().next().flatten() {
+            Some(account) => Ok(OpenOrdersAccount::from_account_data(&account.data)?
+                .order_id_for_client_id(client_order_id)),
+            None => Ok(None),
+        }
     }
 
     /// Cancels all limit orders in the market.
     ///
+    /// When `settle_on_cancel` is set, a settle funds instruction is appended after the cancels,
+    /// so the balances freed by the cancels (including the proceeds of partially filled orders)
+    /// are settled in the same transaction.
+    ///
     /// # Arguments
     ///
     /// * `&self` - A reference to the `OBClient` struct.
     /// * `execute` - A boolean indicating whether to execute the order immediately.
+    /// * `settle_on_cancel` - A boolean indicating whether to settle the freed balances after the cancels.
     ///
     /// # Returns
     ///
@@ -659,6 +4207,8 @@ impl OBClient {
     /// # Errors
     ///
     /// This function may return an error if there is an issue with creating or sending the transaction.
+    /// Fails with `OpenBookError::OpenOrdersNotInitialized` when executing without an open orders
+    /// account.
     ///
     /// # Examples
     ///
@@ -676,7 +4226,7 @@ impl OBClient {
     ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
     ///
     ///     if let Some(ord_ret_type) = ob_client
-    ///         .cancel_orders(true)
+    ///         .cancel_orders(true, true)
     ///         .await?
     ///     {
     ///         match ord_ret_type {
@@ -692,38 +4242,88 @@ impl OBClient {
     ///     Ok(())
     /// }
     /// ```
-    pub async fn cancel_orders(&self, execute: bool) -> Result<Option<OrderReturnType>, Error> {
-        let mut ixs = Vec::new();
-
-        for oid in &self.open_orders.open_bids {
-            let ix = openbook_dex::instruction::cancel_order(
-                &self.market_info.program_id,
-                &self.market_info.market_address,
-                &self.market_info.bids_address,
-                &self.market_info.asks_address,
-                &self.open_orders.oo_key,
-                &self.owner.pubkey(),
-                &self.market_info.event_queue,
-                Side::Bid,
-                *oid,
-            )?;
-            ixs.push(ix);
-        }
-
-        for oid in &self.open_orders.open_asks {
-            let ix = openbook_dex::instruction::cancel_order(
-                &self.market_info.program_id,
-                &self.market_info.market_address,
-                &self.market_info.bids_address,
-                &self.market_info.asks_address,
-                &self.open_orders.oo_key,
-                &self.owner.pubkey(),
-                &self.market_info.event_queue,
-                Side::Ask,
-                *oid,
-            )?;
-            ixs.push(ix);
+    pub async fn cancel_orders(
+        &self,
+        execute: bool,
+        settle_on_cancel: bool,
+    ) -> Result<Option<OrderReturnType>, OpenBookError> {
+        let mut ixs = self
+            .build_cancel_instructions(&self.open_orders.open_bids, &self.open_orders.open_asks)?;
+
+        if ixs.is_empty() {
+            return Ok(None);
+        }
+
+        if settle_on_cancel {
+            ixs.push(self.build_settle_funds_instruction()?);
+        }
+
+        if !execute {
+            return Ok(Some(OrderReturnType::Instructions(ixs)));
         }
+        self.ensure_open_orders_initialized()?;
+
+        let (_, signature) = self.send_and_report(ixs, &TxConfig::default()).await?;
+
+        Ok(Some(OrderReturnType::Signature(signature)))
+    }
+
+    /// Cancels the open orders that have been resting for at least `age`.
+    ///
+    /// The program doesn't record when an order was placed, so the age of an order is measured
+    /// from the first load that found it (see `OpenOrders::order_timestamps`). Orders placed
+    /// before the client was created are therefore aged from the client's first load.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `age` - The minimum age of the orders to cancel.
+    /// * `execute` - A boolean indicating whether to execute the order immediately.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the transaction signature or the cancel instructions, or `None` if
+    /// no order is old enough.
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if there is an issue with creating or sending the transaction.
+    /// Fails with `OpenBookError::OpenOrdersNotInitialized` when executing without an open orders
+    /// account.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::v1::orders::OrderReturnType;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     if let Some(OrderReturnType::Signature(sign)) = ob_client
+    ///         .cancel_orders_older_than(Duration::from_secs(300), true)
+    ///         .await?
+    ///     {
+    ///         println!("[*] Canceled stale orders, signature: {:?}", sign);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn cancel_orders_older_than(
+        &self,
+        age: Duration,
+        execute: bool,
+    ) -> Result<Option<OrderReturnType>, OpenBookError> {
+        let (bids, asks) = self.open_orders.orders_older_than(age, get_unix_secs());
+        let ixs = self.build_cancel_instructions(&bids, &asks)?;
 
         if ixs.is_empty() {
             return Ok(None);
@@ -732,17 +4332,46 @@ impl OBClient {
         if !execute {
             return Ok(Some(OrderReturnType::Instructions(ixs)));
         }
+        self.ensure_open_orders_initialized()?;
 
-        let (_, signature) = self
-            .rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), ixs)
-            .await?;
+        let (_, signature) = self.send_and_report(ixs, &TxConfig::default()).await?;
 
         Ok(Some(OrderReturnType::Signature(signature)))
     }
 
+    /// Builds one cancel instruction per order id, bids first.
+    fn build_cancel_instructions(
+        &self,
+        bids: &[u128],
+        asks: &[u128],
+    ) -> Result<Vec<Instruction>, OpenBookError> {
+        let mut ixs = Vec::with_capacity(bids.len() + asks.len());
+
+        for (side, oids) in [(Side::Bid, bids), (Side::Ask, asks)] {
+            for oid in oids {
+                let ix = openbook_dex::instruction::cancel_order(
+                    &self.market_info.program_id,
+                    &self.market_info.market_address,
+                    &self.market_info.bids_address,
+                    &self.market_info.asks_address,
+                    &self.open_orders.oo_key,
+                    &self.owner.pubkey(),
+                    &self.market_info.event_queue,
+                    side,
+                    *oid,
+                )?;
+                ixs.push(ix);
+            }
+        }
+
+        Ok(ixs)
+    }
+
     /// Settles the balance for a user in the market.
     ///
+    /// When `referrer` is set, the referrer rebate of the settled fees is paid to it. It must be
+    /// a token account of the market's quote mint, or the transaction fails.
+    ///
     /// # Arguments
     ///
     /// * `&self` - A reference to the `OBClient` struct.
@@ -756,6 +4385,8 @@ impl OBClient {
     /// # Errors
     ///
     /// This function may return an error if there is an issue with creating or sending the transaction.
+    /// Fails with `OpenBookError::OpenOrdersNotInitialized` when executing without an open orders
+    /// account.
     ///
     /// # Examples
     ///
@@ -789,8 +4420,178 @@ impl OBClient {
     ///     Ok(())
     /// }
     /// ```
-    pub async fn settle_balance(&self, execute: bool) -> Result<Option<OrderReturnType>, Error> {
-        let ix = openbook_dex::instruction::settle_funds(
+    pub async fn settle_balance(
+        &self,
+        execute: bool,
+    ) -> Result<Option<OrderReturnType>, OpenBookError> {
+        let instructions = vec![self.build_settle_funds_instruction()?];
+
+        if !execute {
+            return Ok(Some(OrderReturnType::Instructions(instructions)));
+        }
+        self.ensure_open_orders_initialized()?;
+
+        let (_, signature) = self
+            .send_and_report(instructions, &TxConfig::default())
+            .await?;
+
+        Ok(Some(OrderReturnType::Signature(signature)))
+    }
+
+    /// Builds the instructions unwrapping the settled wrapped SOL, when `unwrap_sol` is set.
+    ///
+    /// On a market whose base or quote mint is the native mint, this closes the wrapped SOL ATA,
+    /// sending its balance and rent back to the owner as SOL, then creates it again empty so it
+    /// stays a valid destination for the next settle. The combo methods append it at the end of
+    /// their transaction, after settling and placing.
+    ///
+    /// # Returns
+    ///
+    /// The close and create account instructions, or nothing if `unwrap_sol` isn't set or the
+    /// market doesn't trade native SOL.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the instruction can't be built.
+    pub fn build_unwrap_sol_instructions(&self) -> Result<Vec<Instruction>, OpenBookError> {
+        if !self.unwrap_sol {
+            return Ok(vec![]);
+        }
+
+        let native_mint = spl_token::native_mint::id();
+        let wsol_ata = if self.market_info.base_mint == native_mint {
+            self.base_ata
+        } else if self.market_info.quote_mint == native_mint {
+            self.quote_ata
+        } else {
+            return Ok(vec![]);
+        };
+
+        self.build_close_wsol_ata_instructions(&wsol_ata)
+    }
+
+    /// Builds the instructions closing the wrapped SOL ATA, which unwraps its balance to the
+    /// owner, and creating it again empty.
+    ///
+    /// The ATA is also where settled SOL goes, so it must not be left closed: a settle into a
+    /// closed ATA fails. Creating the account again costs its rent, which the next close returns.
+    fn build_close_wsol_ata_instructions(
+        &self,
+        wsol_ata: &Pubkey,
+    ) -> Result<Vec<Instruction>, OpenBookError> {
+        let owner = self.owner.pubkey();
+        let token_program = spl_token::id();
+
+        Ok(vec![
+            spl_token::instruction::close_account(&token_program, wsol_ata, &owner, &owner, &[])?,
+            create_associated_token_account_idempotent(
+                &owner,
+                &owner,
+                &spl_token::native_mint::id(),
+                &token_program,
+            ),
+        ])
+    }
+
+    /// Builds the instructions wrapping the SOL paid by an order, when `wrap_sol` is set.
+    ///
+    /// Only an order paying in native SOL is wrapped: a bid on a market quoted in SOL, or an ask
+    /// on a market whose base is SOL. The wrapped SOL ATA is created if missing and topped up with
+    /// the lamports it lacks to cover `native_amount`, then synced. After the order, the ATA is
+    /// closed, returning what is left, including wrapped SOL held before, to the owner as SOL, and
+    /// created again empty so the proceeds of the order can still be settled into it.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `side` - The side of the order.
+    /// * `native_amount` - The lamports the order can lock.
+    ///
+    /// # Returns
+    ///
+    /// The instructions to put before the order and the ones to put after it, both empty when
+    /// the order doesn't pay in native SOL.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the wrapped SOL ATA can't be fetched or the instructions can't be
+    /// built.
+    pub async fn build_wrap_sol_instructions(
+        &self,
+        side: Side,
+        native_amount: u64,
+    ) -> Result<(Vec<Instruction>, Vec<Instruction>), OpenBookError> {
+        let (mint, wsol_ata) = match side {
+            Side::Bid => (self.market_info.quote_mint, self.quote_ata),
+            Side::Ask => (self.market_info.base_mint, self.base_ata),
+        };
+        if !self.wrap_sol || mint != spl_token::native_mint::id() {
+            return Ok((vec![], vec![]));
+        }
+
+        let owner = self.owner.pubkey();
+        let token_program = spl_token::id();
+        let wrapped = self
+            .rpc_client
+            .fetch_multiple_accounts(&[wsol_ata])
+            .await?
+            .pop()
+            .flatten()
+            .and_then(|account| token_account_amount(&account.data))
+            .unwrap_or(0);
+
+        let mut wrap = vec![];
+        let missing = native_amount.saturating_sub(wrapped);
+        if missing > 0 {
+            wrap.push(create_associated_token_account_idempotent(
+                &owner,
+                &owner,
+                &mint,
+                &token_program,
+            ));
+            wrap.push(system_instruction::transfer(&owner, &wsol_ata, missing));
+            wrap.push(spl_token::instruction::sync_native(
+                &token_program,
+                &wsol_ata,
+            )?);
+        }
+
+        let unwrap = self.build_close_wsol_ata_instructions(&wsol_ata)?;
+
+        Ok((wrap, unwrap))
+    }
+
+    /// Surrounds a `new_order` instruction with the instructions of `build_wrap_sol_instructions`.
+    async fn with_sol_wrapping(
+        &self,
+        side: Side,
+        place_order_ix: Instruction,
+    ) -> Result<Vec<Instruction>, OpenBookError> {
+        let (wrap, unwrap) = if self.wrap_sol {
+            let native_amount =
+                new_order_native_amount(&place_order_ix, self.market_info.coin_lot_size);
+            self.build_wrap_sol_instructions(side, native_amount)
+                .await?
+        } else {
+            (vec![], vec![])
+        };
+
+        Ok(wrap
+            .into_iter()
+            .chain([place_order_ix])
+            .chain(unwrap)
+            .collect())
+    }
+
+    /// Builds the settle funds instruction moving the free open orders balances to the ATAs.
+    ///
+    /// The `referrer` quote token account, if set, receives the referrer rebate.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the instruction can't be built.
+    pub fn build_settle_funds_instruction(&self) -> Result<Instruction, OpenBookError> {
+        Ok(openbook_dex::instruction::settle_funds(
             &self.market_info.program_id,
             &self.market_info.market_address,
             &SPL_TOKEN_ID.parse()?,
@@ -800,33 +4601,62 @@ impl OBClient {
             &self.base_ata,
             &self.market_info.pc_vault,
             &self.quote_ata,
-            None,
+            self.referrer.as_ref(),
             &self.market_info.vault_signer_key,
-        )?;
-
-        let instructions = vec![ix];
-
-        if !execute {
-            return Ok(Some(OrderReturnType::Instructions(instructions)));
-        }
+        )?)
+    }
 
-        let (_, signature) = self
-            .rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), instructions)
-            .await?;
+    /// Builds idempotent creation instructions for the wallet's base and quote token accounts that
+    /// don't exist yet, paid by the owner.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `base_ata_exists` - Whether the base associated token account exists.
+    /// * `quote_ata_exists` - Whether the quote associated token account exists.
+    ///
+    /// # Returns
+    ///
+    /// Up to two instructions, base first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token program id can't be parsed.
+    pub fn build_create_atas_instructions(
+        &self,
+        base_ata_exists: bool,
+        quote_ata_exists: bool,
+    ) -> Result<Vec<Instruction>, OpenBookError> {
+        let token_program = SPL_TOKEN_ID.parse()?;
+        let owner = self.owner.pubkey();
 
-        Ok(Some(OrderReturnType::Signature(signature)))
+        Ok([
+            (base_ata_exists, self.market_info.base_mint),
+            (quote_ata_exists, self.market_info.quote_mint),
+        ]
+        .into_iter()
+        .filter(|(exists, _)| !exists)
+        .map(|(_, mint)| {
+            create_associated_token_account_idempotent(&owner, &owner, &mint, &token_program)
+        })
+        .collect())
     }
 
     /// Creates a new transaction to match orders in the market.
     ///
+    /// The v1 program doesn't log the matches it makes, so the effects of the transaction are
+    /// read from the event queue instead: once the transaction is confirmed, the maker fills
+    /// pushed since before the transaction are counted, one per match. A cranker can keep matching
+    /// while this is above zero. Fills pushed by other transactions landing between the two reads
+    /// of the queue are counted too, and fills consumed by a crank in the meantime are not.
+    ///
     /// # Arguments
     ///
     /// * `limit` - The maximum number of orders to match.
     ///
     /// # Returns
     ///
-    /// A transaction for matching orders.
+    /// A `MatchResult` with the signature of the transaction and the number of matches it made.
     ///
     /// # Errors
     ///
@@ -849,12 +4679,16 @@ impl OBClient {
     ///
     ///     let result = ob_client.match_orders_transaction(100).await?;
     ///
-    ///     println!("{:?}", result);
+    ///     if result.matched == 0 {
+    ///         println!("[*] Nothing left to match");
+    ///     }
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn match_orders_transaction(&self, limit: u16) -> Result<(bool, Signature)> {
+    pub async fn match_orders_transaction(&self, limit: u16) -> Result<MatchResult, OpenBookError> {
+        let seq_num = self.load_event_queue().await?.seq_num;
+
         let ix = openbook_dex::instruction::match_orders(
             &self.market_info.program_id,
             &self.market_info.market_address,
@@ -865,17 +4699,138 @@ impl OBClient {
             &self.market_info.coin_vault,
             &self.market_info.pc_vault,
             limit,
-        )
-        .unwrap();
+        )?;
+
+        let instructions = vec![ix];
+
+        let (confirmed, signature) = self
+            .rpc_client
+            .send_and_confirm((*self.owner).insecure_clone(), instructions)
+            .await?;
+
+        let matched = if confirmed {
+            self.load_event_queue().await?.matches_since(seq_num, None)
+        } else {
+            0
+        };
+
+        Ok(MatchResult {
+            signature,
+            confirmed,
+            matched,
+        })
+    }
+
+    /// Executes a combination of canceling all limit orders, settling balance, and placing new bid and ask orders.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_size_usdc_ask` - The target size in USDC for the ask order.
+    /// * `target_size_usdc_bid` - The target size in USDC for the bid order.
+    /// * `bid_price_jlp_usdc` - The bid price in JLP/USDC.
+    /// * `ask_price_jlp_usdc` - The ask price in JLP/USDC.
+    /// * `order` - The order of the combo steps, `ComboStep::default_order()` being cancel → settle → bid → ask.
+    /// * `ensure_atas` - Whether to create the wallet's missing base and quote token accounts right before the
+    ///   settle step, so a fresh wallet can go through the combo in a single transaction.
+    /// * `fee_strategy` - How to pick the priority fee, `PriorityFeeStrategy::default()` being the 75th percentile of recent fees.
+    /// * `dry_run` - Whether to only simulate the transaction, returning its compute units and logs instead of sending it.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the signature and confirmation status of the sent transaction, or the
+    /// simulation result on a dry run.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `order` contains duplicate steps, or if building or sending the transaction fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::v1::orders::ComboStep;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    /// use openbook::rpc::PriorityFeeStrategy;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
+    ///
+    ///     let target_size_usdc_ask = 0.5;
+    ///     let target_size_usdc_bid = 1.0;
+    ///     let bid_price_jlp_usdc = 1.5;
+    ///     let ask_price_jlp_usdc = 2.5;
+    ///
+    ///     let result = ob_client.cancel_settle_place(
+    ///         target_size_usdc_ask,
+    ///         target_size_usdc_bid,
+    ///         bid_price_jlp_usdc,
+    ///         ask_price_jlp_usdc,
+    ///         ComboStep::default_order(),
+    ///         false,
+    ///         PriorityFeeStrategy::default(),
+    ///         false,
+    ///     ).await?;
+    ///
+    ///     println!("{:?}", result);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub async fn cancel_settle_place(
+        &mut self,
+        target_size_usdc_ask: f64,
+        target_size_usdc_bid: f64,
+        bid_price_jlp_usdc: f64,
+        ask_price_jlp_usdc: f64,
+        order: Vec<ComboStep>,
+        ensure_atas: bool,
+        fee_strategy: PriorityFeeStrategy,
+        dry_run: bool,
+    ) -> Result<TxOutcome, OpenBookError> {
+        let priority_fee = self
+            .rpc_client
+            .estimate_priority_fee(&[], fee_strategy)
+            .await?;
+
+        let instructions = self
+            .build_cancel_settle_place_instructions(
+                target_size_usdc_ask,
+                target_size_usdc_bid,
+                bid_price_jlp_usdc,
+                ask_price_jlp_usdc,
+                &order,
+                ensure_atas,
+            )
+            .await?;
 
-        let instructions = vec![ix];
+        let outcome = self
+            .send_combo(
+                instructions,
+                TxConfig::with_compute_budget(FULL_COMBO_COMPUTE_UNIT_LIMIT, priority_fee),
+                dry_run,
+            )
+            .await?;
 
-        self.rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), instructions)
-            .await
+        if outcome.is_confirmed() {
+            self.record_quote_mid((bid_price_jlp_usdc + ask_price_jlp_usdc) / 2.);
+        }
+
+        Ok(outcome)
     }
 
-    /// Executes a combination of canceling all limit orders, settling balance, and placing new bid and ask orders.
+    /// Builds what `cancel_settle_place` would send, without simulating or sending anything.
+    ///
+    /// The priority fee is estimated and the combo instructions are built as
+    /// `cancel_settle_place` does, including the wrapped SOL unwrap when `unwrap_sol` is set,
+    /// then the compute budget instructions are prepended. Since nothing is simulated, the
+    /// compute unit limit is the default one that `cancel_settle_place` falls back to; the sent
+    /// transaction requests the simulated units plus `compute_unit_margin_percent` instead.
     ///
     /// # Arguments
     ///
@@ -883,17 +4838,27 @@ impl OBClient {
     /// * `target_size_usdc_bid` - The target size in USDC for the bid order.
     /// * `bid_price_jlp_usdc` - The bid price in JLP/USDC.
     /// * `ask_price_jlp_usdc` - The ask price in JLP/USDC.
+    /// * `order` - The order of the combo steps, `ComboStep::default_order()` being cancel → settle → bid → ask.
+    /// * `ensure_atas` - Whether to create the wallet's missing base and quote token accounts right before the
+    ///   settle step.
+    /// * `fee_strategy` - How to pick the priority fee, `PriorityFeeStrategy::default()` being the 75th percentile of recent fees.
     ///
     /// # Returns
     ///
-    /// A `Result` indicating success or failure.
+    /// A `ComboPreview` holding the selected priority fee, the instructions and their decoded kinds.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `order` contains duplicate steps, if the priority fee can't be
+    /// estimated, or if building the instructions fails.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use openbook::v1::orders::OrderReturnType;
+    /// use openbook::v1::orders::ComboStep;
     /// use openbook::commitment_config::CommitmentConfig;
     /// use openbook::v1::ob_client::OBClient;
+    /// use openbook::rpc::PriorityFeeStrategy;
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -901,70 +4866,103 @@ impl OBClient {
     ///
     ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
     ///
-    ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
-    ///
-    ///     let target_size_usdc_ask = 0.5;
-    ///     let target_size_usdc_bid = 1.0;
-    ///     let bid_price_jlp_usdc = 1.5;
-    ///     let ask_price_jlp_usdc = 2.5;
+    ///     let ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
     ///
-    ///     let result = ob_client.cancel_settle_place(target_size_usdc_ask, target_size_usdc_bid, bid_price_jlp_usdc, ask_price_jlp_usdc).await?;
+    ///     let preview = ob_client.preview_combo(
+    ///         0.5,
+    ///         1.0,
+    ///         1.5,
+    ///         2.5,
+    ///         ComboStep::default_order(),
+    ///         false,
+    ///         PriorityFeeStrategy::default(),
+    ///     ).await?;
     ///
-    ///     println!("{:?}", result);
+    ///     println!("Priority fee: {}", preview.priority_fee);
+    ///     println!("Instructions: {:?}", preview.kinds);
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn cancel_settle_place(
-        &mut self,
+    #[allow(clippy::too_many_arguments)]
+    pub async fn preview_combo(
+        &self,
         target_size_usdc_ask: f64,
         target_size_usdc_bid: f64,
         bid_price_jlp_usdc: f64,
         ask_price_jlp_usdc: f64,
-    ) -> Result<(bool, Signature)> {
-        let mut instructions = Vec::new();
-
-        // Fetch recent prioritization fees
-        let r = self
+        order: Vec<ComboStep>,
+        ensure_atas: bool,
+        fee_strategy: PriorityFeeStrategy,
+    ) -> Result<ComboPreview, OpenBookError> {
+        let priority_fee = self
             .rpc_client
-            .inner()
-            .get_recent_prioritization_fees(&[])
+            .estimate_priority_fee(&[], fee_strategy)
             .await?;
-        let mut max_fee = 1;
-        for f in r {
-            if f.prioritization_fee > max_fee {
-                max_fee = f.prioritization_fee;
-            }
-        }
 
-        // Set compute budget and fee instructions
-        let budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(1_000_000);
-        let fee_ix = ComputeBudgetInstruction::set_compute_unit_price(max_fee);
-        instructions.push(budget_ix);
-        instructions.push(fee_ix);
+        let mut instructions = self
+            .build_cancel_settle_place_instructions(
+                target_size_usdc_ask,
+                target_size_usdc_bid,
+                bid_price_jlp_usdc,
+                ask_price_jlp_usdc,
+                &order,
+                ensure_atas,
+            )
+            .await?;
+        instructions.extend(self.build_unwrap_sol_instructions()?);
+
+        let instructions = prepend_compute_budget_parts(
+            instructions,
+            Some(FULL_COMBO_COMPUTE_UNIT_LIMIT),
+            Some(priority_fee),
+        );
+
+        Ok(ComboPreview::new(
+            priority_fee,
+            instructions,
+            &self.market_info.program_id,
+        ))
+    }
+
+    /// Builds the cancel, settle and place instructions of `cancel_settle_place`, in `order`.
+    async fn build_cancel_settle_place_instructions(
+        &self,
+        target_size_usdc_ask: f64,
+        target_size_usdc_bid: f64,
+        bid_price_jlp_usdc: f64,
+        ask_price_jlp_usdc: f64,
+        order: &[ComboStep],
+        ensure_atas: bool,
+    ) -> Result<Vec<Instruction>, OpenBookError> {
+        ComboStep::validate_order(order)?;
+
+        let mut steps = HashMap::new();
 
         // Cancel all limit orders
-        if let Some(ord_ret_type) = self.cancel_orders(false).await? {
-            match ord_ret_type {
-                OrderReturnType::Instructions(insts) => {
-                    instructions.extend(insts);
-                }
-                OrderReturnType::Signature(_) => {}
-            }
+        if let Some(OrderReturnType::Instructions(insts)) = self.cancel_orders(false, false).await?
+        {
+            steps.insert(ComboStep::Cancel, insts);
         }
 
         // Settle balance
-        if let Some(ord_ret_type) = self.settle_balance(false).await? {
-            match ord_ret_type {
-                OrderReturnType::Instructions(insts) => {
-                    instructions.extend(insts);
-                }
-                OrderReturnType::Signature(_) => {}
-            }
+        if let Some(OrderReturnType::Instructions(insts)) = self.settle_balance(false).await? {
+            steps.insert(ComboStep::Settle, insts);
+        }
+
+        // Create the missing token accounts the settle step pays out to
+        if ensure_atas {
+            let accounts = self
+                .rpc_client
+                .fetch_multiple_accounts(&[self.base_ata, self.quote_ata])
+                .await?;
+            let ata_instructions =
+                self.build_create_atas_instructions(accounts[0].is_some(), accounts[1].is_some())?;
+            prepend_step_instructions(&mut steps, ComboStep::Settle, ata_instructions);
         }
 
         // Place bid order
-        if let Some(ord_ret_type) = self
+        if let Some(OrderReturnType::Instructions(insts)) = self
             .place_limit_order(
                 target_size_usdc_bid,
                 Side::Bid,
@@ -974,16 +4972,11 @@ impl OBClient {
             )
             .await?
         {
-            match ord_ret_type {
-                OrderReturnType::Instructions(insts) => {
-                    instructions.extend(insts);
-                }
-                OrderReturnType::Signature(_) => {}
-            }
+            steps.insert(ComboStep::PlaceBid, insts);
         }
 
         // Place ask order
-        if let Some(ord_ret_type) = self
+        if let Some(OrderReturnType::Instructions(insts)) = self
             .place_limit_order(
                 target_size_usdc_ask,
                 Side::Ask,
@@ -993,17 +4986,75 @@ impl OBClient {
             )
             .await?
         {
-            match ord_ret_type {
-                OrderReturnType::Instructions(insts) => {
-                    instructions.extend(insts);
+            steps.insert(ComboStep::PlaceAsk, insts);
+        }
+
+        Ok(order_combo_instructions(order, steps)?)
+    }
+
+    /// Sends a combo transaction with `config`, or only simulates it when `dry_run` is set.
+    ///
+    /// The wrapped SOL is unwrapped at the end of the transaction when `unwrap_sol` is set, see
+    /// `build_unwrap_sol_instructions`.
+    ///
+    /// The transaction is simulated first, and its compute unit limit is set to the consumed
+    /// units plus `compute_unit_margin_percent`, since the priority fee is paid per requested
+    /// unit. The limit of `config` is kept if the simulation fails.
+    ///
+    /// Fails with `OpenBookError::OpenOrdersNotInitialized`, before simulating, when sending
+    /// without an open orders account.
+    async fn send_combo(
+        &self,
+        mut instructions: Vec<Instruction>,
+        config: TxConfig,
+        dry_run: bool,
+    ) -> Result<TxOutcome, OpenBookError> {
+        if !dry_run {
+            self.ensure_open_orders_initialized()?;
+        }
+        instructions.extend(self.build_unwrap_sol_instructions()?);
+
+        let simulation = self
+            .rpc_client
+            .simulate_transaction(
+                &self.owner.pubkey(),
+                prepend_compute_budget_parts(
+                    instructions.clone(),
+                    config.compute_unit_limit,
+                    config.compute_unit_price,
+                ),
+            )
+            .await;
+
+        if dry_run {
+            return Ok(TxOutcome::Simulated(simulation?));
+        }
+
+        let mut config = config;
+        match simulation {
+            Ok(simulation) if !simulation.is_err() => {
+                if let Some(limit) = simulation.compute_unit_limit(self.compute_unit_margin_percent)
+                {
+                    debug!(
+                        "Setting the compute unit limit to {} from {:?} simulated units",
+                        limit, simulation.units_consumed
+                    );
+                    config.compute_unit_limit = Some(limit);
                 }
-                OrderReturnType::Signature(_) => {}
             }
+            Ok(simulation) => debug!(
+                "Simulation failed, keeping the compute unit limit: {:?}",
+                simulation.err
+            ),
+            Err(err) => debug!("Simulation failed, keeping the compute unit limit: {}", err),
         }
 
-        self.rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), instructions)
-            .await
+        let (confirmed, signature) = self.send_and_report(instructions, &config).await?;
+
+        Ok(TxOutcome::Sent {
+            confirmed,
+            signature,
+        })
     }
 
     /// Executes a combination of canceling all limit orders, settling balance, and placing a bid order.
@@ -1012,10 +5063,13 @@ impl OBClient {
     ///
     /// * `target_size_usdc_bid` - The target size in USDC for the bid order.
     /// * `bid_price_jlp_usdc` - The bid price in JLP/USDC.
+    /// * `fee_strategy` - How to pick the priority fee, `PriorityFeeStrategy::default()` being the 75th percentile of recent fees.
+    /// * `dry_run` - Whether to only simulate the transaction, returning its compute units and logs instead of sending it.
     ///
     /// # Returns
     ///
-    /// A `Result` indicating success or failure.
+    /// A `Result` containing the signature and confirmation status of the sent transaction, or the
+    /// simulation result on a dry run.
     ///
     /// # Examples
     ///
@@ -1023,6 +5077,7 @@ impl OBClient {
     /// use openbook::v1::orders::OrderReturnType;
     /// use openbook::commitment_config::CommitmentConfig;
     /// use openbook::v1::ob_client::OBClient;
+    /// use openbook::rpc::PriorityFeeStrategy;
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -1032,7 +5087,9 @@ impl OBClient {
     ///
     ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
     ///
-    ///     let result = ob_client.cancel_settle_place_bid(1.5, 1.0).await?;
+    ///     let result = ob_client
+    ///         .cancel_settle_place_bid(1.5, 1.0, PriorityFeeStrategy::default(), false)
+    ///         .await?;
     ///
     ///     println!("{:?}", result);
     ///
@@ -1043,30 +5100,18 @@ impl OBClient {
         &mut self,
         target_size_usdc_bid: f64,
         bid_price_jlp_usdc: f64,
-    ) -> Result<(bool, Signature)> {
+        fee_strategy: PriorityFeeStrategy,
+        dry_run: bool,
+    ) -> Result<TxOutcome, OpenBookError> {
         let mut instructions = Vec::new();
 
-        // Fetch recent prioritization fees
-        let r = self
+        let priority_fee = self
             .rpc_client
-            .inner()
-            .get_recent_prioritization_fees(&[])
+            .estimate_priority_fee(&[], fee_strategy)
             .await?;
-        let mut max_fee = 1;
-        for f in r {
-            if f.prioritization_fee > max_fee {
-                max_fee = f.prioritization_fee;
-            }
-        }
-
-        // Set compute budget and fee instructions
-        let budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(800_000);
-        let fee_ix = ComputeBudgetInstruction::set_compute_unit_price(max_fee);
-        instructions.push(budget_ix);
-        instructions.push(fee_ix);
 
         // Cancel all limit orders
-        if let Some(ord_ret_type) = self.cancel_orders(false).await? {
+        if let Some(ord_ret_type) = self.cancel_orders(false, false).await? {
             match ord_ret_type {
                 OrderReturnType::Instructions(insts) => {
                     instructions.extend(insts);
@@ -1104,9 +5149,12 @@ impl OBClient {
             }
         }
 
-        self.rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), instructions)
-            .await
+        self.send_combo(
+            instructions,
+            TxConfig::with_compute_budget(COMBO_COMPUTE_UNIT_LIMIT, priority_fee),
+            dry_run,
+        )
+        .await
     }
 
     /// Executes a combination of canceling all limit orders, settling balance, and placing an ask order.
@@ -1115,10 +5163,13 @@ impl OBClient {
     ///
     /// * `target_size_usdc_ask` - The target size in USDC for the ask order.
     /// * `ask_price_jlp_usdc` - The ask price in JLP/USDC.
+    /// * `fee_strategy` - How to pick the priority fee, `PriorityFeeStrategy::default()` being the 75th percentile of recent fees.
+    /// * `dry_run` - Whether to only simulate the transaction, returning its compute units and logs instead of sending it.
     ///
     /// # Returns
     ///
-    /// A `Result` indicating success or failure.
+    /// A `Result` containing the signature and confirmation status of the sent transaction, or the
+    /// simulation result on a dry run.
     ///
     /// # Examples
     ///
@@ -1126,6 +5177,7 @@ impl OBClient {
     /// use openbook::v1::orders::OrderReturnType;
     /// use openbook::commitment_config::CommitmentConfig;
     /// use openbook::v1::ob_client::OBClient;
+    /// use openbook::rpc::PriorityFeeStrategy;
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -1135,7 +5187,9 @@ impl OBClient {
     ///
     ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
     ///
-    ///     let result = ob_client.cancel_settle_place_ask(1.5, 1.0).await?;
+    ///     let result = ob_client
+    ///         .cancel_settle_place_ask(1.5, 1.0, PriorityFeeStrategy::default(), false)
+    ///         .await?;
     ///
     ///     println!("{:?}", result);
     ///
@@ -1146,31 +5200,18 @@ impl OBClient {
         &mut self,
         target_size_usdc_ask: f64,
         ask_price_jlp_usdc: f64,
-    ) -> Result<(bool, Signature)> {
+        fee_strategy: PriorityFeeStrategy,
+        dry_run: bool,
+    ) -> Result<TxOutcome, OpenBookError> {
         let mut instructions = Vec::new();
 
-        // Fetch recent prioritization fees
-        let r = self
+        let priority_fee = self
             .rpc_client
-            .inner()
-            .get_recent_prioritization_fees(&[])
+            .estimate_priority_fee(&[], fee_strategy)
             .await?;
 
-        let mut max_fee = 1;
-        for f in r {
-            if f.prioritization_fee > max_fee {
-                max_fee = f.prioritization_fee;
-            }
-        }
-
-        // Set compute budget and fee instructions
-        let budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(800_000);
-        let fee_ix = ComputeBudgetInstruction::set_compute_unit_price(max_fee);
-        instructions.push(budget_ix);
-        instructions.push(fee_ix);
-
         // Cancel all limit orders
-        if let Some(ord_ret_type) = self.cancel_orders(false).await? {
+        if let Some(ord_ret_type) = self.cancel_orders(false, false).await? {
             match ord_ret_type {
                 OrderReturnType::Instructions(insts) => {
                     instructions.extend(insts);
@@ -1208,16 +5249,25 @@ impl OBClient {
             }
         }
 
-        self.rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), instructions)
-            .await
+        self.send_combo(
+            instructions,
+            TxConfig::with_compute_budget(COMBO_COMPUTE_UNIT_LIMIT, priority_fee),
+            dry_run,
+        )
+        .await
     }
 
     /// Executes a combination of canceling all limit orders and settling balance.
     ///
+    /// # Arguments
+    ///
+    /// * `fee_strategy` - How to pick the priority fee, `PriorityFeeStrategy::default()` being the 75th percentile of recent fees.
+    /// * `dry_run` - Whether to only simulate the transaction, returning its compute units and logs instead of sending it.
+    ///
     /// # Returns
     ///
-    /// A `Result` indicating success or failure.
+    /// A `Result` containing the signature and confirmation status of the sent transaction, or the
+    /// simulation result on a dry run.
     ///
     /// # Examples
     ///
@@ -1225,6 +5275,7 @@ impl OBClient {
     /// use openbook::v1::orders::OrderReturnType;
     /// use openbook::commitment_config::CommitmentConfig;
     /// use openbook::v1::ob_client::OBClient;
+    /// use openbook::rpc::PriorityFeeStrategy;
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -1234,37 +5285,29 @@ impl OBClient {
     ///
     ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
     ///
-    ///     let result = ob_client.cancel_settle().await?;
+    ///     let result = ob_client
+    ///         .cancel_settle(PriorityFeeStrategy::default(), false)
+    ///         .await?;
     ///
     ///     println!("{:?}", result);
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn cancel_settle(&mut self) -> Result<(bool, Signature)> {
+    pub async fn cancel_settle(
+        &mut self,
+        fee_strategy: PriorityFeeStrategy,
+        dry_run: bool,
+    ) -> Result<TxOutcome, OpenBookError> {
         let mut instructions = Vec::new();
 
-        // Fetch recent prioritization fees
-        let r = self
+        let priority_fee = self
             .rpc_client
-            .inner()
-            .get_recent_prioritization_fees(&[])
+            .estimate_priority_fee(&[], fee_strategy)
             .await?;
-        let mut max_fee = 1;
-        for f in r {
-            if f.prioritization_fee > max_fee {
-                max_fee = f.prioritization_fee;
-            }
-        }
-
-        // Set compute budget and fee instructions
-        let budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(800_000);
-        let fee_ix = ComputeBudgetInstruction::set_compute_unit_price(max_fee);
-        instructions.push(budget_ix);
-        instructions.push(fee_ix);
 
         // Cancel all limit orders
-        if let Some(ord_ret_type) = self.cancel_orders(false).await? {
+        if let Some(ord_ret_type) = self.cancel_orders(false, false).await? {
             match ord_ret_type {
                 OrderReturnType::Instructions(insts) => {
                     instructions.extend(insts);
@@ -1283,9 +5326,12 @@ impl OBClient {
             }
         }
 
-        self.rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), instructions)
-            .await
+        self.send_combo(
+            instructions,
+            TxConfig::with_compute_budget(COMBO_COMPUTE_UNIT_LIMIT, priority_fee),
+            dry_run,
+        )
+        .await
     }
 
     /// Consumes events from the market for specified open orders accounts.
@@ -1328,7 +5374,7 @@ impl OBClient {
         &self,
         open_orders_accounts: Vec<Pubkey>,
         limit: u16,
-    ) -> Result<(bool, Signature)> {
+    ) -> Result<(bool, Signature), OpenBookError> {
         let ix = openbook_dex::instruction::consume_events(
             &self.market_info.program_id,
             open_orders_accounts.iter().collect(),
@@ -1340,18 +5386,102 @@ impl OBClient {
         )
         .unwrap();
 
-        self.rpc_client
+        Ok(self
+            .rpc_client
             .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
-            .await
+            .await?)
+    }
+
+    /// Cranks the market, consuming events in batches until the event queue is empty.
+    ///
+    /// Before each batch, the event queue is read to check that events are left and to collect
+    /// the open orders accounts of the next `batch_limit` events, which the program needs to
+    /// credit the fills. At most `MAX_CONSUME_EVENTS_ACCOUNTS` accounts are passed per batch, so a
+    /// batch may consume fewer events than `batch_limit`.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `batch_limit` - The maximum number of events to consume per transaction.
+    /// * `max_iterations` - The maximum number of transactions to send.
+    ///
+    /// # Returns
+    ///
+    /// The signatures of the consume events transactions, in the order they were sent. The list
+    /// is empty if the queue was already empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the event queue can't be read or decoded, or if sending a transaction
+    /// fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v1::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, false, 1000).await?;
+    ///
+    ///     let signatures = ob_client.crank_until_empty(10, 5).await?;
+    ///
+    ///     println!("Cranked in {} transactions: {:?}", signatures.len(), signatures);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn crank_until_empty(
+        &self,
+        batch_limit: u16,
+        max_iterations: usize,
+    ) -> Result<Vec<Signature>, OpenBookError> {
+        let mut signatures = Vec::new();
+
+        for _ in 0..max_iterations {
+            let event_queue = self.load_event_queue().await?;
+            if event_queue.events.is_empty() {
+                break;
+            }
+
+            let open_orders_accounts =
+                event_queue.open_orders_accounts(batch_limit as usize, MAX_CONSUME_EVENTS_ACCOUNTS);
+            debug!(
+                "[*] Consuming up to {} of {} events for {} open orders accounts",
+                batch_limit,
+                event_queue.events.len(),
+                open_orders_accounts.len()
+            );
+
+            let (confirmed, signature) = self
+                .consume_events_instruction(open_orders_accounts, batch_limit)
+                .await?;
+            if !confirmed {
+                debug!("[*] Consume events transaction {} not confirmed", signature);
+            }
+            signatures.push(signature);
+        }
+
+        Ok(signatures)
     }
 
     /// Consumes permissioned events from the market for specified open orders accounts.
     ///
+    /// The market's consume events authority is used unless `authority` is set. The transaction
+    /// is signed by the owner, so an overriding authority must be the owner's key (e.g., a custom
+    /// cranker key loaded through `KEY_PATH`).
+    ///
     /// # Arguments
     ///
     /// * `&self` - A reference to the `OBClient` struct.
     /// * `open_orders_accounts` - A vector of `Pubkey` representing the open orders accounts.
     /// * `limit` - The maximum number of events to consume.
+    /// * `authority` - An optional consume events authority replacing the market's one.
     ///
     /// # Returns
     ///
@@ -1374,7 +5504,7 @@ impl OBClient {
     ///
     ///     let open_orders_accounts = vec![ob_client.open_orders.oo_key];
     ///     let limit = 10;
-    ///     let result = ob_client.consume_events_permissioned_instruction(open_orders_accounts, limit).await?;
+    ///     let result = ob_client.consume_events_permissioned_instruction(open_orders_accounts, limit, None).await?;
     ///
     ///     println!("{:?}", result);
     ///
@@ -1385,20 +5515,48 @@ impl OBClient {
         &self,
         open_orders_accounts: Vec<Pubkey>,
         limit: u16,
-    ) -> Result<(bool, Signature)> {
-        let ix = openbook_dex::instruction::consume_events_permissioned(
+        authority: Option<Pubkey>,
+    ) -> Result<(bool, Signature), OpenBookError> {
+        let ix = self.build_consume_events_permissioned_instruction(
+            open_orders_accounts,
+            limit,
+            authority,
+        )?;
+
+        Ok(self
+            .rpc_client
+            .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
+            .await?)
+    }
+
+    /// Builds the permissioned consume events instruction, without sending it.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `open_orders_accounts` - A vector of `Pubkey` representing the open orders accounts.
+    /// * `limit` - The maximum number of events to consume.
+    /// * `authority` - An optional consume events authority replacing the market's one.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Instruction`, or an error if it can't be built.
+    pub fn build_consume_events_permissioned_instruction(
+        &self,
+        open_orders_accounts: Vec<Pubkey>,
+        limit: u16,
+        authority: Option<Pubkey>,
+    ) -> Result<Instruction, OpenBookError> {
+        let authority = authority.unwrap_or(self.market_info.events_authority);
+
+        Ok(openbook_dex::instruction::consume_events_permissioned(
             &self.market_info.program_id,
             open_orders_accounts.iter().collect(),
             &self.market_info.market_address,
             &self.market_info.event_queue,
-            &self.market_info.events_authority,
+            &authority,
             limit,
-        )
-        .unwrap();
-
-        self.rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
-            .await
+        )?)
     }
 
     /// Loads open orders accounts for the owner, filtering them based on bids and asks.
@@ -1433,12 +5591,12 @@ impl OBClient {
     ///     Ok(())
     /// }
     /// ```
-    pub async fn load_orders_for_owner(&mut self) -> Result<Vec<u128>, Box<dyn std::error::Error>> {
+    pub async fn load_orders_for_owner(&mut self) -> Result<Vec<u128>, OpenBookError> {
         let mut bids = self.load_bids()?;
         let asks = self.load_asks()?;
         bids.extend(asks);
         let _open_orders_accounts = self
-            .find_open_orders_accounts_for_owner(self.open_orders.oo_key.clone(), 5000)
+            .find_open_orders_accounts_for_owner(self.owner.pubkey(), 5000)
             .await?;
 
         Ok(bids)
@@ -1496,11 +5654,21 @@ impl OBClient {
 
     /// Finds open orders accounts for a specified owner and caches them based on the specified duration.
     ///
+    /// For the client's own owner, a cache entry younger than `cache_duration_ms` is returned
+    /// without any RPC request. Otherwise the book and open orders are reloaded, and the result is
+    /// cached with the current timestamp. The client caches its own open orders under its owner's
+    /// key when created.
+    ///
+    /// Any other owner is looked up with `OpenOrders::find_for_market_and_owner` on every call and
+    /// never cached, so the cache only holds the wallet's own open orders. The first account found
+    /// is returned, or a default `OpenOrders` with `exists` unset if the owner has none.
+    ///
     /// # Arguments
     ///
     /// * `&mut self` - A mutable reference to the `OBClient` struct.
-    /// * `owner_address` - A reference to the owner's `Pubkey`.
-    /// * `cache_duration_ms` - The duration in milliseconds for which to cache open orders accounts.
+    /// * `owner_address` - The owner's `Pubkey`.
+    /// * `cache_duration_ms` - The duration in milliseconds for which to cache the wallet's own open
+    ///   orders.
     ///
     /// # Returns
     ///
@@ -1510,6 +5678,7 @@ impl OBClient {
     ///
     /// ```rust
     /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::signature::Signer;
     /// use openbook::v1::ob_client::OBClient;
     ///
     /// #[tokio::main]
@@ -1520,7 +5689,7 @@ impl OBClient {
     ///
     ///     let mut ob_client = OBClient::new(commitment, market_id, true, 1000).await?;
     ///
-    ///     let result = ob_client.find_open_orders_accounts_for_owner(ob_client.open_orders.oo_key, 5000).await?;
+    ///     let result = ob_client.find_open_orders_accounts_for_owner(ob_client.owner.pubkey(), 5000).await?;
     ///
     ///     println!("{:?}", result);
     ///
@@ -1531,27 +5700,221 @@ impl OBClient {
         &mut self,
         owner_address: Pubkey,
         cache_duration_ms: u128,
-    ) -> Result<OpenOrders, Box<dyn std::error::Error>> {
+    ) -> Result<OpenOrders, OpenBookError> {
+        if owner_address != self.owner.pubkey() {
+            let accounts = OpenOrders::find_for_market_and_owner(
+                &self.rpc_client,
+                self.market_info.program_id,
+                self.market_info.market_address,
+                owner_address,
+            )
+            .await?;
+
+            let mut open_orders = OpenOrders::default();
+            if let Some((oo_key, account)) = accounts.first() {
+                open_orders.oo_key = *oo_key;
+                open_orders.apply_account(
+                    account,
+                    self.market_info.coin_decimals,
+                    self.market_info.pc_decimals,
+                );
+            }
+            return Ok(open_orders);
+        }
+
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .expect("Time went backwards")
             .as_millis();
         if let Some(cache_entry) = self.open_orders_cache.get(&owner_address) {
-            if now - cache_entry.ts < cache_duration_ms as u128 {
+            if now.saturating_sub(cache_entry.ts) < cache_duration_ms {
                 return Ok(cache_entry.open_orders.clone());
             }
         }
 
         self.load_bids_asks_info().await?;
 
+        let open_orders = self.open_orders.clone();
         self.open_orders_cache.insert(
             owner_address,
             OpenOrdersCacheEntry {
-                open_orders: self.open_orders.clone(),
+                open_orders: open_orders.clone(),
                 ts: now,
             },
         );
 
-        Ok(self.open_orders.clone())
+        Ok(open_orders)
+    }
+}
+
+#[async_trait]
+impl OrderPlacer for OBClient {
+    async fn place_limit(
+        &self,
+        side: OrderSide,
+        price: f64,
+        size: f64,
+    ) -> Result<OrderHandle, OpenBookError> {
+        let placed = self
+            .place_limit_order_tracked(size, side.into(), price)
+            .await?
+            .ok_or(OpenBookError::OrderTooSmall(size))?;
+
+        Ok(OrderHandle {
+            signature: placed.signature,
+            order_id: placed.order_id,
+            client_order_id: placed.client_order_id,
+        })
+    }
+
+    async fn cancel_all(&self) -> Result<Option<Signature>, OpenBookError> {
+        Ok(self
+            .cancel_orders(true, false)
+            .await?
+            .and_then(|result| match result {
+                OrderReturnType::Signature(signature) => Some(signature),
+                OrderReturnType::Instructions(_) => None,
+            }))
+    }
+
+    async fn settle(&self) -> Result<Signature, OpenBookError> {
+        match self.settle_balance(true).await? {
+            Some(OrderReturnType::Signature(signature)) => Ok(signature),
+            _ => Err(anyhow!("Settling sent no transaction").into()),
+        }
+    }
+
+    async fn mid_price(&self) -> Result<f64, OpenBookError> {
+        Ok(self.get_mid_price().await?)
+    }
+}
+
+/// An order placed with `OBClient::place_limit_order_guarded`, canceled when the guard is dropped.
+///
+/// `Drop` can't wait, so dropping the guard spawns the cancel on the current tokio runtime and
+/// returns right away; the cancel is lost if the runtime shuts down first, or if there is no
+/// runtime. Call `cancel` to cancel and wait for the result, or `release` to keep the order on
+/// the book. The on-chain expiry of the order applies either way.
+pub struct OrderGuard {
+    client: OBClient,
+    client_order_id: u64,
+    signature: Signature,
+    armed: bool,
+}
+
+impl OrderGuard {
+    /// Returns the client order id of the order.
+    pub fn client_order_id(&self) -> u64 {
+        self.client_order_id
+    }
+
+    /// Returns the signature of the transaction placing the order.
+    pub fn signature(&self) -> Signature {
+        self.signature
     }
+
+    /// Cancels the order and waits for the cancel transaction.
+    ///
+    /// # Returns
+    ///
+    /// The signature of the cancel, or `None` if the order is no longer open.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cancel fails, in which case dropping the guard retries it.
+    pub async fn cancel(mut self) -> Result<Option<OrderReturnType>, OpenBookError> {
+        let result = self
+            .client
+            .cancel_order_by_client_id(self.client_order_id, true)
+            .await?;
+        self.armed = false;
+
+        Ok(result)
+    }
+
+    /// Keeps the order on the book and returns its client order id.
+    pub fn release(mut self) -> u64 {
+        self.armed = false;
+        self.client_order_id
+    }
+}
+
+impl Debug for OrderGuard {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        writeln!(f, "OrderGuard {{")?;
+        writeln!(f, "    client_order_id: {:?}", self.client_order_id)?;
+        writeln!(f, "    signature: {:?}", self.signature)?;
+        writeln!(f, "    armed: {:?}", self.armed)?;
+        writeln!(f, "}}")
+    }
+}
+
+impl Drop for OrderGuard {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+
+        let client_order_id = self.client_order_id;
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            tracing::warn!("No runtime to cancel order {} on drop", client_order_id);
+            return;
+        };
+        let client = self.client.clone();
+        runtime.spawn(async move {
+            if let Err(err) = client
+                .cancel_order_by_client_id(client_order_id, true)
+                .await
+            {
+                tracing::warn!(
+                    "Failed to cancel order {} on drop: {}",
+                    client_order_id,
+                    err
+                );
+            }
+        });
+    }
+}
+
+/// Decodes whether `order_id` is in the open orders account, and the event queue.
+///
+/// A missing open orders account holds no order.
+fn order_observation(
+    order_id: u128,
+    open_orders: Option<&Account>,
+    event_queue: &Account,
+) -> Result<(bool, EventQueue)> {
+    let open = match open_orders {
+        Some(account) => OpenOrdersAccount::from_account_data(&account.data)?
+            .active_orders()
+            .iter()
+            .any(|(_, id, _, _)| *id == order_id),
+        None => false,
+    };
+
+    Ok((open, EventQueue::from_account_data(&event_queue.data)?))
+}
+
+/// Returns the native amount a `new_order` instruction can lock: quote for a bid, base for an ask.
+fn new_order_native_amount(ix: &Instruction, coin_lot_size: u64) -> u64 {
+    match MarketInstruction::unpack(&ix.data) {
+        Some(MarketInstruction::NewOrderV3(order)) => match order.side {
+            Side::Bid => order.max_native_pc_qty_including_fees.get(),
+            Side::Ask => order.max_coin_qty.get().saturating_mul(coin_lot_size),
+        },
+        _ => 0,
+    }
+}
+
+/// The wallet's orders found on one side of the book by `ReadOnlyClient::walk_side`.
+#[derive(Default)]
+struct SideOrders {
+    order_ids: Vec<u128>,
+    /// The order prices, as `raw price / 1e4`.
+    prices: Vec<f64>,
+    /// Base lots left on each order, keyed by order id.
+    quantities: HashMap<u128, u64>,
+    client_ids: HashMap<u128, u64>,
+    /// The raw price of the best order of the side, whoever owns it, or `0` if it's empty.
+    best_price: u64,
 }
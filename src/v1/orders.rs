@@ -1,19 +1,36 @@
 //! This module contains structs and functions related to open orders on the Solana blockchain.
 
-use crate::rpc::Rpc;
+use crate::rpc::{Rpc, TxConfig};
+use crate::utils::get_unix_secs;
 use crate::v1::traits::OpenOrdersT;
-use anyhow::{Error, Result};
+use anyhow::{anyhow, Error, Result};
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_client::rpc_config::RpcSendTransactionConfig;
+use openbook_dex::{
+    instruction::{MarketInstruction, SelfTradeBehavior},
+    matching::{OrderType, Side},
+    state::AccountFlag,
+};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    client_error::ClientError,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
 use solana_sdk::{
-    compute_budget::ComputeBudgetInstruction,
+    account::Account,
+    clock::MAX_PROCESSING_AGE,
+    compute_budget::{self, ComputeBudgetInstruction},
     instruction::Instruction,
     pubkey::Pubkey,
     signature::{Signature, Signer},
     signer::keypair::Keypair,
-    transaction::Transaction,
 };
-use std::fmt::{Debug, Formatter};
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    future::Future,
+    time::Duration,
+};
 use tracing::{debug, error};
 
 #[derive(Clone, Default, BorshDeserialize, BorshSerialize)]
@@ -50,6 +67,38 @@ pub struct OpenOrders {
 
     /// The total amount of quote currency (pc) in the open orders account.
     pub quote_total: f64,
+
+    /// Whether the open orders account exists on chain. A wallet that never traded the market
+    /// has none until `OBClient::init_open_orders` creates it.
+    pub exists: bool,
+
+    /// Unix timestamps (in seconds) at which each open order was first seen, keyed by order id.
+    ///
+    /// The program doesn't store when an order was placed and the order id only holds its price
+    /// and sequence number, so orders are stamped when a load first finds them.
+    pub order_timestamps: HashMap<u128, u64>,
+
+    /// Base lots left on each open order, keyed by order id, as found on the book by the last
+    /// book load.
+    pub order_quantities: HashMap<u128, u64>,
+
+    /// Client order id of each open order, keyed by order id.
+    pub client_order_ids: HashMap<u128, u64>,
+}
+
+/// One of the wallet's open orders, as returned by `ReadOnlyClient::open_orders_detailed`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OpenOrderInfo {
+    /// The order id assigned by the program.
+    pub order_id: u128,
+    /// The side of the order.
+    pub side: Side,
+    /// The limit price, in UI units, decoded from the order id.
+    pub price: f64,
+    /// The base quantity left on the order, in UI units. `0.0` until a book load finds it.
+    pub size: f64,
+    /// The client order id the order was placed with, `0` if unknown.
+    pub client_id: u64,
 }
 
 impl Debug for OpenOrders {
@@ -66,13 +115,128 @@ impl Debug for OpenOrders {
         writeln!(f, "        open_bids_prices: {:?}", self.open_bids_prices)?;
         writeln!(f, "        base_total: {:?}", self.base_total)?;
         writeln!(f, "        quote_total: {:?}", self.quote_total)?;
+        writeln!(f, "        exists: {:?}", self.exists)?;
+        writeln!(f, "        order_timestamps: {:?}", self.order_timestamps)?;
+        writeln!(f, "        order_quantities: {:?}", self.order_quantities)?;
+        writeln!(f, "        client_order_ids: {:?}", self.client_order_ids)?;
         writeln!(f, "    }}")
     }
 }
 
+impl OpenOrders {
+    /// Updates the wallet's orders and balances from a decoded open orders account.
+    ///
+    /// Book fields such as `max_bid` and `min_ask` are left untouched. Prices are taken from the
+    /// high 64 bits of each order id, as stored by the program. The client order ids are taken
+    /// from the account, and the quantities of the orders no longer open are dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The decoded open orders account.
+    /// * `base_decimals` - Decimals of the base mint.
+    /// * `quote_decimals` - Decimals of the quote mint.
+    pub fn apply_account(
+        &mut self,
+        account: &OpenOrdersAccount,
+        base_decimals: u8,
+        quote_decimals: u8,
+    ) {
+        self.open_bids.clear();
+        self.open_bids_prices.clear();
+        self.open_asks.clear();
+        self.open_asks_prices.clear();
+
+        self.client_order_ids.clear();
+
+        for (_slot, order_id, client_order_id, is_bid) in account.active_orders() {
+            self.client_order_ids.insert(order_id, client_order_id);
+            let ui_price = (order_id >> 64) as u64 as f64 / 1e4;
+            if is_bid {
+                self.open_bids.push(order_id);
+                self.open_bids_prices.push(ui_price);
+            } else {
+                self.open_asks.push(order_id);
+                self.open_asks_prices.push(ui_price);
+            }
+        }
+
+        let client_order_ids = &self.client_order_ids;
+        self.order_quantities
+            .retain(|order_id, _| client_order_ids.contains_key(order_id));
+
+        self.base_total = account.native_coin_total as f64 / 10u64.pow(base_decimals as u32) as f64;
+        self.quote_total = account.native_pc_total as f64 / 10u64.pow(quote_decimals as u32) as f64;
+        self.exists = true;
+    }
+
+    /// Marks the open orders account as missing and zeroes the wallet's orders and balances.
+    ///
+    /// Book fields such as `max_bid` and `min_ask` are left untouched.
+    pub fn mark_missing(&mut self) {
+        self.open_bids.clear();
+        self.open_bids_prices.clear();
+        self.open_asks.clear();
+        self.open_asks_prices.clear();
+        self.base_total = 0.0;
+        self.quote_total = 0.0;
+        self.exists = false;
+        self.order_timestamps.clear();
+        self.order_quantities.clear();
+        self.client_order_ids.clear();
+    }
+
+    /// Stamps the open orders not seen before with `now` and forgets the orders that left the book.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current Unix timestamp, in seconds.
+    pub fn stamp_orders(&mut self, now: u64) {
+        let open_orders: Vec<u128> = self
+            .open_bids
+            .iter()
+            .chain(&self.open_asks)
+            .copied()
+            .collect();
+
+        self.order_timestamps
+            .retain(|order_id, _| open_orders.contains(order_id));
+        for order_id in open_orders {
+            self.order_timestamps.entry(order_id).or_insert(now);
+        }
+    }
+
+    /// Returns the open bids and asks that were first seen at least `age` before `now`.
+    ///
+    /// Orders without a timestamp are never considered stale.
+    ///
+    /// # Arguments
+    ///
+    /// * `age` - The minimum age of the returned orders.
+    /// * `now` - The current Unix timestamp, in seconds.
+    ///
+    /// # Returns
+    ///
+    /// The ids of the stale bids and of the stale asks.
+    pub fn orders_older_than(&self, age: Duration, now: u64) -> (Vec<u128>, Vec<u128>) {
+        let is_stale = |order_id: &&u128| {
+            self.order_timestamps
+                .get(order_id)
+                .map_or(false, |&ts| now.saturating_sub(ts) >= age.as_secs())
+        };
+
+        (
+            self.open_bids.iter().filter(is_stale).copied().collect(),
+            self.open_asks.iter().filter(is_stale).copied().collect(),
+        )
+    }
+}
+
 impl OpenOrdersT for OpenOrders {
     /// Creates a new `OpenOrders` instance from the given data.
     ///
+    /// The account is resolved with `resolve_open_orders` but never created here; `exists` tells
+    /// whether it is already on chain.
+    ///
     /// # Arguments
     ///
     /// * `rpc_client` - RPC client for interacting with the Solana blockchain.
@@ -93,19 +257,21 @@ impl OpenOrdersT for OpenOrders {
         keypair: Keypair,
         market_address: Pubkey,
     ) -> Result<Self, Error> {
-        let mut oo_account = Self::default();
-
-        let _ = oo_account
-            .make_create_account_transaction(&rpc_client, program_id, &keypair, market_address)
-            .await?;
+        let oo_key = resolve_open_orders(&keypair.pubkey(), &market_address, &program_id, None);
+        let exists = open_orders_initialized(&rpc_client, &oo_key, &program_id).await?;
 
-        Ok(oo_account)
+        Ok(Self {
+            oo_key,
+            exists,
+            ..Default::default()
+        })
     }
 
     /// Generates a new open orders account associated with a wallet.
     ///
     /// This method creates a new open orders account on the Solana blockchain and initializes it with the provided parameters.
-    /// Open orders accounts are used to manage orders within a specific market.
+    /// Open orders accounts are used to manage orders within a specific market. The account is created at the
+    /// address derived by `resolve_open_orders`, so the same wallet always gets the same account for a market.
     ///
     /// # Arguments
     ///
@@ -128,38 +294,115 @@ impl OpenOrdersT for OpenOrders {
         keypair: &Keypair,
         market_account: Pubkey,
     ) -> Result<Pubkey, Error> {
-        let new_account_address = Keypair::new();
-        let space = 0;
+        Ok(self
+            .create_account(connection, program_id, keypair, market_account)
+            .await?
+            .0)
+    }
+}
+
+impl OpenOrders {
+    /// Finds the open orders accounts of `owner` on `market` with a filtered `getProgramAccounts`.
+    ///
+    /// Unlike `resolve_open_orders`, this also finds accounts created at random addresses by
+    /// other frontends. The accounts are filtered on chain by size, market and owner with
+    /// `open_orders_filters`, so a wallet active in many markets only downloads the accounts of
+    /// this one.
+    ///
+    /// # Arguments
+    ///
+    /// * `rpc_client` - RPC client for interacting with the Solana blockchain.
+    /// * `program_id` - The dex program owning the accounts.
+    /// * `market` - The market of the open orders accounts.
+    /// * `owner` - The owner of the open orders accounts.
+    ///
+    /// # Returns
+    ///
+    /// The address and decoded content of each open orders account found.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC request still fails after the configured attempts, or if an
+    /// account can't be decoded.
+    pub async fn find_for_market_and_owner(
+        rpc_client: &Rpc,
+        program_id: Pubkey,
+        market: Pubkey,
+        owner: Pubkey,
+    ) -> Result<Vec<(Pubkey, OpenOrdersAccount)>, Error> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(open_orders_filters(&market, &owner)),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+
+        rpc_client
+            .fetch_program_accounts(&program_id, config)
+            .await?
+            .into_iter()
+            .map(|(key, account)| Ok((key, OpenOrdersAccount::from_account_data(&account.data)?)))
+            .collect()
+    }
+
+    /// Creates the open orders account of a wallet, like `make_create_account_transaction`.
+    ///
+    /// # Returns
+    ///
+    /// The public key of the open orders account, and the signature of the creation transaction
+    /// if it was confirmed. `exists` is updated accordingly.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the rent exemption or the prioritization fees can't be fetched, or if
+    /// `oo_key` was overridden (e.g. by `OOS_KEY`) with an account other than the derived one,
+    /// which can't be created here.
+    pub(crate) async fn create_account(
+        &mut self,
+        connection: &Rpc,
+        program_id: Pubkey,
+        keypair: &Keypair,
+        market_account: Pubkey,
+    ) -> Result<(Pubkey, Option<Signature>), Error> {
+        let seed = open_orders_seed(&market_account);
+        let new_account_address =
+            resolve_open_orders(&keypair.pubkey(), &market_account, &program_id, None);
+        if self.oo_key != Pubkey::default() && self.oo_key != new_account_address {
+            return Err(anyhow!(
+                "Open orders account {} doesn't exist and isn't the derived {}",
+                self.oo_key,
+                new_account_address
+            ));
+        }
+        let space = OPEN_ORDERS_ACCOUNT_SIZE;
         let minimum_balance = connection
-            .inner()
             .get_minimum_balance_for_rent_exemption(space)
             .await?;
 
-        let instruction = solana_sdk::system_instruction::create_account(
+        let instruction = solana_sdk::system_instruction::create_account_with_seed(
+            &keypair.pubkey(),
+            &new_account_address,
             &keypair.pubkey(),
-            &new_account_address.pubkey(),
+            &seed,
             minimum_balance,
             space as u64,
-            &keypair.pubkey(),
+            &program_id,
         );
         let init_ix = openbook_dex::instruction::init_open_orders(
             &program_id,
-            &new_account_address.pubkey(),
+            &new_account_address,
             &keypair.pubkey(),
             &market_account,
             None,
         )?;
-        debug!(
-            "[*] Got New Account Address: {:?}",
-            new_account_address.pubkey()
-        );
+        debug!("[*] Got New Account Address: {:?}", new_account_address);
 
-        let mut instructions = Vec::new();
         let r = connection
-            .inner()
+            .provider()
             .get_recent_prioritization_fees(&[])
-            .await
-            .unwrap();
+            .await?;
         let mut max_fee = 1;
         for f in r {
             if f.prioritization_fee > max_fee {
@@ -167,43 +410,290 @@ impl OpenOrdersT for OpenOrders {
             }
         }
 
-        let budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(1_000_000);
-        let fee_ix = ComputeBudgetInstruction::set_compute_unit_price(max_fee);
-        instructions.push(budget_ix);
-        instructions.push(fee_ix);
-
-        instructions.push(instruction);
-        instructions.push(init_ix);
+        let instructions = vec![instruction, init_ix];
 
         debug!("[*] Using Pubkey: {}", &keypair.pubkey().to_string());
 
-        let recent_hash = connection.inner().get_latest_blockhash().await?;
-        let txn = Transaction::new_signed_with_payer(
-            &instructions,
-            Some(&keypair.pubkey()),
-            &[&new_account_address, &keypair],
-            recent_hash,
-        );
-
-        let mut config = RpcSendTransactionConfig::default();
-        config.skip_preflight = true;
-
+        let config = TxConfig::with_compute_budget(1_000_000, max_fee);
         let result = connection
-            .inner()
-            .send_transaction_with_config(&txn, config)
+            .send_and_confirm_with_config(keypair.insecure_clone(), instructions, &config)
             .await;
 
-        match result {
-            Ok(sig) => debug!("[*] Transaction successful, signature: {:?}", sig),
-            Err(err) => error!("[*] Transaction failed: {:?}", err),
+        let signature = match result {
+            Ok((true, sig)) => {
+                debug!("[*] Transaction successful, signature: {:?}", sig);
+                Some(sig)
+            }
+            Ok((false, _)) => {
+                error!("[*] Transaction failed to confirm");
+                None
+            }
+            Err(err) => {
+                error!("[*] Transaction failed: {:?}", err);
+                None
+            }
         };
 
-        self.oo_key = new_account_address.pubkey();
+        self.exists = signature.is_some();
+        self.oo_key = new_account_address;
 
-        Ok(new_account_address.pubkey())
+        Ok((new_account_address, signature))
     }
 }
 
+/// Checks that the open orders account `oo_key` exists, is owned by `program_id` and is
+/// initialized, as told by `is_initialized_open_orders_account`.
+///
+/// An account at the derived address that the program doesn't own, or that was never
+/// initialized as an open orders account, can't be used to trade, so it counts as missing.
+///
+/// # Errors
+///
+/// Returns an error if the RPC request fails, so that a transient failure isn't mistaken for a
+/// missing account.
+pub(crate) async fn open_orders_initialized(
+    rpc_client: &Rpc,
+    oo_key: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<bool, ClientError> {
+    let account = rpc_client
+        .fetch_multiple_accounts(std::slice::from_ref(oo_key))
+        .await?
+        .pop()
+        .flatten();
+
+    Ok(account.is_some_and(|account| is_initialized_open_orders_account(&account, program_id)))
+}
+
+/// Returns whether `account` is an initialized open orders account of `program_id`.
+///
+/// The account must be owned by the program, and its account flags, stored after the 5 bytes
+/// "serum" head padding, must have both the `Initialized` and `OpenOrders` bits set.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::pubkey::Pubkey;
+/// use openbook::v1::orders::{is_initialized_open_orders_account, OPEN_ORDERS_ACCOUNT_SIZE};
+/// use solana_sdk::account::Account;
+///
+/// let program_id = Pubkey::new_unique();
+/// let account = Account::new(1, OPEN_ORDERS_ACCOUNT_SIZE, &program_id);
+///
+/// // A zeroed account was allocated but never initialized.
+/// assert!(!is_initialized_open_orders_account(&account, &program_id));
+/// ```
+pub fn is_initialized_open_orders_account(account: &Account, program_id: &Pubkey) -> bool {
+    let required = AccountFlag::Initialized as u64 | AccountFlag::OpenOrders as u64;
+
+    account.owner == *program_id
+        && account.data.len() >= 13
+        && read_u64(&account.data, 5) & required == required
+}
+
+/// Returns the seed used to derive the open orders account of a wallet on `market`.
+///
+/// The seed is the first 32 characters of the market address, the maximum seed length
+/// accepted by `Pubkey::create_with_seed`.
+pub fn open_orders_seed(market: &Pubkey) -> String {
+    market.to_string().chars().take(32).collect()
+}
+
+/// Resolves the open orders account used by a wallet on a market.
+///
+/// The precedence is:
+///
+/// 1. `env_override`, typically parsed from the `OOS_KEY` env var, when set.
+/// 2. The address derived with `Pubkey::create_with_seed(owner, open_orders_seed(market), program_id)`,
+///    which is where `OBClient::init_open_orders` creates the account when it doesn't exist yet.
+///
+/// # Arguments
+///
+/// * `owner` - The wallet owning the open orders account.
+/// * `market` - The market the open orders account belongs to.
+/// * `program_id` - The OpenBook program id.
+/// * `env_override` - An explicit open orders account, taking precedence over the derivation.
+///
+/// # Returns
+///
+/// The public key of the open orders account.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::pubkey::Pubkey;
+/// use openbook::v1::orders::resolve_open_orders;
+///
+/// let owner = Pubkey::new_unique();
+/// let market = Pubkey::new_unique();
+/// let program_id = Pubkey::new_unique();
+///
+/// let oo_key = resolve_open_orders(&owner, &market, &program_id, None);
+///
+/// println!("{:?}", oo_key);
+/// ```
+pub fn resolve_open_orders(
+    owner: &Pubkey,
+    market: &Pubkey,
+    program_id: &Pubkey,
+    env_override: Option<Pubkey>,
+) -> Pubkey {
+    env_override.unwrap_or_else(|| {
+        Pubkey::create_with_seed(owner, &open_orders_seed(market), program_id)
+            .expect("a 32 characters seed is always valid")
+    })
+}
+
+/// Size in bytes of an OpenBook v1 open orders account, including the head and tail padding.
+pub const OPEN_ORDERS_ACCOUNT_SIZE: usize = 3228;
+
+/// Returns the `getProgramAccounts` filters matching the open orders accounts of `owner` on
+/// `market`.
+///
+/// The market and owner follow the 5 bytes "serum" head padding and the 8 bytes account flags.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::pubkey::Pubkey;
+/// use openbook::v1::orders::open_orders_filters;
+///
+/// let filters = open_orders_filters(&Pubkey::new_unique(), &Pubkey::new_unique());
+///
+/// assert_eq!(filters.len(), 3);
+/// ```
+pub fn open_orders_filters(market: &Pubkey, owner: &Pubkey) -> Vec<RpcFilterType> {
+    vec![
+        RpcFilterType::DataSize(OPEN_ORDERS_ACCOUNT_SIZE as u64),
+        RpcFilterType::Memcmp(Memcmp::new_raw_bytes(13, market.to_bytes().to_vec())),
+        RpcFilterType::Memcmp(Memcmp::new_raw_bytes(45, owner.to_bytes().to_vec())),
+    ]
+}
+
+/// Decoded content of an OpenBook v1 open orders account.
+#[derive(Clone, Default, BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+pub struct OpenOrdersAccount {
+    /// The market this open orders account belongs to.
+    pub market: Pubkey,
+
+    /// The owner of the open orders account.
+    pub owner: Pubkey,
+
+    /// Base tokens (native units) that can be settled back to the wallet.
+    pub native_coin_free: u64,
+
+    /// Base tokens (native units) held by the account, free or locked in orders.
+    pub native_coin_total: u64,
+
+    /// Quote tokens (native units) that can be settled back to the wallet.
+    pub native_pc_free: u64,
+
+    /// Quote tokens (native units) held by the account, free or locked in orders.
+    pub native_pc_total: u64,
+
+    /// Bitmask of the order slots that are free.
+    pub free_slot_bits: u128,
+
+    /// Bitmask of the order slots holding bids.
+    pub is_bid_bits: u128,
+
+    /// Order ids stored in each of the 128 slots.
+    pub orders: Vec<u128>,
+
+    /// Client order ids stored in each of the 128 slots.
+    pub client_order_ids: Vec<u64>,
+}
+
+impl OpenOrdersAccount {
+    /// Decodes an open orders account from its raw account data.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The data of the open orders account, as returned by the RPC.
+    ///
+    /// # Returns
+    ///
+    /// The decoded `OpenOrdersAccount`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data does not have the size of an open orders account.
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        if data.len() != OPEN_ORDERS_ACCOUNT_SIZE {
+            return Err(anyhow!(
+                "Invalid open orders account size: {} (expected {})",
+                data.len(),
+                OPEN_ORDERS_ACCOUNT_SIZE
+            ));
+        }
+
+        // Skip the 5 bytes "serum" head padding and the 8 bytes account flags.
+        let market = Pubkey::try_from(&data[13..45])?;
+        let owner = Pubkey::try_from(&data[45..77])?;
+        let native_coin_free = read_u64(data, 77);
+        let native_coin_total = read_u64(data, 85);
+        let native_pc_free = read_u64(data, 93);
+        let native_pc_total = read_u64(data, 101);
+        let free_slot_bits = read_u128(data, 109);
+        let is_bid_bits = read_u128(data, 125);
+        let orders = (0..128).map(|i| read_u128(data, 141 + i * 16)).collect();
+        let client_order_ids = (0..128).map(|i| read_u64(data, 2189 + i * 8)).collect();
+
+        Ok(Self {
+            market,
+            owner,
+            native_coin_free,
+            native_coin_total,
+            native_pc_free,
+            native_pc_total,
+            free_slot_bits,
+            is_bid_bits,
+            orders,
+            client_order_ids,
+        })
+    }
+
+    /// Returns the `(slot, order_id, client_order_id, is_bid)` of every order slot in use.
+    pub fn active_orders(&self) -> Vec<(u8, u128, u64, bool)> {
+        (0..128u8)
+            .filter(|slot| self.free_slot_bits & (1u128 << slot) == 0)
+            .map(|slot| {
+                (
+                    slot,
+                    self.orders[slot as usize],
+                    self.client_order_ids[slot as usize],
+                    self.is_bid_bits & (1u128 << slot) != 0,
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the order id assigned by the program to the order with `client_order_id`, if it
+    /// is still open.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_order_id` - The client order id the order was placed with.
+    pub fn order_id_for_client_id(&self, client_order_id: u64) -> Option<u128> {
+        self.active_orders()
+            .into_iter()
+            .find(|(_, _, id, _)| *id == client_order_id)
+            .map(|(_, order_id, _, _)| order_id)
+    }
+}
+
+pub(crate) fn read_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+pub(crate) fn read_u128(data: &[u8], offset: usize) -> u128 {
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&data[offset..offset + 16]);
+    u128::from_le_bytes(bytes)
+}
+
 #[derive(Clone, Default, BorshDeserialize, BorshSerialize, Debug)]
 pub struct OpenOrdersCacheEntry {
     pub open_orders: OpenOrders,
@@ -215,3 +705,530 @@ pub enum OrderReturnType {
     Instructions(Vec<Instruction>),
     Signature(Signature),
 }
+
+impl OrderReturnType {
+    /// Returns the built instructions, or `None` if the order was already sent.
+    pub fn into_instructions(self) -> Option<Vec<Instruction>> {
+        match self {
+            OrderReturnType::Instructions(instructions) => Some(instructions),
+            OrderReturnType::Signature(_) => None,
+        }
+    }
+}
+
+/// The outcome of `OBClient::place_limit_order_outcome`, telling apart the reasons an order
+/// isn't placed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlaceOrderOutcome {
+    /// The order was sent, with the signature of its transaction.
+    Submitted(Signature),
+    /// The order was built but not sent (`execute = false`).
+    Instructions(Vec<Instruction>),
+    /// The size rounds down to less than one base lot, so no order was built. Increase the size
+    /// and retry.
+    BelowMinimumSize {
+        /// The base lots the size rounds down to.
+        computed_base_lots: u64,
+    },
+    /// The book has no bid or ask to price the order from, so no order was built.
+    EmptyBook,
+}
+
+impl From<PlaceOrderOutcome> for Option<OrderReturnType> {
+    /// Maps the outcome to the result of `place_limit_order`, `None` when no order was built.
+    fn from(outcome: PlaceOrderOutcome) -> Self {
+        match outcome {
+            PlaceOrderOutcome::Submitted(signature) => Some(OrderReturnType::Signature(signature)),
+            PlaceOrderOutcome::Instructions(instructions) => {
+                Some(OrderReturnType::Instructions(instructions))
+            }
+            PlaceOrderOutcome::BelowMinimumSize { .. } | PlaceOrderOutcome::EmptyBook => None,
+        }
+    }
+}
+
+/// Flattens the results of several build-only calls (`execute = false`) into one instruction list.
+///
+/// Use it to fold the instructions of `place_limit_order`, `cancel_orders` or `settle_balance`
+/// into a larger transaction. Results with nothing to do (`None`) and results that were already
+/// sent (`Signature`) contribute no instructions.
+///
+/// # Arguments
+///
+/// * `results` - The results of the build-only calls, in transaction order.
+///
+/// # Returns
+///
+/// The instructions of all results, concatenated in order.
+///
+/// # Examples
+///
+/// ```rust
+/// use openbook::v1::orders::{collect_instructions, OrderReturnType};
+/// use solana_sdk::instruction::Instruction;
+/// use solana_sdk::pubkey::Pubkey;
+///
+/// let ix = Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![]);
+///
+/// let instructions = collect_instructions(vec![
+///     Some(OrderReturnType::Instructions(vec![ix.clone()])),
+///     None,
+/// ]);
+///
+/// assert_eq!(instructions, vec![ix]);
+/// ```
+pub fn collect_instructions(results: Vec<Option<OrderReturnType>>) -> Vec<Instruction> {
+    results
+        .into_iter()
+        .flatten()
+        .filter_map(OrderReturnType::into_instructions)
+        .flatten()
+        .collect()
+}
+
+/// A single step of a combo transaction such as `cancel_settle_place`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComboStep {
+    /// Cancel all open orders of the wallet.
+    Cancel,
+    /// Settle free balances back to the wallet's token accounts.
+    Settle,
+    /// Place the bid order.
+    PlaceBid,
+    /// Place the ask order.
+    PlaceAsk,
+}
+
+impl ComboStep {
+    /// Returns the default combo order: cancel → settle → bid → ask.
+    pub fn default_order() -> Vec<ComboStep> {
+        vec![
+            ComboStep::Cancel,
+            ComboStep::Settle,
+            ComboStep::PlaceBid,
+            ComboStep::PlaceAsk,
+        ]
+    }
+
+    /// Validates a combo order, rejecting sequences that contain the same step more than once.
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - The sequence of steps to validate.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the first duplicated step.
+    pub fn validate_order(order: &[ComboStep]) -> Result<()> {
+        for (i, step) in order.iter().enumerate() {
+            if order[..i].contains(step) {
+                return Err(anyhow!("Duplicate combo step: {:?}", step));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Assembles the instructions of each combo step following the given order.
+///
+/// Steps present in `order` but missing from `steps` (e.g. no orders to cancel) are skipped,
+/// and steps missing from `order` are left out of the result.
+///
+/// # Arguments
+///
+/// * `order` - The order in which steps should appear in the transaction.
+/// * `steps` - The instructions generated for each step.
+///
+/// # Returns
+///
+/// The instructions of all steps, concatenated in `order`.
+///
+/// # Errors
+///
+/// Returns an error if `order` contains duplicate steps.
+pub fn order_combo_instructions(
+    order: &[ComboStep],
+    mut steps: HashMap<ComboStep, Vec<Instruction>>,
+) -> Result<Vec<Instruction>> {
+    ComboStep::validate_order(order)?;
+
+    let mut instructions = Vec::new();
+    for step in order {
+        if let Some(insts) = steps.remove(step) {
+            instructions.extend(insts);
+        }
+    }
+    Ok(instructions)
+}
+
+/// Prepends `instructions` to those of `step`, adding the step if it had no instructions.
+///
+/// Used to run setup instructions, such as creating token accounts, right before the step that
+/// needs them.
+///
+/// # Arguments
+///
+/// * `steps` - The instructions generated for each step.
+/// * `step` - The step to prepend the instructions to.
+/// * `instructions` - The instructions to prepend.
+pub fn prepend_step_instructions(
+    steps: &mut HashMap<ComboStep, Vec<Instruction>>,
+    step: ComboStep,
+    instructions: Vec<Instruction>,
+) {
+    let step_instructions = steps.entry(step).or_default();
+    step_instructions.splice(0..0, instructions);
+}
+
+/// The decoded kind of an instruction of a combo transaction, as reported by `ComboPreview`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComboInstructionKind {
+    /// Sets the compute unit limit of the transaction.
+    SetComputeUnitLimit(u32),
+    /// Sets the compute unit price, in micro-lamports, of the transaction.
+    SetComputeUnitPrice(u64),
+    /// Cancels an open order.
+    CancelOrder,
+    /// Settles the free open orders balances to the wallet's token accounts.
+    SettleFunds,
+    /// Places a new order.
+    NewOrder,
+    /// Creates an associated token account.
+    CreateTokenAccount,
+    /// Closes a token account, such as the wrapped SOL account.
+    CloseTokenAccount,
+    /// Any other instruction, with its program id.
+    Other(Pubkey),
+}
+
+impl ComboInstructionKind {
+    /// Decodes the kind of `instruction`, where `dex_program_id` is the program of the market.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::v1::orders::ComboInstructionKind;
+    /// use solana_sdk::compute_budget::ComputeBudgetInstruction;
+    /// use solana_sdk::pubkey::Pubkey;
+    ///
+    /// let ix = ComputeBudgetInstruction::set_compute_unit_price(5);
+    ///
+    /// assert_eq!(
+    ///     ComboInstructionKind::of(&ix, &Pubkey::new_unique()),
+    ///     ComboInstructionKind::SetComputeUnitPrice(5)
+    /// );
+    /// ```
+    pub fn of(instruction: &Instruction, dex_program_id: &Pubkey) -> Self {
+        let program_id = instruction.program_id;
+        if program_id == compute_budget::id() {
+            match ComputeBudgetInstruction::try_from_slice(&instruction.data) {
+                Ok(ComputeBudgetInstruction::SetComputeUnitLimit(limit)) => {
+                    return ComboInstructionKind::SetComputeUnitLimit(limit)
+                }
+                Ok(ComputeBudgetInstruction::SetComputeUnitPrice(price)) => {
+                    return ComboInstructionKind::SetComputeUnitPrice(price)
+                }
+                _ => {}
+            }
+        } else if program_id == *dex_program_id {
+            match MarketInstruction::unpack(&instruction.data) {
+                Some(
+                    MarketInstruction::CancelOrderV2(_)
+                    | MarketInstruction::CancelOrderByClientIdV2(_)
+                    | MarketInstruction::CancelOrdersByClientIds(_),
+                ) => return ComboInstructionKind::CancelOrder,
+                Some(MarketInstruction::SettleFunds) => return ComboInstructionKind::SettleFunds,
+                Some(MarketInstruction::NewOrderV3(_)) => return ComboInstructionKind::NewOrder,
+                _ => {}
+            }
+        } else if program_id == spl_associated_token_account::id() {
+            return ComboInstructionKind::CreateTokenAccount;
+        } else if program_id == spl_token::id()
+            && matches!(
+                spl_token::instruction::TokenInstruction::unpack(&instruction.data),
+                Ok(spl_token::instruction::TokenInstruction::CloseAccount)
+            )
+        {
+            return ComboInstructionKind::CloseTokenAccount;
+        }
+
+        ComboInstructionKind::Other(program_id)
+    }
+}
+
+/// What a combo method such as `cancel_settle_place` would send, as built by
+/// `OBClient::preview_combo`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComboPreview {
+    /// The priority fee picked by the fee strategy, in micro-lamports per compute unit.
+    pub priority_fee: u64,
+    /// The instructions of the transaction, compute budget included, in order.
+    pub instructions: Vec<Instruction>,
+    /// The decoded kind of each instruction.
+    pub kinds: Vec<ComboInstructionKind>,
+}
+
+impl ComboPreview {
+    /// Decodes the kinds of `instructions`, where `dex_program_id` is the program of the market.
+    pub fn new(priority_fee: u64, instructions: Vec<Instruction>, dex_program_id: &Pubkey) -> Self {
+        let kinds = instructions
+            .iter()
+            .map(|ix| ComboInstructionKind::of(ix, dex_program_id))
+            .collect();
+        Self {
+            priority_fee,
+            instructions,
+            kinds,
+        }
+    }
+
+    /// Returns the compute unit limit set by the instructions, if any.
+    pub fn compute_unit_limit(&self) -> Option<u32> {
+        self.kinds.iter().find_map(|kind| match kind {
+            ComboInstructionKind::SetComputeUnitLimit(limit) => Some(*limit),
+            _ => None,
+        })
+    }
+
+    /// Returns the most the priority fee can cost, in lamports: the compute unit limit times the
+    /// compute unit price, rounded up.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::v1::orders::ComboPreview;
+    /// use solana_sdk::compute_budget::ComputeBudgetInstruction;
+    /// use solana_sdk::pubkey::Pubkey;
+    ///
+    /// let instructions = vec![
+    ///     ComputeBudgetInstruction::set_compute_unit_limit(800_000),
+    ///     ComputeBudgetInstruction::set_compute_unit_price(5),
+    /// ];
+    /// let preview = ComboPreview::new(5, instructions, &Pubkey::new_unique());
+    ///
+    /// assert_eq!(preview.max_priority_fee_lamports(), 4);
+    /// ```
+    pub fn max_priority_fee_lamports(&self) -> u64 {
+        let price = self
+            .kinds
+            .iter()
+            .find_map(|kind| match kind {
+                ComboInstructionKind::SetComputeUnitPrice(price) => Some(*price),
+                _ => None,
+            })
+            .unwrap_or_default();
+        let micro_lamports =
+            u128::from(self.compute_unit_limit().unwrap_or_default()) * u128::from(price);
+        ((micro_lamports + 999_999) / 1_000_000) as u64
+    }
+}
+
+/// Result of a single attempt at sending a placement transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SendAttempt {
+    /// The transaction was confirmed.
+    Confirmed(Signature),
+    /// The transaction was not confirmed and its blockhash expired.
+    Dropped(Signature),
+    /// The order was found on the book, placed by a previous attempt that landed late.
+    AlreadyPlaced,
+}
+
+/// An order placed by `place_limit_order_tracked`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlacedOrder {
+    /// The signature of the confirmed transaction.
+    pub signature: Signature,
+    /// The order id assigned by the program, or `None` if the order no longer rested in the open
+    /// orders account when it was read back, e.g. because it was filled right away.
+    pub order_id: Option<u128>,
+    /// The client order id the order was placed with.
+    pub client_order_id: u64,
+    /// The slot the transaction landed in.
+    pub slot: u64,
+}
+
+/// Outcome of `place_limit_order_durable`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaceOutcome {
+    /// The client order id shared by every attempt.
+    pub client_order_id: u64,
+    /// The signature of the confirmed transaction, or of the last attempt if none confirmed.
+    pub signature: Option<Signature>,
+    /// The number of attempts made.
+    pub attempts: u32,
+    /// Whether the order was confirmed on chain.
+    pub confirmed: bool,
+}
+
+/// Calls `send` with increasing attempt numbers (starting at 1) until an attempt confirms,
+/// reports the order as already placed, or `max_attempts` is reached.
+///
+/// # Arguments
+///
+/// * `client_order_id` - The client order id shared by every attempt.
+/// * `max_attempts` - The maximum number of attempts.
+/// * `send` - Performs one attempt.
+///
+/// # Returns
+///
+/// The `PlaceOutcome` of the attempts.
+///
+/// # Errors
+///
+/// Returns the first error returned by `send`.
+pub async fn resend_until_confirmed<F, Fut>(
+    client_order_id: u64,
+    max_attempts: u32,
+    mut send: F,
+) -> Result<PlaceOutcome>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<SendAttempt>>,
+{
+    let mut outcome = PlaceOutcome {
+        client_order_id,
+        signature: None,
+        attempts: 0,
+        confirmed: false,
+    };
+
+    for attempt in 1..=max_attempts {
+        outcome.attempts = attempt;
+        match send(attempt).await? {
+            SendAttempt::Confirmed(signature) => {
+                outcome.signature = Some(signature);
+                outcome.confirmed = true;
+                break;
+            }
+            SendAttempt::AlreadyPlaced => {
+                outcome.confirmed = true;
+                break;
+            }
+            SendAttempt::Dropped(signature) => {
+                debug!("[*] Attempt {} dropped, re-sending", attempt);
+                outcome.signature = Some(signature);
+            }
+        }
+    }
+
+    Ok(outcome)
+}
+
+/// When an order stops being valid, enforced on chain through the order's `max_ts`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expiry {
+    /// The order never expires.
+    Never,
+    /// The order expires after the given number of seconds.
+    Seconds(u64),
+    /// The order expires at the given unix timestamp, in seconds.
+    At(u64),
+    /// The order expires at about the time its transaction's blockhash stops being valid.
+    ///
+    /// A blockhash is valid for `MAX_PROCESSING_AGE` (150) blocks, so the TTL is approximated as
+    /// 150 slots at the given slot time (about 60 seconds at 400ms slots). The program only sees
+    /// unix timestamps, so this is a wall clock approximation: slow or skipped slots make the
+    /// blockhash outlive the order, and fast slots the other way around.
+    BlockhashValidity,
+}
+
+impl Default for Expiry {
+    fn default() -> Self {
+        Expiry::Seconds(30)
+    }
+}
+
+impl Expiry {
+    /// Returns the time to live of the order in seconds, or `None` if it never expires.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot_time` - The current average slot time, used by `Expiry::BlockhashValidity`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use openbook::v1::orders::Expiry;
+    /// use std::time::Duration;
+    ///
+    /// assert_eq!(Expiry::BlockhashValidity.ttl_secs(Duration::from_millis(400)), Some(60));
+    /// ```
+    pub fn ttl_secs(&self, slot_time: Duration) -> Option<u64> {
+        match self {
+            Expiry::Never => None,
+            Expiry::Seconds(secs) => Some(*secs),
+            Expiry::At(timestamp) => Some(timestamp.saturating_sub(get_unix_secs())),
+            Expiry::BlockhashValidity => {
+                let validity = slot_time * MAX_PROCESSING_AGE as u32;
+                Some(validity.as_secs_f64().ceil() as u64)
+            }
+        }
+    }
+
+    /// Returns the `max_ts` of the `new_order` instruction for an order sent at `now_secs`.
+    ///
+    /// # Arguments
+    ///
+    /// * `now_secs` - The current unix timestamp in seconds.
+    /// * `slot_time` - The current average slot time, used by `Expiry::BlockhashValidity`.
+    pub fn max_ts(&self, now_secs: u64, slot_time: Duration) -> i64 {
+        if let Expiry::At(timestamp) = self {
+            return (*timestamp).min(i64::MAX as u64) as i64;
+        }
+
+        match self.ttl_secs(slot_time) {
+            Some(ttl) => now_secs.saturating_add(ttl).min(i64::MAX as u64) as i64,
+            None => i64::MAX,
+        }
+    }
+}
+
+impl From<Duration> for Expiry {
+    /// Expires the order `ttl` from now, rounded down to whole seconds.
+    fn from(ttl: Duration) -> Self {
+        Expiry::Seconds(ttl.as_secs())
+    }
+}
+
+/// Parameters of a limit order placed with `place_limit_order_with_params`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LimitOrderParams {
+    /// The order type, `PostOnly` by default.
+    pub order_type: OrderType,
+    /// How the order matches against the owner's own orders, `AbortTransaction` by default.
+    pub self_trade_behavior: SelfTradeBehavior,
+    /// The client order id to track the order with, random when `None`.
+    pub client_order_id: Option<u64>,
+    /// When the order expires, 30 seconds after it is sent by default.
+    pub expiry: Expiry,
+    /// Whether the order may be priced further than `OBClient::max_price_deviation_percent` from
+    /// the mid, `false` by default. The price must still be a positive number of at least one
+    /// price lot.
+    pub allow_off_market: bool,
+}
+
+impl Default for LimitOrderParams {
+    fn default() -> Self {
+        Self {
+            order_type: OrderType::PostOnly,
+            self_trade_behavior: SelfTradeBehavior::AbortTransaction,
+            client_order_id: None,
+            expiry: Expiry::default(),
+            allow_off_market: false,
+        }
+    }
+}
+
+/// A limit order of a batch placed with `place_orders_batch`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LimitOrderSpec {
+    /// The side of the order.
+    pub side: Side,
+    /// The size of the order, in quote UI units.
+    pub target_amount_quote: f64,
+    /// The UI price of the order.
+    pub price: f64,
+    /// The order type, self trade behavior, client order id and expiry of the order.
+    pub params: LimitOrderParams,
+}
@@ -5,10 +5,18 @@ use openbookdex_v2::{
     accounts_zerocopy::KeyedAccountReader,
     error::OpenBookError,
     pubkey_option::NonZeroPubkeyOption,
-    state::{oracle, OracleConfig, OracleState, OracleType, Side, FEES_SCALE_FACTOR},
+    state::{
+        oracle, BookSide, Market, OracleConfig, OracleState, OracleType, Side, FEES_SCALE_FACTOR,
+    },
 };
+use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
 use std::fmt::{Debug, Formatter, Result as FmtResult};
+use thiserror::Error;
+
+use crate::orderbook::OrderBook;
+use crate::utils::serde_pubkey;
 
 #[derive(Clone, Default, BorshSerialize, BorshDeserialize)]
 pub struct CreateMarketArgs {
@@ -28,21 +36,402 @@ pub struct CreateMarketArgs {
     pub time_expiry: i64,
 }
 
-#[derive(Clone, BorshSerialize, BorshDeserialize)]
+impl CreateMarketArgs {
+    /// Returns a `CreateMarketArgsBuilder`, validating the arguments before a market is created.
+    ///
+    /// # Example
+    ///
+    /// ```rust , ignore
+    /// use openbook::pubkey::Pubkey;
+    /// use openbook::v2::market::CreateMarketArgs;
+    ///
+    /// let args = CreateMarketArgs::builder()
+    ///     .name("JLP/USDC")
+    ///     .base_mint("27G8MtK7VtTcCHkpASjSDdkWWYfoqT6ggEuKidVJidD4".parse()?)
+    ///     .quote_mint("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".parse()?)
+    ///     .base_lot_size(1_000_000)
+    ///     .quote_lot_size(1)
+    ///     .taker_fee(400)
+    ///     .collect_fee_admin(Pubkey::new_unique())
+    ///     .build()?;
+    /// ```
+    pub fn builder() -> CreateMarketArgsBuilder {
+        CreateMarketArgsBuilder::default()
+    }
+}
+
+/// The longest market name accepted by the program, in bytes.
+pub const MAX_MARKET_NAME_LEN: usize = 16;
+
+/// Errors raised by `CreateMarketArgsBuilder::build` on invalid market arguments.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CreateMarketError {
+    /// A required argument wasn't set.
+    #[error("Missing {0}")]
+    MissingField(&'static str),
+    /// The name is longer than `MAX_MARKET_NAME_LEN` bytes.
+    #[error("Market name {0:?} is longer than {MAX_MARKET_NAME_LEN} bytes")]
+    NameTooLong(String),
+    /// A lot size is zero or negative.
+    #[error("The {field} must be positive, got {value}")]
+    NonPositiveLotSize {
+        /// The name of the lot size.
+        field: &'static str,
+        /// The rejected lot size.
+        value: i64,
+    },
+    /// The fees are out of the range accepted by the program.
+    #[error(
+        "Fees out of range: maker {maker_fee}, taker {taker_fee}; the taker fee must be in \
+         [0, {FEES_SCALE_FACTOR}] and the maker rebate can't exceed it"
+    )]
+    FeesOutOfRange {
+        /// The maker fee, scaled by `FEES_SCALE_FACTOR`.
+        maker_fee: i64,
+        /// The taker fee, scaled by `FEES_SCALE_FACTOR`.
+        taker_fee: i64,
+    },
+    /// The base and quote mints are the same.
+    #[error("The base and quote mints are both {0}")]
+    SameMints(Pubkey),
+}
+
+/// Builds validated `CreateMarketArgs`, see `CreateMarketArgs::builder`.
+///
+/// The mints, lot sizes and collect fee admin are required. The fees default to zero, the
+/// markets to no expiry, and the oracles and optional admins to `None`.
+#[derive(Debug, Clone, Default)]
+pub struct CreateMarketArgsBuilder {
+    name: String,
+    base_mint: Option<Pubkey>,
+    quote_mint: Option<Pubkey>,
+    base_lot_size: Option<i64>,
+    quote_lot_size: Option<i64>,
+    maker_fee: i64,
+    taker_fee: i64,
+    oracle_a: Option<Pubkey>,
+    oracle_b: Option<Pubkey>,
+    open_orders_admin: Option<Pubkey>,
+    collect_fee_admin: Option<Pubkey>,
+    consume_events_admin: Option<Pubkey>,
+    close_market_admin: Option<Pubkey>,
+    time_expiry: i64,
+}
+
+impl CreateMarketArgsBuilder {
+    /// Sets the name of the market, at most `MAX_MARKET_NAME_LEN` bytes.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Sets the mint of the base token.
+    pub fn base_mint(mut self, base_mint: Pubkey) -> Self {
+        self.base_mint = Some(base_mint);
+        self
+    }
+
+    /// Sets the mint of the quote token.
+    pub fn quote_mint(mut self, quote_mint: Pubkey) -> Self {
+        self.quote_mint = Some(quote_mint);
+        self
+    }
+
+    /// Sets the base lot size, in native base units.
+    pub fn base_lot_size(mut self, base_lot_size: i64) -> Self {
+        self.base_lot_size = Some(base_lot_size);
+        self
+    }
+
+    /// Sets the quote lot size, in native quote units.
+    pub fn quote_lot_size(mut self, quote_lot_size: i64) -> Self {
+        self.quote_lot_size = Some(quote_lot_size);
+        self
+    }
+
+    /// Sets the maker fee, scaled by `FEES_SCALE_FACTOR`. A negative fee is a rebate.
+    pub fn maker_fee(mut self, maker_fee: i64) -> Self {
+        self.maker_fee = maker_fee;
+        self
+    }
+
+    /// Sets the taker fee, scaled by `FEES_SCALE_FACTOR`.
+    pub fn taker_fee(mut self, taker_fee: i64) -> Self {
+        self.taker_fee = taker_fee;
+        self
+    }
+
+    /// Sets the first oracle of the market.
+    pub fn oracle_a(mut self, oracle_a: impl Into<Option<Pubkey>>) -> Self {
+        self.oracle_a = oracle_a.into();
+        self
+    }
+
+    /// Sets the second oracle of the market, quoting the first one.
+    pub fn oracle_b(mut self, oracle_b: impl Into<Option<Pubkey>>) -> Self {
+        self.oracle_b = oracle_b.into();
+        self
+    }
+
+    /// Sets the admin that must sign the creation of open orders accounts.
+    pub fn open_orders_admin(mut self, open_orders_admin: impl Into<Option<Pubkey>>) -> Self {
+        self.open_orders_admin = open_orders_admin.into();
+        self
+    }
+
+    /// Sets the admin allowed to collect the fees.
+    pub fn collect_fee_admin(mut self, collect_fee_admin: Pubkey) -> Self {
+        self.collect_fee_admin = Some(collect_fee_admin);
+        self
+    }
+
+    /// Sets the admin that must sign the consume events instructions.
+    pub fn consume_events_admin(mut self, consume_events_admin: impl Into<Option<Pubkey>>) -> Self {
+        self.consume_events_admin = consume_events_admin.into();
+        self
+    }
+
+    /// Sets the admin allowed to close the market.
+    pub fn close_market_admin(mut self, close_market_admin: impl Into<Option<Pubkey>>) -> Self {
+        self.close_market_admin = close_market_admin.into();
+        self
+    }
+
+    /// Sets the Unix timestamp after which the market expires, or 0 for none.
+    pub fn time_expiry(mut self, time_expiry: i64) -> Self {
+        self.time_expiry = time_expiry;
+        self
+    }
+
+    /// Validates the arguments and builds the `CreateMarketArgs`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `CreateMarketError` if a required argument is missing, the name is too long,
+    /// a lot size isn't positive, the fees are out of range, or the mints are the same.
+    pub fn build(self) -> Result<CreateMarketArgs, CreateMarketError> {
+        let base_mint = self
+            .base_mint
+            .ok_or(CreateMarketError::MissingField("base mint"))?;
+        let quote_mint = self
+            .quote_mint
+            .ok_or(CreateMarketError::MissingField("quote mint"))?;
+        let base_lot_size = self
+            .base_lot_size
+            .ok_or(CreateMarketError::MissingField("base lot size"))?;
+        let quote_lot_size = self
+            .quote_lot_size
+            .ok_or(CreateMarketError::MissingField("quote lot size"))?;
+        let collect_fee_admin = self
+            .collect_fee_admin
+            .ok_or(CreateMarketError::MissingField("collect fee admin"))?;
+
+        if self.name.len() > MAX_MARKET_NAME_LEN {
+            return Err(CreateMarketError::NameTooLong(self.name));
+        }
+        for (field, value) in [
+            ("base lot size", base_lot_size),
+            ("quote lot size", quote_lot_size),
+        ] {
+            if value <= 0 {
+                return Err(CreateMarketError::NonPositiveLotSize { field, value });
+            }
+        }
+        let (maker_fee, taker_fee) = (self.maker_fee, self.taker_fee);
+        if !(0..=FEES_SCALE_FACTOR as i64).contains(&taker_fee)
+            || !(-taker_fee..=FEES_SCALE_FACTOR as i64).contains(&maker_fee)
+        {
+            return Err(CreateMarketError::FeesOutOfRange {
+                maker_fee,
+                taker_fee,
+            });
+        }
+        if base_mint == quote_mint {
+            return Err(CreateMarketError::SameMints(base_mint));
+        }
+
+        Ok(CreateMarketArgs {
+            name: self.name,
+            base_mint,
+            quote_mint,
+            base_lot_size,
+            quote_lot_size,
+            maker_fee,
+            taker_fee,
+            oracle_a: self.oracle_a,
+            oracle_b: self.oracle_b,
+            open_orders_admin: self.open_orders_admin,
+            collect_fee_admin,
+            consume_events_admin: self.consume_events_admin,
+            close_market_admin: self.close_market_admin,
+            time_expiry: self.time_expiry,
+        })
+    }
+}
+
+/// The addresses of the accounts of a market created by `OBClient::create_market`.
+///
+/// They are everything needed to trade on the market right away, without deriving its PDAs
+/// again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CreatedMarket {
+    /// The address of the market account.
+    pub market: Pubkey,
+    /// The PDA signing for the market vaults.
+    pub market_authority: Pubkey,
+    /// The vault holding the base tokens, the ATA of the market authority.
+    pub base_vault: Pubkey,
+    /// The vault holding the quote tokens, the ATA of the market authority.
+    pub quote_vault: Pubkey,
+    /// The bids book side.
+    pub bids: Pubkey,
+    /// The asks book side.
+    pub asks: Pubkey,
+    /// The event heap of the market.
+    pub event_heap: Pubkey,
+}
+
+impl CreatedMarket {
+    /// Derives the market authority and vaults of a market from its accounts and mints.
+    ///
+    /// # Arguments
+    ///
+    /// * `market` - The address of the market account.
+    /// * `bids` - The address of the bids account.
+    /// * `asks` - The address of the asks account.
+    /// * `event_heap` - The address of the event heap account.
+    /// * `base_mint` - The base token mint.
+    /// * `quote_mint` - The quote token mint.
+    ///
+    /// # Returns
+    ///
+    /// The `CreatedMarket` holding every address of the market.
+    pub fn derive(
+        market: Pubkey,
+        bids: Pubkey,
+        asks: Pubkey,
+        event_heap: Pubkey,
+        base_mint: &Pubkey,
+        quote_mint: &Pubkey,
+    ) -> Self {
+        let (market_authority, _bump_seed) = Pubkey::find_program_address(
+            &[b"Market".as_ref(), &market.to_bytes()],
+            &openbookdex_v2::id(),
+        );
+
+        Self {
+            market,
+            market_authority,
+            base_vault: get_associated_token_address(&market_authority, base_mint),
+            quote_vault: get_associated_token_address(&market_authority, quote_mint),
+            bids,
+            asks,
+            event_heap,
+        }
+    }
+}
+
+/// The fee rates of a market, as fractions of the traded notional.
+///
+/// A negative maker rate is a rebate.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FeeRates {
+    /// The fee rate paid by resting orders when they are filled.
+    pub maker: f64,
+    /// The fee rate paid by orders taking liquidity from the book.
+    pub taker: f64,
+}
+
+/// Serializes a `NonZeroPubkeyOption` as an optional base58 string.
+mod serde_pubkey_option {
+    use openbookdex_v2::pubkey_option::NonZeroPubkeyOption;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use solana_sdk::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    pub fn serialize<S: Serializer>(
+        pubkey: &NonZeroPubkeyOption,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match Option::<Pubkey>::from(*pubkey) {
+            Some(pubkey) => serializer.collect_str(&pubkey),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<NonZeroPubkeyOption, D::Error> {
+        let pubkey = Option::<String>::deserialize(deserializer)?
+            .map(|pubkey| {
+                Pubkey::from_str(&pubkey)
+                    .map_err(|e| D::Error::custom(format!("{}: {}", pubkey, e)))
+            })
+            .transpose()?;
+        Ok(NonZeroPubkeyOption::from(pubkey))
+    }
+}
+
+/// Serializes an `OracleConfig` without its reserved bytes.
+mod serde_oracle_config {
+    use openbookdex_v2::state::OracleConfig;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct OracleConfigJson {
+        conf_filter: f64,
+        max_staleness_slots: i64,
+    }
+
+    pub fn serialize<S: Serializer>(
+        oracle_config: &OracleConfig,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        OracleConfigJson {
+            conf_filter: oracle_config.conf_filter,
+            max_staleness_slots: oracle_config.max_staleness_slots,
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<OracleConfig, D::Error> {
+        let oracle_config = OracleConfigJson::deserialize(deserializer)?;
+        Ok(OracleConfig {
+            conf_filter: oracle_config.conf_filter,
+            max_staleness_slots: oracle_config.max_staleness_slots,
+            reserved: [0; 72],
+        })
+    }
+}
+
+#[derive(Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
 pub struct MarketInfo {
     pub name: String,
     pub base_decimals: u8,
     pub quote_decimals: u8,
+    #[serde(with = "serde_pubkey")]
     pub market_authority: Pubkey,
+    #[serde(with = "serde_pubkey")]
     pub collect_fee_admin: Pubkey,
+    #[serde(with = "serde_pubkey_option")]
     pub open_orders_admin: NonZeroPubkeyOption,
+    #[serde(with = "serde_pubkey_option")]
     pub consume_events_admin: NonZeroPubkeyOption,
+    #[serde(with = "serde_pubkey_option")]
     pub close_market_admin: NonZeroPubkeyOption,
+    #[serde(with = "serde_pubkey")]
     pub bids: Pubkey,
+    #[serde(with = "serde_pubkey")]
     pub asks: Pubkey,
+    #[serde(with = "serde_pubkey")]
     pub event_heap: Pubkey,
+    #[serde(with = "serde_pubkey_option")]
     pub oracle_a: NonZeroPubkeyOption,
+    #[serde(with = "serde_pubkey_option")]
     pub oracle_b: NonZeroPubkeyOption,
+    #[serde(with = "serde_oracle_config")]
     pub oracle_config: OracleConfig,
     pub quote_lot_size: i64,
     pub base_lot_size: i64,
@@ -56,10 +445,14 @@ pub struct MarketInfo {
     pub fees_available: u64,
     pub maker_volume: u128,
     pub taker_volume_wo_oo: u128,
+    #[serde(with = "serde_pubkey")]
     pub base_mint: Pubkey,
+    #[serde(with = "serde_pubkey")]
     pub quote_mint: Pubkey,
+    #[serde(with = "serde_pubkey")]
     pub market_base_vault: Pubkey,
     pub base_deposit_total: u64,
+    #[serde(with = "serde_pubkey")]
     pub market_quote_vault: Pubkey,
     pub quote_deposit_total: u64,
 }
@@ -116,6 +509,97 @@ impl Debug for MarketInfo {
 }
 
 impl MarketInfo {
+    /// Copies the fields of an on-chain `Market` account.
+    pub fn from_market(market: &Market) -> Self {
+        let oracle_config = OracleConfig {
+            conf_filter: market.oracle_config.conf_filter,
+            max_staleness_slots: market.oracle_config.max_staleness_slots,
+            reserved: market.oracle_config.reserved,
+        };
+
+        MarketInfo {
+            name: market.name().to_string(),
+            base_decimals: market.base_decimals,
+            quote_decimals: market.quote_decimals,
+            market_authority: market.market_authority,
+            collect_fee_admin: market.collect_fee_admin,
+            open_orders_admin: market.open_orders_admin,
+            consume_events_admin: market.consume_events_admin,
+            close_market_admin: market.close_market_admin,
+            bids: market.bids,
+            asks: market.asks,
+            event_heap: market.event_heap,
+            oracle_a: market.oracle_a,
+            oracle_b: market.oracle_b,
+            oracle_config,
+            quote_lot_size: market.quote_lot_size,
+            base_lot_size: market.base_lot_size,
+            seq_num: market.seq_num,
+            registration_time: market.registration_time,
+            maker_fee: market.maker_fee,
+            taker_fee: market.taker_fee,
+            fees_accrued: market.fees_accrued,
+            fees_to_referrers: market.fees_to_referrers,
+            referrer_rebates_accrued: market.referrer_rebates_accrued,
+            fees_available: market.fees_available,
+            maker_volume: market.maker_volume,
+            taker_volume_wo_oo: market.taker_volume_wo_oo,
+            base_mint: market.base_mint,
+            quote_mint: market.quote_mint,
+            market_base_vault: market.market_base_vault,
+            base_deposit_total: market.base_deposit_total,
+            market_quote_vault: market.market_quote_vault,
+            quote_deposit_total: market.quote_deposit_total,
+        }
+    }
+
+    /// Serializes the market info to JSON, with pubkeys as base58 strings.
+    ///
+    /// Like the v1 `Market::to_json`, the snapshot holds no keypair.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Deserializes a market info written by `MarketInfo::to_json`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is invalid or a pubkey isn't valid base58.
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Returns the oracle config if the market has an oracle, `None` otherwise.
+    pub fn configured_oracle(&self) -> Option<OracleConfig> {
+        Option::<Pubkey>::from(self.oracle_a)
+            .is_some()
+            .then(|| OracleConfig {
+                conf_filter: self.oracle_config.conf_filter,
+                max_staleness_slots: self.oracle_config.max_staleness_slots,
+                reserved: self.oracle_config.reserved,
+            })
+    }
+
+    /// Converts a UI price offset from the oracle price, in quote per base, into price lots.
+    ///
+    /// The offset is first scaled to native units, `peg_offset * 10^quote_decimals /
+    /// 10^base_decimals`, then to lots, `* base_lot_size / quote_lot_size`, and rounded to the
+    /// nearest lot. A negative offset pegs below the oracle price.
+    ///
+    /// # Examples
+    ///
+    /// On a SOL/USDC market with 9 base decimals, 6 quote decimals, a base lot size of 1_000_000
+    /// and a quote lot size of 1, one lot is 0.001 USDC, so an offset of -0.05 USDC is -50 lots.
+    pub fn peg_offset_to_lots(&self, peg_offset: f64) -> i64 {
+        let native_offset = peg_offset * 10f64.powi(self.quote_decimals as i32)
+            / 10f64.powi(self.base_decimals as i32);
+        (native_offset * self.base_lot_size as f64 / self.quote_lot_size as f64).round() as i64
+    }
+
     pub fn is_expired(&self, timestamp: i64) -> bool {
         self.registration_time != 0 && self.registration_time < timestamp
     }
@@ -127,6 +611,32 @@ impl MarketInfo {
             && self.referrer_rebates_accrued == 0
     }
 
+    /// Checks that `signer` can close the market and that the market holds no funds.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the market has no close market admin, if `signer` isn't that admin,
+    /// or if the market still holds deposits, fees or rebates.
+    pub fn ensure_closable_by(&self, signer: &Pubkey) -> Result<()> {
+        match Option::<Pubkey>::from(self.close_market_admin) {
+            None => anyhow::bail!("The market has no close market admin"),
+            Some(admin) if admin != *signer => {
+                anyhow::bail!("{} is not the close market admin {}", signer, admin)
+            }
+            Some(_) => {}
+        }
+        if !self.is_empty() {
+            anyhow::bail!(
+                "The market still holds {} base and {} quote deposits, {} fees and {} rebates",
+                self.base_deposit_total,
+                self.quote_deposit_total,
+                self.fees_available,
+                self.referrer_rebates_accrued
+            );
+        }
+        Ok(())
+    }
+
     pub fn is_market_vault(&self, pubkey: Pubkey) -> bool {
         pubkey == self.market_quote_vault || pubkey == self.market_base_vault
     }
@@ -160,11 +670,57 @@ impl MarketInfo {
             .unwrap()
     }
 
+    /// Returns the base lots of an order of `quote_size` quote UI units at the UI price `price`,
+    /// rounded down.
+    ///
+    /// This is the sizing of the v1 client: the quote size is divided by the price, scaled to
+    /// native base units and rounded down to whole base lots.
+    ///
+    /// # Examples
+    ///
+    /// On a market with 9 base decimals and a base lot size of 1_000_000, 10.5 USDC at 2.0 buys
+    /// 5.25 base, which is 5_250 base lots.
+    pub fn base_lots_from_quote(&self, quote_size: f64, price: f64) -> i64 {
+        let native_base = quote_size / price * 10f64.powi(self.base_decimals as i32);
+        self.max_base_lots_from_lamports(native_base as u64)
+    }
+
     pub fn lot_to_native_price(&self, price: i64) -> I80F48 {
         I80F48::from_num(price) * I80F48::from_num(self.quote_lot_size)
             / I80F48::from_num(self.base_lot_size)
     }
 
+    /// Decodes the order book ladder from the bids and asks book sides, up to `depth` levels per side.
+    pub fn orderbook_from_book_sides(
+        &self,
+        bids: &BookSide,
+        asks: &BookSide,
+        depth: usize,
+    ) -> OrderBook {
+        let base_factor = 10f64.powi(self.base_decimals as i32);
+        let quote_factor = 10f64.powi(self.quote_decimals as i32);
+        let level = |price_lots: i64, quantity: i64| {
+            let native_price = self.lot_to_native_price(price_lots).to_num::<f64>();
+            (
+                native_price * base_factor / quote_factor,
+                quantity as f64 * self.base_lot_size as f64 / base_factor,
+            )
+        };
+
+        OrderBook {
+            bids: OrderBook::aggregate_levels(
+                bids.iter_valid(0, None)
+                    .map(|item| level(item.price_lots, item.node.quantity)),
+                depth,
+            ),
+            asks: OrderBook::aggregate_levels(
+                asks.iter_valid(0, None)
+                    .map(|item| level(item.price_lots, item.node.quantity)),
+                depth,
+            ),
+        }
+    }
+
     pub fn native_price_to_lot(&self, price: I80F48) -> Result<i64, OpenBookError> {
         price
             .checked_mul(I80F48::from_num(self.base_lot_size))
@@ -260,6 +816,22 @@ impl MarketInfo {
         }
     }
 
+    /// Returns the maker and taker fee rates of the market, as fractions of the traded notional.
+    pub fn fee_rates(&self) -> FeeRates {
+        FeeRates {
+            maker: self.maker_fee as f64 / FEES_SCALE_FACTOR as f64,
+            taker: self.taker_fee as f64 / FEES_SCALE_FACTOR as f64,
+        }
+    }
+
+    /// Returns the spread, in bps of the mid price, a maker needs to break even on a round trip.
+    ///
+    /// Both legs pay the maker fee, so the break-even spread is twice the maker fee rate. It is
+    /// negative when makers earn a rebate.
+    pub fn breakeven_spread_bps(&self) -> f64 {
+        2.0 * self.fee_rates().maker * 10_000.0
+    }
+
     pub fn subtract_taker_fees(&self, quote: i64) -> i64 {
         ((quote as i128) * FEES_SCALE_FACTOR / (FEES_SCALE_FACTOR + (self.taker_fee as i128)))
             .try_into()
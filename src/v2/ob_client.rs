@@ -1,23 +1,27 @@
 use std::{
     collections::HashMap,
     fmt::{Debug, Formatter},
+    path::Path,
     str::FromStr,
     sync::Arc,
 };
 
-use anchor_lang::{prelude::System, Id};
+use anchor_lang::{prelude::System, AccountDeserialize, Id};
 use anchor_spl::{associated_token::AssociatedToken, token::Token};
-use anyhow::{Context, Error, Result};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
 use borsh::{BorshDeserialize, BorshSerialize};
 use fixed::types::I80F48;
+use futures::Stream;
 use rand::random;
 use serde::{Deserialize, Serialize};
 use spl_associated_token_account::get_associated_token_address;
 
 use openbookdex_v2::{
     state::{
-        BookSide, Market, OpenOrdersAccount, OracleConfig, OracleConfigParams, PlaceOrderType,
-        SelfTradeBehavior, Side,
+        BookSide, EventHeap, Market, OpenOrdersAccount, OracleConfig, OracleConfigParams,
+        PlaceOrderType, SelfTradeBehavior, Side,
     },
     PlaceMultipleOrdersArgs, PlaceOrderArgs, PlaceOrderPeggedArgs,
 };
@@ -25,27 +29,40 @@ use openbookdex_v2::{
 use solana_sdk::{
     clock::Slot,
     commitment_config::CommitmentConfig,
-    instruction::Instruction,
+    instruction::{AccountMeta, Instruction},
+    program::MAX_RETURN_DATA,
     pubkey::Pubkey,
     signature::{Keypair, Signature},
     signer::Signer,
+    system_instruction,
 };
+use solana_transaction_status::UiTransactionReturnData;
+
+pub use crate::orderbook::BestQuotes;
 
 use crate::{
-    rpc::Rpc,
+    error::OpenBookError,
+    health::{HealthCheckAccounts, HealthReport},
+    orderbook::OrderBook,
+    rpc::{Rpc, TxConfig},
     rpc_client::RpcClient,
-    utils::{get_unix_secs, read_keypair},
+    subscription::spawn_account_stream,
+    traits::{confirmed_signature, OrderHandle, OrderPlacer, OrderSide},
+    utils::{get_rpc_url, get_unix_secs, get_ws_url, read_keypair_from_env, serde_pubkey},
     v2::{
         account_fetcher::{
             account_fetcher_fetch_openorders_account, AccountFetcherTrait, CachedAccountFetcher,
             RpcAccountFetcher,
         },
         context::MarketContext,
-        market::{CreateMarketArgs, MarketInfo},
+        market::{CreateMarketArgs, CreatedMarket, FeeRates, MarketInfo},
     },
 };
 
-#[derive(Clone, BorshDeserialize, BorshSerialize)]
+/// The name of the open orders account used by the client.
+pub const OPEN_ORDERS_ACCOUNT_NAME: &str = "random";
+
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
 pub struct OpenOrderNode {
     pub is_buy: bool,
     pub price: f64,
@@ -68,7 +85,7 @@ impl Debug for OpenOrderNode {
     }
 }
 
-#[derive(Clone, Default, BorshDeserialize, BorshSerialize)]
+#[derive(Clone, Default, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
 pub struct OpenOrderState {
     pub asks_base_in_oos: f64,
     pub bids_base_in_oos: f64,
@@ -87,12 +104,6 @@ impl Debug for OpenOrderState {
     }
 }
 
-#[derive(Debug, Clone, BorshDeserialize, BorshSerialize)]
-pub struct BestQuotes {
-    pub highest_bid: f64,
-    pub lowest_ask: f64,
-}
-
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct PricePoint {
@@ -126,6 +137,70 @@ impl Debug for AtaBalances {
     }
 }
 
+/// Snapshot of the open orders view of a client, to recover it after a restart.
+///
+/// A restarted bot can restore its last known orders with `OBClient::restore_snapshot` and
+/// reconcile them against the chain, instead of starting from an empty view. The snapshot holds
+/// no keypair.
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+pub struct ClientSnapshot {
+    /// The market the snapshot was taken on.
+    #[serde(with = "serde_pubkey")]
+    pub market_id: Pubkey,
+    /// The open orders account of the client.
+    #[serde(with = "serde_pubkey")]
+    pub open_orders_account: Pubkey,
+    /// The open orders of the client.
+    pub open_orders: Vec<OpenOrderNode>,
+    /// The balances of the open orders account.
+    pub oo_state: OpenOrderState,
+    /// The Unix timestamp, in seconds, at which the snapshot was taken.
+    pub taken_at: u64,
+}
+
+impl ClientSnapshot {
+    /// Writes the snapshot to `path`, as JSON if the path ends with `.json` and as Borsh
+    /// otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the snapshot can't be serialized or the file can't be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let data = if is_json_path(path) {
+            serde_json::to_vec_pretty(self)?
+        } else {
+            self.try_to_vec()?
+        };
+
+        std::fs::write(path, data)
+            .with_context(|| format!("Failed to write snapshot {}", path.display()))
+    }
+
+    /// Reads a snapshot written by `ClientSnapshot::save` from `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or doesn't hold a snapshot.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read(path)
+            .with_context(|| format!("Failed to read snapshot {}", path.display()))?;
+
+        if is_json_path(path) {
+            Ok(serde_json::from_slice(&data)?)
+        } else {
+            Ok(Self::try_from_slice(&data)?)
+        }
+    }
+}
+
+/// Returns whether `path` has a `.json` extension.
+fn is_json_path(path: &Path) -> bool {
+    path.extension()
+        .map_or(false, |extension| extension.eq_ignore_ascii_case("json"))
+}
+
 /// OpenBook v2 Client to interact with the OpenBook market and perform actions.
 #[derive(Clone)]
 pub struct OBClient {
@@ -204,7 +279,7 @@ impl OBClient {
     /// # Returns
     ///
     /// Returns a `Result` wrapping a new instance of the `OBClient` struct initialized with the provided parameters,
-    /// or an `Error` if the initialization process fails.
+    /// or an `OpenBookError` if the initialization process fails.
     ///
     /// # Example
     ///
@@ -229,7 +304,7 @@ impl OBClient {
     /// # Business Logic
     ///
     /// 1. Retrieve necessary environment variables, such as the `RPC_URL`, `KEY_PATH`, open orders key `OOS_KEY`, and index key `INDEX_KEY`.
-    /// 2. Read the owner's keypair from the specified key path.
+    /// 2. Read the owner's keypair with `read_keypair_from_env` (`KEY_BASE58`, `KEY_BYTES`, then `KEY_PATH`).
     /// 3. Initialize the RPC client with the given commitment configuration.
     /// 4. Fetch the market information from the Solana blockchain.
     /// 5. Generate associated token accounts (ATA) for the base and quote tokens.
@@ -237,25 +312,97 @@ impl OBClient {
     /// 7. Initialize the account fetcher for fetching account data.
     /// 8. Populate the initial fields of the `OBClient` struct.
     /// 9. Load open orders and bids/asks information if the `load` parameter is set to `true`.
-    /// 10. Create new open orders and index accounts if the `new` parameter is set to `true`.
+    /// 10. Create the missing open orders and index accounts if the `new` parameter is set to `true`, see `ensure_accounts`.
     ///
     pub async fn new(
         commitment: CommitmentConfig,
         market_id: Pubkey,
         new: bool,
         load: bool,
-    ) -> Result<Self, Error> {
-        let rpc_url =
-            std::env::var("RPC_URL").unwrap_or("https://api.mainnet-beta.solana.com".to_string());
-        let key_path = std::env::var("KEY_PATH").unwrap_or("".to_string());
-
-        let owner = read_keypair(&key_path);
-        let rpc_client = RpcClient::new_with_commitment(rpc_url.clone(), commitment);
+    ) -> Result<Self, OpenBookError> {
+        let rpc_url = get_rpc_url();
+        let owner = read_keypair_from_env()?;
+        let rpc_client = RpcClient::new_with_commitment(rpc_url, commitment);
         let oos_key_str = std::env::var("OOS_KEY").unwrap_or("".to_string());
         let index_key_str = std::env::var("INDEX_KEY").unwrap_or("".to_string());
 
-        let orders_key = Pubkey::from_str(oos_key_str.as_str());
-        let index_key = Pubkey::from_str(index_key_str.as_str());
+        let orders_key = Pubkey::from_str(oos_key_str.as_str()).ok();
+        let index_key = Pubkey::from_str(index_key_str.as_str()).ok();
+
+        Self::from_parts(
+            rpc_client, owner, market_id, orders_key, index_key, new, load,
+        )
+        .await
+    }
+
+    /// Initializes a new instance of the `OBClient` struct from an explicit RPC client and owner,
+    /// without reading any env var.
+    ///
+    /// `OBClient::new` reads the RPC URL and keypair from the environment and is a thin wrapper
+    /// around this method. Clients built with `new_with_rpc` can talk to different RPC nodes, with
+    /// different wallets, in the same process. The open orders and index accounts are looked up
+    /// from the owner, `OOS_KEY` and `INDEX_KEY` are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `rpc_client` - The RPC client, whose URL and commitment are used for every request.
+    /// * `owner` - The keypair of the wallet trading on the market.
+    /// * `market_id` - Public key (ID) of the market to fetch information about.
+    /// * `new` - Boolean indicating whether to create new open orders and index accounts.
+    /// * `load` - Boolean indicating whether to load market data immediately after initialization.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` wrapping a new instance of the `OBClient` struct initialized with the provided parameters,
+    /// or an `OpenBookError` if the initialization process fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust , ignore
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::keypair::Keypair;
+    /// use openbook::rpc_client::RpcClient;
+    /// use openbook::v2::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let rpc_client = RpcClient::new_with_commitment(
+    ///         "https://api.mainnet-beta.solana.com".to_string(),
+    ///         CommitmentConfig::confirmed(),
+    ///     );
+    ///     let owner = Keypair::new();
+    ///
+    ///     let market_id = "gQN1TNHiqj5x82ZQd7JZ8rm8WD4xwWtXxd4onReWZNK".parse()?;
+    ///
+    ///     let ob_client = OBClient::new_with_rpc(rpc_client, owner, market_id, false, true).await?;
+    ///
+    ///     println!("Initialized OBClient: {:?}", ob_client);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn new_with_rpc(
+        rpc_client: RpcClient,
+        owner: Keypair,
+        market_id: Pubkey,
+        new: bool,
+        load: bool,
+    ) -> Result<Self, OpenBookError> {
+        Self::from_parts(rpc_client, owner, market_id, None, None, new, load).await
+    }
+
+    /// Builds the client shared by `new` and `new_with_rpc`, from explicit configuration.
+    async fn from_parts(
+        rpc_client: RpcClient,
+        owner: Keypair,
+        market_id: Pubkey,
+        orders_key: Option<Pubkey>,
+        index_key: Option<Pubkey>,
+        new: bool,
+        load: bool,
+    ) -> Result<Self, OpenBookError> {
+        let rpc_url = rpc_client.url();
+        let commitment = rpc_client.commitment();
 
         let pub_owner_key = owner.pubkey().clone();
 
@@ -275,52 +422,13 @@ impl OBClient {
             market: market,
             address: market_id,
         };
-        let rpc_client = RpcClient::new_with_commitment(rpc_url.clone(), commitment);
+        let rpc_client = RpcClient::new_with_commitment(rpc_url, commitment);
 
         let account_fetcher = Arc::new(CachedAccountFetcher::new(Arc::new(RpcAccountFetcher {
             rpc: rpc_client,
         })));
 
-        let oracle_config = OracleConfig {
-            conf_filter: market.oracle_config.conf_filter,
-            max_staleness_slots: market.oracle_config.max_staleness_slots,
-            reserved: market.oracle_config.reserved,
-        };
-
-        let market_info = MarketInfo {
-            name: market.name().to_string(),
-            base_decimals: market.base_decimals,
-            quote_decimals: market.quote_decimals,
-            market_authority: market.market_authority,
-            collect_fee_admin: market.collect_fee_admin,
-            open_orders_admin: market.open_orders_admin,
-            consume_events_admin: market.consume_events_admin,
-            close_market_admin: market.close_market_admin,
-            bids: market.bids,
-            asks: market.asks,
-            event_heap: market.event_heap,
-            oracle_a: market.oracle_a,
-            oracle_b: market.oracle_b,
-            oracle_config: oracle_config,
-            quote_lot_size: market.quote_lot_size,
-            base_lot_size: market.base_lot_size,
-            seq_num: market.seq_num,
-            registration_time: market.registration_time,
-            maker_fee: market.maker_fee,
-            taker_fee: market.taker_fee,
-            fees_accrued: market.fees_accrued,
-            fees_to_referrers: market.fees_to_referrers,
-            referrer_rebates_accrued: market.referrer_rebates_accrued,
-            fees_available: market.fees_available,
-            maker_volume: market.maker_volume,
-            taker_volume_wo_oo: market.taker_volume_wo_oo,
-            base_mint: market.base_mint,
-            quote_mint: market.quote_mint,
-            market_base_vault: market.market_base_vault,
-            base_deposit_total: market.base_deposit_total,
-            market_quote_vault: market.market_quote_vault,
-            quote_deposit_total: market.quote_deposit_total,
-        };
+        let market_info = MarketInfo::from_market(&market);
 
         let mut ob_client = Self {
             rpc_client: rpc,
@@ -339,21 +447,18 @@ impl OBClient {
         };
 
         if new {
-            ob_client.index_account = ob_client.create_open_orders_indexer(true).await?.3;
-            ob_client.open_orders_account = ob_client.find_or_create_account().await?;
+            ob_client.ensure_accounts().await?;
         }
 
         if load {
-            if !orders_key.is_err() {
-                ob_client.open_orders_account = orders_key.unwrap();
-            } else {
-                ob_client.open_orders_account = ob_client.find_or_create_account().await?;
-            }
-            if !index_key.is_err() {
-                ob_client.index_account = index_key.unwrap();
-            } else {
-                ob_client.index_account = ob_client.create_open_orders_indexer(false).await?.3;
-            }
+            ob_client.open_orders_account = match orders_key {
+                Some(orders_key) => orders_key,
+                None => ob_client.find_or_create_account().await?,
+            };
+            ob_client.index_account = match index_key {
+                Some(index_key) => index_key,
+                None => ob_client.create_open_orders_indexer(false).await?.3,
+            };
             let (open_orders, _best_quotes) = ob_client.load_bids_asks_info().await?;
             ob_client.open_orders = open_orders;
             ob_client.oo_state = ob_client.load_oo_state().await?;
@@ -363,6 +468,127 @@ impl OBClient {
         Ok(ob_client)
     }
 
+    /// Returns a copy of the client sending its transactions with `commitment`.
+    ///
+    /// Place orders with `processed` for speed and settle with `finalized` for safety, without
+    /// building a second client. Reads keep the commitment of the client. See
+    /// `Rpc::with_send_commitment`.
+    ///
+    /// # Example
+    ///
+    /// ```rust , ignore
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v2::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "gQN1TNHiqj5x82ZQd7JZ8rm8WD4xwWtXxd4onReWZNK".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, false, true).await?;
+    ///
+    ///     let (confirmed, sig) = ob_client
+    ///         .with_commitment(CommitmentConfig::finalized())
+    ///         .settle_funds()
+    ///         .await?;
+    ///
+    ///     println!("Got Signature: {:?}", sig);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn with_commitment(&self, commitment: CommitmentConfig) -> Self {
+        let mut ob_client = self.clone();
+        ob_client.rpc_client = ob_client
+            .rpc_client
+            .clone()
+            .with_send_commitment(commitment);
+        ob_client
+    }
+
+    /// Returns a snapshot of the open orders view of the client, to save before a restart.
+    ///
+    /// # Example
+    ///
+    /// ```rust , ignore
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v2::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "gQN1TNHiqj5x82ZQd7JZ8rm8WD4xwWtXxd4onReWZNK".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, false, true).await?;
+    ///
+    ///     ob_client.snapshot().save("orders.json")?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn snapshot(&self) -> ClientSnapshot {
+        ClientSnapshot {
+            market_id: self.market_id,
+            open_orders_account: self.open_orders_account,
+            open_orders: self.open_orders.clone(),
+            oo_state: self.oo_state.clone(),
+            taken_at: get_unix_secs(),
+        }
+    }
+
+    /// Restores the open orders view saved in `snapshot`.
+    ///
+    /// Only the client's view is replaced; reload the open orders from the chain to reconcile
+    /// them with the orders filled or canceled since the snapshot was taken.
+    ///
+    /// # Errors
+    ///
+    /// Returns `OpenBookError::SnapshotMarketMismatch` if the snapshot was taken on another market,
+    /// leaving the client untouched.
+    ///
+    /// # Example
+    ///
+    /// ```rust , ignore
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v2::ob_client::{ClientSnapshot, OBClient};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "gQN1TNHiqj5x82ZQd7JZ8rm8WD4xwWtXxd4onReWZNK".parse()?;
+    ///
+    ///     let mut ob_client = OBClient::new(commitment, market_id, false, false).await?;
+    ///
+    ///     ob_client.restore_snapshot(ClientSnapshot::load("orders.json")?)?;
+    ///
+    ///     println!("Last known orders: {:?}", ob_client.open_orders);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn restore_snapshot(&mut self, snapshot: ClientSnapshot) -> Result<(), OpenBookError> {
+        if snapshot.market_id != self.market_id {
+            return Err(OpenBookError::SnapshotMarketMismatch {
+                expected: self.market_id,
+                found: snapshot.market_id,
+            });
+        }
+
+        self.open_orders = snapshot.open_orders;
+        self.oo_state = snapshot.oo_state;
+        Ok(())
+    }
+
+    /// Settles the free base and quote balances of the open orders account back to the wallet's
+    /// associated token accounts, from the market's base and quote vaults.
+    ///
+    /// # Returns
+    ///
+    /// Whether the transaction was confirmed, and its signature.
+    ///
     /// # Example
     ///
     /// ```rust , ignore
@@ -384,7 +610,7 @@ impl OBClient {
     ///     Ok(())
     /// }
     /// ```
-    pub async fn settle_funds(&self) -> Result<(bool, Signature)> {
+    pub async fn settle_funds(&self) -> Result<(bool, Signature), OpenBookError> {
         let ix = Instruction {
             program_id: openbookdex_v2::id(),
             accounts: {
@@ -408,11 +634,19 @@ impl OBClient {
             },
             data: anchor_lang::InstructionData::data(&openbookdex_v2::instruction::SettleFunds {}),
         };
-        self.rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
-            .await
+        Ok(self.send_and_report(ix, 0, 0, true).await?)
     }
 
+    /// Places a post-only limit order of `quote_size` whole quote units at `limit_price`.
+    ///
+    /// The price must be a positive number of at least one price lot. Unlike the v1 client, it
+    /// isn't checked against the mid of the book.
+    ///
+    /// # Errors
+    ///
+    /// Returns `OpenBookError::InvalidPrice` if the price isn't a positive number of price lots,
+    /// or an error if the transaction fails.
+    ///
     /// # Example
     ///
     /// ```rust , ignore
@@ -436,18 +670,43 @@ impl OBClient {
     /// }
     /// ```
     pub async fn place_limit_order(
-        &mut self,
+        &self,
         limit_price: f64,
         quote_size: u64,
         side: Side,
-    ) -> Result<(bool, Signature, u64, Slot)> {
-        let current_time = get_unix_secs();
-        let price_lots = self.native_price_to_lots_price(limit_price);
+    ) -> Result<(bool, Signature, u64, Slot), OpenBookError> {
+        let price_lots = self.checked_price_lots(limit_price)?;
         let max_quote_lots = self
             .context
             .max_quote_lots_including_maker_fees_from_usd(quote_size);
         let base_size = self.get_base_size_from_quote(quote_size, limit_price);
         let max_base_lots = self.context.max_base_lots_from_usd(base_size);
+
+        self.place_limit_order_lots(price_lots, max_base_lots, max_quote_lots, side)
+            .await
+    }
+
+    /// Converts a UI limit price to price lots, rejecting prices that aren't a positive number
+    /// of lots.
+    fn checked_price_lots(&self, limit_price: f64) -> Result<i64, OpenBookError> {
+        let price_lots = self.native_price_to_lots_price(limit_price);
+        if !limit_price.is_finite() || price_lots <= 0 {
+            return Err(OpenBookError::InvalidPrice(limit_price));
+        }
+
+        Ok(price_lots)
+    }
+
+    /// Places a post-only order of `max_base_lots` at `price_lots`, locking at most
+    /// `max_quote_lots` including fees, and returns like `place_limit_order`.
+    async fn place_limit_order_lots(
+        &self,
+        price_lots: i64,
+        max_base_lots: u64,
+        max_quote_lots: u64,
+        side: Side,
+    ) -> Result<(bool, Signature, u64, Slot), OpenBookError> {
+        let current_time = get_unix_secs();
         let ata = self.get_ata_by_side(side);
         let vault = self.market_info.get_vault_by_side(side);
 
@@ -490,10 +749,7 @@ impl OBClient {
             }),
         };
 
-        let (confirmed, sig) = self
-            .rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
-            .await?;
+        let (confirmed, sig) = self.send_and_report(ix, 1, 0, false).await?;
 
         // get slot
         let max_slot: Slot = self
@@ -505,72 +761,71 @@ impl OBClient {
         Ok((confirmed, sig, oid, max_slot))
     }
 
-    pub async fn place_market_order(
-        &mut self,
-        limit_price: f64,
-        quote_size: u64,
-        side: Side,
+    /// Sends `ix` like `Rpc::send_and_confirm`, and reports the `placed` and `canceled` orders,
+    /// and a settlement when `settled` is set, to the metrics of the RPC client once confirmed.
+    ///
+    /// Cancel-all instructions don't say how many orders they cancel, so their callers count the
+    /// wallet's orders as last loaded in `open_orders`.
+    async fn send_and_report(
+        &self,
+        ix: Instruction,
+        placed: u64,
+        canceled: u64,
+        settled: bool,
     ) -> Result<(bool, Signature)> {
-        let current_time = get_unix_secs();
-        let price_lots = self.native_price_to_lots_price(limit_price);
-        let max_quote_lots = self
-            .context
-            .max_quote_lots_including_maker_fees_from_usd(quote_size);
-        let base_size = self.get_base_size_from_quote(quote_size, limit_price);
-        let max_base_lots = self.context.max_base_lots_from_usd(base_size);
-        let ata = self.get_ata_by_side(side);
-        let vault = self.market_info.get_vault_by_side(side);
-
-        tracing::debug!("base: {max_base_lots}, quote: {max_quote_lots}");
-        let oid = self.gen_order_id();
-
-        // TODO: update to market order inst
-        let ix = Instruction {
-            program_id: openbookdex_v2::id(),
-            accounts: {
-                anchor_lang::ToAccountMetas::to_account_metas(
-                    &openbookdex_v2::accounts::PlaceOrder {
-                        open_orders_account: self.open_orders_account,
-                        open_orders_admin: None,
-                        signer: self.owner(),
-                        market: self.market_id,
-                        bids: self.market_info.bids,
-                        asks: self.market_info.asks,
-                        event_heap: self.market_info.event_heap,
-                        oracle_a: self.market_info.oracle_a.into(),
-                        oracle_b: self.market_info.oracle_b.into(),
-                        user_token_account: ata,
-                        market_vault: vault,
-                        token_program: Token::id(),
-                    },
-                    None,
-                )
-            },
-            data: anchor_lang::InstructionData::data(&openbookdex_v2::instruction::PlaceOrder {
-                args: PlaceOrderArgs {
-                    side,
-                    price_lots,
-                    max_base_lots: max_base_lots as i64,
-                    max_quote_lots_including_fees: max_quote_lots as i64,
-                    client_order_id: oid,
-                    order_type: PlaceOrderType::PostOnly,
-                    expiry_timestamp: current_time + 86_400,
-                    self_trade_behavior: SelfTradeBehavior::AbortTransaction,
-                    limit: 12,
-                },
-            }),
-        };
-
-        self.rpc_client
+        let (confirmed, sig) = self
+            .rpc_client
             .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
-            .await
+            .await?;
+        if confirmed {
+            let metrics = self.rpc_client.metrics();
+            if placed > 0 {
+                metrics.on_orders_placed(placed);
+            }
+            if canceled > 0 {
+                metrics.on_orders_canceled(canceled);
+            }
+            if settled {
+                metrics.on_settled();
+            }
+        }
+
+        Ok((confirmed, sig))
     }
 
+    /// Places an oracle-pegged order, whose price tracks the market's oracle price.
+    ///
+    /// `side`, `peg_offset` and `max_base` override the matching fields of `args`; the peg limit,
+    /// quote size, order type, expiry, self trade behavior and match limit come from `args`. A
+    /// client order id is generated when `args.client_order_id` is zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `side` - The side of the order.
+    /// * `peg_offset` - The offset from the oracle price, in quote per base UI units (e.g. -0.05
+    ///   USDC to bid 5 cents under the oracle). It is converted to price lots with the market's
+    ///   decimals and lot sizes, see `MarketInfo::peg_offset_to_lots`.
+    /// * `max_base` - The maximum size of the order in native base units, rounded down to whole
+    ///   base lots.
+    /// * `args` - The rest of the pegged order parameters.
+    ///
+    /// # Returns
+    ///
+    /// The confirmation status, the signature, the client order id and the slot of the
+    /// transaction, like `place_limit_order`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the market has no oracle to peg to, or if the transaction fails.
+    ///
     /// # Example
     ///
     /// ```rust , ignore
     /// use openbook::commitment_config::CommitmentConfig;
     /// use openbook::v2::ob_client::OBClient;
+    /// use openbook::v2_state::{PlaceOrderType, SelfTradeBehavior, Side};
+    /// use openbookdex_v2::PlaceOrderPeggedArgs;
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -580,43 +835,152 @@ impl OBClient {
     ///
     ///     let ob_client = OBClient::new(commitment, market_id, false, true).await?;
     ///
-    ///     let (confirmed, sig) = ob_client.cancel_limit_order(12345678123578).await?;
+    ///     let args = PlaceOrderPeggedArgs {
+    ///         side: Side::Bid,
+    ///         price_offset_lots: 0,
+    ///         peg_limit: -1,
+    ///         max_base_lots: 0,
+    ///         max_quote_lots_including_fees: i64::MAX,
+    ///         client_order_id: 0,
+    ///         order_type: PlaceOrderType::PostOnly,
+    ///         expiry_timestamp: 0,
+    ///         self_trade_behavior: SelfTradeBehavior::AbortTransaction,
+    ///         limit: 12,
+    ///     };
+    ///
+    ///     let (confirmed, sig, order_id, slot) = ob_client
+    ///         .place_pegged_order(Side::Bid, -0.05, 1_000_000_000, args)
+    ///         .await?;
     ///
-    ///     println!("Got Sig: {:?}", sig);
+    ///     println!("Got Order ID: {:?}", order_id);
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn cancel_limit_order(&self, order_id: u128) -> Result<(bool, Signature)> {
-        let ix = Instruction {
-            program_id: openbookdex_v2::id(),
-            accounts: {
+    pub async fn place_pegged_order(
+        &self,
+        side: Side,
+        peg_offset: f64,
+        max_base: u64,
+        args: PlaceOrderPeggedArgs,
+    ) -> Result<(bool, Signature, u64, Slot), OpenBookError> {
+        if self.market_info.configured_oracle().is_none() {
+            return Err(OpenBookError::Other(anyhow::anyhow!(
+                "Market {} has no oracle to peg orders to",
+                self.market_id
+            )));
+        }
+
+        let client_order_id = match args.client_order_id {
+            0 => self.gen_order_id(),
+            client_order_id => client_order_id,
+        };
+        let args = PlaceOrderPeggedArgs {
+            side,
+            price_offset_lots: self.market_info.peg_offset_to_lots(peg_offset),
+            max_base_lots: self.context.max_base_lots(max_base) as i64,
+            client_order_id,
+            ..args
+        };
+        tracing::debug!(
+            "pegged offset: {}, base: {}",
+            args.price_offset_lots,
+            args.max_base_lots
+        );
+
+        let ix = Instruction {
+            program_id: openbookdex_v2::id(),
+            accounts: {
                 anchor_lang::ToAccountMetas::to_account_metas(
-                    &openbookdex_v2::accounts::CancelOrder {
+                    &openbookdex_v2::accounts::PlaceOrder {
                         open_orders_account: self.open_orders_account,
+                        open_orders_admin: None,
                         signer: self.owner(),
                         market: self.market_id,
                         bids: self.market_info.bids,
                         asks: self.market_info.asks,
+                        event_heap: self.market_info.event_heap,
+                        oracle_a: self.market_info.oracle_a.into(),
+                        oracle_b: self.market_info.oracle_b.into(),
+                        user_token_account: self.get_ata_by_side(side),
+                        market_vault: self.market_info.get_vault_by_side(side),
+                        token_program: Token::id(),
                     },
                     None,
                 )
             },
-            data: anchor_lang::InstructionData::data(&openbookdex_v2::instruction::CancelOrder {
-                order_id,
-            }),
+            data: anchor_lang::InstructionData::data(
+                &openbookdex_v2::instruction::PlaceOrderPegged { args },
+            ),
         };
 
-        self.rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
+        let (confirmed, sig) = self.send_and_report(ix, 1, 0, false).await?;
+
+        let max_slot: Slot = self
+            .account_fetcher
+            .transaction_max_slot(sig)
+            .await?
+            .unwrap_or(0);
+
+        Ok((confirmed, sig, client_order_id, max_slot))
+    }
+
+    /// Places an immediate-or-cancel order of `quote_size` whole quote units, matching the book
+    /// up to `limit_price`.
+    ///
+    /// The order only takes liquidity: whatever can't be filled at `limit_price` or better is
+    /// canceled instead of resting on the book. The quote locked includes the taker fee.
+    ///
+    /// # Errors
+    ///
+    /// Returns `OpenBookError::InvalidPrice` if the price isn't a positive number of price lots,
+    /// or an error if the transaction fails.
+    pub async fn place_market_order(
+        &mut self,
+        limit_price: f64,
+        quote_size: u64,
+        side: Side,
+    ) -> Result<(bool, Signature), OpenBookError> {
+        let price_lots = self.checked_price_lots(limit_price)?;
+        let max_quote_lots = self
+            .context
+            .max_quote_lots_including_taker_fees_from_usd(quote_size);
+        let base_size = self.get_base_size_from_quote(quote_size, limit_price);
+        let max_base_lots = self.context.max_base_lots_from_usd(base_size);
+
+        self.place_ioc_order_lots(price_lots, max_base_lots, max_quote_lots, side)
             .await
     }
 
+    /// Swaps `amount` UI units of the token sold by `side` on the book, at `limit_price` or better.
+    ///
+    /// A bid sells `amount` quote tokens, plus the taker fee, for base tokens, an ask sells
+    /// `amount` base tokens for quote tokens. The order is immediate-or-cancel, so `limit_price` bounds the slippage: a bid
+    /// never pays more than it per base token and an ask never gets less. Unlike
+    /// `place_market_order`, fractional amounts are kept down to the token decimals.
+    ///
+    /// # Arguments
+    ///
+    /// * `side` - `Side::Bid` to buy base tokens with quote tokens, `Side::Ask` to sell them.
+    /// * `amount` - The UI amount of the token sold.
+    /// * `limit_price` - The worst UI price to fill at.
+    ///
+    /// # Returns
+    ///
+    /// Whether the transaction was confirmed, and its signature.
+    ///
+    /// # Errors
+    ///
+    /// Returns `OpenBookError::InvalidPrice` if the price isn't a positive number of price lots,
+    /// `OpenBookError::OrderTooSmall` if `amount` rounds down to zero lots, or an error if the
+    /// transaction fails.
+    ///
     /// # Example
     ///
     /// ```rust , ignore
     /// use openbook::commitment_config::CommitmentConfig;
     /// use openbook::v2::ob_client::OBClient;
+    /// use openbook::v2_state::Side;
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -626,86 +990,161 @@ impl OBClient {
     ///
     ///     let ob_client = OBClient::new(commitment, market_id, false, true).await?;
     ///
-    ///     let (confirmed, sig) = ob_client.cancel_all().await?;
+    ///     // Buy with 12.5 USDC, paying at most 2.02 USDC per base token.
+    ///     let (confirmed, sig) = ob_client.place_swap_order(Side::Bid, 12.5, 2.02).await?;
     ///
-    ///     println!("Got Sig: {:?}", sig);
+    ///     println!("Got Signature: {:?}", sig);
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn cancel_all(&self) -> Result<(bool, Signature)> {
+    pub async fn place_swap_order(
+        &self,
+        side: Side,
+        amount: f64,
+        limit_price: f64,
+    ) -> Result<(bool, Signature), OpenBookError> {
+        let price_lots = self.checked_price_lots(limit_price)?;
+        let (max_base_lots, max_quote_lots) = match side {
+            Side::Bid => {
+                let quote_factor = 10f64.powi(self.market_info.quote_decimals as i32);
+                let native_quote = (amount * quote_factor) as u64;
+                let max_quote_lots = self
+                    .context
+                    .max_quote_lots_including_taker_fees(native_quote);
+                (i64::MAX as u64, max_quote_lots)
+            }
+            Side::Ask => {
+                let base_factor = 10f64.powi(self.market_info.base_decimals as i32);
+                let max_base_lots = self.context.max_base_lots((amount * base_factor) as u64);
+                (max_base_lots, i64::MAX as u64)
+            }
+        };
+        if max_base_lots == 0 || max_quote_lots == 0 {
+            return Err(OpenBookError::OrderTooSmall(amount));
+        }
+
+        self.place_ioc_order_lots(price_lots, max_base_lots, max_quote_lots, side)
+            .await
+    }
+
+    /// Places an immediate-or-cancel order of `max_base_lots` at `price_lots`, locking at most
+    /// `max_quote_lots` including fees.
+    async fn place_ioc_order_lots(
+        &self,
+        price_lots: i64,
+        max_base_lots: u64,
+        max_quote_lots: u64,
+        side: Side,
+    ) -> Result<(bool, Signature), OpenBookError> {
+        let ata = self.get_ata_by_side(side);
+        let vault = self.market_info.get_vault_by_side(side);
+
+        tracing::debug!("base: {max_base_lots}, quote: {max_quote_lots}");
+        let oid = self.gen_order_id();
+
         let ix = Instruction {
             program_id: openbookdex_v2::id(),
             accounts: {
                 anchor_lang::ToAccountMetas::to_account_metas(
-                    &openbookdex_v2::accounts::CancelOrder {
+                    &openbookdex_v2::accounts::PlaceOrder {
                         open_orders_account: self.open_orders_account,
+                        open_orders_admin: None,
                         signer: self.owner(),
                         market: self.market_id,
                         bids: self.market_info.bids,
                         asks: self.market_info.asks,
+                        event_heap: self.market_info.event_heap,
+                        oracle_a: self.market_info.oracle_a.into(),
+                        oracle_b: self.market_info.oracle_b.into(),
+                        user_token_account: ata,
+                        market_vault: vault,
+                        token_program: Token::id(),
                     },
                     None,
                 )
             },
-            data: anchor_lang::InstructionData::data(
-                &openbookdex_v2::instruction::CancelAllOrders {
-                    side_option: None,
-                    limit: 255,
+            data: anchor_lang::InstructionData::data(&openbookdex_v2::instruction::PlaceOrder {
+                args: PlaceOrderArgs {
+                    side,
+                    price_lots,
+                    max_base_lots: max_base_lots as i64,
+                    max_quote_lots_including_fees: max_quote_lots as i64,
+                    client_order_id: oid,
+                    order_type: PlaceOrderType::ImmediateOrCancel,
+                    expiry_timestamp: 0,
+                    self_trade_behavior: SelfTradeBehavior::AbortTransaction,
+                    limit: 12,
                 },
-            ),
+            }),
         };
 
-        self.rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
-            .await
+        Ok(self.send_and_report(ix, 1, 0, false).await?)
     }
 
-    pub async fn cancel_all_and_place_orders(
+    /// Cancels a single order of the open orders account by its order id.
+    ///
+    /// # Arguments
+    ///
+    /// * `order_id` - The id of the order to cancel, as found in the open orders account.
+    ///
+    /// # Returns
+    ///
+    /// Whether the transaction was confirmed, and its signature.
+    ///
+    /// # Example
+    ///
+    /// ```rust , ignore
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v2::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "gQN1TNHiqj5x82ZQd7JZ8rm8WD4xwWtXxd4onReWZNK".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, false, true).await?;
+    ///
+    ///     let (confirmed, sig) = ob_client.cancel_order_by_id(12345678123578).await?;
+    ///
+    ///     println!("Got Sig: {:?}", sig);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn cancel_order_by_id(
         &self,
-        bids: Vec<PlaceMultipleOrdersArgs>,
-        asks: Vec<PlaceMultipleOrdersArgs>,
-    ) -> Result<(bool, Signature)> {
-        let orders_type = PlaceOrderType::PostOnly;
-
+        order_id: u128,
+    ) -> Result<(bool, Signature), OpenBookError> {
         let ix = Instruction {
             program_id: openbookdex_v2::id(),
             accounts: {
                 anchor_lang::ToAccountMetas::to_account_metas(
-                    &openbookdex_v2::accounts::CancelAllAndPlaceOrders {
+                    &openbookdex_v2::accounts::CancelOrder {
                         open_orders_account: self.open_orders_account,
                         signer: self.owner(),
-                        open_orders_admin: self.market_info.open_orders_admin.into(),
-                        user_quote_account: self.quote_ata,
-                        user_base_account: self.base_ata,
                         market: self.market_id,
                         bids: self.market_info.bids,
                         asks: self.market_info.asks,
-                        event_heap: self.market_info.event_heap,
-                        market_quote_vault: self.market_info.market_quote_vault,
-                        market_base_vault: self.market_info.market_base_vault,
-                        oracle_a: self.market_info.oracle_a.into(),
-                        oracle_b: self.market_info.oracle_b.into(),
-                        token_program: Token::id(),
                     },
                     None,
                 )
             },
-            data: anchor_lang::InstructionData::data(
-                &openbookdex_v2::instruction::CancelAllAndPlaceOrders {
-                    orders_type,
-                    bids,
-                    asks,
-                    limit: 255,
-                },
-            ),
+            data: anchor_lang::InstructionData::data(&openbookdex_v2::instruction::CancelOrder {
+                order_id,
+            }),
         };
 
-        self.rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
-            .await
+        Ok(self.send_and_report(ix, 0, 1, false).await?)
     }
 
+    /// Cancels all the orders of the open orders account, on both sides of the book.
+    ///
+    /// # Returns
+    ///
+    /// Whether the transaction was confirmed, and its signature.
+    ///
     /// # Example
     ///
     /// ```rust , ignore
@@ -720,54 +1159,100 @@ impl OBClient {
     ///
     ///     let ob_client = OBClient::new(commitment, market_id, false, true).await?;
     ///
-    ///     let account = ob_client.find_or_create_account().await?;
+    ///     let (confirmed, sig) = ob_client.cancel_all_orders().await?;
     ///
-    ///     println!("Got Account: {:?}", account);
+    ///     println!("Got Sig: {:?}", sig);
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn find_or_create_account(&self) -> Result<Pubkey> {
-        let program = openbookdex_v2::id();
+    pub async fn cancel_all_orders(&self) -> Result<(bool, Signature), OpenBookError> {
+        let canceled = self.open_orders.len() as u64;
+        let ix = Instruction {
+            program_id: openbookdex_v2::id(),
+            accounts: {
+                anchor_lang::ToAccountMetas::to_account_metas(
+                    &openbookdex_v2::accounts::CancelOrder {
+                        open_orders_account: self.open_orders_account,
+                        signer: self.owner(),
+                        market: self.market_id,
+                        bids: self.market_info.bids,
+                        asks: self.market_info.asks,
+                    },
+                    None,
+                )
+            },
+            data: anchor_lang::InstructionData::data(
+                &openbookdex_v2::instruction::CancelAllOrders {
+                    side_option: None,
+                    limit: 255,
+                },
+            ),
+        };
 
-        let openbook_account_name = "random";
+        Ok(self.send_and_report(ix, 0, canceled, false).await?)
+    }
 
-        let mut openbook_account_tuples = self
-            .rpc_client
-            .fetch_openbook_accounts(program, self.owner())
-            .await?;
-        let openbook_account_opt = openbook_account_tuples
-            .iter()
-            .find(|(_, account)| account.name() == openbook_account_name);
-        if openbook_account_opt.is_none() {
-            openbook_account_tuples
-                .sort_by(|a, b| a.1.account_num.partial_cmp(&b.1.account_num).unwrap());
-            let account_num = match openbook_account_tuples.last() {
-                Some(tuple) => tuple.1.account_num + 1,
-                None => 0u32,
-            };
-            self.create_open_orders_account(account_num, openbook_account_name)
-                .await
-                .context("Failed to create account...")?;
-        }
-        let openbook_account_tuples = self
-            .rpc_client
-            .fetch_openbook_accounts(program, self.owner())
-            .await?;
+    /// Cancels a single order of the open orders account by its order id.
+    #[deprecated(note = "use `cancel_order_by_id` instead")]
+    pub async fn cancel_limit_order(
+        &self,
+        order_id: u128,
+    ) -> Result<(bool, Signature), OpenBookError> {
+        self.cancel_order_by_id(order_id).await
+    }
 
-        let index = openbook_account_tuples
-            .iter()
-            .position(|tuple| tuple.1.name() == openbook_account_name)
-            .unwrap_or(0);
+    /// Cancels all the orders of the open orders account, on both sides of the book.
+    #[deprecated(note = "use `cancel_all_orders` instead")]
+    pub async fn cancel_all(&self) -> Result<(bool, Signature), OpenBookError> {
+        self.cancel_all_orders().await
+    }
 
-        Ok(openbook_account_tuples[index].0)
+    pub async fn cancel_all_and_place_orders(
+        &self,
+        bids: Vec<PlaceMultipleOrdersArgs>,
+        asks: Vec<PlaceMultipleOrdersArgs>,
+    ) -> Result<(bool, Signature), OpenBookError> {
+        let (placed, canceled) = (
+            (bids.len() + asks.len()) as u64,
+            self.open_orders.len() as u64,
+        );
+        let ix = self.build_place_multiple_orders_instruction(bids, asks, true);
+
+        Ok(self.send_and_report(ix, placed, canceled, false).await?)
     }
 
+    /// Places a set of post-only bids and asks in a single instruction, optionally canceling all
+    /// the existing orders of the open orders account first.
+    ///
+    /// With `cancel_existing`, the whole quote is replaced atomically by the program's
+    /// `CancelAllAndPlaceOrders` instruction, so no fill can land between the cancel and the new
+    /// orders. Otherwise the orders are added with `PlaceOrders`. The program assigns the client
+    /// order ids of the orders from their index, bids first.
+    ///
+    /// # Arguments
+    ///
+    /// * `&self` - A reference to the `OBClient` struct.
+    /// * `bids` - The bids to place, in price lots and quote lots including fees.
+    /// * `asks` - The asks to place, in price lots and quote lots including fees.
+    /// * `cancel_existing` - Whether to cancel all the existing orders first.
+    ///
+    /// # Returns
+    ///
+    /// The confirmation status, the signature, and the order ids returned by the program, bids
+    /// then asks. An order that was filled or dropped on placement has no id. The ids are empty
+    /// if the transaction isn't confirmed or can't be fetched back.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction can't be sent.
+    ///
     /// # Example
     ///
     /// ```rust , ignore
     /// use openbook::commitment_config::CommitmentConfig;
     /// use openbook::v2::ob_client::OBClient;
+    /// use openbookdex_v2::PlaceMultipleOrdersArgs;
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -777,58 +1262,815 @@ impl OBClient {
     ///
     ///     let ob_client = OBClient::new(commitment, market_id, false, true).await?;
     ///
-    ///     let indexer = ob_client.create_open_orders_indexer().await?.3;
+    ///     let bid = PlaceMultipleOrdersArgs {
+    ///         price_lots: ob_client.native_price_to_lots_price(165.0),
+    ///         max_quote_lots_including_fees: 1_000,
+    ///         expiry_timestamp: 0,
+    ///     };
+    ///     let ask = PlaceMultipleOrdersArgs {
+    ///         price_lots: ob_client.native_price_to_lots_price(166.0),
+    ///         max_quote_lots_including_fees: 1_000,
+    ///         expiry_timestamp: 0,
+    ///     };
     ///
-    ///     println!("Got Indexer Account: {:?}", indexer);
+    ///     let (confirmed, sig, order_ids) = ob_client
+    ///         .place_multiple_orders(vec![bid], vec![ask], true)
+    ///         .await?;
+    ///
+    ///     println!("Got Order IDs: {:?}", order_ids);
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn create_open_orders_indexer(
+    pub async fn place_multiple_orders(
         &self,
-        execute: bool,
-    ) -> Result<(bool, Instruction, Signature, Pubkey)> {
-        let owner = &self.owner;
-        let payer = &self.owner;
+        bids: Vec<PlaceMultipleOrdersArgs>,
+        asks: Vec<PlaceMultipleOrdersArgs>,
+        cancel_existing: bool,
+    ) -> Result<(bool, Signature, Vec<Option<u128>>), OpenBookError> {
+        let placed = (bids.len() + asks.len()) as u64;
+        let canceled = if cancel_existing {
+            self.open_orders.len() as u64
+        } else {
+            0
+        };
+        let ix = self.build_place_multiple_orders_instruction(bids, asks, cancel_existing);
 
-        let open_orders_indexer = Pubkey::find_program_address(
-            &[b"OpenOrdersIndexer".as_ref(), owner.pubkey().as_ref()],
-            &openbookdex_v2::id(),
-        )
-        .0;
+        let (confirmed, sig) = self.send_and_report(ix, placed, canceled, false).await?;
+        if !confirmed {
+            return Ok((confirmed, sig, vec![]));
+        }
 
-        let ix = Instruction {
-            program_id: openbookdex_v2::id(),
-            accounts: anchor_lang::ToAccountMetas::to_account_metas(
-                &openbookdex_v2::accounts::CreateOpenOrdersIndexer {
-                    owner: owner.pubkey(),
-                    open_orders_indexer,
-                    payer: payer.pubkey(),
-                    system_program: System::id(),
-                },
-                None,
-            ),
-            data: anchor_lang::InstructionData::data(
-                &openbookdex_v2::instruction::CreateOpenOrdersIndexer {},
-            ),
+        let order_ids = match self.rpc_client.fetch_transaction(&sig).await {
+            Ok(transaction) => transaction
+                .transaction
+                .meta
+                .and_then(|meta| Option::from(meta.return_data))
+                .and_then(|return_data: UiTransactionReturnData| {
+                    STANDARD.decode(return_data.data.0).ok()
+                })
+                .map(|data| decode_placed_order_ids(&data))
+                .unwrap_or_default(),
+            Err(err) => {
+                tracing::warn!("Failed to fetch transaction {}: {:?}", sig, err);
+                vec![]
+            }
         };
 
-        let mut sig = Signature::default();
-        let mut confirmed = false;
-        if execute {
-            (confirmed, sig) = self
-                .rpc_client
-                .send_and_confirm((*self.owner).insecure_clone(), vec![ix.clone()])
-                .await?;
-        }
+        Ok((confirmed, sig, order_ids))
+    }
+
+    /// Builds the instruction placing `bids` and `asks` as post-only orders, canceling all the
+    /// existing orders first when `cancel_existing` is set.
+    fn build_place_multiple_orders_instruction(
+        &self,
+        bids: Vec<PlaceMultipleOrdersArgs>,
+        asks: Vec<PlaceMultipleOrdersArgs>,
+        cancel_existing: bool,
+    ) -> Instruction {
+        let orders_type = PlaceOrderType::PostOnly;
+
+        let data = if cancel_existing {
+            anchor_lang::InstructionData::data(
+                &openbookdex_v2::instruction::CancelAllAndPlaceOrders {
+                    orders_type,
+                    bids,
+                    asks,
+                    limit: 255,
+                },
+            )
+        } else {
+            anchor_lang::InstructionData::data(&openbookdex_v2::instruction::PlaceOrders {
+                orders_type,
+                bids,
+                asks,
+                limit: 255,
+            })
+        };
+
+        Instruction {
+            program_id: openbookdex_v2::id(),
+            accounts: {
+                anchor_lang::ToAccountMetas::to_account_metas(
+                    &openbookdex_v2::accounts::CancelAllAndPlaceOrders {
+                        open_orders_account: self.open_orders_account,
+                        signer: self.owner(),
+                        open_orders_admin: self.market_info.open_orders_admin.into(),
+                        user_quote_account: self.quote_ata,
+                        user_base_account: self.base_ata,
+                        market: self.market_id,
+                        bids: self.market_info.bids,
+                        asks: self.market_info.asks,
+                        event_heap: self.market_info.event_heap,
+                        market_quote_vault: self.market_info.market_quote_vault,
+                        market_base_vault: self.market_info.market_base_vault,
+                        oracle_a: self.market_info.oracle_a.into(),
+                        oracle_b: self.market_info.oracle_b.into(),
+                        token_program: Token::id(),
+                    },
+                    None,
+                )
+            },
+            data,
+        }
+    }
+
+    /// # Example
+    ///
+    /// ```rust , ignore
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v2::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "gQN1TNHiqj5x82ZQd7JZ8rm8WD4xwWtXxd4onReWZNK".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, false, true).await?;
+    ///
+    ///     let account = ob_client.find_or_create_account().await?;
+    ///
+    ///     println!("Got Account: {:?}", account);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn find_or_create_account(&self) -> Result<Pubkey, OpenBookError> {
+        let account_num = match self.find_open_orders_account().await? {
+            Ok(account) => return Ok(account),
+            Err(account_num) => account_num,
+        };
+        self.create_open_orders_account(account_num, OPEN_ORDERS_ACCOUNT_NAME)
+            .await
+            .context("Failed to create account...")?;
+
+        Ok(self.find_open_orders_account().await?.unwrap_or_default())
+    }
+
+    /// Looks up the owner's open orders account named `OPEN_ORDERS_ACCOUNT_NAME`.
+    ///
+    /// Returns the account if found, or else the account number to create it with.
+    async fn find_open_orders_account(&self) -> Result<Result<Pubkey, u32>, OpenBookError> {
+        let accounts = self
+            .rpc_client
+            .fetch_openbook_accounts(openbookdex_v2::id(), self.owner())
+            .await?;
+        if let Some((account, _)) = accounts
+            .iter()
+            .find(|(_, account)| account.name() == OPEN_ORDERS_ACCOUNT_NAME)
+        {
+            return Ok(Ok(*account));
+        }
+
+        Ok(Err(accounts
+            .iter()
+            .map(|(_, account)| account.account_num + 1)
+            .max()
+            .unwrap_or(0)))
+    }
+
+    /// Creates the open orders indexer and the open orders account of the owner, only if they
+    /// are missing on chain.
+    ///
+    /// Unlike the `new` flag of `OBClient::new`, this can be called at every startup: each
+    /// account is looked up first and only the missing ones are created. `index_account` and
+    /// `open_orders_account` are set to the found or created accounts.
+    ///
+    /// # Returns
+    ///
+    /// The signatures of the creation transactions, empty if both accounts already existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC requests fail or a creation transaction doesn't confirm.
+    ///
+    /// # Example
+    ///
+    /// ```rust , ignore
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v2::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "gQN1TNHiqj5x82ZQd7JZ8rm8WD4xwWtXxd4onReWZNK".parse()?;
+    ///
+    ///     let mut ob_client = OBClient::new(commitment, market_id, false, false).await?;
+    ///
+    ///     let signatures = ob_client.ensure_accounts().await?;
+    ///     println!("Created {} accounts", signatures.len());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn ensure_accounts(&mut self) -> Result<Vec<Signature>, OpenBookError> {
+        let mut signatures = Vec::new();
+
+        let (_, ix, _, index_account) = self.create_open_orders_indexer(false).await?;
+        if self
+            .rpc_client
+            .fetch_multiple_accounts(&[index_account])
+            .await?[0]
+            .is_none()
+        {
+            let (confirmed, sig) = self
+                .rpc_client
+                .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
+                .await?;
+            if !confirmed {
+                return Err(anyhow::anyhow!(
+                    "Failed to create open orders indexer {}",
+                    index_account
+                )
+                .into());
+            }
+            signatures.push(sig);
+        }
+        self.index_account = index_account;
+
+        self.open_orders_account = match self.find_open_orders_account().await? {
+            Ok(account) => account,
+            Err(account_num) => {
+                let (confirmed, sig, account) = self
+                    .create_open_orders_account(account_num, OPEN_ORDERS_ACCOUNT_NAME)
+                    .await?;
+                if !confirmed {
+                    return Err(anyhow::anyhow!(
+                        "Failed to create open orders account {}",
+                        account
+                    )
+                    .into());
+                }
+                signatures.push(sig);
+                account
+            }
+        };
+
+        Ok(signatures)
+    }
+
+    /// # Example
+    ///
+    /// ```rust , ignore
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v2::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "gQN1TNHiqj5x82ZQd7JZ8rm8WD4xwWtXxd4onReWZNK".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, false, true).await?;
+    ///
+    ///     let indexer = ob_client.create_open_orders_indexer().await?.3;
+    ///
+    ///     println!("Got Indexer Account: {:?}", indexer);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn create_open_orders_indexer(
+        &self,
+        execute: bool,
+    ) -> Result<(bool, Instruction, Signature, Pubkey), OpenBookError> {
+        let owner = &self.owner;
+        let payer = &self.owner;
+
+        let open_orders_indexer = Pubkey::find_program_address(
+            &[b"OpenOrdersIndexer".as_ref(), owner.pubkey().as_ref()],
+            &openbookdex_v2::id(),
+        )
+        .0;
+
+        let ix = Instruction {
+            program_id: openbookdex_v2::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &openbookdex_v2::accounts::CreateOpenOrdersIndexer {
+                    owner: owner.pubkey(),
+                    open_orders_indexer,
+                    payer: payer.pubkey(),
+                    system_program: System::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &openbookdex_v2::instruction::CreateOpenOrdersIndexer {},
+            ),
+        };
+
+        let mut sig = Signature::default();
+        let mut confirmed = false;
+        if execute {
+            (confirmed, sig) = self
+                .rpc_client
+                .send_and_confirm((*self.owner).insecure_clone(), vec![ix.clone()])
+                .await?;
+        }
+
+        Ok((confirmed, ix, sig, open_orders_indexer))
+    }
+
+    /// # Example
+    ///
+    /// ```rust , ignore
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v2::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "gQN1TNHiqj5x82ZQd7JZ8rm8WD4xwWtXxd4onReWZNK".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, false, true).await?;
+    ///
+    ///     let (confirmed, sig, account) = ob_client.create_open_orders_account(2, "Sol-USDC-OO-Account").await?;
+    ///
+    ///     println!("Got New OO Account: {:?}", account);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn create_open_orders_account(
+        &self,
+        account_num: u32,
+        name: &str,
+    ) -> Result<(bool, Signature, Pubkey), OpenBookError> {
+        let owner = &self.owner;
+        let payer = &self.owner;
+        let market = self.market_id;
+
+        let delegate = None;
+
+        let open_orders_indexer = Pubkey::find_program_address(
+            &[b"OpenOrdersIndexer".as_ref(), owner.pubkey().as_ref()],
+            &openbookdex_v2::id(),
+        )
+        .0;
+
+        let account = Pubkey::find_program_address(
+            &[
+                b"OpenOrders".as_ref(),
+                owner.pubkey().as_ref(),
+                &account_num.to_le_bytes(),
+            ],
+            &openbookdex_v2::id(),
+        )
+        .0;
+
+        let ix = Instruction {
+            program_id: openbookdex_v2::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &openbookdex_v2::accounts::CreateOpenOrdersAccount {
+                    owner: owner.pubkey(),
+                    open_orders_indexer,
+                    open_orders_account: account,
+                    payer: payer.pubkey(),
+                    delegate_account: delegate,
+                    market,
+                    system_program: System::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &openbookdex_v2::instruction::CreateOpenOrdersAccount {
+                    name: name.to_string(),
+                },
+            ),
+        };
+
+        let (confirmed, sig) = self
+            .rpc_client
+            .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
+            .await?;
+
+        Ok((confirmed, sig, account))
+    }
+
+    /// Closes the open orders account of the client, returning its rent to the owner.
+    ///
+    /// The account must be empty: no order on the book, no free balance left to settle and no
+    /// locked fees or rebates. Cancel the orders and settle the funds first.
+    ///
+    /// # Returns
+    ///
+    /// Whether the transaction was confirmed, and its signature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the open orders account can't be fetched, if it isn't empty, or if the
+    /// transaction can't be sent.
+    ///
+    /// # Example
+    ///
+    /// ```rust , ignore
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v2::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "gQN1TNHiqj5x82ZQd7JZ8rm8WD4xwWtXxd4onReWZNK".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, false, true).await?;
+    ///
+    ///     ob_client.cancel_all_orders().await?;
+    ///     ob_client.settle_funds().await?;
+    ///
+    ///     let (confirmed, sig) = ob_client.close_open_orders_account().await?;
+    ///
+    ///     println!("Got Sig: {:?}", sig);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn close_open_orders_account(&self) -> Result<(bool, Signature), OpenBookError> {
+        ensure_open_orders_account_empty(&self.openorders_account().await?)?;
+
+        let owner = self.owner();
+        let open_orders_indexer = Pubkey::find_program_address(
+            &[b"OpenOrdersIndexer".as_ref(), owner.as_ref()],
+            &openbookdex_v2::id(),
+        )
+        .0;
+
+        let ix = Instruction {
+            program_id: openbookdex_v2::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &openbookdex_v2::accounts::CloseOpenOrdersAccount {
+                    owner,
+                    open_orders_indexer,
+                    open_orders_account: self.open_orders_account,
+                    sol_destination: owner,
+                    system_program: System::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(
+                &openbookdex_v2::instruction::CloseOpenOrdersAccount {},
+            ),
+        };
+
+        Ok(self
+            .rpc_client
+            .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
+            .await?)
+    }
+
+    /// Closes the market along with its bids, asks and event heap accounts, returning their rent
+    /// to the owner.
+    ///
+    /// The owner must be the close market admin of the market, and the market must hold no
+    /// deposits, fees or rebates; see `MarketInfo::ensure_closable_by`. The program also requires
+    /// the market to have expired and its book and event heap to be empty.
+    ///
+    /// # Returns
+    ///
+    /// Whether the transaction was confirmed, and its signature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the market can't be fetched, if the owner can't close it, if it still
+    /// holds funds, or if the transaction can't be sent.
+    ///
+    /// # Example
+    ///
+    /// ```rust , ignore
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v2::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "gQN1TNHiqj5x82ZQd7JZ8rm8WD4xwWtXxd4onReWZNK".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, false, true).await?;
+    ///
+    ///     let (confirmed, sig) = ob_client.close_market().await?;
+    ///
+    ///     println!("Got Sig: {:?}", sig);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn close_market(&self) -> Result<(bool, Signature), OpenBookError> {
+        let owner = self.owner();
+        let market = self
+            .rpc_client
+            .fetch_anchor_account::<Market>(&self.market_id)
+            .await?;
+        let market_info = MarketInfo::from_market(&market);
+        market_info.ensure_closable_by(&owner)?;
+
+        let ix = Instruction {
+            program_id: openbookdex_v2::id(),
+            accounts: anchor_lang::ToAccountMetas::to_account_metas(
+                &openbookdex_v2::accounts::CloseMarket {
+                    close_market_admin: owner,
+                    market: self.market_id,
+                    bids: market_info.bids,
+                    asks: market_info.asks,
+                    event_heap: market_info.event_heap,
+                    sol_destination: owner,
+                    token_program: Token::id(),
+                },
+                None,
+            ),
+            data: anchor_lang::InstructionData::data(&openbookdex_v2::instruction::CloseMarket {}),
+        };
+
+        Ok(self
+            .rpc_client
+            .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
+            .await?)
+    }
+
+    /// Consumes up to `limit` events of the market's event heap, cranking the market.
+    ///
+    /// This is the v2 counterpart of the v1 consume events instruction. Fills and outs only
+    /// update the balances of the open orders accounts once consumed, and an event is only
+    /// consumed if its open orders account is passed. On markets with a consume events admin,
+    /// the owner signs as that admin.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of events to consume.
+    /// * `open_orders_accounts` - The open orders accounts of the events to consume.
+    ///
+    /// # Returns
+    ///
+    /// Whether the transaction was confirmed, and its signature.
+    ///
+    /// # Example
+    ///
+    /// ```rust , ignore
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v2::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "gQN1TNHiqj5x82ZQd7JZ8rm8WD4xwWtXxd4onReWZNK".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, false, true).await?;
+    ///
+    ///     while ob_client.load_event_heap().await? > 0 {
+    ///         let (confirmed, sig) = ob_client
+    ///             .consume_events(8, vec![ob_client.open_orders_account])
+    ///             .await?;
+    ///
+    ///         println!("Got Sig: {:?}", sig);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn consume_events(
+        &self,
+        limit: u16,
+        open_orders_accounts: Vec<Pubkey>,
+    ) -> Result<(bool, Signature), OpenBookError> {
+        let consume_events_admin =
+            Option::<Pubkey>::from(self.market_info.consume_events_admin).map(|_| self.owner());
+
+        let mut accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &openbookdex_v2::accounts::ConsumeEvents {
+                consume_events_admin,
+                market: self.market_id,
+                event_heap: self.market_info.event_heap,
+            },
+            None,
+        );
+        accounts.extend(
+            open_orders_accounts
+                .into_iter()
+                .map(|key| AccountMeta::new(key, false)),
+        );
+
+        let ix = Instruction {
+            program_id: openbookdex_v2::id(),
+            accounts,
+            data: anchor_lang::InstructionData::data(&openbookdex_v2::instruction::ConsumeEvents {
+                limit: limit as usize,
+            }),
+        };
+
+        Ok(self
+            .rpc_client
+            .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
+            .await?)
+    }
+
+    /// Loads the event heap of the market and returns the number of events waiting to be consumed.
+    ///
+    /// A crank loop calls `consume_events` until this returns zero.
+    ///
+    /// # Returns
+    ///
+    /// The number of pending events.
+    ///
+    /// # Example
+    ///
+    /// ```rust , ignore
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v2::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "gQN1TNHiqj5x82ZQd7JZ8rm8WD4xwWtXxd4onReWZNK".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, false, false).await?;
+    ///
+    ///     println!("Pending events: {}", ob_client.load_event_heap().await?);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn load_event_heap(&self) -> Result<usize, OpenBookError> {
+        let event_heap = self
+            .rpc_client
+            .fetch_anchor_account::<EventHeap>(&self.market_info.event_heap)
+            .await?;
+
+        Ok(event_heap.len())
+    }
+
+    pub fn owner(&self) -> Pubkey {
+        self.owner.pubkey()
+    }
+
+    /// Checks that the client is ready to trade, running every check instead of failing on the
+    /// first problem.
+    ///
+    /// The market, open orders, wallet and token accounts are fetched in a single
+    /// `getMultipleAccounts` call, which also tells whether the RPC node is reachable.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_sol_lamports` - The minimum SOL balance of the wallet, in lamports, to pay fees.
+    ///
+    /// # Returns
+    ///
+    /// A `HealthReport` with the outcome of each check and a description of the failed ones.
+    ///
+    /// # Errors
+    ///
+    /// Failed checks, including an unreachable RPC node, are reported in the `HealthReport`
+    /// rather than as an error.
+    ///
+    /// # Example
+    ///
+    /// ```rust , ignore
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v2::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "gQN1TNHiqj5x82ZQd7JZ8rm8WD4xwWtXxd4onReWZNK".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, false, false).await?;
+    ///
+    ///     let report = ob_client.health_check(10_000_000).await?;
+    ///
+    ///     println!("Ready: {}", report.is_ready());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn health_check(&self, min_sol_lamports: u64) -> Result<HealthReport, OpenBookError> {
+        let accounts = HealthCheckAccounts {
+            program_id: openbookdex_v2::id(),
+            market: self.market_id,
+            market_state_loaded: self.market_info.bids != Pubkey::default()
+                && self.market_info.asks != Pubkey::default(),
+            open_orders: self.open_orders_account,
+            owner: self.owner(),
+            base_ata: self.base_ata,
+            quote_ata: self.quote_ata,
+        };
+
+        Ok(accounts.check(&self.rpc_client, min_sol_lamports).await)
+    }
+
+    /// Returns the oracle confidence and staleness parameters of the market.
+    ///
+    /// Pegged orders are priced off the market's oracle, so check these before relying on them.
+    ///
+    /// # Returns
+    ///
+    /// The `OracleConfig` of the market, or `None` if the market has no oracle configured.
+    ///
+    /// # Example
+    ///
+    /// ```rust , ignore
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v2::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "gQN1TNHiqj5x82ZQd7JZ8rm8WD4xwWtXxd4onReWZNK".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, false, false).await?;
+    ///
+    ///     if let Some(oracle_config) = ob_client.oracle_config() {
+    ///         println!("Confidence filter: {}", oracle_config.conf_filter);
+    ///         println!("Max staleness slots: {}", oracle_config.max_staleness_slots);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn oracle_config(&self) -> Option<OracleConfig> {
+        self.market_info.configured_oracle()
+    }
+
+    /// Returns the maker and taker fee rates of the market.
+    ///
+    /// # Returns
+    ///
+    /// A `FeeRates` with the rates as fractions of the traded notional, e.g. `0.0002` for 2 bps.
+    pub fn fee_rates(&self) -> FeeRates {
+        self.market_info.fee_rates()
+    }
+
+    /// Returns the minimum spread, in bps of the mid price, for a maker round trip to be profitable.
+    ///
+    /// Both the bid and the ask are assumed to rest on the book, so each leg pays the maker fee.
+    /// Quoting a market whose spread is below this value loses money after fees.
+    ///
+    /// # Returns
+    ///
+    /// The break-even spread in bps, negative when the market pays maker rebates.
+    ///
+    /// # Example
+    ///
+    /// ```rust , ignore
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v2::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "gQN1TNHiqj5x82ZQd7JZ8rm8WD4xwWtXxd4onReWZNK".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, false, false).await?;
+    ///
+    ///     println!("Break-even spread: {} bps", ob_client.breakeven_spread_bps());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn breakeven_spread_bps(&self) -> f64 {
+        self.market_info.breakeven_spread_bps()
+    }
 
-        Ok((confirmed, ix, sig, open_orders_indexer))
+    pub async fn openorders_account(&self) -> Result<OpenOrdersAccount, OpenBookError> {
+        Ok(account_fetcher_fetch_openorders_account(
+            &*self.account_fetcher,
+            &self.open_orders_account,
+        )
+        .await?)
     }
 
+    /// Creates a new market, with its vaults owned by the market authority.
+    ///
+    /// Both mints are fetched in a single request and checked before the transaction is sent, so
+    /// a mistyped mint fails early instead of in the program. The bids, asks and event heap are
+    /// allocated in the same transaction, which is signed by the new market and book accounts.
+    ///
+    /// # Arguments
+    ///
+    /// * `market_args` - The arguments of the market, see `CreateMarketArgs::builder`.
+    ///
+    /// # Returns
+    ///
+    /// Whether the transaction was confirmed, its signature, and the addresses of every account
+    /// of the market.
+    ///
+    /// # Errors
+    ///
+    /// Returns `OpenBookError::InvalidMint` if a mint doesn't exist or isn't owned by the SPL
+    /// token program, or an error if the mints or the rent exemption can't be fetched.
+    ///
     /// # Example
     ///
     /// ```rust , ignore
     /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::pubkey::Pubkey;
+    /// use openbook::v2::market::CreateMarketArgs;
     /// use openbook::v2::ob_client::OBClient;
     ///
     /// #[tokio::main]
@@ -837,87 +2079,71 @@ impl OBClient {
     ///
     ///     let market_id = "gQN1TNHiqj5x82ZQd7JZ8rm8WD4xwWtXxd4onReWZNK".parse()?;
     ///
-    ///     let ob_client = OBClient::new(commitment, market_id, false, true).await?;
+    ///     let ob_client = OBClient::new(commitment, market_id, false, false).await?;
     ///
-    ///     let (confirmed, sig, account) = ob_client.create_open_orders_account(2, "Sol-USDC-OO-Account").await?;
+    ///     let args = CreateMarketArgs::builder()
+    ///         .name("JLP/USDC")
+    ///         .base_mint("27G8MtK7VtTcCHkpASjSDdkWWYfoqT6ggEuKidVJidD4".parse()?)
+    ///         .quote_mint("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".parse()?)
+    ///         .base_lot_size(1_000_000)
+    ///         .quote_lot_size(1)
+    ///         .collect_fee_admin(ob_client.owner())
+    ///         .build()?;
     ///
-    ///     println!("Got New OO Account: {:?}", account);
+    ///     let (confirmed, sig, created) = ob_client.create_market(args).await?;
+    ///
+    ///     println!("[*] Created market {} ({}): {:?}", created.market, confirmed, sig);
+    ///     println!("[*] Base vault: {}, quote vault: {}", created.base_vault, created.quote_vault);
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn create_open_orders_account(
-        &self,
-        account_num: u32,
-        name: &str,
-    ) -> Result<(bool, Signature, Pubkey)> {
-        let owner = &self.owner;
-        let payer = &self.owner;
-        let market = self.market_id;
-
-        let delegate = None;
-
-        let open_orders_indexer = Pubkey::find_program_address(
-            &[b"OpenOrdersIndexer".as_ref(), owner.pubkey().as_ref()],
-            &openbookdex_v2::id(),
-        )
-        .0;
-
-        let account = Pubkey::find_program_address(
-            &[
-                b"OpenOrders".as_ref(),
-                owner.pubkey().as_ref(),
-                &account_num.to_le_bytes(),
-            ],
-            &openbookdex_v2::id(),
-        )
-        .0;
-
-        let ix = Instruction {
-            program_id: openbookdex_v2::id(),
-            accounts: anchor_lang::ToAccountMetas::to_account_metas(
-                &openbookdex_v2::accounts::CreateOpenOrdersAccount {
-                    owner: owner.pubkey(),
-                    open_orders_indexer,
-                    open_orders_account: account,
-                    payer: payer.pubkey(),
-                    delegate_account: delegate,
-                    market,
-                    system_program: System::id(),
-                },
-                None,
-            ),
-            data: anchor_lang::InstructionData::data(
-                &openbookdex_v2::instruction::CreateOpenOrdersAccount {
-                    name: name.to_string(),
-                },
-            ),
-        };
-
-        let (confirmed, sig) = self
-            .rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
-            .await?;
-
-        Ok((confirmed, sig, account))
-    }
-
-    pub fn owner(&self) -> Pubkey {
-        self.owner.pubkey()
-    }
-
-    pub async fn openorders_account(&self) -> Result<OpenOrdersAccount> {
-        account_fetcher_fetch_openorders_account(&*self.account_fetcher, &self.open_orders_account)
-            .await
-    }
-
     pub async fn create_market(
         &self,
         market_args: CreateMarketArgs,
-    ) -> Result<(bool, Signature, Pubkey)> {
+    ) -> Result<(bool, Signature, CreatedMarket), OpenBookError> {
         let program_id = openbookdex_v2::id();
 
-        let market = Keypair::new().pubkey();
+        let mints = [market_args.base_mint, market_args.quote_mint];
+        let mint_accounts = self.rpc_client.fetch_multiple_accounts(&mints).await?;
+        for (mint, account) in mints.iter().zip(mint_accounts) {
+            if !account.is_some_and(|account| account.owner == Token::id()) {
+                return Err(OpenBookError::InvalidMint(*mint));
+            }
+        }
+
+        // The market is created by the program, the books and event heap must be allocated
+        // beforehand. All of them are new accounts, so they sign the transaction.
+        let market_keypair = Keypair::new();
+        let bids_keypair = Keypair::new();
+        let asks_keypair = Keypair::new();
+        let event_heap_keypair = Keypair::new();
+        let market = market_keypair.pubkey();
+
+        let book_side_space = 8 + std::mem::size_of::<BookSide>();
+        let event_heap_space = 8 + std::mem::size_of::<EventHeap>();
+        let book_side_rent = self
+            .rpc_client
+            .get_minimum_balance_for_rent_exemption(book_side_space)
+            .await?;
+        let event_heap_rent = self
+            .rpc_client
+            .get_minimum_balance_for_rent_exemption(event_heap_space)
+            .await?;
+        let mut instructions = Vec::new();
+        for (keypair, lamports, space) in [
+            (&bids_keypair, book_side_rent, book_side_space),
+            (&asks_keypair, book_side_rent, book_side_space),
+            (&event_heap_keypair, event_heap_rent, event_heap_space),
+        ] {
+            instructions.push(system_instruction::create_account(
+                &self.owner(),
+                &keypair.pubkey(),
+                lamports,
+                space as u64,
+                &program_id,
+            ));
+        }
 
         let oracle_config = OracleConfigParams {
             conf_filter: 0.069,
@@ -929,19 +2155,14 @@ impl OBClient {
         let (event_authority, _bump_seed) =
             Pubkey::find_program_address(event_authority_slice, &program_id);
 
-        let market_seeds = &[b"Market".as_ref(), &market.to_bytes()];
-
-        let (market_authority, _bump_seed) =
-            Pubkey::find_program_address(market_seeds, &program_id);
-
-        let market_base_vault =
-            get_associated_token_address(&market_authority, &market_args.base_mint);
-        let market_quote_vault =
-            get_associated_token_address(&market_authority, &market_args.quote_mint);
-
-        let event_heap = Keypair::new().pubkey();
-        let asks = Keypair::new().pubkey();
-        let bids = Keypair::new().pubkey();
+        let created = CreatedMarket::derive(
+            market,
+            bids_keypair.pubkey(),
+            asks_keypair.pubkey(),
+            event_heap_keypair.pubkey(),
+            &market_args.base_mint,
+            &market_args.quote_mint,
+        );
 
         let ix = Instruction {
             program_id: program_id,
@@ -949,13 +2170,13 @@ impl OBClient {
                 anchor_lang::ToAccountMetas::to_account_metas(
                     &openbookdex_v2::accounts::CreateMarket {
                         market,
-                        market_authority,
-                        bids,
-                        asks,
-                        event_heap,
+                        market_authority: created.market_authority,
+                        bids: created.bids,
+                        asks: created.asks,
+                        event_heap: created.event_heap,
                         payer: self.owner(),
-                        market_base_vault,
-                        market_quote_vault,
+                        market_base_vault: created.base_vault,
+                        market_quote_vault: created.quote_vault,
                         base_mint: market_args.base_mint,
                         quote_mint: market_args.quote_mint,
                         system_program: solana_sdk::system_program::id(),
@@ -984,12 +2205,24 @@ impl OBClient {
             }),
         };
 
+        instructions.push(ix);
+
         let (confirmed, sig) = self
             .rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
+            .send_and_confirm_with_signers(
+                (*self.owner).insecure_clone(),
+                &[
+                    &market_keypair,
+                    &bids_keypair,
+                    &asks_keypair,
+                    &event_heap_keypair,
+                ],
+                instructions,
+                &TxConfig::default(),
+            )
             .await?;
 
-        Ok((confirmed, sig, market))
+        Ok((confirmed, sig, created))
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -1009,7 +2242,7 @@ impl OBClient {
         user_token_account: Pubkey,
         market_vault: Pubkey,
         self_trade_behavior: SelfTradeBehavior,
-    ) -> Result<(bool, Signature)> {
+    ) -> Result<(bool, Signature), OpenBookError> {
         let ix = Instruction {
             program_id: openbookdex_v2::id(),
             accounts: {
@@ -1049,9 +2282,7 @@ impl OBClient {
             ),
         };
 
-        self.rpc_client
-            .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
-            .await
+        Ok(self.send_and_report(ix, 1, 0, false).await?)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -1064,7 +2295,7 @@ impl OBClient {
         user_quote_account: Pubkey,
         market_base_vault: Pubkey,
         market_quote_vault: Pubkey,
-    ) -> Result<(bool, Signature)> {
+    ) -> Result<(bool, Signature), OpenBookError> {
         let ix = Instruction {
             program_id: openbookdex_v2::id(),
             accounts: {
@@ -1088,9 +2319,10 @@ impl OBClient {
             }),
         };
 
-        self.rpc_client
+        Ok(self
+            .rpc_client
             .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
-            .await
+            .await?)
     }
 
     /// # Example
@@ -1114,7 +2346,7 @@ impl OBClient {
     ///     Ok(())
     /// }
     /// ```
-    pub async fn consume_events(&self, limit: usize) -> Result<(bool, Signature)> {
+    pub async fn consume_events(&self, limit: usize) -> Result<(bool, Signature), OpenBookError> {
         let ix = Instruction {
             program_id: openbookdex_v2::id(),
             accounts: {
@@ -1132,9 +2364,10 @@ impl OBClient {
             }),
         };
 
-        self.rpc_client
+        Ok(self
+            .rpc_client
             .send_and_confirm((*self.owner).insecure_clone(), vec![ix])
-            .await
+            .await?)
     }
 
     pub fn native_price_to_lots_price(&self, limit_price: f64) -> i64 {
@@ -1154,20 +2387,105 @@ impl OBClient {
         base_size
     }
 
-    pub async fn load_bids_asks_info(&self) -> Result<(Vec<OpenOrderNode>, BestQuotes)> {
-        let mut best_quotes = BestQuotes {
-            highest_bid: 0.,
-            lowest_ask: 0.,
+    /// Fetches the bids and asks book sides in a single `getMultipleAccounts` call.
+    async fn fetch_book_sides(&self) -> Result<(BookSide, BookSide)> {
+        let mut book_sides = self
+            .rpc_client
+            .fetch_multiple_anchor_accounts::<BookSide>(&[
+                self.market_info.bids,
+                self.market_info.asks,
+            ])
+            .await?;
+        let asks = book_sides
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("Missing asks account"))?;
+        let bids = book_sides
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("Missing bids account"))?;
+
+        Ok((bids, asks))
+    }
+
+    pub async fn load_bids_asks_info(
+        &self,
+    ) -> Result<(Vec<OpenOrderNode>, BestQuotes), OpenBookError> {
+        let (bids_book_side, asks_book_side) = self.fetch_book_sides().await?;
+
+        Ok(self.orders_from_book_sides(&bids_book_side, &asks_book_side))
+    }
+
+    /// Re-reads the market state, the bids and asks, the open orders and the balances in place.
+    ///
+    /// A long-lived client calls this in its loop instead of being rebuilt. The market, bids and
+    /// asks accounts are fetched in a single `getMultipleAccounts` call, then `market_info`,
+    /// `context` and `open_orders` are updated, and `oo_state` and `ata_balances` are reloaded
+    /// once the client has an open orders account. Prices, open orders and balances are only as
+    /// fresh as the last `refresh`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an account is missing or can't be decoded, or if the balances can't be
+    /// fetched.
+    ///
+    /// # Example
+    ///
+    /// ```rust , ignore
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v2::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "gQN1TNHiqj5x82ZQd7JZ8rm8WD4xwWtXxd4onReWZNK".parse()?;
+    ///
+    ///     let mut ob_client = OBClient::new(commitment, market_id, false, true).await?;
+    ///
+    ///     loop {
+    ///         ob_client.refresh().await?;
+    ///
+    ///         println!("Open orders: {:?}", ob_client.open_orders);
+    ///
+    ///         tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    ///     }
+    /// }
+    /// ```
+    pub async fn refresh(&mut self) -> Result<(), OpenBookError> {
+        let keys = [self.market_id, self.market_info.bids, self.market_info.asks];
+        let accounts = self.rpc_client.fetch_multiple_accounts(&keys).await?;
+        let data = |i: usize| {
+            accounts[i]
+                .as_ref()
+                .map(|account| &account.data as &[u8])
+                .ok_or_else(|| anyhow::anyhow!("Account {} not found", keys[i]))
         };
+        let market = Market::try_deserialize(&mut data(0)?)?;
+        let bids = BookSide::try_deserialize(&mut data(1)?)?;
+        let asks = BookSide::try_deserialize(&mut data(2)?)?;
+
+        self.market_info = MarketInfo::from_market(&market);
+        self.context.market = market;
+        self.open_orders = self.orders_from_book_sides(&bids, &asks).0;
+
+        if self.open_orders_account != Pubkey::default() {
+            self.oo_state = self.load_oo_state().await?;
+            self.ata_balances = self.get_base_quote_total().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Collects the wallet's open orders and the best quotes from the bids and asks book sides.
+    fn orders_from_book_sides(
+        &self,
+        bids_book_side: &BookSide,
+        asks_book_side: &BookSide,
+    ) -> (Vec<OpenOrderNode>, BestQuotes) {
+        let mut best_quotes = BestQuotes::default();
         let mut open_orders = Vec::new();
         let _current_time = get_unix_secs();
         let orders_key = self.open_orders_account;
 
-        let bids_book_side = self
-            .rpc_client
-            .fetch_anchor_account::<BookSide>(&self.market_info.bids)
-            .await?;
-
         for (i, bid_book_side) in bids_book_side.iter_valid(0, None).enumerate() {
             let node = bid_book_side.node;
             let slot = node.owner_slot;
@@ -1179,7 +2497,7 @@ impl OBClient {
             let ui_price: f64 = I80F48::to_num::<f64>(native_price) * 1000.;
             let ui_amount = node.quantity as f64 / 1e1;
             if i == 0 {
-                best_quotes.highest_bid = ui_amount;
+                best_quotes.highest_bid = Some(ui_price);
             }
             if owner_address == orders_key {
                 open_orders.push(OpenOrderNode {
@@ -1193,12 +2511,6 @@ impl OBClient {
             }
         }
 
-        let asks_book_side = self
-            .rpc_client
-            .fetch_anchor_account::<BookSide>(&self.market_info.asks)
-            .await
-            .unwrap();
-
         for (i, ask_book_side) in asks_book_side.iter_valid(0, None).enumerate() {
             let node = ask_book_side.node;
             let slot = node.owner_slot;
@@ -1209,7 +2521,7 @@ impl OBClient {
             let native_price = self.market_info.lot_to_native_price(lot_price);
             let ui_price: f64 = I80F48::to_num::<f64>(native_price) * 1000.;
             if i == 0 {
-                best_quotes.lowest_ask = ui_price;
+                best_quotes.lowest_ask = Some(ui_price);
             }
             let ui_amount = node.quantity as f64 / 1e1;
             if owner_address == orders_key {
@@ -1224,10 +2536,140 @@ impl OBClient {
             }
         }
 
-        Ok((open_orders, best_quotes))
+        (open_orders, best_quotes)
+    }
+
+    /// Fetches the bids and asks and returns the best bid and ask UI prices.
+    ///
+    /// An empty side of the book is reported as `None`.
+    ///
+    /// # Returns
+    ///
+    /// The `BestQuotes` of the current book.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bids or asks account can't be fetched or decoded.
+    ///
+    /// # Example
+    ///
+    /// ```rust , ignore
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v2::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "gQN1TNHiqj5x82ZQd7JZ8rm8WD4xwWtXxd4onReWZNK".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, false, false).await?;
+    ///
+    ///     let quotes = ob_client.get_best_quotes().await?;
+    ///
+    ///     println!("Bid: {:?}, ask: {:?}", quotes.highest_bid, quotes.lowest_ask);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn get_best_quotes(&self) -> Result<BestQuotes, OpenBookError> {
+        let (bids, asks) = self.fetch_book_sides().await?;
+
+        Ok(self
+            .market_info
+            .orderbook_from_book_sides(&bids, &asks, 1)
+            .best_quotes())
+    }
+
+    /// Fetches the bids and asks and returns the midpoint between the best bid and ask.
+    ///
+    /// # Returns
+    ///
+    /// The mid UI price of the current book.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the book can't be fetched, or if either side of it is empty; use
+    /// `get_best_quotes` to handle a one-sided book.
+    ///
+    /// # Example
+    ///
+    /// ```rust , ignore
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v2::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "gQN1TNHiqj5x82ZQd7JZ8rm8WD4xwWtXxd4onReWZNK".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, false, false).await?;
+    ///
+    ///     println!("Mid: {}", ob_client.get_mid_price().await?);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn get_mid_price(&self) -> Result<f64, OpenBookError> {
+        self.get_best_quotes().await?.mid().ok_or_else(|| {
+            OpenBookError::Other(anyhow::anyhow!(
+                "Market {} has no two-sided book",
+                self.market_id
+            ))
+        })
+    }
+
+    /// Subscribes to the bids and asks accounts and streams the order book on every change.
+    ///
+    /// This is the v2 counterpart of the v1 `subscribe_orderbook`: the websocket URL comes from the
+    /// `WS_URL` env var, or is derived from the RPC URL, and a dropped websocket is reconnected
+    /// with a backoff and resynced instead of ending the stream.
+    ///
+    /// # Example
+    ///
+    /// ```rust , ignore
+    /// use futures::StreamExt;
+    /// use openbook::commitment_config::CommitmentConfig;
+    /// use openbook::v2::ob_client::OBClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let commitment = CommitmentConfig::confirmed();
+    ///
+    ///     let market_id = "gQN1TNHiqj5x82ZQd7JZ8rm8WD4xwWtXxd4onReWZNK".parse()?;
+    ///
+    ///     let ob_client = OBClient::new(commitment, market_id, false, true).await?;
+    ///
+    ///     let mut books = Box::pin(ob_client.subscribe_orderbook().await?);
+    ///
+    ///     while let Some(book) = books.next().await {
+    ///         println!("Best bid: {:?}, best ask: {:?}", book.best_bid(), book.best_ask());
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn subscribe_orderbook(
+        &self,
+    ) -> Result<impl Stream<Item = OrderBook>, OpenBookError> {
+        let market_info = self.market_info.clone();
+        let keys = vec![market_info.bids, market_info.asks];
+        let ws_url = get_ws_url(&self.rpc_client.inner().url());
+
+        Ok(spawn_account_stream(
+            self.rpc_client.clone(),
+            ws_url,
+            keys,
+            move |accounts| {
+                let bids = BookSide::try_deserialize(&mut (&accounts[0].data as &[u8]))?;
+                let asks = BookSide::try_deserialize(&mut (&accounts[1].data as &[u8]))?;
+                Ok(market_info.orderbook_from_book_sides(&bids, &asks, usize::MAX))
+            },
+        ))
     }
 
-    pub async fn load_oo_state(&self) -> Result<OpenOrderState> {
+    pub async fn load_oo_state(&self) -> Result<OpenOrderState, OpenBookError> {
         let open_orders_account = self.openorders_account().await?;
 
         let asks_base_lots = open_orders_account.position.asks_base_lots;
@@ -1258,7 +2700,7 @@ impl OBClient {
         })
     }
 
-    pub async fn get_token_balance(&self, ata: &Pubkey) -> Result<f64> {
+    pub async fn get_token_balance(&self, ata: &Pubkey) -> Result<f64, OpenBookError> {
         let r = self
             .rpc_client
             .inner()
@@ -1267,7 +2709,7 @@ impl OBClient {
         Ok(r.ui_amount.unwrap())
     }
 
-    pub async fn get_base_quote_total(&self) -> Result<AtaBalances> {
+    pub async fn get_base_quote_total(&self) -> Result<AtaBalances, OpenBookError> {
         let base_ata = self.base_ata.clone();
         let quote_ata = self.quote_ata.clone();
         let base_balance = self.get_token_balance(&base_ata).await?;
@@ -1294,6 +2736,143 @@ impl OBClient {
     }
 }
 
+#[async_trait]
+impl OrderPlacer for OBClient {
+    /// Sizes the order like the v1 client, see `MarketInfo::base_lots_from_quote`, and places it
+    /// post-only like `place_limit_order`. The program's order id isn't read back, so the handle
+    /// only carries the client order id.
+    async fn place_limit(
+        &self,
+        side: OrderSide,
+        price: f64,
+        size: f64,
+    ) -> Result<OrderHandle, OpenBookError> {
+        let price_lots = self.checked_price_lots(price)?;
+        let max_base_lots = self.market_info.base_lots_from_quote(size, price);
+        if max_base_lots <= 0 {
+            return Err(OpenBookError::OrderTooSmall(size));
+        }
+        let native_quote = size * 10f64.powi(self.market_info.quote_decimals as i32);
+        let max_quote_lots = self
+            .context
+            .max_quote_lots_including_maker_fees(native_quote as u64);
+
+        let (confirmed, signature, client_order_id, _) = self
+            .place_limit_order_lots(
+                price_lots,
+                max_base_lots as u64,
+                max_quote_lots,
+                side.into(),
+            )
+            .await?;
+
+        Ok(OrderHandle {
+            signature: confirmed_signature((confirmed, signature))?,
+            order_id: None,
+            client_order_id,
+        })
+    }
+
+    async fn cancel_all(&self) -> Result<Option<Signature>, OpenBookError> {
+        Ok(Some(confirmed_signature(self.cancel_all_orders().await?)?))
+    }
+
+    async fn settle(&self) -> Result<Signature, OpenBookError> {
+        confirmed_signature(self.settle_funds().await?)
+    }
+
+    async fn mid_price(&self) -> Result<f64, OpenBookError> {
+        self.get_mid_price().await
+    }
+}
+
+/// Checks that an open orders account can be closed: no base lots on the book, no free balance
+/// left to settle and no locked maker fees or referrer rebates.
+///
+/// # Errors
+///
+/// Returns an error describing what the account still holds.
+pub fn ensure_open_orders_account_empty(account: &OpenOrdersAccount) -> Result<()> {
+    let position = &account.position;
+    if position.bids_base_lots != 0 || position.asks_base_lots != 0 {
+        anyhow::bail!(
+            "The open orders account still has {} base lots bid and {} asked, cancel them first",
+            position.bids_base_lots,
+            position.asks_base_lots
+        );
+    }
+    if position.base_free_native != 0 || position.quote_free_native != 0 {
+        anyhow::bail!(
+            "The open orders account still holds {} base and {} quote, settle them first",
+            position.base_free_native,
+            position.quote_free_native
+        );
+    }
+    if position.locked_maker_fees != 0 || position.referrer_rebates_available != 0 {
+        anyhow::bail!(
+            "The open orders account still holds {} locked maker fees and {} referrer rebates",
+            position.locked_maker_fees,
+            position.referrer_rebates_available
+        );
+    }
+    Ok(())
+}
+
+/// Decodes the order ids returned by the `PlaceOrders` and `CancelAllAndPlaceOrders`
+/// instructions, a borsh-encoded `Vec<Option<u128>>`.
+///
+/// The runtime strips the trailing zero bytes of the return data, so missing bytes are read as
+/// zeros. The stripped bytes can hold any number of trailing `None` entries, so the length prefix
+/// can't be checked against the bytes received; instead, decoding stops at the entries the
+/// unstripped return data, at most `MAX_RETURN_DATA` bytes long, can hold.
+///
+/// # Arguments
+///
+/// * `return_data` - The return data of the transaction, base64-decoded.
+///
+/// # Returns
+///
+/// The order ids, in the order the orders were passed to the instruction.
+///
+/// # Examples
+///
+/// ```rust , ignore
+/// use openbook::v2::ob_client::decode_placed_order_ids;
+///
+/// let mut data = vec![2, 0, 0, 0, 1];
+/// data.extend_from_slice(&42u128.to_le_bytes());
+///
+/// assert_eq!(decode_placed_order_ids(&data), vec![Some(42), None]);
+/// ```
+pub fn decode_placed_order_ids(return_data: &[u8]) -> Vec<Option<u128>> {
+    let byte_at = |index: usize| return_data.get(index).copied().unwrap_or(0);
+    let mut len = [0u8; 4];
+    for (i, byte) in len.iter_mut().enumerate() {
+        *byte = byte_at(i);
+    }
+
+    let mut offset = len.len();
+    (0..u32::from_le_bytes(len))
+        .map_while(|_| {
+            // No entry starts past the end of the unstripped return data.
+            if offset >= MAX_RETURN_DATA {
+                return None;
+            }
+            let tag = byte_at(offset);
+            offset += 1;
+            if tag == 0 {
+                return Some(None);
+            }
+            let mut id = [0u8; 16];
+            for (i, byte) in id.iter_mut().enumerate() {
+                *byte = byte_at(offset + i);
+            }
+            offset += id.len();
+            Some(Some(u128::from_le_bytes(id)))
+        })
+        .collect()
+}
+
 pub async fn get_base_price(quote_mint: &str) -> Result<f64> {
     let base_url = "https://price.jup.ag/v4/price?ids=";
     let url = format!("{base_url}{quote_mint}");
@@ -0,0 +1,62 @@
+#![cfg(feature = "cli")]
+
+use clap::Parser;
+use openbook::cli::{Cli, Commands, OutputFormat, TxReport, V1ActionsCommands};
+use openbook::signature::Signature;
+
+#[test]
+fn test_output_flag_is_global() {
+    let args = Cli::try_parse_from(["openbook", "v1", "info"]).unwrap();
+    assert_eq!(args.output, OutputFormat::Text);
+
+    let args = Cli::try_parse_from(["openbook", "--output", "json", "v1", "info"]).unwrap();
+    assert_eq!(args.output, OutputFormat::Json);
+
+    let args = Cli::try_parse_from(["openbook", "v2", "info", "--output", "json"]).unwrap();
+    assert_eq!(args.output, OutputFormat::Json);
+    assert!(matches!(args.command, Some(Commands::V2(_))));
+
+    assert!(Cli::try_parse_from(["openbook", "--output", "yaml", "v1", "info"]).is_err());
+}
+
+#[test]
+fn test_tx_report_to_json() {
+    let signature = Signature::new_unique();
+    let order_id = u128::MAX - 1;
+
+    let value: serde_json::Value =
+        serde_json::from_str(&TxReport::new(&signature, Some(order_id), Some(42)).to_json())
+            .unwrap();
+    assert_eq!(value["signature"], signature.to_string());
+    assert_eq!(value["order_id"], order_id.to_string());
+    assert_eq!(value["slot"], 42);
+
+    let value: serde_json::Value =
+        serde_json::from_str(&TxReport::new(&signature, None, None).to_json()).unwrap();
+    assert!(value["order_id"].is_null());
+    assert!(value["slot"].is_null());
+}
+
+#[test]
+fn test_export_tx_flag() {
+    let export_tx = |args: &[&str]| match Cli::try_parse_from(args).unwrap().command {
+        Some(Commands::V1(v1)) => match v1.command {
+            Some(V1ActionsCommands::Settle(settle)) => settle.export_tx,
+            _ => panic!("expected the settle command"),
+        },
+        _ => panic!("expected the v1 command"),
+    };
+
+    assert_eq!(export_tx(&["openbook", "v1", "settle"]), None);
+    assert_eq!(
+        export_tx(&["openbook", "v1", "settle", "--export-tx"]),
+        Some("-".to_string())
+    );
+    assert_eq!(
+        export_tx(&["openbook", "v1", "settle", "--export-tx", "settle.b64"]),
+        Some("settle.b64".to_string())
+    );
+
+    // Exporting the unsigned transaction and sending it are exclusive.
+    assert!(Cli::try_parse_from(["openbook", "v1", "settle", "-e", "--export-tx"]).is_err());
+}
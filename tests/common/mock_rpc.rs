@@ -0,0 +1,328 @@
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use openbook::account::Account;
+use openbook::commitment_config::CommitmentConfig;
+use openbook::pubkey::Pubkey;
+use openbook::rpc::RpcProvider;
+use openbook::rpc_config::RpcAccountInfoConfig;
+use openbook::signature::Signature;
+use solana_client::client_error::ClientError;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::{
+    RpcProgramAccountsConfig, RpcSendTransactionConfig, RpcSimulateTransactionConfig,
+    RpcTransactionConfig,
+};
+use solana_client::rpc_filter::RpcFilterType;
+use solana_client::rpc_request::RpcError;
+use solana_client::rpc_response::{
+    Response, RpcConfirmedTransactionStatusWithSignature, RpcPrioritizationFee, RpcResponseContext,
+    RpcSimulateTransactionResult,
+};
+use solana_sdk::hash::Hash;
+use solana_sdk::rent::Rent;
+use solana_sdk::transaction::{Transaction, TransactionError};
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction,
+    EncodedTransactionWithStatusMeta, TransactionBinaryEncoding,
+};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Account writes of a sent transaction, as `(pubkey, owner, data)`.
+type SendEffect = Arc<dyn Fn(&Transaction) -> Vec<(Pubkey, Pubkey, Vec<u8>)> + Send + Sync>;
+
+/// `RpcProvider` answering from canned accounts, for tests that must not hit the network.
+///
+/// Clones share their state, so a test can keep a clone to inspect the requests made through
+/// the `Rpc` built with `Rpc::with_provider`. Sent transactions are recorded, confirmed right
+/// away and returned by `getTransaction` in slot 1, unless they are dropped. Blockhashes expire
+/// right away, so a dropped transaction is known to be dropped without waiting.
+#[derive(Clone, Default)]
+pub struct MockRpc {
+    accounts: Arc<Mutex<HashMap<Pubkey, Account>>>,
+    sent: Arc<Mutex<Vec<Transaction>>>,
+    send_effect: Option<SendEffect>,
+    dropped: Arc<Mutex<HashSet<Signature>>>,
+    transactions: Arc<Mutex<HashMap<Signature, EncodedConfirmedTransactionWithStatusMeta>>>,
+    requests: Arc<AtomicUsize>,
+    /// The recent prioritization fees returned by the node.
+    pub prioritization_fees: Vec<u64>,
+    /// The compute units reported by simulations; `None` makes simulations fail.
+    pub units_consumed: Option<u64>,
+    /// The number of transactions sent first that never land.
+    pub dropped_sends: usize,
+}
+
+impl MockRpc {
+    /// Returns a mock holding no account.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an account holding `data`, owned by `owner`.
+    pub fn with_account(self, pubkey: Pubkey, owner: Pubkey, data: Vec<u8>) -> Self {
+        self.accounts.lock().unwrap().insert(
+            pubkey,
+            Account {
+                lamports: 1_000_000,
+                data,
+                owner,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+        self
+    }
+
+    /// Adds a confirmed transaction, returned by `getTransaction` for `signature`.
+    pub fn with_transaction(
+        self,
+        signature: Signature,
+        transaction: EncodedConfirmedTransactionWithStatusMeta,
+    ) -> Self {
+        self.transactions
+            .lock()
+            .unwrap()
+            .insert(signature, transaction);
+        self
+    }
+
+    /// Writes the accounts returned by `effect` whenever a transaction is sent, standing in for
+    /// the program executing it.
+    pub fn with_send_effect(
+        mut self,
+        effect: impl Fn(&Transaction) -> Vec<(Pubkey, Pubkey, Vec<u8>)> + Send + Sync + 'static,
+    ) -> Self {
+        self.send_effect = Some(Arc::new(effect));
+        self
+    }
+
+    /// Returns the number of requests answered so far.
+    pub fn requests(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+
+    /// Returns the transactions sent so far.
+    pub fn sent(&self) -> Vec<Transaction> {
+        self.sent.lock().unwrap().clone()
+    }
+
+    fn is_dropped(&self, signature: &Signature) -> bool {
+        self.dropped.lock().unwrap().contains(signature)
+    }
+
+    fn record_request(&self) {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn response<T>(value: T) -> Response<T> {
+        Response {
+            context: RpcResponseContext {
+                slot: 1,
+                api_version: None,
+            },
+            value,
+        }
+    }
+}
+
+#[async_trait]
+impl RpcProvider for MockRpc {
+    fn commitment(&self) -> CommitmentConfig {
+        CommitmentConfig::confirmed()
+    }
+
+    fn url(&self) -> String {
+        "http://127.0.0.1:1".to_string()
+    }
+
+    async fn get_multiple_accounts_with_config(
+        &self,
+        pubkeys: &[Pubkey],
+        _config: RpcAccountInfoConfig,
+    ) -> Result<Response<Vec<Option<Account>>>, ClientError> {
+        self.record_request();
+        let accounts = self.accounts.lock().unwrap();
+        Ok(Self::response(
+            pubkeys
+                .iter()
+                .map(|key| accounts.get(key).cloned())
+                .collect(),
+        ))
+    }
+
+    async fn get_latest_blockhash_with_commitment(
+        &self,
+        _commitment: CommitmentConfig,
+    ) -> Result<(Hash, u64), ClientError> {
+        self.record_request();
+        Ok((Hash::new_unique(), 100))
+    }
+
+    async fn get_recent_prioritization_fees(
+        &self,
+        _addresses: &[Pubkey],
+    ) -> Result<Vec<RpcPrioritizationFee>, ClientError> {
+        self.record_request();
+        Ok(self
+            .prioritization_fees
+            .iter()
+            .map(|&prioritization_fee| RpcPrioritizationFee {
+                slot: 1,
+                prioritization_fee,
+            })
+            .collect())
+    }
+
+    async fn send_transaction_with_config(
+        &self,
+        transaction: &Transaction,
+        _config: RpcSendTransactionConfig,
+    ) -> Result<Signature, ClientError> {
+        self.record_request();
+        let mut sent = self.sent.lock().unwrap();
+        sent.push(transaction.clone());
+        if sent.len() <= self.dropped_sends {
+            self.dropped
+                .lock()
+                .unwrap()
+                .insert(transaction.signatures[0]);
+            return Ok(transaction.signatures[0]);
+        }
+        if let Some(effect) = &self.send_effect {
+            for (pubkey, owner, data) in effect(transaction) {
+                self.accounts.lock().unwrap().insert(
+                    pubkey,
+                    Account {
+                        lamports: 1_000_000,
+                        data,
+                        owner,
+                        executable: false,
+                        rent_epoch: 0,
+                    },
+                );
+            }
+        }
+        Ok(transaction.signatures[0])
+    }
+
+    async fn confirm_transaction(&self, signature: &Signature) -> Result<bool, ClientError> {
+        self.record_request();
+        Ok(!self.is_dropped(signature))
+    }
+
+    async fn get_signature_status(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<Result<(), TransactionError>>, ClientError> {
+        self.record_request();
+        let landed = !self.is_dropped(signature)
+            && self
+                .sent
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|transaction| transaction.signatures.first() == Some(signature));
+        Ok(landed.then_some(Ok(())))
+    }
+
+    async fn is_blockhash_valid(
+        &self,
+        _blockhash: &Hash,
+        _commitment: CommitmentConfig,
+    ) -> Result<bool, ClientError> {
+        self.record_request();
+        Ok(false)
+    }
+
+    async fn simulate_transaction_with_config(
+        &self,
+        _transaction: &Transaction,
+        _config: RpcSimulateTransactionConfig,
+    ) -> Result<Response<RpcSimulateTransactionResult>, ClientError> {
+        self.record_request();
+        let err = self
+            .units_consumed
+            .is_none()
+            .then_some(TransactionError::AccountNotFound);
+        Ok(Self::response(RpcSimulateTransactionResult {
+            err,
+            logs: Some(vec![]),
+            accounts: None,
+            units_consumed: self.units_consumed,
+            return_data: None,
+        }))
+    }
+
+    async fn get_transaction_with_config(
+        &self,
+        signature: &Signature,
+        _config: RpcTransactionConfig,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, ClientError> {
+        self.record_request();
+        if let Some(transaction) = self.transactions.lock().unwrap().get(signature) {
+            return Ok(transaction.clone());
+        }
+        let sent = self.sent.lock().unwrap();
+        let transaction = sent
+            .iter()
+            .filter(|_| !self.is_dropped(signature))
+            .find(|transaction| transaction.signatures.first() == Some(signature))
+            .ok_or_else(|| RpcError::ForUser(format!("Transaction {} not found", signature)))?;
+
+        Ok(EncodedConfirmedTransactionWithStatusMeta {
+            slot: 1,
+            transaction: EncodedTransactionWithStatusMeta {
+                transaction: EncodedTransaction::Binary(
+                    STANDARD.encode(bincode::serialize(transaction).unwrap()),
+                    TransactionBinaryEncoding::Base64,
+                ),
+                meta: None,
+                version: None,
+            },
+            block_time: None,
+        })
+    }
+
+    async fn get_signatures_for_address_with_config(
+        &self,
+        _address: &Pubkey,
+        _config: GetConfirmedSignaturesForAddress2Config,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, ClientError> {
+        self.record_request();
+        Ok(vec![])
+    }
+
+    async fn get_program_accounts_with_config(
+        &self,
+        program: &Pubkey,
+        config: RpcProgramAccountsConfig,
+    ) -> Result<Vec<(Pubkey, Account)>, ClientError> {
+        self.record_request();
+        let filters = config.filters.unwrap_or_default();
+        Ok(self
+            .accounts
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, account)| account.owner == *program)
+            .filter(|(_, account)| {
+                filters.iter().all(|filter| match filter {
+                    RpcFilterType::DataSize(size) => account.data.len() as u64 == *size,
+                    RpcFilterType::Memcmp(memcmp) => memcmp.bytes_match(&account.data),
+                    _ => true,
+                })
+            })
+            .map(|(key, account)| (*key, account.clone()))
+            .collect())
+    }
+
+    async fn get_minimum_balance_for_rent_exemption(
+        &self,
+        data_len: usize,
+    ) -> Result<u64, ClientError> {
+        self.record_request();
+        Ok(Rent::default().minimum_balance(data_len))
+    }
+}
@@ -0,0 +1,144 @@
+#![allow(dead_code)]
+
+pub mod mock_rpc;
+
+pub use mock_rpc::MockRpc;
+
+use openbook::keypair::Keypair;
+use openbook::pubkey::Pubkey;
+use openbook::rpc::Rpc;
+use openbook::rpc_client::RpcClient;
+use openbook::v1::events::EVENT_SIZE;
+use openbook::v1::market::Market;
+use openbook::v1::ob_client::{
+    OBClient, ReadOnlyClient, DEFAULT_COMPUTE_UNIT_MARGIN_PERCENT,
+    DEFAULT_MAX_PRICE_DEVIATION_PERCENT,
+};
+use openbook::v1::orders::{OpenOrders, OPEN_ORDERS_ACCOUNT_SIZE};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub const MARKET_ID: &str = "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6";
+
+/// Builds a read-only client around an unreachable RPC endpoint, for tests that must not hit
+/// the network.
+///
+/// With 9 base decimals, 6 quote decimals and a base lot size of 1_000_000, one unit of UI
+/// price equals 1_000 price lots.
+pub fn offline_reader(max_bid: u64, min_ask: u64) -> ReadOnlyClient {
+    ReadOnlyClient {
+        rpc_client: Rpc::new(RpcClient::new("http://127.0.0.1:1".to_string())),
+        market_info: Market {
+            market_address: MARKET_ID.parse().unwrap(),
+            coin_decimals: 9,
+            pc_decimals: 6,
+            coin_lot_size: 1_000_000,
+            pc_lot_size: 1,
+            ..Default::default()
+        },
+        open_orders: OpenOrders {
+            max_bid,
+            min_ask,
+            ..Default::default()
+        },
+    }
+}
+
+/// Builds a trading client around `offline_reader` with a throwaway keypair, whose open orders
+/// account exists.
+pub fn offline_client(max_bid: u64, min_ask: u64) -> OBClient {
+    let mut reader = offline_reader(max_bid, min_ask);
+    reader.open_orders.exists = true;
+
+    OBClient {
+        owner: Arc::new(Keypair::new()),
+        reader,
+        quote_ata: Default::default(),
+        base_ata: Default::default(),
+        open_orders_cache: HashMap::new(),
+        last_quote_mid: None,
+        compute_unit_margin_percent: DEFAULT_COMPUTE_UNIT_MARGIN_PERCENT,
+        unwrap_sol: false,
+        wrap_sol: false,
+        round_to_tick: false,
+        referrer: None,
+        max_price_deviation_percent: DEFAULT_MAX_PRICE_DEVIATION_PERCENT,
+    }
+}
+
+/// Order stored in a slot of a recorded open orders account.
+pub struct RecordedOrder {
+    pub slot: u8,
+    pub order_id: u128,
+    pub client_order_id: u64,
+    pub is_bid: bool,
+}
+
+/// Encodes the data of an open orders account the way the program lays it out on chain.
+pub fn open_orders_account_data(
+    market: &Pubkey,
+    owner: &Pubkey,
+    (coin_free, coin_total): (u64, u64),
+    (pc_free, pc_total): (u64, u64),
+    orders: &[RecordedOrder],
+) -> Vec<u8> {
+    let mut data = vec![0u8; OPEN_ORDERS_ACCOUNT_SIZE];
+    let mut free_slot_bits = u128::MAX;
+    let mut is_bid_bits = 0u128;
+    for order in orders {
+        let slot = order.slot as usize;
+        free_slot_bits &= !(1u128 << slot);
+        if order.is_bid {
+            is_bid_bits |= 1u128 << slot;
+        }
+        data[141 + slot * 16..157 + slot * 16].copy_from_slice(&order.order_id.to_le_bytes());
+        data[2189 + slot * 8..2197 + slot * 8]
+            .copy_from_slice(&order.client_order_id.to_le_bytes());
+    }
+
+    data[..5].copy_from_slice(b"serum");
+    // The Initialized and OpenOrders account flags.
+    data[5..13].copy_from_slice(&5u64.to_le_bytes());
+    data[13..45].copy_from_slice(market.as_ref());
+    data[45..77].copy_from_slice(owner.as_ref());
+    data[77..85].copy_from_slice(&coin_free.to_le_bytes());
+    data[85..93].copy_from_slice(&coin_total.to_le_bytes());
+    data[93..101].copy_from_slice(&pc_free.to_le_bytes());
+    data[101..109].copy_from_slice(&pc_total.to_le_bytes());
+    data[109..125].copy_from_slice(&free_slot_bits.to_le_bytes());
+    data[125..141].copy_from_slice(&is_bid_bits.to_le_bytes());
+    data[OPEN_ORDERS_ACCOUNT_SIZE - 7..].copy_from_slice(b"padding");
+    data
+}
+
+/// Encodes the data of an SPL token account holding `amount`.
+pub fn token_account_data(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Vec<u8> {
+    let mut data = vec![0u8; 165];
+    data[..32].copy_from_slice(mint.as_ref());
+    data[32..64].copy_from_slice(owner.as_ref());
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    data
+}
+
+/// Encodes an event of the event queue with `flags`, for the order `order_id` of `owner`.
+pub fn event_data(flags: u8, order_id: u128, owner: &Pubkey) -> Vec<u8> {
+    let mut data = vec![0u8; EVENT_SIZE];
+    data[0] = flags;
+    data[32..48].copy_from_slice(&order_id.to_le_bytes());
+    data[48..80].copy_from_slice(owner.as_ref());
+    data
+}
+
+/// Builds event queue account data with `slots` written in the ring buffer.
+pub fn event_queue_data(head: u64, count: u64, seq_num: u64, slots: &[Vec<u8>]) -> Vec<u8> {
+    let mut data = b"serum".to_vec();
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.extend_from_slice(&head.to_le_bytes());
+    data.extend_from_slice(&count.to_le_bytes());
+    data.extend_from_slice(&seq_num.to_le_bytes());
+    for slot in slots {
+        data.extend_from_slice(slot);
+    }
+    data.extend_from_slice(b"padding");
+    data
+}
@@ -0,0 +1,76 @@
+use anyhow::anyhow;
+use openbook::error::OpenBookError;
+use openbook::pubkey::Pubkey;
+use solana_client::client_error::ClientError;
+use solana_client::rpc_request::{RpcError, RpcResponseErrorData};
+use solana_client::rpc_response::RpcSimulateTransactionResult;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::program_error::ProgramError;
+use solana_sdk::transaction::TransactionError;
+
+#[test]
+fn test_from_anyhow_keeps_typed_errors() {
+    let market = Pubkey::new_unique();
+
+    let err = OpenBookError::from(anyhow::Error::from(OpenBookError::MarketNotFound(market)));
+    assert!(matches!(err, OpenBookError::MarketNotFound(address) if address == market));
+
+    let err = OpenBookError::from(anyhow::Error::from(ProgramError::InvalidAccountData));
+    assert!(matches!(err, OpenBookError::AccountDeserialize(_)));
+}
+
+#[test]
+fn test_from_anyhow_falls_back_to_other() {
+    let err = OpenBookError::from(anyhow!("Something went wrong"));
+
+    assert!(matches!(err, OpenBookError::Other(_)));
+    assert_eq!(err.to_string(), "Something went wrong");
+}
+
+fn preflight_failure(err: TransactionError, logs: &[&str]) -> ClientError {
+    ClientError::from(RpcError::RpcResponseError {
+        code: -32002,
+        message: "Transaction simulation failed".to_string(),
+        data: RpcResponseErrorData::SendTransactionPreflightFailure(RpcSimulateTransactionResult {
+            err: Some(err),
+            logs: Some(logs.iter().map(|log| log.to_string()).collect()),
+            accounts: None,
+            units_consumed: None,
+            return_data: None,
+        }),
+    })
+}
+
+#[test]
+fn test_from_client_error_maps_insufficient_funds() {
+    let token_transfer = TransactionError::InstructionError(2, InstructionError::Custom(1));
+    let err = OpenBookError::from(preflight_failure(
+        token_transfer.clone(),
+        &[
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program log: Instruction: Transfer",
+            "Program log: Error: insufficient funds",
+        ],
+    ));
+    assert!(matches!(err, OpenBookError::InsufficientBalance(ref err) if *err == token_transfer));
+
+    let err = OpenBookError::from(preflight_failure(
+        TransactionError::InsufficientFundsForFee,
+        &[],
+    ));
+    assert!(matches!(err, OpenBookError::InsufficientBalance(_)));
+
+    // The custom error 0x1 of another program is something else.
+    let err = OpenBookError::from(preflight_failure(
+        token_transfer,
+        &["Program log: Error: not enough base lots"],
+    ));
+    assert!(matches!(err, OpenBookError::RpcError(_)));
+
+    // Through anyhow too.
+    let err = OpenBookError::from(anyhow::Error::from(preflight_failure(
+        TransactionError::InsufficientFundsForFee,
+        &[],
+    )));
+    assert!(matches!(err, OpenBookError::InsufficientBalance(_)));
+}
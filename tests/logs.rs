@@ -0,0 +1,90 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use openbook::logs::{event_discriminator, parse_program_logs, ProgramEvent};
+use openbook::pubkey::Pubkey;
+
+const V2_PROGRAM_ID: &str = "opnb2LAfJYbRMAHHvqjCwQxanZn7ReEHp1k81EohpZb";
+const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+fn recorded_logs(fill_data: &str) -> Vec<String> {
+    [
+        "Program ComputeBudget111111111111111111111111111111 invoke [1]".to_string(),
+        "Program ComputeBudget111111111111111111111111111111 success".to_string(),
+        format!("Program {} invoke [1]", V2_PROGRAM_ID),
+        "Program log: Instruction: CancelAllAndPlaceOrders".to_string(),
+        format!("Program data: {}", fill_data),
+        format!("Program {} invoke [2]", TOKEN_PROGRAM_ID),
+        "Program log: Instruction: Transfer".to_string(),
+        format!(
+            "Program {} consumed 4645 of 1365887 compute units",
+            TOKEN_PROGRAM_ID
+        ),
+        format!("Program {} success", TOKEN_PROGRAM_ID),
+        format!(
+            "Program {} consumed 36402 of 1399700 compute units",
+            V2_PROGRAM_ID
+        ),
+        format!("Program {} success", V2_PROGRAM_ID),
+        format!("Program {} invoke [1]", V2_PROGRAM_ID),
+        "Program log: Instruction: CancelOrderByClientOrderId".to_string(),
+        format!(
+            "Program {} failed: custom program error: 0x1789",
+            V2_PROGRAM_ID
+        ),
+    ]
+    .to_vec()
+}
+
+#[test]
+fn test_parse_program_logs() {
+    let program_id: Pubkey = V2_PROGRAM_ID.parse().unwrap();
+    let market = Pubkey::new_unique();
+    let mut fill = event_discriminator("FillLog").to_vec();
+    fill.extend_from_slice(market.as_ref());
+    fill.extend_from_slice(&[1, 0, 1]);
+
+    let events = parse_program_logs(&recorded_logs(&STANDARD.encode(&fill)));
+
+    assert_eq!(
+        events,
+        vec![
+            ProgramEvent::OrderCanceled {
+                program_id,
+                instruction: "CancelAllAndPlaceOrders".to_string(),
+            },
+            ProgramEvent::OrderPlaced {
+                program_id,
+                instruction: "CancelAllAndPlaceOrders".to_string(),
+            },
+            ProgramEvent::OrderFilled {
+                program_id,
+                market,
+                data: fill[8..].to_vec(),
+            },
+            ProgramEvent::OrderCanceled {
+                program_id,
+                instruction: "CancelOrderByClientOrderId".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_parse_program_logs_skips_unknown_events() {
+    let mut other = event_discriminator("TotalOrderFillEvent").to_vec();
+    other.extend_from_slice(&[0u8; 40]);
+
+    let events = parse_program_logs(&recorded_logs(&STANDARD.encode(other)));
+    assert_eq!(events.len(), 3);
+    assert!(events
+        .iter()
+        .all(|event| !matches!(event, ProgramEvent::OrderFilled { .. })));
+
+    // Garbage data and lines outside any invocation are ignored.
+    let events = parse_program_logs(&[
+        "Program log: Instruction: PlaceOrder".to_string(),
+        format!("Program {} invoke [1]", V2_PROGRAM_ID),
+        "Program data: not base64!".to_string(),
+        "Log truncated".to_string(),
+    ]);
+    assert!(events.is_empty());
+}
@@ -0,0 +1,80 @@
+use openbook::metrics::{CounterMetrics, Metrics, MetricsCounts, PrometheusMetrics};
+use std::time::Duration;
+
+#[test]
+fn test_prometheus_metrics_render() {
+    let metrics = PrometheusMetrics::new();
+    metrics.on_orders_placed(2);
+    metrics.on_orders_placed(1);
+    metrics.on_orders_canceled(4);
+    metrics.on_settled();
+    metrics.on_tx_sent(true);
+    metrics.on_tx_sent(false);
+    metrics.on_retry("sendTransaction");
+    metrics.on_rpc_call("getMultipleAccounts", Duration::from_millis(30));
+    metrics.on_rpc_call("getMultipleAccounts", Duration::from_millis(300));
+    metrics.on_rpc_call("sendTransaction", Duration::from_secs(20));
+
+    let text = metrics.render();
+
+    for line in [
+        "# TYPE openbook_orders_placed_total counter",
+        "openbook_orders_placed_total 3",
+        "openbook_orders_canceled_total 4",
+        "openbook_settles_total 1",
+        "openbook_transactions_confirmed_total 1",
+        "openbook_transactions_failed_total 1",
+        "openbook_rpc_retries_total 1",
+        "# TYPE openbook_rpc_latency_seconds histogram",
+        "openbook_rpc_latency_seconds_bucket{method=\"getMultipleAccounts\",le=\"0.025\"} 0",
+        "openbook_rpc_latency_seconds_bucket{method=\"getMultipleAccounts\",le=\"0.05\"} 1",
+        "openbook_rpc_latency_seconds_bucket{method=\"getMultipleAccounts\",le=\"0.5\"} 2",
+        "openbook_rpc_latency_seconds_bucket{method=\"getMultipleAccounts\",le=\"+Inf\"} 2",
+        "openbook_rpc_latency_seconds_count{method=\"getMultipleAccounts\"} 2",
+        "openbook_rpc_latency_seconds_bucket{method=\"sendTransaction\",le=\"10\"} 0",
+        "openbook_rpc_latency_seconds_bucket{method=\"sendTransaction\",le=\"+Inf\"} 1",
+        "openbook_rpc_latency_seconds_count{method=\"sendTransaction\"} 1",
+    ] {
+        assert!(
+            text.lines().any(|l| l == line),
+            "missing {:?} in:\n{}",
+            line,
+            text
+        );
+    }
+}
+
+#[test]
+fn test_prometheus_metrics_render_empty() {
+    let text = PrometheusMetrics::new().render();
+
+    assert!(text.contains("openbook_orders_placed_total 0"));
+    assert!(!text.contains("openbook_rpc_latency_seconds_count"));
+}
+
+#[test]
+fn test_counter_metrics() {
+    let metrics = CounterMetrics::new();
+    metrics.on_rpc_call("getMultipleAccounts", Duration::from_millis(30));
+    metrics.on_rpc_call("sendTransaction", Duration::from_millis(70));
+    metrics.on_retry("sendTransaction");
+    metrics.on_tx_sent(true);
+    metrics.on_tx_sent(false);
+    metrics.on_tx_sent(true);
+    metrics.on_orders_placed(2);
+    metrics.on_settled();
+
+    assert_eq!(
+        metrics.counts(),
+        MetricsCounts {
+            rpc_calls: 2,
+            rpc_latency: Duration::from_millis(100),
+            retries: 1,
+            tx_confirmed: 2,
+            tx_failed: 1,
+            orders_placed: 2,
+            orders_canceled: 0,
+            settles: 1,
+        }
+    );
+}
@@ -0,0 +1,116 @@
+use openbook::orderbook::{scale_bars, BestQuotes, FillEstimate, OrderBook};
+
+#[test]
+fn test_aggregate_levels_sums_same_price() {
+    let bids = vec![
+        (2.0, 1.0),
+        (2.0, 0.5),
+        (1.99, 3.0),
+        (1.98, 1.0),
+        (1.98, 1.0),
+    ];
+
+    assert_eq!(
+        OrderBook::aggregate_levels(bids, 10),
+        vec![(2.0, 1.5), (1.99, 3.0), (1.98, 2.0)]
+    );
+}
+
+#[test]
+fn test_aggregate_levels_caps_depth() {
+    let asks = vec![(2.01, 1.0), (2.02, 1.0), (2.02, 4.0), (2.03, 1.0)];
+
+    assert_eq!(
+        OrderBook::aggregate_levels(asks.clone(), 2),
+        vec![(2.01, 1.0), (2.02, 5.0)]
+    );
+    assert!(OrderBook::aggregate_levels(asks, 0).is_empty());
+}
+
+#[test]
+fn test_best_levels() {
+    let book = OrderBook {
+        bids: vec![(1.99, 2.0), (1.98, 1.0)],
+        asks: vec![(2.01, 3.0)],
+    };
+
+    assert_eq!(book.best_bid(), Some((1.99, 2.0)));
+    assert_eq!(book.best_ask(), Some((2.01, 3.0)));
+    assert_eq!(OrderBook::default().best_bid(), None);
+}
+
+#[test]
+fn test_scale_bars_fits_width() {
+    let sizes = [0.5, 2.0, 10.0, 0.0, 5.0];
+
+    assert_eq!(scale_bars(&sizes, 20), vec![1, 4, 20, 0, 10]);
+    // A narrow terminal keeps every positive level visible.
+    assert_eq!(scale_bars(&sizes, 4), vec![1, 1, 4, 0, 2]);
+    assert_eq!(scale_bars(&sizes, 0), vec![0; 5]);
+    assert_eq!(scale_bars(&[0.0, 0.0], 10), vec![0, 0]);
+    assert!(scale_bars(&[], 10).is_empty());
+}
+
+#[test]
+fn test_depth_chart_is_cumulative() {
+    let book = OrderBook {
+        bids: vec![(1.99, 1.0), (1.98, 3.0), (1.97, 4.0)],
+        asks: vec![(2.01, 2.0), (2.02, 2.0)],
+    };
+
+    let chart = book.depth_chart(2, 40);
+    let lines: Vec<&str> = chart.lines().collect();
+
+    assert_eq!(lines.len(), 5);
+    assert!(lines[0]
+        .trim_start()
+        .starts_with("2.020000 | -------- 4.0000"));
+    assert!(lines[1].trim_start().starts_with("2.010000 | ---- 2.0000"));
+    assert!(lines[2].chars().all(|c| c == '-'));
+    assert!(lines[3].trim_start().starts_with("1.990000 | ++ 1.0000"));
+    assert!(lines[4]
+        .trim_start()
+        .starts_with("1.980000 | ++++++++ 4.0000"));
+}
+
+#[test]
+fn test_best_quotes_one_sided_book() {
+    let book = OrderBook {
+        bids: vec![],
+        asks: vec![(2.01, 1.0), (2.02, 3.0)],
+    };
+
+    let quotes = book.best_quotes();
+    assert_eq!(
+        quotes,
+        BestQuotes {
+            highest_bid: None,
+            lowest_ask: Some(2.01),
+        }
+    );
+    assert_eq!(quotes.mid(), None);
+    assert_eq!(OrderBook::default().best_quotes(), BestQuotes::default());
+}
+
+#[test]
+fn test_fill_estimate_from_levels() {
+    let bids = [(2.0, 1.0), (1.9, 2.0), (1.8, 5.0)];
+
+    let estimate = FillEstimate::from_levels(&bids, 2.0);
+    assert!((estimate.avg_price - 1.95).abs() < 1e-9);
+    assert_eq!(estimate.worst_price, 1.9);
+    assert_eq!(estimate.filled_qty, 2.0);
+    assert!(!estimate.insufficient_liquidity);
+
+    // The book is too thin: the partial fill is reported.
+    let estimate = FillEstimate::from_levels(&bids, 10.0);
+    assert!((estimate.avg_price - 14.8 / 8.0).abs() < 1e-9);
+    assert_eq!(estimate.worst_price, 1.8);
+    assert_eq!(estimate.filled_qty, 8.0);
+    assert!(estimate.insufficient_liquidity);
+
+    let estimate = FillEstimate::from_levels(&[], 1.0);
+    assert_eq!(estimate.filled_qty, 0.0);
+    assert_eq!(estimate.avg_price, 0.0);
+    assert!(estimate.insufficient_liquidity);
+}
@@ -0,0 +1,86 @@
+use openbook::pubkey::Pubkey;
+use openbook::registry::MarketRegistry;
+
+const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+const JLP: &str = "27G8MtK7VtTcCHkpASjSDdkWWYfoqT6ggEuKidVJidD4";
+const SOL: &str = "So11111111111111111111111111111111111111112";
+
+fn entry(symbol: &str, base_mint: &str, quote_mint: &str, market_address: &Pubkey) -> String {
+    format!(
+        r#"{{"symbol": "{}", "base_mint": "{}", "quote_mint": "{}", "market_address": "{}"}}"#,
+        symbol, base_mint, quote_mint, market_address
+    )
+}
+
+fn registry_json(entries: &[String]) -> String {
+    format!("[{}]", entries.join(","))
+}
+
+#[test]
+fn test_registry_rejects_duplicates() {
+    let json = registry_json(&[
+        entry("JLP/USDC", JLP, USDC, &Pubkey::new_unique()),
+        entry("SOL/USDC", SOL, USDC, &Pubkey::new_unique()),
+        entry("JLP/USDC", SOL, JLP, &Pubkey::new_unique()),
+        entry("JLP-USDC", JLP, USDC, &Pubkey::new_unique()),
+    ]);
+
+    let err = MarketRegistry::from_json(&json, false)
+        .unwrap_err()
+        .to_string();
+
+    assert!(err.contains("#2 JLP/USDC"));
+    assert!(err.contains("#3 JLP-USDC"));
+    assert!(!err.contains("SOL/USDC"));
+}
+
+#[test]
+fn test_registry_allow_duplicates_keeps_first() {
+    let first = Pubkey::new_unique();
+    let json = registry_json(&[
+        entry("JLP/USDC", JLP, USDC, &first),
+        entry("JLP/USDC", SOL, USDC, &Pubkey::new_unique()),
+        entry("JLP-USDC", JLP, USDC, &Pubkey::new_unique()),
+    ]);
+
+    let registry = MarketRegistry::from_json(&json, true).unwrap();
+
+    assert_eq!(registry.entries().len(), 1);
+    assert_eq!(registry.get("JLP/USDC").unwrap().market_address, first);
+    assert!(registry.get("JLP-USDC").is_none());
+    assert_eq!(
+        registry
+            .find_by_mints(&JLP.parse().unwrap(), &USDC.parse().unwrap())
+            .unwrap()
+            .market_address,
+        first
+    );
+}
+
+#[test]
+fn test_registry_from_json_file_resolves_program_id() {
+    let market_address = Pubkey::new_unique();
+    let program_id = Pubkey::new_unique();
+    let json = format!(
+        r#"[{{"symbol": "JLP/USDC", "base_mint": "{}", "quote_mint": "{}", "market_address": "{}", "program_id": "{}"}}, {}]"#,
+        JLP,
+        USDC,
+        market_address,
+        program_id,
+        entry("SOL/USDC", SOL, USDC, &Pubkey::new_unique())
+    );
+    let path =
+        std::env::temp_dir().join(format!("openbook-registry-{}.json", Pubkey::new_unique()));
+    std::fs::write(&path, json).unwrap();
+
+    let registry = MarketRegistry::from_json_file(&path);
+    std::fs::remove_file(&path).unwrap();
+    let registry = registry.unwrap();
+
+    let jlp = registry.resolve("JLP/USDC").unwrap();
+    assert_eq!(jlp.market_address, market_address);
+    assert_eq!(jlp.program_id, Some(program_id));
+    assert_eq!(registry.resolve("SOL/USDC").unwrap().program_id, None);
+    assert!(registry.resolve("USDT/USDC").is_none());
+    assert!(MarketRegistry::from_json_file(&path).is_err());
+}
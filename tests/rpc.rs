@@ -1,7 +1,23 @@
+use openbook::commitment_config::CommitmentConfig;
+use openbook::metrics::{CounterMetrics, MetricsCounts};
 use openbook::pubkey::Pubkey;
-use openbook::rpc::Rpc;
+use openbook::rpc::{
+    is_transient, lower_commitment, read_with_commitment_fallback, FailoverProvider,
+    PriorityFeeStrategy, RetryConfig, Rpc, RpcProvider, SimulationResult, TxConfig, TxOutcome,
+};
 use openbook::rpc_client::RpcClient;
 use openbook::signature::Signature;
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::rpc_request::{RpcError, RpcResponseErrorData};
+use solana_client::rpc_response::RpcSimulateTransactionResult;
+use solana_sdk::transaction::TransactionError;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+mod common;
+
+use common::MockRpc;
+use solana_sdk::rent::Rent;
 
 #[tokio::test]
 async fn test_fetch_transaction() {
@@ -29,3 +45,254 @@ async fn test_fetch_multiple_accounts() {
     let result = rpc.fetch_multiple_accounts(&[Pubkey::default()]).await;
     assert!(result.is_ok());
 }
+
+#[test]
+fn test_tx_config_defaults() {
+    let config = TxConfig::default();
+    assert!(!config.skip_preflight);
+    assert_eq!(config.compute_unit_limit, None);
+    assert_eq!(config.compute_unit_price, None);
+
+    let config = TxConfig::with_compute_budget(800_000, 5);
+    assert!(!config.skip_preflight);
+    assert_eq!(config.compute_unit_limit, Some(800_000));
+    assert_eq!(config.compute_unit_price, Some(5));
+    assert_eq!(config.max_retries, None);
+    assert_eq!(config.commitment, None);
+}
+
+#[test]
+fn test_send_commitment() {
+    let rpc = Rpc::new(RpcClient::new_with_commitment(
+        "http://127.0.0.1:1".to_string(),
+        CommitmentConfig::confirmed(),
+    ));
+    assert_eq!(rpc.send_commitment(), CommitmentConfig::confirmed());
+
+    let rpc = rpc.with_send_commitment(CommitmentConfig::processed());
+    assert_eq!(rpc.send_commitment(), CommitmentConfig::processed());
+    // Reads keep the commitment of the client.
+    assert_eq!(rpc.provider().commitment(), CommitmentConfig::confirmed());
+}
+
+#[test]
+fn test_priority_fee_strategy_select() {
+    let fees = [40, 10, 30, 20];
+    assert_eq!(
+        PriorityFeeStrategy::default(),
+        PriorityFeeStrategy::Percentile(75)
+    );
+    assert_eq!(PriorityFeeStrategy::Max.select(&fees), 40);
+    assert_eq!(PriorityFeeStrategy::Percentile(75).select(&fees), 30);
+    assert_eq!(PriorityFeeStrategy::Percentile(50).select(&fees), 20);
+    assert_eq!(PriorityFeeStrategy::Percentile(0).select(&fees), 10);
+    assert_eq!(PriorityFeeStrategy::Mean.select(&fees), 25);
+    assert_eq!(PriorityFeeStrategy::Fixed(7).select(&fees), 7);
+    assert_eq!(PriorityFeeStrategy::Max.select(&[]), 1);
+    assert_eq!(PriorityFeeStrategy::Percentile(75).select(&[0, 0]), 1);
+}
+
+#[test]
+fn test_is_transient() {
+    let response_error = |code: i64| {
+        ClientError::from(ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            code,
+            message: String::new(),
+            data: RpcResponseErrorData::Empty,
+        }))
+    };
+
+    assert!(is_transient(&ClientError::from(ClientErrorKind::Io(
+        std::io::ErrorKind::ConnectionReset.into()
+    ))));
+    assert!(is_transient(&response_error(-32005)));
+    assert!(!is_transient(&response_error(-32002)));
+    assert!(!is_transient(&ClientError::from(
+        ClientErrorKind::RpcError(RpcError::ForUser(
+            "AccountNotFound: pubkey=11111111111111111111111111111111".to_string()
+        ))
+    )));
+    assert!(!is_transient(&ClientError::from(ClientErrorKind::Custom(
+        "invalid".to_string()
+    ))));
+}
+
+#[tokio::test]
+async fn test_get_account_retries_connection_errors() {
+    // Nothing listens on port 1, so every attempt fails to connect.
+    let rpc =
+        Rpc::new(RpcClient::new("http://127.0.0.1:1".to_string())).with_retry_config(RetryConfig {
+            max_attempts: 3,
+            base_delay_ms: 30,
+            max_delay_ms: 1_000,
+        });
+
+    let start = Instant::now();
+    let err = rpc.get_account(&Pubkey::new_unique()).await.unwrap_err();
+
+    assert!(is_transient(&err));
+    // Two retries, after 30ms then 60ms.
+    assert!(start.elapsed() >= Duration::from_millis(90));
+
+    let rpc = rpc.with_retry_config(RetryConfig::disabled());
+    assert_eq!(rpc.retry_config().max_attempts, 1);
+    assert!(rpc.get_account(&Pubkey::new_unique()).await.is_err());
+}
+
+#[tokio::test]
+async fn test_reads_go_through_the_provider() {
+    let mock = MockRpc::new();
+    let rpc = Rpc::with_provider(mock.clone()).with_retry_config(RetryConfig::disabled());
+
+    assert!(rpc.fetch_transaction(&Signature::default()).await.is_err());
+    assert!(rpc
+        .fetch_signatures_for_address(&Pubkey::default(), None, None)
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        rpc.get_minimum_balance_for_rent_exemption(165)
+            .await
+            .unwrap(),
+        Rent::default().minimum_balance(165)
+    );
+    assert_eq!(mock.requests(), 3);
+}
+
+#[tokio::test]
+async fn test_failover_provider_marks_unhealthy_endpoints() {
+    // Nothing listens on ports 1 and 2, so every request fails to connect.
+    let urls = vec![
+        "http://127.0.0.1:1".to_string(),
+        "http://127.0.0.1:2".to_string(),
+    ];
+    let provider = FailoverProvider::new(urls.clone(), CommitmentConfig::confirmed())
+        .unwrap()
+        .with_health_check(2, Duration::from_secs(60));
+
+    // A single error doesn't fail over.
+    let err = provider
+        .get_latest_blockhash_with_commitment(CommitmentConfig::confirmed())
+        .await
+        .unwrap_err();
+    assert!(is_transient(&err));
+    assert_eq!(
+        provider.endpoint_health(),
+        vec![(urls[0].clone(), true), (urls[1].clone(), true)]
+    );
+
+    // The second error marks the first endpoint unhealthy and fails over to the next one.
+    assert!(provider
+        .get_latest_blockhash_with_commitment(CommitmentConfig::confirmed())
+        .await
+        .is_err());
+    assert_eq!(
+        provider.endpoint_health(),
+        vec![(urls[0].clone(), false), (urls[1].clone(), true)]
+    );
+    assert_eq!(provider.current_url(), urls[0]);
+
+    assert!(FailoverProvider::new(vec![], CommitmentConfig::confirmed()).is_err());
+
+    let rpc = Rpc::new_with_endpoints(urls.clone(), CommitmentConfig::confirmed()).unwrap();
+    assert_eq!(rpc.current_endpoint(), urls[0]);
+}
+
+#[tokio::test]
+async fn test_rpc_reports_metrics() {
+    let metrics = Arc::new(CounterMetrics::new());
+    let rpc =
+        Rpc::new(RpcClient::new("http://127.0.0.1:1".to_string())).with_retry_config(RetryConfig {
+            max_attempts: 3,
+            base_delay_ms: 1,
+            max_delay_ms: 10,
+        });
+    assert!(rpc.get_account(&Pubkey::new_unique()).await.is_err());
+    assert_eq!(metrics.counts(), MetricsCounts::default());
+
+    let rpc = rpc.with_metrics(metrics.clone());
+    assert!(rpc.get_account(&Pubkey::new_unique()).await.is_err());
+
+    let counts = metrics.counts();
+    assert_eq!(counts.rpc_calls, 3);
+    assert_eq!(counts.retries, 2);
+    assert_eq!(counts.tx_confirmed + counts.tx_failed, 0);
+}
+
+#[test]
+fn test_simulation_result() {
+    let simulation = SimulationResult::from(RpcSimulateTransactionResult {
+        err: Some(TransactionError::AccountNotFound),
+        logs: Some(vec!["Program log: Instruction: NewOrderV3".to_string()]),
+        accounts: None,
+        units_consumed: Some(123_457),
+        return_data: None,
+    });
+
+    assert!(simulation.is_err());
+    assert!(!simulation.is_insufficient_funds());
+    assert!(SimulationResult {
+        err: Some(TransactionError::InsufficientFundsForFee),
+        ..SimulationResult::default()
+    }
+    .is_insufficient_funds());
+    assert_eq!(simulation.logs.len(), 1);
+    assert_eq!(simulation.compute_unit_limit(0), Some(123_457));
+    assert_eq!(simulation.compute_unit_limit(20), Some(148_149));
+    assert_eq!(SimulationResult::default().compute_unit_limit(20), None);
+
+    let dry_run = TxOutcome::Simulated(simulation);
+    assert_eq!(dry_run.signature(), None);
+    assert!(!dry_run.is_confirmed());
+
+    let sent = TxOutcome::Sent {
+        confirmed: true,
+        signature: Signature::default(),
+    };
+    assert_eq!(sent.signature(), Some(Signature::default()));
+    assert!(sent.is_confirmed());
+}
+
+#[tokio::test]
+async fn test_read_with_commitment_fallback() {
+    let timeout = Duration::from_millis(50);
+    let reads = std::sync::Mutex::new(Vec::new());
+
+    // A node behind the tip never answers finalized reads.
+    let start = Instant::now();
+    let (value, commitment) =
+        read_with_commitment_fallback(CommitmentConfig::finalized(), timeout, |commitment| {
+            reads.lock().unwrap().push(commitment);
+            async move {
+                if commitment.is_finalized() {
+                    std::future::pending::<()>().await;
+                }
+                Ok::<_, ClientError>(42)
+            }
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(value, 42);
+    assert_eq!(commitment, CommitmentConfig::confirmed());
+    assert!(start.elapsed() >= timeout);
+    assert_eq!(
+        *reads.lock().unwrap(),
+        vec![CommitmentConfig::finalized(), CommitmentConfig::confirmed()]
+    );
+
+    // Errors are returned without falling back.
+    let err = read_with_commitment_fallback(CommitmentConfig::confirmed(), timeout, |_| async {
+        Err::<u8, _>(ClientError::from(ClientErrorKind::Custom(
+            "boom".to_string(),
+        )))
+    })
+    .await;
+    assert!(err.is_err());
+
+    assert_eq!(
+        lower_commitment(CommitmentConfig::confirmed()),
+        Some(CommitmentConfig::processed())
+    );
+    assert_eq!(lower_commitment(CommitmentConfig::processed()), None);
+}
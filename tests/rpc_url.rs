@@ -0,0 +1,40 @@
+use openbook::utils::{get_rpc_url, MAINNET_RPC_URL};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+/// Collects formatted log lines in memory.
+#[derive(Clone, Default)]
+struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for LogBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_warns_once_when_rpc_url_is_unset() {
+    std::env::remove_var("RPC_URL");
+
+    let logs = LogBuffer::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .finish();
+
+    let (first, second) =
+        tracing::subscriber::with_default(subscriber, || (get_rpc_url(), get_rpc_url()));
+
+    let output = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+
+    assert_eq!(first, MAINNET_RPC_URL);
+    assert_eq!(second, MAINNET_RPC_URL);
+    assert_eq!(output.matches("RPC_URL is not set").count(), 1);
+    assert!(output.contains("WARN"));
+}
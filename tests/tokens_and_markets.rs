@@ -0,0 +1,34 @@
+use openbook::pubkey::Pubkey;
+use openbook::tokens_and_markets::{display_mint, symbol_for_mint, Token};
+
+#[test]
+fn test_symbol_for_known_mints() {
+    let cases = [
+        ("So11111111111111111111111111111111111111112", "SOL"),
+        ("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", "USDC"),
+        ("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB", "USDT"),
+        ("27G8MtK7VtTcCHkpASjSDdkWWYfoqT6ggEuKidVJidD4", "JLP"),
+    ];
+
+    for (mint, symbol) in cases {
+        let mint: Pubkey = mint.parse().unwrap();
+        assert_eq!(symbol_for_mint(&mint), Some(symbol));
+        assert_eq!(display_mint(&mint), symbol);
+    }
+}
+
+#[test]
+fn test_symbol_round_trip() {
+    for token in Token::ALL {
+        assert_eq!(Token::from_mint(&token.mint()), Some(token));
+        assert_eq!(symbol_for_mint(&token.mint()), Some(token.symbol()));
+    }
+}
+
+#[test]
+fn test_symbol_for_unknown_mint() {
+    let mint = Pubkey::new_unique();
+
+    assert_eq!(symbol_for_mint(&mint), None);
+    assert_eq!(display_mint(&mint), mint.to_string());
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "cli")]
+
+use openbook::tui::install_panic_hook;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn test_panic_hook_runs_cleanup() {
+    let cleaned_up = Arc::new(AtomicBool::new(false));
+    let flag = cleaned_up.clone();
+    install_panic_hook(move || flag.store(true, Ordering::SeqCst));
+
+    let result = std::panic::catch_unwind(|| panic!("bad input"));
+
+    let _ = std::panic::take_hook();
+    assert!(result.is_err());
+    assert!(cleaned_up.load(Ordering::SeqCst));
+}
+
+#[cfg(feature = "v2")]
+#[test]
+fn test_format_open_orders_splits_sides() {
+    use openbook::tui::format_open_orders;
+    use openbook::v2::ob_client::OpenOrderNode;
+
+    let order = |is_buy: bool, price: f64, amount: f64| OpenOrderNode {
+        is_buy,
+        price,
+        amount,
+        order_id: 0,
+        timestamp: 0,
+        slot: 0,
+    };
+    let orders = vec![
+        order(true, 1.99, 2.0),
+        order(false, 2.01, 1.5),
+        order(true, 1.98, 3.0),
+    ];
+
+    assert_eq!(format_open_orders(&orders, true), "1.99 x 2, 1.98 x 3");
+    assert_eq!(format_open_orders(&orders, false), "2.01 x 1.5");
+    assert_eq!(format_open_orders(&[], false), "");
+}
@@ -0,0 +1,179 @@
+use openbook::keypair::Keypair;
+use openbook::pubkey::Pubkey;
+use openbook::signature::Signer;
+use openbook::utils::{
+    pack_instructions, premium_bps, prepend_compute_budget, read_keypair_from_base58,
+    read_keypair_from_bytes, websocket_url, SdkVersion, DEFAULT_MAX_IX_PER_TX,
+    OPENBOOK_V1_PROGRAM_ID, OPENBOOK_V2_PROGRAM_ID,
+};
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::Message;
+use solana_sdk::packet::PACKET_DATA_SIZE;
+
+fn memo_instruction() -> Instruction {
+    Instruction::new_with_bytes(Pubkey::new_unique(), b"memo", vec![])
+}
+
+#[test]
+fn test_prepend_compute_budget_to_plain_instructions() {
+    let memo = memo_instruction();
+
+    let instructions = prepend_compute_budget(vec![memo.clone()], 800_000, 5);
+
+    assert_eq!(
+        instructions,
+        vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(800_000),
+            ComputeBudgetInstruction::set_compute_unit_price(5),
+            memo,
+        ]
+    );
+}
+
+#[test]
+fn test_prepend_compute_budget_skips_existing_budget() {
+    let limit = ComputeBudgetInstruction::set_compute_unit_limit(200_000);
+    let price = ComputeBudgetInstruction::set_compute_unit_price(1_000);
+    let memo = memo_instruction();
+
+    let instructions =
+        prepend_compute_budget(vec![limit.clone(), price.clone(), memo.clone()], 800_000, 5);
+    assert_eq!(instructions, vec![limit.clone(), price, memo.clone()]);
+
+    let instructions = prepend_compute_budget(vec![limit.clone(), memo.clone()], 800_000, 5);
+    assert_eq!(
+        instructions,
+        vec![
+            ComputeBudgetInstruction::set_compute_unit_price(5),
+            limit,
+            memo,
+        ]
+    );
+}
+
+#[test]
+fn test_pack_instructions_respects_packet_size() {
+    let payer = Pubkey::new_unique();
+    let program_id = Pubkey::new_unique();
+    let instructions = vec![Instruction::new_with_bytes(program_id, &[7; 300], vec![]); 10];
+
+    let batches = pack_instructions(instructions, &payer, DEFAULT_MAX_IX_PER_TX);
+
+    assert_eq!(batches.iter().map(Vec::len).sum::<usize>(), 10);
+    assert!(batches.len() > 1);
+    for batch in &batches {
+        let message = Message::new(batch, Some(&payer));
+        let size = 1 + 64 + message.serialize().len();
+        assert!(size <= PACKET_DATA_SIZE);
+    }
+}
+
+#[test]
+fn test_pack_instructions_keeps_oversized_instruction_alone() {
+    let payer = Pubkey::new_unique();
+    let program_id = Pubkey::new_unique();
+    let small = Instruction::new_with_bytes(program_id, &[1; 10], vec![]);
+    let oversized = Instruction::new_with_bytes(program_id, &[2; 2_000], vec![]);
+
+    let batches = pack_instructions(
+        vec![small.clone(), oversized.clone(), small.clone()],
+        &payer,
+        DEFAULT_MAX_IX_PER_TX,
+    );
+
+    assert_eq!(
+        batches,
+        vec![vec![small.clone()], vec![oversized], vec![small]]
+    );
+}
+
+#[test]
+fn test_pack_instructions_max_ix_per_tx() {
+    let payer = Pubkey::new_unique();
+    let program_id = Pubkey::new_unique();
+    let instructions = vec![Instruction::new_with_bytes(program_id, &[3; 8], vec![]); 23];
+
+    for max_ix_per_tx in [1, 4, 5, 10, 23, 30] {
+        let batches = pack_instructions(instructions.clone(), &payer, max_ix_per_tx);
+
+        assert_eq!(batches.len(), (23 + max_ix_per_tx - 1) / max_ix_per_tx);
+        assert!(batches.iter().all(|batch| batch.len() <= max_ix_per_tx));
+        assert_eq!(batches.concat(), instructions);
+    }
+
+    assert_eq!(pack_instructions(instructions, &payer, 0).len(), 23);
+}
+
+#[test]
+fn test_websocket_url() {
+    assert_eq!(
+        websocket_url("https://api.mainnet-beta.solana.com"),
+        "wss://api.mainnet-beta.solana.com"
+    );
+    assert_eq!(
+        websocket_url("http://127.0.0.1:8899"),
+        "ws://127.0.0.1:8899"
+    );
+    assert_eq!(websocket_url("wss://node.example"), "wss://node.example");
+}
+
+#[test]
+fn test_premium_bps() {
+    assert!((premium_bps(100.0, 100.5) - 50.0).abs() < 1e-9);
+    assert!((premium_bps(2.0, 2.0)).abs() < 1e-9);
+
+    // The perp trading below spot is a discount, a negative premium.
+    assert!((premium_bps(100.0, 99.0) + 100.0).abs() < 1e-9);
+    assert!((premium_bps(4.0, 3.999) + 2.5).abs() < 1e-9);
+
+    assert!(premium_bps(0.0, 1.0).is_nan());
+    assert!(premium_bps(-1.0, 1.0).is_nan());
+}
+
+#[test]
+fn test_read_keypair_from_bytes_and_base58() {
+    let keypair = Keypair::new();
+
+    let from_bytes = read_keypair_from_bytes(&keypair.to_bytes()).unwrap();
+    assert_eq!(from_bytes.pubkey(), keypair.pubkey());
+
+    let encoded = format!("  {}\n", keypair.to_base58_string());
+    assert_eq!(
+        read_keypair_from_base58(&encoded).unwrap().pubkey(),
+        keypair.pubkey()
+    );
+
+    // Wrong lengths and a public key not matching the secret are rejected without panicking.
+    let err = read_keypair_from_bytes(&keypair.to_bytes()[..63]).unwrap_err();
+    assert!(err.to_string().contains("expected 64 bytes, got 63"));
+    let mut mismatched = keypair.to_bytes();
+    mismatched[32..].copy_from_slice(Keypair::new().pubkey().as_ref());
+    assert!(read_keypair_from_bytes(&mismatched).is_err());
+
+    assert!(read_keypair_from_base58("0OIl").is_err());
+    assert!(read_keypair_from_base58(&Pubkey::new_unique().to_string()).is_err());
+}
+
+#[test]
+fn test_sdk_version_from_program_id() {
+    let v1: Pubkey = OPENBOOK_V1_PROGRAM_ID.parse().unwrap();
+    let v2: Pubkey = OPENBOOK_V2_PROGRAM_ID.parse().unwrap();
+
+    assert_eq!(SdkVersion::from_program_id(&v1), Some(SdkVersion::V1));
+    assert_eq!(SdkVersion::from_program_id(&v2), Some(SdkVersion::V2));
+    assert_eq!(SdkVersion::from_program_id(&Pubkey::new_unique()), None);
+
+    // A fork of v1 is recognized once its program id is given.
+    let fork = Pubkey::new_unique();
+    assert_eq!(SdkVersion::from_program_id(&fork), None);
+    assert_eq!(
+        SdkVersion::from_program_ids(&fork, &fork, &v2),
+        Some(SdkVersion::V1)
+    );
+    assert_eq!(
+        SdkVersion::from_program_ids(&v2, &fork, &v2),
+        Some(SdkVersion::V2)
+    );
+    assert_eq!(SdkVersion::from_program_ids(&v1, &fork, &v2), None);
+}
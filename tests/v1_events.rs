@@ -0,0 +1,230 @@
+#![cfg(feature = "v1")]
+
+mod common;
+
+use common::{event_data, event_queue_data};
+use openbook::matching::Side;
+use openbook::pubkey::Pubkey;
+use openbook::v1::events::{
+    EventQueue, OrderStatus, OrderTracker, EVENT_FLAG_BID, EVENT_FLAG_FILL, EVENT_FLAG_MAKER,
+    EVENT_FLAG_OUT, EVENT_SIZE, MAX_CONSUME_EVENTS_ACCOUNTS,
+};
+use openbook::v1::market::Market;
+
+fn fill_data(
+    flags: u8,
+    order_id: u128,
+    owner: &Pubkey,
+    released: u64,
+    paid: u64,
+    fee: u64,
+) -> Vec<u8> {
+    let mut data = event_data(flags | EVENT_FLAG_FILL, order_id, owner);
+    data[8..16].copy_from_slice(&released.to_le_bytes());
+    data[16..24].copy_from_slice(&paid.to_le_bytes());
+    data[24..32].copy_from_slice(&fee.to_le_bytes());
+    data
+}
+
+#[test]
+fn test_event_queue_counts_matches_since_seq_num() {
+    let owner = Pubkey::new_unique();
+    let out = event_data(EVENT_FLAG_OUT, 3, &owner);
+    let taker = event_data(EVENT_FLAG_FILL | EVENT_FLAG_BID, 1, &owner);
+    let maker = event_data(EVENT_FLAG_FILL | EVENT_FLAG_MAKER, 2, &owner);
+    // The queue wraps around: the oldest event is in slot 2, the newest in slot 0.
+    let data = event_queue_data(2, 3, 10, &[out, vec![0; EVENT_SIZE], taker, maker]);
+
+    let queue = EventQueue::from_account_data(&data).unwrap();
+
+    assert_eq!(queue.seq_num, 10);
+    assert_eq!(
+        queue
+            .events
+            .iter()
+            .map(|event| event.order_id)
+            .collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+    assert_eq!(queue.events[0].owner, owner);
+    assert_eq!(queue.matches_since(7, None), 1);
+    assert_eq!(queue.matches_since(9, None), 0);
+    assert_eq!(queue.matches_since(10, None), 0);
+    // Events consumed before the queue was read can't be counted.
+    assert_eq!(queue.events_since(2).len(), 3);
+}
+
+#[test]
+fn test_event_queue_counts_the_matches_of_an_owner() {
+    let (own, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+    // Our bid took an ask of another trader, then two other traders matched in between.
+    let slots = [
+        event_data(EVENT_FLAG_FILL | EVENT_FLAG_BID, 1, &own),
+        event_data(EVENT_FLAG_FILL | EVENT_FLAG_MAKER, 2, &other),
+        event_data(EVENT_FLAG_FILL | EVENT_FLAG_BID, 3, &other),
+        event_data(EVENT_FLAG_FILL | EVENT_FLAG_MAKER, 4, &Pubkey::new_unique()),
+        // Our ask was then taken as a maker, and our other order was canceled.
+        event_data(EVENT_FLAG_FILL, 5, &other),
+        event_data(EVENT_FLAG_FILL | EVENT_FLAG_MAKER, 6, &own),
+        event_data(EVENT_FLAG_OUT, 7, &own),
+    ];
+    let data = event_queue_data(0, 7, 27, &slots);
+
+    let queue = EventQueue::from_account_data(&data).unwrap();
+
+    assert_eq!(queue.matches_since(20, None), 3);
+    assert_eq!(queue.matches_since(20, Some(&own)), 2);
+    assert_eq!(queue.matches_since(22, Some(&own)), 1);
+    assert_eq!(queue.matches_since(20, Some(&Pubkey::new_unique())), 0);
+}
+
+#[test]
+fn test_event_queue_rejects_invalid_data() {
+    assert!(EventQueue::from_account_data(b"serum").is_err());
+    assert!(
+        EventQueue::from_account_data(&event_queue_data(0, 3, 3, &[vec![0; EVENT_SIZE]])).is_err()
+    );
+}
+
+#[test]
+fn test_event_queue_open_orders_accounts() {
+    // Read as four little-endian u64 words, `first` sorts before `second` although its first
+    // byte is larger.
+    let mut first_bytes = [0u8; 32];
+    first_bytes[0] = 1;
+    let mut second_bytes = [0u8; 32];
+    second_bytes[7] = 1;
+    let (first, second, other) = (
+        Pubkey::new_from_array(first_bytes),
+        Pubkey::new_from_array(second_bytes),
+        Pubkey::new_unique(),
+    );
+    let slots = vec![
+        event_data(EVENT_FLAG_FILL | EVENT_FLAG_MAKER, 1, &second),
+        event_data(EVENT_FLAG_FILL | EVENT_FLAG_BID, 2, &first),
+        event_data(EVENT_FLAG_OUT, 3, &second),
+        event_data(EVENT_FLAG_OUT, 4, &other),
+    ];
+    let queue = EventQueue::from_account_data(&event_queue_data(0, 4, 4, &slots)).unwrap();
+
+    assert_eq!(
+        queue.open_orders_accounts(3, MAX_CONSUME_EVENTS_ACCOUNTS),
+        vec![first, second]
+    );
+    assert_eq!(queue.open_orders_accounts(10, 1), vec![second]);
+    assert_eq!(queue.open_orders_accounts(10, 3).len(), 3);
+    assert!(EventQueue::default()
+        .open_orders_accounts(10, MAX_CONSUME_EVENTS_ACCOUNTS)
+        .is_empty());
+}
+
+#[test]
+fn test_event_queue_fills() {
+    let (maker, taker) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let market = Market {
+        coin_decimals: 9,
+        pc_decimals: 6,
+        ..Default::default()
+    };
+    let slots = vec![
+        // 2 base bought at 1.5 by a resting bid, minus a 600 native quote rebate.
+        fill_data(
+            EVENT_FLAG_BID | EVENT_FLAG_MAKER,
+            1,
+            &maker,
+            2_000_000_000,
+            2_999_400,
+            600,
+        ),
+        // 2 base sold at 1.5 by a taker ask, minus a 3_000 native quote fee.
+        fill_data(0, 2, &taker, 2_997_000, 2_000_000_000, 3_000),
+        event_data(EVENT_FLAG_OUT, 3, &maker),
+    ];
+    let queue = EventQueue::from_account_data(&event_queue_data(0, 3, 3, &slots)).unwrap();
+
+    let fills = queue.fills(&market, None);
+    assert_eq!(fills.len(), 2);
+    assert_eq!(fills[0].side, Side::Bid);
+    assert!(fills[0].maker);
+    assert_eq!(fills[0].owner, maker);
+    assert_eq!(fills[0].order_id, 1);
+    assert!((fills[0].price - 1.5).abs() < 1e-9);
+    assert!((fills[0].quantity - 2.0).abs() < 1e-9);
+    assert_eq!(fills[1].side, Side::Ask);
+    assert!(!fills[1].maker);
+    assert!((fills[1].price - 1.5).abs() < 1e-9);
+    assert!((fills[1].quantity - 2.0).abs() < 1e-9);
+
+    let own = queue.fills(&market, Some(&taker));
+    assert_eq!(own.len(), 1);
+    assert_eq!(own[0].order_id, 2);
+}
+
+#[test]
+fn test_order_tracker() {
+    let (owner, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let market = Market {
+        coin_decimals: 9,
+        pc_decimals: 6,
+        ..Default::default()
+    };
+    // Resting ask 7 sells 1 base at 2.0, then 1 base at 2.2, then leaves the book filled.
+    let first = fill_data(EVENT_FLAG_MAKER, 7, &owner, 2_000_000, 1_000_000_000, 0);
+    let second = fill_data(EVENT_FLAG_MAKER, 7, &owner, 2_200_000, 1_000_000_000, 0);
+    let foreign = fill_data(EVENT_FLAG_MAKER, 7, &other, 2_000_000, 1_000_000_000, 0);
+    let out = event_data(EVENT_FLAG_OUT, 7, &owner);
+    let queue = |slots: &[Vec<u8>]| {
+        let count = slots.len() as u64;
+        EventQueue::from_account_data(&event_queue_data(0, count, count, slots)).unwrap()
+    };
+
+    let mut tracker = OrderTracker::new(7, owner);
+    assert_eq!(tracker.timeout_status(), OrderStatus::Timeout);
+    assert_eq!(
+        tracker.observe(&queue(&[first.clone(), foreign.clone()]), &market, true),
+        None
+    );
+    // Events already recorded are not counted twice.
+    assert_eq!(
+        tracker.observe(
+            &queue(&[first.clone(), foreign.clone(), second.clone()]),
+            &market,
+            true
+        ),
+        None
+    );
+    assert!((tracker.filled_qty() - 2.0).abs() < 1e-9);
+    assert_eq!(
+        tracker.timeout_status(),
+        OrderStatus::PartiallyFilled {
+            qty: tracker.filled_qty()
+        }
+    );
+    match tracker.observe(
+        &queue(&[first.clone(), foreign, second, out]),
+        &market,
+        true,
+    ) {
+        Some(OrderStatus::Filled { qty, avg_price }) => {
+            assert!((qty - 2.0).abs() < 1e-9);
+            assert!((avg_price - 2.1).abs() < 1e-9);
+        }
+        other => panic!("unexpected status: {:?}", other),
+    }
+
+    // An out event unlocking funds is a cancel, even after a partial fill.
+    let mut canceled = event_data(EVENT_FLAG_OUT, 7, &owner);
+    canceled[8..16].copy_from_slice(&1_000_000_000u64.to_le_bytes());
+    let mut tracker = OrderTracker::new(7, owner);
+    assert_eq!(
+        tracker.observe(&queue(&[first, canceled]), &market, true),
+        Some(OrderStatus::Cancelled)
+    );
+
+    // An order gone from its open orders account without fills was canceled.
+    let mut tracker = OrderTracker::new(7, owner);
+    assert_eq!(
+        tracker.observe(&EventQueue::default(), &market, false),
+        Some(OrderStatus::Cancelled)
+    );
+}
@@ -0,0 +1,2295 @@
+#![cfg(feature = "v1")]
+
+mod common;
+
+use common::{
+    event_data, event_queue_data, offline_client, offline_reader, open_orders_account_data,
+    token_account_data, MockRpc, RecordedOrder, MARKET_ID,
+};
+use openbook::account::Account;
+use openbook::commitment_config::CommitmentConfig;
+use openbook::error::OpenBookError;
+use openbook::matching::{OrderType, Side};
+use openbook::metrics::CounterMetrics;
+use openbook::pubkey::Pubkey;
+use openbook::rpc::{PriorityFeeStrategy, Rpc, TxOutcome};
+use openbook::traits::{OrderPlacer, OrderSide};
+use openbook::utils::{decode_transaction, encode_transaction, DEFAULT_MAX_IX_PER_TX};
+use openbook::v1::events::{EVENT_FLAG_BID, EVENT_FLAG_FILL, EVENT_FLAG_MAKER, EVENT_FLAG_OUT};
+use openbook::v1::market::{Market, QUEUE_HEADER_SIZE};
+use openbook::v1::ob_client::{
+    Balances, CostEstimate, MarketPrice, OBClient, ReadOnlyClient, WalletSnapshot,
+    COMBO_COMPUTE_UNIT_LIMIT, FULL_COMBO_COMPUTE_UNIT_LIMIT, LAMPORTS_PER_SIGNATURE, SPL_TOKEN_ID,
+};
+use openbook::v1::orders::{
+    order_combo_instructions, prepend_step_instructions, resolve_open_orders, ComboInstructionKind,
+    ComboStep, Expiry, LimitOrderParams, LimitOrderSpec, OpenOrderInfo, OpenOrdersCacheEntry,
+    OrderReturnType, PlaceOrderOutcome,
+};
+use openbook_dex::fees::FeeTier;
+use openbook_dex::instruction::{MarketInstruction, SelfTradeBehavior};
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signer::keypair::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::Transaction;
+use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[tokio::test]
+#[ignore = "requires an archival RPC_URL and a HISTORICAL_SLOT"]
+async fn test_load_at_slot() -> anyhow::Result<()> {
+    let slot: u64 = std::env::var("HISTORICAL_SLOT")
+        .expect("HISTORICAL_SLOT is not set in .env file")
+        .parse()?;
+
+    let mut ob_client = OBClient::new(
+        CommitmentConfig::confirmed(),
+        MARKET_ID.parse()?,
+        false,
+        1000,
+    )
+    .await?;
+
+    ob_client.load_at_slot(slot).await?;
+
+    assert!(ob_client.open_orders.max_bid > 0);
+    assert!(ob_client.open_orders.min_ask > ob_client.open_orders.max_bid);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_read_only_client_loads_book_without_keypair() -> anyhow::Result<()> {
+    let client =
+        ReadOnlyClient::new(CommitmentConfig::confirmed(), MARKET_ID.parse()?, true).await?;
+
+    assert_eq!(
+        client.market_info.market_address,
+        MARKET_ID.parse::<Pubkey>()?
+    );
+    assert!(client.open_orders.max_bid > 0);
+    assert!(client.open_orders.min_ask > client.open_orders.max_bid);
+
+    Ok(())
+}
+
+#[test]
+fn test_read_only_client_queries() {
+    let reader = offline_reader(1_990, 2_010);
+    let ob_client = offline_client(1_990, 2_010);
+
+    assert!(reader.would_cross(Side::Bid, 2.02));
+    assert!(!reader.would_cross(Side::Ask, 2.0));
+    assert_eq!(
+        reader.would_cross(Side::Bid, 2.02),
+        ob_client.would_cross(Side::Bid, 2.02)
+    );
+}
+
+#[tokio::test]
+async fn test_health_check_reports_every_failure() {
+    let mut ob_client = offline_client(1_990, 2_010);
+    ob_client.market_info.bids_address = Pubkey::new_unique();
+    ob_client.market_info.asks_address = Pubkey::new_unique();
+    ob_client.market_info.program_id = Pubkey::new_unique();
+    ob_client.open_orders.oo_key = Pubkey::new_unique();
+    ob_client.base_ata = Pubkey::new_unique();
+    ob_client.quote_ata = Pubkey::new_unique();
+    let program_id = ob_client.market_info.program_id;
+    let owner = ob_client.owner.pubkey();
+    let token_program: Pubkey = SPL_TOKEN_ID.parse().unwrap();
+    let mock = MockRpc::new()
+        .with_account(ob_client.market_info.market_address, program_id, vec![])
+        .with_account(owner, Pubkey::default(), vec![])
+        .with_account(ob_client.base_ata, token_program, vec![]);
+    ob_client.rpc_client = Rpc::with_provider(mock.clone());
+
+    let report = ob_client.health_check(2_000_000).await.unwrap();
+
+    assert_eq!(mock.requests(), 1);
+    assert!(report.rpc_reachable);
+    assert!(report.market_loaded);
+    assert!(report.base_ata_exists);
+    assert!(!report.open_orders_exists);
+    assert!(!report.quote_ata_exists);
+    assert_eq!(report.sol_balance, 1_000_000);
+    assert!(!report.sol_balance_ok);
+    assert_eq!(report.errors.len(), 3);
+    assert!(!report.is_ready());
+
+    let mock = mock
+        .with_account(ob_client.open_orders.oo_key, program_id, vec![])
+        .with_account(ob_client.quote_ata, token_program, vec![]);
+    let report = ob_client.health_check(500_000).await.unwrap();
+    assert!(report.is_ready(), "{:?}", report.errors);
+    assert_eq!(mock.requests(), 2);
+}
+
+#[tokio::test]
+async fn test_load_bids_asks_info_batches_fetches() {
+    let mut reader = offline_reader(0, 0);
+    reader.market_info.bids_address = Pubkey::new_unique();
+    reader.market_info.asks_address = Pubkey::new_unique();
+    let mock = MockRpc::new().with_account(
+        reader.market_info.market_address,
+        reader.market_info.program_id,
+        vec![0; 388],
+    );
+    reader.rpc_client = Rpc::with_provider(mock.clone());
+
+    let err = reader.load_bids_asks_info().await.unwrap_err();
+
+    assert!(err
+        .to_string()
+        .contains(&reader.market_info.bids_address.to_string()));
+    assert_eq!(mock.requests(), 1);
+}
+
+#[tokio::test]
+async fn test_load_bids_asks_info_uninitialized_book() {
+    let mut reader = offline_reader(0, 0);
+    reader.market_info.asks_address = Pubkey::new_unique();
+    assert_eq!(reader.market_info.bids_address, Pubkey::default());
+
+    let err = reader.load_bids_asks_info().await.unwrap_err();
+
+    assert!(matches!(
+        err.downcast_ref::<OpenBookError>(),
+        Some(OpenBookError::MarketBookUninitialized(market))
+            if *market == reader.market_info.market_address
+    ));
+}
+
+#[test]
+fn test_would_cross_bid() {
+    let ob_client = offline_client(1_990, 2_010);
+
+    assert!(!ob_client.would_cross(Side::Bid, 2.0));
+    assert!(ob_client.would_cross(Side::Bid, 2.02));
+    assert!(ob_client.would_cross(Side::Bid, 2.5));
+}
+
+#[test]
+fn test_would_cross_ask() {
+    let ob_client = offline_client(1_990, 2_010);
+
+    assert!(!ob_client.would_cross(Side::Ask, 2.0));
+    assert!(ob_client.would_cross(Side::Ask, 1.98));
+    assert!(ob_client.would_cross(Side::Ask, 1.5));
+}
+
+#[test]
+fn test_would_cross_empty_book() {
+    let ob_client = offline_client(0, 0);
+
+    assert!(!ob_client.would_cross(Side::Bid, 100.0));
+    assert!(!ob_client.would_cross(Side::Ask, 0.01));
+}
+
+#[test]
+fn test_wallet_snapshot_from_accounts() {
+    let owner = Pubkey::new_unique();
+    let market = MARKET_ID.parse().unwrap();
+    let account = |data: Vec<u8>| Account {
+        lamports: 1,
+        data,
+        owner: Pubkey::new_unique(),
+        executable: false,
+        rent_epoch: 0,
+    };
+
+    let base_ata = account(token_account_data(
+        &Pubkey::new_unique(),
+        &owner,
+        3_500_000_000,
+    ));
+    let quote_ata = account(token_account_data(
+        &Pubkey::new_unique(),
+        &owner,
+        12_250_000,
+    ));
+    let open_orders = account(open_orders_account_data(
+        &market,
+        &owner,
+        (1_000_000_000, 1_500_000_000),
+        (2_000_000, 7_000_000),
+        &[],
+    ));
+
+    let snapshot =
+        WalletSnapshot::from_accounts(Some(&base_ata), Some(&quote_ata), Some(&open_orders), 9, 6)
+            .unwrap();
+
+    assert_eq!(
+        snapshot,
+        WalletSnapshot {
+            base_balance: 3.5,
+            quote_balance: 12.25,
+            base_free: 1.0,
+            quote_free: 2.0,
+            base_in_orders: 0.5,
+            quote_in_orders: 5.0,
+        }
+    );
+}
+
+#[test]
+fn test_wallet_snapshot_missing_accounts() {
+    let snapshot = WalletSnapshot::from_accounts(None, None, None, 9, 6).unwrap();
+
+    assert_eq!(snapshot, WalletSnapshot::default());
+}
+
+#[test]
+fn test_balances_from_snapshot() {
+    let snapshot = WalletSnapshot {
+        base_balance: 1.0,
+        quote_balance: 10.0,
+        base_free: 0.5,
+        quote_free: 2.0,
+        base_in_orders: 0.5,
+        quote_in_orders: 3.0,
+    };
+
+    let balances = Balances::from_snapshot(&snapshot, 2.0);
+
+    assert_eq!(balances.base_in_open_orders, 1.0);
+    assert_eq!(balances.quote_in_open_orders, 5.0);
+    assert_eq!(balances.price, 2.0);
+    assert_eq!(balances.total_balance, 15.0 + 2.0 * 2.0);
+}
+
+#[test]
+fn test_should_requote_without_last_quote() {
+    let ob_client = offline_client(0, 0);
+
+    assert!(ob_client.should_requote(2.0, 50.0));
+}
+
+#[test]
+fn test_should_requote_on_mid_moves() {
+    let mut ob_client = offline_client(0, 0);
+    ob_client.record_quote_mid(2.0);
+
+    // 0 bps
+    assert!(!ob_client.should_requote(2.0, 10.0));
+    // 5 bps up
+    assert!(!ob_client.should_requote(2.001, 10.0));
+    // 5 bps down
+    assert!(!ob_client.should_requote(1.999, 10.0));
+    // 25 bps up
+    assert!(ob_client.should_requote(2.005, 10.0));
+    // 50 bps down
+    assert!(ob_client.should_requote(1.99, 10.0));
+
+    ob_client.record_quote_mid(1.99);
+    assert!(!ob_client.should_requote(1.99, 10.0));
+}
+
+#[test]
+fn test_bid_cost_estimate_open_orders_rent() {
+    let account = |data: Vec<u8>| Account {
+        lamports: 1,
+        data,
+        owner: Pubkey::new_unique(),
+        executable: false,
+        rent_epoch: 0,
+    };
+    let owner = Pubkey::new_unique();
+    let open_orders = account(open_orders_account_data(
+        &MARKET_ID.parse().unwrap(),
+        &owner,
+        (0, 0),
+        (0, 0),
+        &[],
+    ));
+    let base_ata = account(token_account_data(&Pubkey::new_unique(), &owner, 0));
+    let quote_ata = account(token_account_data(&Pubkey::new_unique(), &owner, 0));
+    let (open_orders_rent, token_account_rent) = (23_357_760, 2_039_280);
+
+    let existing = CostEstimate::from_accounts(
+        20.0,
+        Some(&open_orders),
+        Some(&base_ata),
+        Some(&quote_ata),
+        open_orders_rent,
+        token_account_rent,
+    );
+    let missing = CostEstimate::from_accounts(
+        20.0,
+        None,
+        Some(&base_ata),
+        Some(&quote_ata),
+        open_orders_rent,
+        token_account_rent,
+    );
+
+    assert_eq!(
+        existing,
+        CostEstimate {
+            quote_needed: 20.0,
+            sol_for_rent: 0,
+            sol_for_fees: LAMPORTS_PER_SIGNATURE,
+        }
+    );
+    assert_eq!(missing.sol_for_rent, open_orders_rent);
+    assert_eq!(missing.sol_for_fees, 2 * LAMPORTS_PER_SIGNATURE);
+    assert!(missing.sol_for_rent > existing.sol_for_rent);
+}
+
+#[test]
+fn test_consume_events_permissioned_authority_override() {
+    let mut ob_client = offline_client(0, 0);
+    ob_client.market_info.events_authority = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let open_orders_accounts = vec![Pubkey::new_unique()];
+
+    let default_ix = ob_client
+        .build_consume_events_permissioned_instruction(open_orders_accounts.clone(), 10, None)
+        .unwrap();
+    let override_ix = ob_client
+        .build_consume_events_permissioned_instruction(open_orders_accounts, 10, Some(authority))
+        .unwrap();
+
+    let signers = |ix: &Instruction| -> Vec<Pubkey> {
+        ix.accounts
+            .iter()
+            .filter(|meta| meta.is_signer)
+            .map(|meta| meta.pubkey)
+            .collect()
+    };
+
+    assert_eq!(
+        signers(&default_ix),
+        vec![ob_client.market_info.events_authority]
+    );
+    assert_eq!(signers(&override_ix), vec![authority]);
+}
+
+#[test]
+fn test_market_price_empty_book() {
+    let reader = offline_reader(0, 0);
+
+    assert_eq!(
+        reader.market_price(),
+        MarketPrice {
+            best_bid: None,
+            best_ask: None,
+            mid: None,
+            has_liquidity: false,
+        }
+    );
+}
+
+#[test]
+fn test_market_price_two_sided_book() {
+    let reader = offline_reader(1_990, 2_010);
+    let market_price = reader.market_price();
+
+    assert_eq!(market_price.best_bid, Some(1.99));
+    assert_eq!(market_price.best_ask, Some(2.01));
+    assert!((market_price.mid.unwrap() - 2.0).abs() < 1e-9);
+    assert!(market_price.has_liquidity);
+}
+
+#[tokio::test]
+async fn test_best_quotes_and_mid_price() {
+    let reader = offline_reader(1_990, 2_010);
+    let quotes = reader.get_best_quotes().await.unwrap();
+    assert_eq!(quotes.highest_bid, Some(1.99));
+    assert_eq!(quotes.lowest_ask, Some(2.01));
+    assert!((reader.get_mid_price().await.unwrap() - 2.0).abs() < 1e-9);
+
+    // A one-sided book reports the available side only, and has no mid.
+    let reader = offline_reader(1_990, 0);
+    let quotes = reader.get_best_quotes().await.unwrap();
+    assert_eq!(quotes.highest_bid, Some(1.99));
+    assert_eq!(quotes.lowest_ask, None);
+    assert!(reader.get_mid_price().await.is_err());
+}
+
+#[test]
+fn test_spread() {
+    let reader = offline_reader(1_990, 2_010);
+
+    assert!((reader.spread().unwrap() - 0.02).abs() < 1e-9);
+    assert_eq!(offline_reader(1_990, 0).spread(), None);
+    assert_eq!(offline_reader(0, 2_010).spread(), None);
+}
+
+#[test]
+fn test_price_levels() {
+    let reader = offline_reader(0, 0);
+
+    let levels = reader.price_levels(1.995, 2.0, 2);
+    let expected = [1.995, 1.997, 1.999];
+    assert_eq!(levels.len(), expected.len());
+    for (level, expected) in levels.iter().zip(expected) {
+        assert!((level - expected).abs() < 1e-9);
+    }
+
+    // Off-tick bounds snap inwards to the nearest valid ticks.
+    let levels = reader.price_levels(1.9955, 1.9985, 1);
+    assert_eq!(levels.len(), 3);
+    assert!((levels[0] - 1.996).abs() < 1e-9);
+    assert!((levels[2] - 1.998).abs() < 1e-9);
+
+    assert!(reader.price_levels(2.0, 1.0, 1).is_empty());
+    assert!(reader.price_levels(1.0, 2.0, 0).is_empty());
+}
+
+#[test]
+fn test_apply_open_orders_account_keeps_book() {
+    let mut reader = offline_reader(1_990, 2_010);
+    let owner = Pubkey::new_unique();
+    let bid_id = (1_980u128 << 64) | 1;
+    let ask_id = (2_020u128 << 64) | 2;
+    let data = open_orders_account_data(
+        &MARKET_ID.parse().unwrap(),
+        &owner,
+        (0, 2_000_000_000),
+        (500_000, 4_460_000),
+        &[
+            RecordedOrder {
+                slot: 0,
+                order_id: bid_id,
+                client_order_id: 11,
+                is_bid: true,
+            },
+            RecordedOrder {
+                slot: 3,
+                order_id: ask_id,
+                client_order_id: 12,
+                is_bid: false,
+            },
+        ],
+    );
+
+    reader.apply_open_orders_account(&data).unwrap();
+
+    assert_eq!(reader.open_orders.open_bids, vec![bid_id]);
+    assert_eq!(reader.open_orders.open_asks, vec![ask_id]);
+    assert_eq!(reader.open_orders.base_total, 2.0);
+    assert_eq!(reader.open_orders.quote_total, 4.46);
+    assert_eq!(reader.open_orders.max_bid, 1_990);
+    assert_eq!(reader.open_orders.min_ask, 2_010);
+    assert!(reader.open_orders.exists);
+}
+
+#[test]
+fn test_load_without_open_orders_account() {
+    let mut reader = offline_reader(1_990, 2_010);
+    assert!(!reader.open_orders.exists);
+
+    let data = open_orders_account_data(
+        &MARKET_ID.parse().unwrap(),
+        &Pubkey::new_unique(),
+        (0, 2_000_000_000),
+        (0, 0),
+        &[RecordedOrder {
+            slot: 0,
+            order_id: (1_980u128 << 64) | 1,
+            client_order_id: 11,
+            is_bid: true,
+        }],
+    );
+    reader.apply_open_orders_account(&data).unwrap();
+    reader.open_orders.mark_missing();
+
+    assert!(!reader.open_orders.exists);
+    assert!(reader.open_orders.open_bids.is_empty());
+    assert!(reader.open_orders.open_bids_prices.is_empty());
+    assert!(reader.open_orders.open_asks.is_empty());
+    assert_eq!(reader.open_orders.base_total, 0.0);
+    assert_eq!(reader.open_orders.quote_total, 0.0);
+    assert_eq!(reader.open_orders.max_bid, 1_990);
+    assert_eq!(reader.open_orders.min_ask, 2_010);
+}
+
+#[tokio::test]
+async fn test_limit_order_reserves_taker_fee() {
+    async fn max_native_pc(ob_client: &OBClient, order_type: OrderType) -> u64 {
+        let params = LimitOrderParams {
+            order_type,
+            ..Default::default()
+        };
+        let instructions = match ob_client
+            .place_limit_order_with_params(5.0, Side::Bid, 0.0, false, 0.0, params)
+            .await
+            .unwrap()
+        {
+            Some(OrderReturnType::Instructions(instructions)) => instructions,
+            other => panic!("unexpected result: {:?}", other),
+        };
+        match MarketInstruction::unpack(&instructions[0].data) {
+            Some(MarketInstruction::NewOrderV3(order)) => {
+                order.max_native_pc_qty_including_fees.get()
+            }
+            other => panic!("unexpected instruction: {:?}", other),
+        }
+    }
+
+    // 2_500 base lots at the best bid of 2_000 price lots.
+    let mut ob_client = offline_client(2_000, 2_010);
+    assert_eq!(ob_client.market_info.taker_fee_bps, 0.0);
+    assert_eq!(max_native_pc(&ob_client, OrderType::Limit).await, 5_000_000);
+
+    ob_client.market_info.taker_fee_bps = 22.0;
+    assert_eq!(ob_client.market_info.taker_fee(5_000_000), 11_000);
+    assert_eq!(max_native_pc(&ob_client, OrderType::Limit).await, 5_011_000);
+    assert_eq!(
+        max_native_pc(&ob_client, OrderType::ImmediateOrCancel).await,
+        5_011_000
+    );
+    assert_eq!(
+        max_native_pc(&ob_client, OrderType::PostOnly).await,
+        5_000_000
+    );
+}
+
+#[tokio::test]
+async fn test_bid_cost_estimate_includes_taker_fee() {
+    let mut ob_client = offline_client(2_000, 2_010);
+    ob_client.rpc_client = Rpc::with_provider(MockRpc::new());
+
+    // 2_500 base lots at 2_000 price lots lock 5 quote, plus 22 bps when the bid crosses.
+    let estimate = ob_client.bid_cost_estimate(2.0, 2.5).await.unwrap();
+    assert_eq!(estimate.quote_needed, 5.0);
+
+    ob_client.market_info.taker_fee_bps = 22.0;
+    let estimate = ob_client.bid_cost_estimate(2.0, 2.5).await.unwrap();
+    assert_eq!(estimate.quote_needed, 5.011);
+}
+
+#[test]
+fn test_market_order_empty_book() {
+    let ob_client = offline_client(1_990, 0);
+
+    assert!(ob_client
+        .build_market_order_instruction(Side::Bid, 5.0, 50, 1)
+        .unwrap()
+        .is_none());
+    assert!(ob_client
+        .build_market_order_instruction(Side::Ask, 5.0, 50, 1)
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn test_market_order_crosses_with_slippage() {
+    let ob_client = offline_client(1_990, 2_010);
+
+    let ix = ob_client
+        .build_market_order_instruction(Side::Bid, 5.0, 50, 7)
+        .unwrap()
+        .unwrap();
+
+    match MarketInstruction::unpack(&ix.data) {
+        Some(MarketInstruction::NewOrderV3(order)) => {
+            assert_eq!(order.side, Side::Bid);
+            assert_eq!(order.order_type, OrderType::ImmediateOrCancel);
+            // 2.01 * 1.005 = 2.02005, truncated to 2_020 price lots.
+            assert_eq!(order.limit_price.get(), 2_020);
+            assert_eq!(order.client_order_id, 7);
+        }
+        other => panic!("unexpected instruction: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_place_limit_order_with_params() {
+    let ob_client = offline_client(1_990, 2_010);
+    let params = LimitOrderParams {
+        order_type: OrderType::Limit,
+        self_trade_behavior: SelfTradeBehavior::DecrementTake,
+        client_order_id: Some(42),
+        expiry: Expiry::Never,
+        allow_off_market: false,
+    };
+
+    let instructions = match ob_client
+        .place_limit_order_with_params(5.0, Side::Bid, 0.0, false, 0.0, params)
+        .await
+        .unwrap()
+    {
+        Some(OrderReturnType::Instructions(instructions)) => instructions,
+        other => panic!("unexpected result: {:?}", other),
+    };
+
+    match MarketInstruction::unpack(&instructions[0].data) {
+        Some(MarketInstruction::NewOrderV3(order)) => {
+            assert_eq!(order.order_type, OrderType::Limit);
+            assert_eq!(order.self_trade_behavior, SelfTradeBehavior::DecrementTake);
+            assert_eq!(order.client_order_id, 42);
+            assert_eq!(order.max_ts, i64::MAX);
+        }
+        other => panic!("unexpected instruction: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_place_limit_order_outcome() {
+    let ob_client = offline_client(1_990, 2_010);
+
+    // 0.001 quote at 1.99 is half a base lot.
+    let outcome = ob_client
+        .place_limit_order_outcome(
+            0.001,
+            Side::Bid,
+            0.0,
+            false,
+            0.0,
+            LimitOrderParams::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        outcome,
+        PlaceOrderOutcome::BelowMinimumSize {
+            computed_base_lots: 0
+        }
+    );
+    assert!(Option::<OrderReturnType>::from(outcome).is_none());
+
+    let outcome = ob_client
+        .place_limit_order_outcome(5.0, Side::Bid, 0.0, false, 0.0, LimitOrderParams::default())
+        .await
+        .unwrap();
+    assert!(matches!(outcome, PlaceOrderOutcome::Instructions(ref ixs) if ixs.len() == 1));
+    assert!(matches!(
+        Option::<OrderReturnType>::from(outcome),
+        Some(OrderReturnType::Instructions(_))
+    ));
+
+    let empty = offline_client(0, 0);
+    let outcome = empty
+        .place_limit_order_outcome(5.0, Side::Ask, 0.0, false, 0.0, LimitOrderParams::default())
+        .await
+        .unwrap();
+    assert_eq!(outcome, PlaceOrderOutcome::EmptyBook);
+}
+
+#[test]
+fn test_build_orders_batch_skips_zero_lot_orders() {
+    let ob_client = offline_client(1_990, 2_010);
+    let spec = |side: Side, target_amount_quote: f64, price: f64| LimitOrderSpec {
+        side,
+        target_amount_quote,
+        price,
+        params: LimitOrderParams::default(),
+    };
+
+    let batches = ob_client
+        .build_orders_batch(
+            &[
+                spec(Side::Bid, 5.0, 1.98),
+                spec(Side::Bid, 0.0001, 1.97),
+                spec(Side::Ask, 5.0, 2.02),
+            ],
+            DEFAULT_MAX_IX_PER_TX,
+        )
+        .unwrap();
+
+    assert_eq!(batches.len(), 1);
+    let sides: Vec<Side> = batches[0]
+        .iter()
+        .map(|ix| match MarketInstruction::unpack(&ix.data) {
+            Some(MarketInstruction::NewOrderV3(order)) => order.side,
+            other => panic!("unexpected instruction: {:?}", other),
+        })
+        .collect();
+    assert_eq!(sides, vec![Side::Bid, Side::Ask]);
+}
+
+#[test]
+fn test_round_price_to_tick() {
+    let mut ob_client = offline_client(1_990, 2_010);
+
+    assert!((ob_client.round_price_to_tick(1.9955, Side::Bid) - 1.996).abs() < 1e-9);
+    assert!((ob_client.round_price_to_tick(1.9955, Side::Ask) - 1.995).abs() < 1e-9);
+    assert!((ob_client.round_price_to_tick(1.997, Side::Bid) - 1.997).abs() < 1e-9);
+    assert!((ob_client.round_price_to_tick(1.997, Side::Ask) - 1.997).abs() < 1e-9);
+    assert!((ob_client.round_price_to_tick(0.0001, Side::Ask) - 0.001).abs() < 1e-9);
+
+    let limit_price = |ob_client: &OBClient| {
+        let batches = ob_client
+            .build_orders_batch(
+                &[LimitOrderSpec {
+                    side: Side::Bid,
+                    target_amount_quote: 5.0,
+                    price: 1.9855,
+                    params: LimitOrderParams::default(),
+                }],
+                DEFAULT_MAX_IX_PER_TX,
+            )
+            .unwrap();
+        match MarketInstruction::unpack(&batches[0][0].data) {
+            Some(MarketInstruction::NewOrderV3(order)) => order.limit_price.get(),
+            other => panic!("unexpected instruction: {:?}", other),
+        }
+    };
+
+    assert_eq!(limit_price(&ob_client), 1_985);
+    ob_client.round_to_tick = true;
+    assert_eq!(limit_price(&ob_client), 1_986);
+}
+
+#[test]
+fn test_custom_program_id_flows_into_new_order() {
+    let mut ob_client = offline_client(1_990, 2_010);
+    let program_id = Pubkey::new_unique();
+    ob_client.market_info.program_id = program_id;
+
+    let ix = ob_client
+        .build_market_order_instruction(Side::Bid, 5.0, 50, 1)
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(ix.program_id, program_id);
+}
+
+#[tokio::test]
+async fn test_with_program_id_targets_the_deployment() {
+    let mut ob_client = offline_client(1_990, 2_010);
+    ob_client.round_to_tick = true;
+    ob_client.open_orders.oo_key = resolve_open_orders(
+        &ob_client.owner.pubkey(),
+        &ob_client.market_info.market_address,
+        &ob_client.market_info.program_id,
+        None,
+    );
+    let program_id = Pubkey::new_unique();
+    let mut market = ob_client.market_info.clone();
+    market.program_id = program_id;
+    market.bids_address = Pubkey::new_unique();
+    market.asks_address = Pubkey::new_unique();
+    // The Initialized flag with the Bids or Asks flag.
+    let mock = MockRpc::new()
+        .with_account(
+            market.market_address,
+            program_id,
+            market_account_data(&market),
+        )
+        .with_account(market.bids_address, program_id, slab_account_data(1 | 32))
+        .with_account(market.asks_address, program_id, slab_account_data(1 | 64));
+    ob_client.rpc_client = Rpc::with_provider(mock);
+
+    let mut forked = ob_client.with_program_id(program_id).await.unwrap();
+
+    assert_eq!(forked.market_info.program_id, program_id);
+    assert_eq!(
+        forked.open_orders.oo_key,
+        resolve_open_orders(
+            &ob_client.owner.pubkey(),
+            &market.market_address,
+            &program_id,
+            None
+        )
+    );
+    assert!(!forked.open_orders.exists);
+    assert!(forked.round_to_tick);
+    // The book of the deployment is empty, so quote against the same book as before.
+    forked.open_orders.max_bid = 1_990;
+    forked.open_orders.min_ask = 2_010;
+    let ix = forked
+        .build_market_order_instruction(Side::Bid, 5.0, 50, 1)
+        .unwrap()
+        .unwrap();
+    assert_eq!(ix.program_id, program_id);
+}
+
+#[tokio::test]
+async fn test_cancel_orders_settle_on_cancel() {
+    let mut ob_client = offline_client(1_990, 2_010);
+    ob_client.open_orders.open_bids = vec![(1_980u128 << 64) | 1];
+    ob_client.open_orders.open_asks = vec![(2_020u128 << 64) | 2];
+
+    let instructions = |ret: Option<OrderReturnType>| match ret {
+        Some(OrderReturnType::Instructions(ixs)) => ixs,
+        _ => panic!("expected instructions"),
+    };
+    let without_settle = instructions(ob_client.cancel_orders(false, false).await.unwrap());
+    let with_settle = instructions(ob_client.cancel_orders(false, true).await.unwrap());
+
+    assert_eq!(without_settle.len(), 2);
+    assert_eq!(with_settle.len(), 3);
+    assert_eq!(with_settle[..2], without_settle[..]);
+    assert!(matches!(
+        MarketInstruction::unpack(&with_settle[2].data),
+        Some(MarketInstruction::SettleFunds)
+    ));
+}
+
+#[tokio::test]
+async fn test_ensure_atas_precede_settle() {
+    let mut ob_client = offline_client(1_990, 2_010);
+    ob_client.market_info.base_mint = Pubkey::new_unique();
+    ob_client.market_info.quote_mint = Pubkey::new_unique();
+    ob_client.open_orders.open_bids = vec![(1_980u128 << 64) | 1];
+    let owner = ob_client.owner.pubkey();
+
+    assert!(ob_client
+        .build_create_atas_instructions(true, true)
+        .unwrap()
+        .is_empty());
+    let quote_only = ob_client
+        .build_create_atas_instructions(true, false)
+        .unwrap();
+    assert_eq!(quote_only.len(), 1);
+    assert_eq!(
+        quote_only[0].accounts[1].pubkey,
+        get_associated_token_address(&owner, &ob_client.market_info.quote_mint)
+    );
+
+    // Neither ATA exists.
+    let ata_instructions = ob_client
+        .build_create_atas_instructions(false, false)
+        .unwrap();
+    assert_eq!(ata_instructions.len(), 2);
+    assert_eq!(
+        ata_instructions[0].accounts[1].pubkey,
+        get_associated_token_address(&owner, &ob_client.market_info.base_mint)
+    );
+
+    let cancel = match ob_client.cancel_orders(false, false).await.unwrap() {
+        Some(OrderReturnType::Instructions(ixs)) => ixs,
+        _ => panic!("expected instructions"),
+    };
+    let settle = ob_client.build_settle_funds_instruction().unwrap();
+    let mut steps = HashMap::new();
+    steps.insert(ComboStep::Cancel, cancel.clone());
+    steps.insert(ComboStep::Settle, vec![settle.clone()]);
+    prepend_step_instructions(&mut steps, ComboStep::Settle, ata_instructions.clone());
+
+    let instructions = order_combo_instructions(&ComboStep::default_order(), steps).unwrap();
+
+    let expected: Vec<Instruction> = cancel
+        .into_iter()
+        .chain(ata_instructions)
+        .chain([settle])
+        .collect();
+    assert_eq!(instructions, expected);
+    assert!(instructions[1..3]
+        .iter()
+        .all(|ix| ix.program_id == spl_associated_token_account::id()));
+}
+
+#[tokio::test]
+async fn test_cancel_orders_older_than() {
+    let mut ob_client = offline_client(1_990, 2_010);
+    let stale_bid = (1_980u128 << 64) | 1;
+    let fresh_bid = (1_985u128 << 64) | 4;
+    let stale_ask = (2_020u128 << 64) | 2;
+    let fresh_ask = (2_015u128 << 64) | 3;
+    ob_client.open_orders.open_bids = vec![stale_bid, fresh_bid];
+    ob_client.open_orders.open_asks = vec![fresh_ask, stale_ask];
+    ob_client.open_orders.order_timestamps = HashMap::from([
+        (stale_bid, 1_000),
+        (fresh_bid, 1_250),
+        (stale_ask, 1_100),
+        (fresh_ask, 1_290),
+    ]);
+
+    let (bids, asks) = ob_client
+        .open_orders
+        .orders_older_than(Duration::from_secs(100), 1_300);
+    assert_eq!(bids, vec![stale_bid]);
+    assert_eq!(asks, vec![stale_ask]);
+
+    // Orders seen on a later load keep their first timestamp, and orders gone are forgotten.
+    ob_client.open_orders.open_bids = vec![fresh_bid];
+    ob_client.open_orders.stamp_orders(1_400);
+    assert_eq!(
+        ob_client.open_orders.order_timestamps,
+        HashMap::from([(fresh_bid, 1_250), (stale_ask, 1_100), (fresh_ask, 1_290)])
+    );
+
+    // Against the wall clock, the orders stamped at 1_000..1_300 are all stale.
+    let instructions = match ob_client
+        .cancel_orders_older_than(Duration::from_secs(60), false)
+        .await
+        .unwrap()
+    {
+        Some(OrderReturnType::Instructions(ixs)) => ixs,
+        _ => panic!("expected instructions"),
+    };
+    let canceled: Vec<(Side, u128)> = instructions
+        .iter()
+        .map(|ix| match MarketInstruction::unpack(&ix.data) {
+            Some(MarketInstruction::CancelOrderV2(cancel)) => (cancel.side, cancel.order_id),
+            other => panic!("unexpected instruction {:?}", other),
+        })
+        .collect();
+    assert_eq!(
+        canceled,
+        vec![
+            (Side::Bid, fresh_bid),
+            (Side::Ask, fresh_ask),
+            (Side::Ask, stale_ask)
+        ]
+    );
+
+    // Orders stamped in the future are never old enough.
+    for timestamp in ob_client.open_orders.order_timestamps.values_mut() {
+        *timestamp = u64::MAX;
+    }
+    assert!(ob_client
+        .cancel_orders_older_than(Duration::from_secs(60), false)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_send_reports_metrics() {
+    let mut ob_client = offline_client(1_990, 2_010);
+    let metrics = Arc::new(CounterMetrics::new());
+    let mock = MockRpc::new();
+    ob_client.rpc_client = Rpc::with_provider(mock.clone()).with_metrics(metrics.clone());
+
+    ob_client.settle_balance(true).await.unwrap();
+
+    let counts = metrics.counts();
+    assert_eq!(counts.tx_confirmed, 1);
+    // The blockhash, the send and the confirmation.
+    assert_eq!(counts.rpc_calls, 3);
+    assert_eq!(mock.requests(), 3);
+    assert_eq!(counts.settles, 1);
+
+    // One cancel per order, then the settle.
+    ob_client.open_orders.open_bids = vec![(1_980u128 << 64) | 1, (1_970u128 << 64) | 2];
+    ob_client.cancel_orders(true, true).await.unwrap();
+    let counts = metrics.counts();
+    assert_eq!(counts.orders_canceled, 2);
+    assert_eq!(counts.settles, 2);
+    assert_eq!(counts.orders_placed, 0);
+
+    ob_client
+        .place_limit_order(5.0, Side::Bid, 0., true, 1.98)
+        .await
+        .unwrap();
+    let counts = metrics.counts();
+    assert_eq!(counts.orders_placed, 1);
+    assert_eq!(counts.tx_confirmed, 3);
+}
+
+#[tokio::test]
+async fn test_place_limit_order_durable_resends_a_dropped_order() {
+    let mut ob_client = offline_client(1_990, 2_010);
+    let metrics = Arc::new(CounterMetrics::new());
+    let mut mock = MockRpc::new();
+    mock.dropped_sends = 1;
+    ob_client.rpc_client = Rpc::with_provider(mock.clone()).with_metrics(metrics.clone());
+
+    let outcome = ob_client
+        .place_limit_order_durable(5.0, Side::Bid, 1.98, 3, Duration::ZERO)
+        .await
+        .unwrap();
+
+    let sent = mock.sent();
+    assert_eq!(sent.len(), 2);
+    assert_eq!(outcome.attempts, 2);
+    assert!(outcome.confirmed);
+    assert_eq!(outcome.signature, Some(sent[1].signatures[0]));
+    // The re-send is signed with a fresh blockhash, under the same client order id.
+    assert_ne!(
+        sent[0].message.recent_blockhash,
+        sent[1].message.recent_blockhash
+    );
+    for transaction in &sent {
+        match MarketInstruction::unpack(&transaction.message.instructions[0].data) {
+            Some(MarketInstruction::NewOrderV3(order)) => {
+                assert_eq!(order.client_order_id, outcome.client_order_id)
+            }
+            other => panic!("unexpected instruction: {:?}", other),
+        }
+    }
+    let counts = metrics.counts();
+    assert_eq!(counts.tx_failed, 1);
+    assert_eq!(counts.tx_confirmed, 1);
+    assert_eq!(counts.orders_placed, 1);
+}
+
+#[tokio::test]
+async fn test_missing_open_orders_fails_before_sending() {
+    let mut ob_client = offline_client(1_990, 2_010);
+    ob_client.open_orders.exists = false;
+    ob_client.open_orders.open_bids = vec![(1_980u128 << 64) | 1];
+    let mock = MockRpc::new();
+    ob_client.rpc_client = Rpc::with_provider(mock.clone());
+
+    assert!(!ob_client.open_orders_initialized());
+    assert!(matches!(
+        ob_client.settle_balance(true).await,
+        Err(OpenBookError::OpenOrdersNotInitialized(key)) if key == ob_client.open_orders.oo_key
+    ));
+    assert!(matches!(
+        ob_client.cancel_orders(true, false).await,
+        Err(OpenBookError::OpenOrdersNotInitialized(_))
+    ));
+    assert_eq!(mock.requests(), 0);
+
+    // Building the instructions doesn't need the account.
+    assert!(ob_client.settle_balance(false).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_init_open_orders_keeps_overridden_account() {
+    let mut ob_client = offline_client(1_990, 2_010);
+    ob_client.open_orders.exists = false;
+    ob_client.open_orders.oo_key = Pubkey::new_unique();
+    let mock = MockRpc::new();
+    ob_client.rpc_client = Rpc::with_provider(mock.clone());
+
+    assert!(ob_client.init_open_orders().await.is_err());
+    assert_eq!(mock.requests(), 0);
+    assert!(!ob_client.open_orders.exists);
+}
+
+#[tokio::test]
+async fn test_order_placer() {
+    let mut ob_client = offline_client(1_990, 2_010);
+    let mock = MockRpc::new();
+    ob_client.rpc_client = Rpc::with_provider(mock.clone());
+    let placer: Box<dyn OrderPlacer> = Box::new(ob_client);
+
+    let signature = placer.settle().await.unwrap();
+    assert_eq!(mock.sent().len(), 1);
+    assert_eq!(signature, mock.sent()[0].signatures[0]);
+
+    // No open orders, nothing to cancel.
+    assert_eq!(placer.cancel_all().await.unwrap(), None);
+    assert!(matches!(
+        placer.place_limit(OrderSide::Bid, 1.99, 0.001).await,
+        Err(OpenBookError::OrderTooSmall(_))
+    ));
+    assert_eq!(mock.sent().len(), 1);
+
+    // The bid doesn't rest in the open orders account, so only its client order id is known.
+    let handle = placer.place_limit(OrderSide::Bid, 1.99, 5.0).await.unwrap();
+    assert_eq!(mock.sent().len(), 2);
+    assert_eq!(handle.signature, mock.sent()[1].signatures[0]);
+    assert_eq!(handle.order_id, None);
+    match MarketInstruction::unpack(&mock.sent()[1].message.instructions.last().unwrap().data) {
+        Some(MarketInstruction::NewOrderV3(order)) => {
+            assert_eq!(order.client_order_id, handle.client_order_id)
+        }
+        other => panic!("unexpected instruction: {:?}", other),
+    }
+}
+
+#[test]
+fn test_open_orders_detailed() {
+    let mut reader = offline_reader(1_990, 2_010);
+    let bid = (1_980u128 << 64) | 1;
+    let ask = (2_020u128 << 64) | 2;
+    reader.open_orders.open_bids = vec![bid];
+    reader.open_orders.open_asks = vec![ask];
+    reader.open_orders.order_quantities = HashMap::from([(bid, 500), (ask, 2_000)]);
+    reader.open_orders.client_order_ids = HashMap::from([(bid, 42)]);
+
+    assert_eq!(
+        reader.open_orders_detailed(),
+        vec![
+            OpenOrderInfo {
+                order_id: bid,
+                side: Side::Bid,
+                price: 1.98,
+                size: 0.5,
+                client_id: 42,
+            },
+            OpenOrderInfo {
+                order_id: ask,
+                side: Side::Ask,
+                price: 2.02,
+                size: 2.0,
+                client_id: 0,
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_cancel_single_order() {
+    let mut ob_client = offline_client(1_990, 2_010);
+    let bid = (1_980u128 << 64) | 1;
+    let ask = (2_020u128 << 64) | 2;
+    ob_client.open_orders.open_bids = vec![bid];
+    ob_client.open_orders.open_asks = vec![ask];
+
+    let ix = match ob_client
+        .cancel_order_by_id(Side::Ask, ask, false)
+        .await
+        .unwrap()
+    {
+        Some(OrderReturnType::Instructions(ixs)) if ixs.len() == 1 => ixs[0].clone(),
+        _ => panic!("expected one instruction"),
+    };
+    match MarketInstruction::unpack(&ix.data) {
+        Some(MarketInstruction::CancelOrderV2(cancel)) => {
+            assert_eq!((cancel.side, cancel.order_id), (Side::Ask, ask))
+        }
+        other => panic!("unexpected instruction {:?}", other),
+    }
+
+    // The id of a bid isn't an open ask.
+    assert!(ob_client
+        .cancel_order_by_id(Side::Ask, bid, false)
+        .await
+        .unwrap()
+        .is_none());
+
+    let owner = ob_client.owner.pubkey();
+    let mock = MockRpc::new().with_account(
+        ob_client.open_orders.oo_key,
+        ob_client.market_info.program_id,
+        open_orders_account_data(
+            &ob_client.market_info.market_address,
+            &owner,
+            (0, 0),
+            (0, 0),
+            &[RecordedOrder {
+                slot: 0,
+                order_id: bid,
+                client_order_id: 42,
+                is_bid: true,
+            }],
+        ),
+    );
+    ob_client.rpc_client = Rpc::with_provider(mock.clone());
+
+    let ix = match ob_client
+        .cancel_order_by_client_id(42, false)
+        .await
+        .unwrap()
+    {
+        Some(OrderReturnType::Instructions(ixs)) if ixs.len() == 1 => ixs[0].clone(),
+        _ => panic!("expected one instruction"),
+    };
+    assert!(matches!(
+        MarketInstruction::unpack(&ix.data),
+        Some(MarketInstruction::CancelOrderByClientIdV2(42))
+    ));
+
+    assert!(ob_client
+        .cancel_order_by_client_id(7, false)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(mock.sent().is_empty());
+}
+
+#[tokio::test]
+async fn test_match_orders_transaction_counts_the_matches_of_a_recorded_transaction() {
+    let mut ob_client = offline_client(1_990, 2_010);
+    ob_client.market_info.event_queue = Pubkey::new_unique();
+    let program_id = ob_client.market_info.program_id;
+    let event_queue = ob_client.market_info.event_queue;
+    let (taker, maker) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let out = event_data(EVENT_FLAG_OUT, 9, &maker);
+    // The recorded match transaction filled a bid of another trader than the cranker against
+    // two asks resting on the book.
+    let matched = vec![
+        out.clone(),
+        event_data(EVENT_FLAG_FILL | EVENT_FLAG_BID, 1, &taker),
+        event_data(EVENT_FLAG_FILL | EVENT_FLAG_MAKER, 2, &maker),
+        event_data(EVENT_FLAG_FILL | EVENT_FLAG_BID, 1, &taker),
+        event_data(EVENT_FLAG_FILL | EVENT_FLAG_MAKER, 3, &maker),
+    ];
+    let mock = MockRpc::new()
+        .with_account(event_queue, program_id, event_queue_data(0, 1, 41, &[out]))
+        .with_send_effect(move |_| {
+            vec![(
+                event_queue,
+                program_id,
+                event_queue_data(0, 5, 45, &matched),
+            )]
+        });
+    ob_client.rpc_client = Rpc::with_provider(mock.clone());
+
+    let result = ob_client.match_orders_transaction(100).await.unwrap();
+
+    let sent = mock.sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(result.signature, sent[0].signatures[0]);
+    assert!(matches!(
+        MarketInstruction::unpack(&sent[0].message.instructions[0].data),
+        Some(MarketInstruction::MatchOrders(100))
+    ));
+    assert!(result.confirmed);
+    assert_eq!(result.matched, 2);
+
+    // Nothing is left to match once the book no longer crosses.
+    let result = ob_client.match_orders_transaction(100).await.unwrap();
+    assert_eq!(result.matched, 0);
+}
+
+#[tokio::test]
+async fn test_place_limit_order_tracked_reports_filled_orders() {
+    let mut ob_client = offline_client(1_990, 2_010);
+    let owner = ob_client.owner.pubkey();
+    // The open orders account holds no order, as if the bid was filled as it was placed.
+    let mock = MockRpc::new().with_account(
+        ob_client.open_orders.oo_key,
+        ob_client.market_info.program_id,
+        open_orders_account_data(
+            &ob_client.market_info.market_address,
+            &owner,
+            (0, 0),
+            (0, 0),
+            &[],
+        ),
+    );
+    ob_client.rpc_client = Rpc::with_provider(mock.clone());
+
+    let placed = ob_client
+        .place_limit_order_tracked(5.0, Side::Bid, 2.01)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(mock.sent().len(), 1);
+    let sent = &mock.sent()[0];
+    assert_eq!(placed.signature, sent.signatures[0]);
+    assert_eq!(placed.order_id, None);
+    assert_eq!(placed.slot, 1);
+    match MarketInstruction::unpack(&sent.message.instructions.last().unwrap().data) {
+        Some(MarketInstruction::NewOrderV3(order)) => {
+            assert_eq!(order.client_order_id, placed.client_order_id)
+        }
+        other => panic!("unexpected instruction: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_place_limit_order_tracked_decodes_the_order_id() {
+    let mut ob_client = offline_client(1_990, 2_010);
+    let owner = ob_client.owner.pubkey();
+    let market = ob_client.market_info.market_address;
+    let program_id = ob_client.market_info.program_id;
+    let oo_key = ob_client.open_orders.oo_key;
+    let order_id = (1_980u128 << 64) | !7u64 as u128;
+    // The recorded place transaction rests the bid in the first slot of the open orders account.
+    let mock = MockRpc::new().with_send_effect(move |transaction| {
+        let client_order_id =
+            match MarketInstruction::unpack(&transaction.message.instructions.last().unwrap().data)
+            {
+                Some(MarketInstruction::NewOrderV3(order)) => order.client_order_id,
+                other => panic!("unexpected instruction: {:?}", other),
+            };
+        let data = open_orders_account_data(
+            &market,
+            &owner,
+            (0, 0),
+            (0, 9_900_000),
+            &[RecordedOrder {
+                slot: 0,
+                order_id,
+                client_order_id,
+                is_bid: true,
+            }],
+        );
+        vec![(oo_key, program_id, data)]
+    });
+    ob_client.rpc_client = Rpc::with_provider(mock.clone());
+
+    let placed = ob_client
+        .place_limit_order_tracked(5.0, Side::Bid, 1.98)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(mock.sent().len(), 1);
+    assert_eq!(placed.signature, mock.sent()[0].signatures[0]);
+    assert_eq!(placed.order_id, Some(order_id));
+    assert_eq!(placed.slot, 1);
+}
+
+#[tokio::test]
+async fn test_place_limit_order_guarded() {
+    let mut ob_client = offline_client(1_990, 2_010);
+    let owner = ob_client.owner.pubkey();
+    let mock = MockRpc::new().with_account(
+        ob_client.open_orders.oo_key,
+        ob_client.market_info.program_id,
+        open_orders_account_data(
+            &ob_client.market_info.market_address,
+            &owner,
+            (0, 0),
+            (0, 0),
+            &[RecordedOrder {
+                slot: 0,
+                order_id: (1_980u128 << 64) | 1,
+                client_order_id: 42,
+                is_bid: true,
+            }],
+        ),
+    );
+    ob_client.rpc_client = Rpc::with_provider(mock.clone());
+    let params = LimitOrderParams {
+        client_order_id: Some(42),
+        expiry: Expiry::At(4_000_000_000),
+        ..Default::default()
+    };
+    let instruction_of = |transaction: &Transaction| {
+        let ix = transaction.message.instructions.last().unwrap();
+        MarketInstruction::unpack(&ix.data)
+    };
+
+    let guard = ob_client
+        .place_limit_order_guarded(5.0, Side::Bid, 1.98, params)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(guard.client_order_id(), 42);
+    assert_eq!(guard.signature(), mock.sent()[0].signatures[0]);
+    match instruction_of(&mock.sent()[0]) {
+        Some(MarketInstruction::NewOrderV3(order)) => assert_eq!(order.max_ts, 4_000_000_000),
+        other => panic!("unexpected instruction: {:?}", other),
+    }
+
+    assert!(guard.cancel().await.unwrap().is_some());
+    assert_eq!(mock.sent().len(), 2);
+    assert!(matches!(
+        instruction_of(&mock.sent()[1]),
+        Some(MarketInstruction::CancelOrderByClientIdV2(42))
+    ));
+
+    // A released order stays on the book.
+    let guard = ob_client
+        .place_limit_order_guarded(5.0, Side::Bid, 1.98, params)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(guard.release(), 42);
+    assert_eq!(mock.sent().len(), 3);
+
+    // Dropping the guard cancels in the background.
+    let guard = ob_client
+        .place_limit_order_guarded(5.0, Side::Bid, 1.98, params)
+        .await
+        .unwrap()
+        .unwrap();
+    drop(guard);
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while mock.sent().len() < 5 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert!(matches!(
+        instruction_of(&mock.sent()[4]),
+        Some(MarketInstruction::CancelOrderByClientIdV2(42))
+    ));
+}
+
+#[test]
+fn test_market_json_roundtrip() {
+    let reader = offline_reader(1_990, 2_010);
+    let mut market = reader.market_info.clone();
+    market.events_authority = Pubkey::new_unique();
+
+    let json = market.to_json().unwrap();
+    assert!(json.contains(&format!("\"market_address\": \"{}\"", MARKET_ID)));
+
+    let restored = Market::from_json(&json).unwrap();
+    assert_eq!(restored.market_address, market.market_address);
+    assert_eq!(restored.events_authority, market.events_authority);
+    assert_eq!(restored.coin_lot_size, market.coin_lot_size);
+    assert_eq!(restored.price_factor(), market.price_factor());
+
+    assert!(Market::from_json(&json.replace(MARKET_ID, "not-a-pubkey")).is_err());
+}
+
+#[tokio::test]
+async fn test_find_open_orders_accounts_for_owner_cache() {
+    let mock = MockRpc::new();
+    let mut ob_client = offline_client(1_990, 2_010);
+    ob_client.rpc_client = Rpc::with_provider(mock.clone());
+    ob_client.market_info.bids_address = Pubkey::new_unique();
+    ob_client.market_info.asks_address = Pubkey::new_unique();
+    let owner = ob_client.owner.pubkey();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis();
+    ob_client.open_orders_cache.insert(
+        owner,
+        OpenOrdersCacheEntry {
+            open_orders: ob_client.open_orders.clone(),
+            ts: now,
+        },
+    );
+
+    let open_orders = ob_client
+        .find_open_orders_accounts_for_owner(owner, 60_000)
+        .await
+        .unwrap();
+    assert_eq!(open_orders.max_bid, 1_990);
+    assert_eq!(open_orders.min_ask, 2_010);
+    assert_eq!(mock.requests(), 0);
+
+    // An entry stamped in the future doesn't underflow and is still fresh.
+    ob_client.open_orders_cache.get_mut(&owner).unwrap().ts = now + 1_000;
+    assert!(ob_client
+        .find_open_orders_accounts_for_owner(owner, 60_000)
+        .await
+        .is_ok());
+    assert_eq!(mock.requests(), 0);
+
+    // Another owner is looked up with a filtered scan on every call and never cached.
+    let other_owner = Pubkey::new_unique();
+    let other_oo_key = Pubkey::new_unique();
+    let mock = mock.with_account(
+        other_oo_key,
+        ob_client.market_info.program_id,
+        open_orders_account_data(
+            &ob_client.market_info.market_address,
+            &other_owner,
+            (0, 2_000_000_000),
+            (0, 0),
+            &[],
+        ),
+    );
+    for requests in [1, 2] {
+        let open_orders = ob_client
+            .find_open_orders_accounts_for_owner(other_owner, 60_000)
+            .await
+            .unwrap();
+        assert_eq!(open_orders.oo_key, other_oo_key);
+        assert!(open_orders.exists);
+        assert_eq!(open_orders.base_total, 2.0);
+        assert_eq!(mock.requests(), requests);
+    }
+    assert_eq!(ob_client.open_orders_cache.len(), 1);
+    assert!(!ob_client.open_orders_cache.contains_key(&other_owner));
+
+    // An owner without any account gets a missing one.
+    let open_orders = ob_client
+        .find_open_orders_accounts_for_owner(Pubkey::new_unique(), 60_000)
+        .await
+        .unwrap();
+    assert!(!open_orders.exists);
+    assert_eq!(mock.requests(), 3);
+
+    // An expired entry reloads the book, which the mock doesn't hold.
+    assert!(ob_client
+        .find_open_orders_accounts_for_owner(owner, 0)
+        .await
+        .is_err());
+    assert_eq!(mock.requests(), 4);
+}
+
+#[tokio::test]
+async fn test_wallet_snapshot_with_mock_rpc() {
+    let mut ob_client = offline_client(1_990, 2_010);
+    ob_client.base_ata = Pubkey::new_unique();
+    ob_client.quote_ata = Pubkey::new_unique();
+    ob_client.open_orders.oo_key = Pubkey::new_unique();
+    let owner = ob_client.owner.pubkey();
+    let market = ob_client.market_info.market_address;
+    let token_program: Pubkey = SPL_TOKEN_ID.parse().unwrap();
+
+    let mock = MockRpc::new()
+        .with_account(
+            ob_client.base_ata,
+            token_program,
+            token_account_data(&Pubkey::new_unique(), &owner, 2_000_000_000),
+        )
+        .with_account(
+            ob_client.quote_ata,
+            token_program,
+            token_account_data(&Pubkey::new_unique(), &owner, 15_000_000),
+        )
+        .with_account(
+            ob_client.open_orders.oo_key,
+            ob_client.market_info.program_id,
+            open_orders_account_data(
+                &market,
+                &owner,
+                (1_000_000_000, 1_000_000_000),
+                (2_000_000, 5_000_000),
+                &[],
+            ),
+        );
+    ob_client.rpc_client = Rpc::with_provider(mock.clone());
+
+    let snapshot = ob_client.wallet_snapshot().await.unwrap();
+
+    assert_eq!(snapshot.base_balance, 2.0);
+    assert_eq!(snapshot.quote_balance, 15.0);
+    assert_eq!(snapshot.base_free, 1.0);
+    assert_eq!(snapshot.quote_free, 2.0);
+    assert_eq!(snapshot.quote_in_orders, 3.0);
+    assert_eq!(mock.requests(), 1);
+}
+
+#[tokio::test]
+async fn test_cancel_settle_sizes_compute_units_from_simulation() {
+    let limit_of = |transaction: &Transaction| transaction.message.instructions[0].data.clone();
+
+    let mut mock = MockRpc::new();
+    mock.units_consumed = Some(100_000);
+    let mut ob_client = offline_client(1_990, 2_010);
+    ob_client.rpc_client = Rpc::with_provider(mock.clone());
+
+    let outcome = ob_client
+        .cancel_settle(PriorityFeeStrategy::Fixed(5), false)
+        .await
+        .unwrap();
+    assert!(outcome.is_confirmed());
+    let sent = mock.sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(outcome.signature(), Some(sent[0].signatures[0]));
+    assert_eq!(
+        limit_of(&sent[0]),
+        ComputeBudgetInstruction::set_compute_unit_limit(115_000).data
+    );
+
+    // A dry run only simulates.
+    let outcome = ob_client
+        .cancel_settle(PriorityFeeStrategy::Fixed(5), true)
+        .await
+        .unwrap();
+    assert!(matches!(outcome, TxOutcome::Simulated(ref simulation) if !simulation.is_err()));
+    assert_eq!(mock.sent().len(), 1);
+
+    // A failed simulation keeps the default limit.
+    let mock = MockRpc::new();
+    ob_client.rpc_client = Rpc::with_provider(mock.clone());
+    ob_client
+        .cancel_settle(PriorityFeeStrategy::Fixed(5), false)
+        .await
+        .unwrap();
+    assert_eq!(
+        limit_of(&mock.sent()[0]),
+        ComputeBudgetInstruction::set_compute_unit_limit(COMBO_COMPUTE_UNIT_LIMIT).data
+    );
+}
+
+#[tokio::test]
+async fn test_unwrap_sol_only_on_native_mint_markets() {
+    let native_mint = spl_token::native_mint::id();
+    let mut ob_client = offline_client(1_990, 2_010);
+    ob_client.base_ata = Pubkey::new_unique();
+    ob_client.quote_ata = Pubkey::new_unique();
+
+    // Enabled on a market without native SOL: nothing to unwrap.
+    ob_client.unwrap_sol = true;
+    assert!(ob_client
+        .build_unwrap_sol_instructions()
+        .unwrap()
+        .is_empty());
+
+    // Native SOL base, disabled.
+    ob_client.market_info.base_mint = native_mint;
+    ob_client.unwrap_sol = false;
+    assert!(ob_client
+        .build_unwrap_sol_instructions()
+        .unwrap()
+        .is_empty());
+
+    // Native SOL base, enabled: the base ATA is closed back to the owner, then created again so
+    // the next settle can land in it.
+    ob_client.unwrap_sol = true;
+    let owner = ob_client.owner.pubkey();
+    let close = spl_token::instruction::close_account(
+        &spl_token::id(),
+        &ob_client.base_ata,
+        &owner,
+        &owner,
+        &[],
+    )
+    .unwrap();
+    let recreate =
+        create_associated_token_account_idempotent(&owner, &owner, &native_mint, &spl_token::id());
+    assert_eq!(
+        ob_client.build_unwrap_sol_instructions().unwrap(),
+        vec![close.clone(), recreate.clone()]
+    );
+
+    // The combo methods close and recreate it after settling.
+    let mut mock = MockRpc::new();
+    mock.units_consumed = Some(100_000);
+    ob_client.rpc_client = Rpc::with_provider(mock.clone());
+    ob_client
+        .cancel_settle(PriorityFeeStrategy::Fixed(5), false)
+        .await
+        .unwrap();
+    let message = &mock.sent()[0].message;
+    let program_of = |index: usize| {
+        let instruction = &message.instructions[message.instructions.len() - index];
+        message.account_keys[instruction.program_id_index as usize]
+    };
+    assert_eq!(program_of(2), spl_token::id());
+    assert_eq!(program_of(1), spl_associated_token_account::id());
+    assert_eq!(
+        message.instructions[message.instructions.len() - 2].data,
+        close.data
+    );
+
+    // Native SOL quote: the quote ATA is closed instead.
+    ob_client.market_info.base_mint = Pubkey::new_unique();
+    ob_client.market_info.quote_mint = native_mint;
+    let instructions = ob_client.build_unwrap_sol_instructions().unwrap();
+    assert_eq!(instructions.len(), 2);
+    assert_eq!(instructions[0].accounts[0].pubkey, ob_client.quote_ata);
+}
+
+#[tokio::test]
+async fn test_preview_combo() {
+    let mut mock = MockRpc::new();
+    mock.prioritization_fees = vec![10, 40, 20, 30];
+    let mut ob_client = offline_client(1_990, 2_010);
+    ob_client.open_orders.open_bids = vec![(1_980u128 << 64) | 1];
+    ob_client.rpc_client = Rpc::with_provider(mock.clone());
+
+    let preview = ob_client
+        .preview_combo(
+            5.0,
+            5.0,
+            1.98,
+            2.02,
+            ComboStep::default_order(),
+            false,
+            PriorityFeeStrategy::Percentile(50),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(preview.priority_fee, 20);
+    assert_eq!(
+        preview.kinds,
+        vec![
+            ComboInstructionKind::SetComputeUnitLimit(FULL_COMBO_COMPUTE_UNIT_LIMIT),
+            ComboInstructionKind::SetComputeUnitPrice(20),
+            ComboInstructionKind::CancelOrder,
+            ComboInstructionKind::SettleFunds,
+            ComboInstructionKind::NewOrder,
+            ComboInstructionKind::NewOrder,
+        ]
+    );
+    assert_eq!(preview.instructions.len(), preview.kinds.len());
+    assert_eq!(
+        preview.compute_unit_limit(),
+        Some(FULL_COMBO_COMPUTE_UNIT_LIMIT)
+    );
+    assert_eq!(preview.max_priority_fee_lamports(), 20);
+
+    // Nothing was simulated or sent.
+    assert_eq!(mock.requests(), 1);
+    assert!(mock.sent().is_empty());
+}
+
+#[tokio::test]
+async fn test_ensure_accounts_skips_existing_open_orders() {
+    let mut ob_client = offline_client(1_990, 2_010);
+    ob_client.open_orders.oo_key = Pubkey::new_unique();
+    ob_client.open_orders.exists = false;
+    let mock = MockRpc::new().with_account(
+        ob_client.open_orders.oo_key,
+        ob_client.market_info.program_id,
+        open_orders_account_data(
+            &ob_client.market_info.market_address,
+            &ob_client.owner.pubkey(),
+            (0, 0),
+            (0, 0),
+            &[],
+        ),
+    );
+    ob_client.rpc_client = Rpc::with_provider(mock.clone());
+
+    let signatures = ob_client.ensure_accounts().await.unwrap();
+
+    assert!(signatures.is_empty());
+    assert!(ob_client.open_orders.exists);
+    assert!(mock.sent().is_empty());
+}
+
+#[test]
+fn test_premium_bps_between_markets() {
+    let spot = offline_reader(1_990, 2_010);
+
+    let premium = spot.premium_bps(&offline_reader(2_010, 2_030)).unwrap();
+    assert!((premium - 100.0).abs() < 1e-9);
+
+    let discount = spot.premium_bps(&offline_reader(1_970, 1_990)).unwrap();
+    assert!((discount + 100.0).abs() < 1e-9);
+
+    // A one-sided book has no mid.
+    assert!(spot.premium_bps(&offline_reader(0, 2_010)).is_none());
+}
+
+fn queue_header(seq_num: u64) -> Vec<u8> {
+    let mut data = vec![0u8; QUEUE_HEADER_SIZE];
+    data[..5].copy_from_slice(b"serum");
+    data[29..37].copy_from_slice(&seq_num.to_le_bytes());
+    data
+}
+
+/// Encodes the data of a market account, with the account flags of an initialized market.
+fn market_account_data(market: &Market) -> Vec<u8> {
+    let mut data = vec![0u8; 388];
+    data[..5].copy_from_slice(b"serum");
+    data[5..13].copy_from_slice(&3u64.to_le_bytes());
+    for (offset, key) in [
+        (13, market.market_address),
+        (53, market.base_mint),
+        (85, market.quote_mint),
+        (117, market.coin_vault),
+        (165, market.pc_vault),
+        (221, market.request_queue),
+        (253, market.event_queue),
+        (285, market.bids_address),
+        (317, market.asks_address),
+    ] {
+        data[offset..offset + 32].copy_from_slice(key.as_ref());
+    }
+    data[349..357].copy_from_slice(&market.coin_lot_size.to_le_bytes());
+    data[357..365].copy_from_slice(&market.pc_lot_size.to_le_bytes());
+    data[381..].copy_from_slice(b"padding");
+    data
+}
+
+#[tokio::test]
+async fn test_new_from_market_id_fetches_market_once() {
+    let mut expected = offline_reader(1_990, 2_010).market_info;
+    for key in [
+        &mut expected.program_id,
+        &mut expected.base_mint,
+        &mut expected.quote_mint,
+        &mut expected.coin_vault,
+        &mut expected.pc_vault,
+        &mut expected.request_queue,
+        &mut expected.event_queue,
+        &mut expected.bids_address,
+        &mut expected.asks_address,
+    ] {
+        *key = Pubkey::new_unique();
+    }
+    let mock = MockRpc::new().with_account(
+        expected.market_address,
+        expected.program_id,
+        market_account_data(&expected),
+    );
+
+    let market = Market::new_from_market_id(
+        Rpc::with_provider(mock.clone()),
+        expected.program_id,
+        expected.market_address,
+        true,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(mock.requests(), 1);
+    assert_eq!(market.base_mint, expected.base_mint);
+    assert_eq!(market.quote_mint, expected.quote_mint);
+    assert_eq!(market.events_authority, Pubkey::default());
+    assert_eq!(market.bids_address, expected.bids_address);
+    assert_eq!(market.asks_address, expected.asks_address);
+    assert_eq!(market.event_queue, expected.event_queue);
+    assert_eq!(market.coin_lot_size, expected.coin_lot_size);
+    // No fee discount account is passed, so orders pay the market's base taker fee tier.
+    let fee_tier = FeeTier::from_srm_and_msrm_balances(&expected.market_address, 0, 0);
+    assert_eq!(
+        market.taker_fee_bps,
+        fee_tier.taker_fee(1_000_000) as f64 / 100.0
+    );
+    assert!(market.taker_fee_bps > 0.0);
+}
+
+/// Encodes the data of an empty bids or asks account with the account `flags`.
+fn slab_account_data(flags: u64) -> Vec<u8> {
+    // The head padding, the account flags, the slab header, one free node and the tail padding.
+    let mut data = vec![0u8; 5 + 8 + 32 + 72 + 7];
+    data[..5].copy_from_slice(b"serum");
+    data[5..13].copy_from_slice(&flags.to_le_bytes());
+    let len = data.len();
+    data[len - 7..].copy_from_slice(b"padding");
+    data
+}
+
+#[tokio::test]
+async fn test_refresh() {
+    let mut reader = offline_reader(1_990, 2_010);
+    reader.market_info.bids_address = Pubkey::new_unique();
+    reader.market_info.asks_address = Pubkey::new_unique();
+    reader.open_orders.oo_key = Pubkey::new_unique();
+    reader.open_orders.exists = true;
+    reader.open_orders.open_bids = vec![(1_980u128 << 64) | 1];
+    reader.open_orders.base_total = 1.0;
+    let (market, program_id) = (
+        reader.market_info.market_address,
+        reader.market_info.program_id,
+    );
+    let mut reloaded = reader.market_info.clone();
+    reloaded.event_queue = Pubkey::new_unique();
+    reloaded.coin_lot_size = 100_000;
+    // The Initialized flag with the Bids or Asks flag.
+    let mock = MockRpc::new()
+        .with_account(market, program_id, market_account_data(&reloaded))
+        .with_account(reloaded.bids_address, program_id, slab_account_data(1 | 32))
+        .with_account(reloaded.asks_address, program_id, slab_account_data(1 | 64));
+    reader.rpc_client = Rpc::with_provider(mock.clone());
+
+    // The market state is reloaded, and the open orders account is missing.
+    reader.refresh().await.unwrap();
+    assert_eq!(mock.requests(), 1);
+    assert_eq!(reader.market_info.event_queue, reloaded.event_queue);
+    assert_eq!(reader.market_info.coin_lot_size, 100_000);
+    assert_eq!(reader.open_orders.max_bid, 0);
+    assert_eq!(reader.open_orders.min_ask, 0);
+    assert!(!reader.open_orders.exists);
+    assert!(reader.open_orders.open_bids.is_empty());
+    assert_eq!(reader.open_orders.base_total, 0.0);
+
+    // Once the open orders account exists, its balances are read in the same request.
+    let owner = Pubkey::new_unique();
+    let mock = mock.with_account(
+        reader.open_orders.oo_key,
+        program_id,
+        open_orders_account_data(&market, &owner, (0, 2_000_000_000), (0, 0), &[]),
+    );
+    reader.refresh().await.unwrap();
+    assert_eq!(mock.requests(), 2);
+    assert!(reader.open_orders.exists);
+    assert_eq!(reader.open_orders.base_total, 2.0);
+
+    // A missing book account fails the refresh.
+    reader.market_info.asks_address = Pubkey::new_unique();
+    assert!(reader.refresh().await.is_err());
+    assert_eq!(mock.requests(), 3);
+}
+
+#[tokio::test]
+async fn test_market_seq_num_change_detection() {
+    let mut market = offline_reader(1_990, 2_010).market_info;
+    market.request_queue = Pubkey::new_unique();
+    market.event_queue = Pubkey::new_unique();
+    let program_id = market.program_id;
+    let mock = MockRpc::new()
+        .with_account(market.request_queue, program_id, queue_header(10))
+        .with_account(market.event_queue, program_id, queue_header(5));
+    let rpc_client = Rpc::with_provider(mock.clone());
+
+    assert_eq!(market.load_seq_num(&rpc_client).await.unwrap(), 15);
+    let snapshot = market.clone();
+    assert!(!market.seq_changed(snapshot.seq_num));
+
+    // A new order bumps the request queue.
+    let mock = mock.with_account(market.request_queue, program_id, queue_header(11));
+    market.load_seq_num(&rpc_client).await.unwrap();
+    assert!(market.seq_changed(snapshot.seq_num));
+    let snapshot = market.clone();
+
+    // A cancel pushes an out event.
+    mock.with_account(market.event_queue, program_id, queue_header(6));
+    assert_eq!(market.load_seq_num(&rpc_client).await.unwrap(), 17);
+    assert!(market.seq_changed(snapshot.seq_num));
+
+    assert!(Market::seq_num_from_queue_headers(&[0; 29], &queue_header(1)).is_err());
+}
+
+#[tokio::test]
+async fn test_export_unsigned_settle_transaction() {
+    let mock = MockRpc::new();
+    let mut ob_client = offline_client(1_990, 2_010);
+    ob_client.rpc_client = Rpc::with_provider(mock.clone());
+    let owner = ob_client.owner.pubkey();
+
+    let instructions = match ob_client.settle_balance(false).await.unwrap() {
+        Some(OrderReturnType::Instructions(instructions)) => instructions,
+        _ => panic!("expected the settle instructions"),
+    };
+    let transaction = ob_client
+        .rpc_client
+        .build_unsigned_transaction(&owner, instructions.clone())
+        .await
+        .unwrap();
+
+    assert!(mock.sent().is_empty());
+    assert_eq!(transaction.message.account_keys[0], owner);
+    assert_ne!(transaction.message.recent_blockhash, Default::default());
+    assert!(!transaction.is_signed());
+
+    let decoded = decode_transaction(&encode_transaction(&transaction).unwrap()).unwrap();
+    assert_eq!(decoded, transaction);
+    assert_eq!(decoded.message.instructions.len(), instructions.len());
+}
+
+#[tokio::test]
+async fn test_blockhash_cache() {
+    let payer = Pubkey::new_unique();
+    let recent_blockhash = |rpc_client: Rpc| async move {
+        rpc_client
+            .build_unsigned_transaction(&payer, vec![])
+            .await
+            .unwrap()
+            .message
+            .recent_blockhash
+    };
+
+    let mock = MockRpc::new();
+    let rpc_client = Rpc::with_provider(mock.clone());
+    assert_ne!(
+        recent_blockhash(rpc_client.clone()).await,
+        recent_blockhash(rpc_client).await
+    );
+    assert_eq!(mock.requests(), 2);
+
+    let mock = MockRpc::new();
+    let rpc_client =
+        Rpc::with_provider(mock.clone()).with_blockhash_cache(Duration::from_secs(3600));
+    let cached = recent_blockhash(rpc_client.clone()).await;
+    assert_eq!(recent_blockhash(rpc_client.clone()).await, cached);
+    assert_eq!(mock.requests(), 1);
+
+    // A blockhash cached with another commitment isn't reused.
+    let processed = rpc_client.with_send_commitment(CommitmentConfig::processed());
+    assert_ne!(recent_blockhash(processed).await, cached);
+    assert_eq!(mock.requests(), 2);
+}
+
+#[tokio::test]
+async fn test_blockhash_cache_never_repeats_a_transaction() {
+    let mut ob_client = offline_client(1_990, 2_010);
+    let mock = MockRpc::new();
+    ob_client.rpc_client =
+        Rpc::with_provider(mock.clone()).with_blockhash_cache(Duration::from_secs(3600));
+
+    // The same settle twice, which the cached blockhash would sign the same way.
+    ob_client.settle_balance(true).await.unwrap();
+    ob_client.settle_balance(true).await.unwrap();
+
+    let sent = mock.sent();
+    assert_eq!(sent.len(), 2);
+    assert_eq!(sent[0].message.instructions, sent[1].message.instructions);
+    assert_ne!(sent[0].signatures[0], sent[1].signatures[0]);
+    assert_ne!(
+        sent[0].message.recent_blockhash,
+        sent[1].message.recent_blockhash
+    );
+
+    // Other instructions keep using the cached blockhash.
+    ob_client.open_orders.open_bids = vec![(1_980u128 << 64) | 1];
+    ob_client.cancel_orders(true, false).await.unwrap();
+    let sent = mock.sent();
+    assert_eq!(
+        sent[2].message.recent_blockhash,
+        sent[1].message.recent_blockhash
+    );
+}
+
+#[test]
+fn test_price_sanity_guard() {
+    let mut ob_client = offline_client(1_990, 2_010);
+    let build = |ob_client: &OBClient, price: f64, allow_off_market: bool| {
+        let order = LimitOrderSpec {
+            side: Side::Bid,
+            target_amount_quote: 5.0,
+            price,
+            params: LimitOrderParams {
+                allow_off_market,
+                ..Default::default()
+            },
+        };
+        ob_client.build_orders_batch(&[order], DEFAULT_MAX_IX_PER_TX)
+    };
+
+    assert!(build(&ob_client, 2.9, false).is_ok());
+    assert!(matches!(
+        build(&ob_client, 0.9, false),
+        Err(OpenBookError::PriceOffMarket { mid, max_deviation_percent, .. })
+            if mid == 2.0 && max_deviation_percent == 50.0
+    ));
+    assert!(matches!(
+        build(&ob_client, 0.0, false),
+        Err(OpenBookError::InvalidPrice(_))
+    ));
+    assert_eq!(build(&ob_client, 0.9, true).unwrap()[0].len(), 1);
+
+    // Allowing off-market orders doesn't let prices through that can't be placed.
+    for price in [0.0, -1.0, f64::NAN, f64::INFINITY, 0.0005] {
+        assert!(matches!(
+            build(&ob_client, price, true),
+            Err(OpenBookError::InvalidPrice(_))
+        ));
+    }
+
+    // A market order is bounded by its slippage instead of the deviation from the mid.
+    assert!(ob_client
+        .build_market_order_instruction(Side::Bid, 5.0, 9_000, 42)
+        .unwrap()
+        .is_some());
+
+    ob_client.max_price_deviation_percent = 10.0;
+    assert!(matches!(
+        build(&ob_client, 2.3, false),
+        Err(OpenBookError::PriceOffMarket { .. })
+    ));
+
+    // Without a mid, only the sign of the price is checked.
+    let empty = offline_client(1_990, 0);
+    assert!(build(&empty, 10.0, false).is_ok());
+}
+
+#[test]
+fn test_settle_funds_referrer() {
+    let mut ob_client = offline_client(1_990, 2_010);
+    let without = ob_client.build_settle_funds_instruction().unwrap();
+
+    let referrer = Pubkey::new_unique();
+    ob_client.referrer = Some(referrer);
+    let with = ob_client.build_settle_funds_instruction().unwrap();
+
+    assert_eq!(with.accounts.len(), without.accounts.len() + 1);
+    assert!(!without.accounts.iter().any(|meta| meta.pubkey == referrer));
+    let referrer_meta = with.accounts.last().unwrap();
+    assert_eq!(referrer_meta.pubkey, referrer);
+    assert!(referrer_meta.is_writable);
+    assert_eq!(with.data, without.data);
+}
+
+async fn placed_instructions(ob_client: &OBClient, side: Side) -> Vec<Instruction> {
+    match ob_client
+        .place_limit_order(5.0, side, 0.0, false, 0.0)
+        .await
+        .unwrap()
+    {
+        Some(OrderReturnType::Instructions(instructions)) => instructions,
+        _ => panic!("expected the order instructions"),
+    }
+}
+
+#[tokio::test]
+async fn test_place_limit_order_wraps_sol() {
+    let native_mint = spl_token::native_mint::id();
+    let mock = MockRpc::new();
+    let mut ob_client = offline_client(1_990, 2_010);
+    ob_client.rpc_client = Rpc::with_provider(mock.clone());
+    let owner = ob_client.owner.pubkey();
+    ob_client.market_info.quote_mint = native_mint;
+    ob_client.quote_ata = get_associated_token_address(&owner, &native_mint);
+
+    // Disabled: only the order.
+    assert_eq!(placed_instructions(&ob_client, Side::Bid).await.len(), 1);
+    assert_eq!(mock.requests(), 0);
+
+    // A bid pays in SOL: the missing ATA is created and funded with what the order can lock.
+    ob_client.wrap_sol = true;
+    let instructions = placed_instructions(&ob_client, Side::Bid).await;
+    assert_eq!(instructions.len(), 6);
+    assert_eq!(
+        instructions[0].program_id,
+        spl_associated_token_account::id()
+    );
+    let max_native_pc = match MarketInstruction::unpack(&instructions[3].data) {
+        Some(MarketInstruction::NewOrderV3(order)) => order.max_native_pc_qty_including_fees.get(),
+        other => panic!("unexpected instruction: {:?}", other),
+    };
+    assert_eq!(
+        instructions[1],
+        system_instruction::transfer(&owner, &ob_client.quote_ata, max_native_pc)
+    );
+    assert_eq!(
+        instructions[2],
+        spl_token::instruction::sync_native(&spl_token::id(), &ob_client.quote_ata).unwrap()
+    );
+    assert_eq!(
+        instructions[4],
+        spl_token::instruction::close_account(
+            &spl_token::id(),
+            &ob_client.quote_ata,
+            &owner,
+            &owner,
+            &[]
+        )
+        .unwrap()
+    );
+    // The ATA is created again after unwrapping, as the settle destination.
+    assert_eq!(
+        instructions[5],
+        create_associated_token_account_idempotent(&owner, &owner, &native_mint, &spl_token::id())
+    );
+
+    // An ATA already holding part of it is only topped up.
+    let _ = mock.clone().with_account(
+        ob_client.quote_ata,
+        spl_token::id(),
+        token_account_data(&native_mint, &owner, max_native_pc - 1_000),
+    );
+    let instructions = placed_instructions(&ob_client, Side::Bid).await;
+    assert_eq!(
+        instructions[1],
+        system_instruction::transfer(&owner, &ob_client.quote_ata, 1_000)
+    );
+
+    // Holding enough, nothing is wrapped but the leftover is still unwrapped.
+    let _ = mock.clone().with_account(
+        ob_client.quote_ata,
+        spl_token::id(),
+        token_account_data(&native_mint, &owner, max_native_pc),
+    );
+    let instructions = placed_instructions(&ob_client, Side::Bid).await;
+    assert_eq!(instructions.len(), 3);
+    assert_eq!(instructions[1].program_id, spl_token::id());
+    assert_eq!(
+        instructions[2].program_id,
+        spl_associated_token_account::id()
+    );
+
+    // An ask pays in the base token, which isn't SOL.
+    assert_eq!(placed_instructions(&ob_client, Side::Ask).await.len(), 1);
+}
+
+#[tokio::test]
+async fn test_market_durable_and_guarded_orders_wrap_sol() {
+    let native_mint = spl_token::native_mint::id();
+    let mock = MockRpc::new();
+    let mut ob_client = offline_client(1_990, 2_010);
+    ob_client.rpc_client = Rpc::with_provider(mock.clone());
+    let owner = ob_client.owner.pubkey();
+    ob_client.market_info.quote_mint = native_mint;
+    ob_client.quote_ata = get_associated_token_address(&owner, &native_mint);
+    ob_client.market_info.program_id = Pubkey::new_unique();
+    ob_client.wrap_sol = true;
+
+    ob_client
+        .place_market_order(Side::Bid, 5.0, 100)
+        .await
+        .unwrap();
+    ob_client
+        .place_limit_order_durable(5.0, Side::Bid, 1.98, 1, Duration::ZERO)
+        .await
+        .unwrap();
+    ob_client
+        .place_limit_order_guarded(5.0, Side::Bid, 1.98, LimitOrderParams::default())
+        .await
+        .unwrap()
+        .unwrap()
+        .release();
+
+    // The bids are wrapped like `place_limit_order`: create, transfer and sync, the order, then
+    // close and re-create the ATA.
+    let sent = mock.sent();
+    assert_eq!(sent.len(), 3);
+    for transaction in &sent {
+        let message = &transaction.message;
+        let program_ids: Vec<Pubkey> = message
+            .instructions
+            .iter()
+            .map(|ix| message.account_keys[ix.program_id_index as usize])
+            .collect();
+        assert_eq!(
+            program_ids,
+            vec![
+                spl_associated_token_account::id(),
+                solana_sdk::system_program::id(),
+                spl_token::id(),
+                ob_client.market_info.program_id,
+                spl_token::id(),
+                spl_associated_token_account::id(),
+            ]
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_ensure_token_accounts() {
+    let mock = MockRpc::new();
+    let mut ob_client = offline_client(1_990, 2_010);
+    ob_client.rpc_client = Rpc::with_provider(mock.clone());
+    let owner = ob_client.owner.pubkey();
+    let (base_mint, quote_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    ob_client.market_info.base_mint = base_mint;
+    ob_client.market_info.quote_mint = quote_mint;
+    ob_client.base_ata = get_associated_token_address(&owner, &base_mint);
+    ob_client.quote_ata = get_associated_token_address(&owner, &quote_mint);
+
+    // Only the quote ATA is missing: a single creation is sent.
+    let _ = mock.clone().with_account(
+        ob_client.base_ata,
+        spl_token::id(),
+        token_account_data(&base_mint, &owner, 0),
+    );
+    let signature = ob_client.ensure_token_accounts().await.unwrap();
+    let sent = mock.sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(signature, Some(sent[0].signatures[0]));
+    let message = &sent[0].message;
+    assert_eq!(message.instructions.len(), 1);
+    let program_id = message.account_keys[message.instructions[0].program_id_index as usize];
+    assert_eq!(program_id, spl_associated_token_account::id());
+    assert!(message.account_keys.contains(&ob_client.quote_ata));
+    assert!(!message.account_keys.contains(&ob_client.base_ata));
+
+    // Both exist: nothing is sent.
+    let _ = mock.clone().with_account(
+        ob_client.quote_ata,
+        spl_token::id(),
+        token_account_data(&quote_mint, &owner, 0),
+    );
+    assert_eq!(ob_client.ensure_token_accounts().await.unwrap(), None);
+    assert_eq!(mock.sent().len(), 1);
+}
+
+#[tokio::test]
+async fn test_builder_creates_the_token_accounts() {
+    let mut market = offline_reader(1_990, 2_010).market_info;
+    for key in [
+        &mut market.program_id,
+        &mut market.base_mint,
+        &mut market.quote_mint,
+        &mut market.bids_address,
+        &mut market.asks_address,
+    ] {
+        *key = Pubkey::new_unique();
+    }
+    let mock = MockRpc::new().with_account(
+        market.market_address,
+        market.program_id,
+        market_account_data(&market),
+    );
+    let owner = Keypair::new();
+    let owner_key = owner.pubkey();
+
+    let ob_client = OBClient::builder(market.market_address)
+        .rpc_client(Rpc::with_provider(mock.clone()))
+        .owner(owner)
+        .program_id(market.program_id)
+        .create_atas(true)
+        .build()
+        .await
+        .unwrap();
+
+    assert_eq!(ob_client.owner.pubkey(), owner_key);
+    assert_eq!(ob_client.market_info.program_id, market.program_id);
+    assert_eq!(
+        ob_client.base_ata,
+        get_associated_token_address(&owner_key, &market.base_mint)
+    );
+    // Both ATAs are missing: they are created in a single transaction.
+    let sent = mock.sent();
+    assert_eq!(sent.len(), 1);
+    let message = &sent[0].message;
+    assert_eq!(message.instructions.len(), 2);
+    assert!(message.account_keys.contains(&ob_client.base_ata));
+    assert!(message.account_keys.contains(&ob_client.quote_ata));
+
+    // Without the flag, nothing is sent.
+    OBClient::builder(market.market_address)
+        .rpc_client(Rpc::with_provider(mock.clone()))
+        .owner(Keypair::new())
+        .program_id(market.program_id)
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(mock.sent().len(), 1);
+}
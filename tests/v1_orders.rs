@@ -0,0 +1,355 @@
+#![cfg(feature = "v1")]
+
+mod common;
+
+use common::{open_orders_account_data, MockRpc, RecordedOrder, MARKET_ID};
+use openbook::pubkey::Pubkey;
+use openbook::rpc::Rpc;
+use openbook::signature::Signature;
+use openbook::v1::orders::{
+    collect_instructions, is_initialized_open_orders_account, open_orders_filters,
+    open_orders_seed, order_combo_instructions, resend_until_confirmed, resolve_open_orders,
+    ComboStep, Expiry, OpenOrders, OpenOrdersAccount, OrderReturnType, PlaceOutcome, SendAttempt,
+};
+use solana_client::rpc_filter::RpcFilterType;
+use solana_sdk::account::Account;
+use solana_sdk::instruction::Instruction;
+use std::collections::HashMap;
+use std::time::Duration;
+
+fn step_instruction(program_id: Pubkey) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![],
+        data: vec![],
+    }
+}
+
+#[test]
+fn test_combo_custom_order() {
+    let (cancel, settle, bid, ask) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let steps = HashMap::from([
+        (ComboStep::Cancel, vec![step_instruction(cancel)]),
+        (ComboStep::Settle, vec![step_instruction(settle)]),
+        (ComboStep::PlaceBid, vec![step_instruction(bid)]),
+        (ComboStep::PlaceAsk, vec![step_instruction(ask)]),
+    ]);
+    let order = vec![
+        ComboStep::PlaceAsk,
+        ComboStep::Cancel,
+        ComboStep::PlaceBid,
+        ComboStep::Settle,
+    ];
+
+    let instructions = order_combo_instructions(&order, steps).unwrap();
+    let program_ids: Vec<Pubkey> = instructions.iter().map(|ix| ix.program_id).collect();
+
+    assert_eq!(program_ids, vec![ask, cancel, bid, settle]);
+}
+
+#[test]
+fn test_combo_duplicate_steps() {
+    let order = vec![ComboStep::Cancel, ComboStep::Settle, ComboStep::Cancel];
+
+    assert!(ComboStep::validate_order(&order).is_err());
+    assert!(ComboStep::validate_order(&ComboStep::default_order()).is_ok());
+}
+
+#[tokio::test]
+async fn test_resend_after_dropped_attempt() {
+    let dropped = Signature::new_unique();
+    let confirmed = Signature::new_unique();
+
+    let outcome = resend_until_confirmed(7, 3, |attempt| async move {
+        if attempt == 1 {
+            Ok(SendAttempt::Dropped(dropped))
+        } else {
+            Ok(SendAttempt::Confirmed(confirmed))
+        }
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(
+        outcome,
+        PlaceOutcome {
+            client_order_id: 7,
+            signature: Some(confirmed),
+            attempts: 2,
+            confirmed: true,
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_resend_stops_when_already_placed() {
+    let dropped = Signature::new_unique();
+
+    let outcome = resend_until_confirmed(7, 3, |attempt| async move {
+        if attempt == 1 {
+            Ok(SendAttempt::Dropped(dropped))
+        } else {
+            Ok(SendAttempt::AlreadyPlaced)
+        }
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(outcome.attempts, 2);
+    assert_eq!(outcome.signature, Some(dropped));
+    assert!(outcome.confirmed);
+}
+
+#[tokio::test]
+async fn test_resend_gives_up_after_max_attempts() {
+    let outcome = resend_until_confirmed(7, 3, |_| async {
+        Ok(SendAttempt::Dropped(Signature::default()))
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(outcome.attempts, 3);
+    assert!(!outcome.confirmed);
+}
+
+#[test]
+fn test_resolve_open_orders_override() {
+    let owner = Pubkey::new_unique();
+    let market = Pubkey::new_unique();
+    let program_id = Pubkey::new_unique();
+    let oos_key = Pubkey::new_unique();
+
+    assert_eq!(
+        resolve_open_orders(&owner, &market, &program_id, Some(oos_key)),
+        oos_key
+    );
+}
+
+#[test]
+fn test_resolve_open_orders_derived() {
+    let owner = Pubkey::new_unique();
+    let market = Pubkey::new_unique();
+    let program_id = Pubkey::new_unique();
+
+    let derived = resolve_open_orders(&owner, &market, &program_id, None);
+
+    assert_eq!(open_orders_seed(&market).len(), 32);
+    assert_eq!(
+        derived,
+        Pubkey::create_with_seed(&owner, &open_orders_seed(&market), &program_id).unwrap()
+    );
+    assert_eq!(
+        derived,
+        resolve_open_orders(&owner, &market, &program_id, None)
+    );
+    assert_ne!(
+        derived,
+        resolve_open_orders(&owner, &Pubkey::new_unique(), &program_id, None)
+    );
+}
+
+#[test]
+fn test_expiry_blockhash_validity() {
+    // 150 slots of blockhash validity.
+    assert_eq!(
+        Expiry::BlockhashValidity.ttl_secs(Duration::from_millis(400)),
+        Some(60)
+    );
+    assert_eq!(
+        Expiry::BlockhashValidity.ttl_secs(Duration::from_millis(450)),
+        Some(68)
+    );
+    assert_eq!(
+        Expiry::BlockhashValidity.max_ts(1_700_000_000, Duration::from_millis(400)),
+        1_700_000_060
+    );
+}
+
+#[test]
+fn test_expiry_seconds_and_never() {
+    let slot_time = Duration::from_millis(400);
+
+    assert_eq!(Expiry::default().max_ts(100, slot_time), 130);
+    assert_eq!(Expiry::Seconds(5).ttl_secs(slot_time), Some(5));
+    assert_eq!(Expiry::Never.max_ts(100, slot_time), i64::MAX);
+    assert_eq!(
+        Expiry::At(4_000_000_000).max_ts(100, slot_time),
+        4_000_000_000
+    );
+    assert_eq!(Expiry::At(100).ttl_secs(slot_time), Some(0));
+    assert_eq!(
+        Expiry::from(Duration::from_millis(90_500)),
+        Expiry::Seconds(90)
+    );
+}
+
+#[test]
+fn test_order_id_for_client_id() {
+    // Bid at 2_000 price lots placed with client order id 42, next to an older ask.
+    let placed_id = (2_000u128 << 64) | 0xffff_ffff_ffff_fffe;
+    let data = open_orders_account_data(
+        &MARKET_ID.parse().unwrap(),
+        &Pubkey::new_unique(),
+        (0, 1_000_000_000),
+        (0, 2_000_000),
+        &[
+            RecordedOrder {
+                slot: 0,
+                order_id: (2_100u128 << 64) | 5,
+                client_order_id: 41,
+                is_bid: false,
+            },
+            RecordedOrder {
+                slot: 1,
+                order_id: placed_id,
+                client_order_id: 42,
+                is_bid: true,
+            },
+        ],
+    );
+
+    let account = OpenOrdersAccount::from_account_data(&data).unwrap();
+
+    assert_eq!(account.order_id_for_client_id(42), Some(placed_id));
+    assert_eq!(account.order_id_for_client_id(43), None);
+}
+
+#[test]
+fn test_collect_instructions() {
+    let (first, second, third) = (
+        step_instruction(Pubkey::new_unique()),
+        step_instruction(Pubkey::new_unique()),
+        step_instruction(Pubkey::new_unique()),
+    );
+
+    let instructions = collect_instructions(vec![
+        Some(OrderReturnType::Instructions(vec![
+            first.clone(),
+            second.clone(),
+        ])),
+        None,
+        Some(OrderReturnType::Signature(Signature::default())),
+        Some(OrderReturnType::Instructions(vec![third.clone()])),
+    ]);
+
+    assert_eq!(instructions, vec![first, second, third]);
+    assert!(OrderReturnType::Signature(Signature::default())
+        .into_instructions()
+        .is_none());
+}
+
+#[test]
+fn test_open_orders_filters_match_market_and_owner() {
+    let market: Pubkey = MARKET_ID.parse().unwrap();
+    let owner = Pubkey::new_unique();
+    let filters = open_orders_filters(&market, &owner);
+
+    let matches = |data: &[u8]| {
+        filters.iter().all(|filter| match filter {
+            RpcFilterType::DataSize(size) => data.len() as u64 == *size,
+            RpcFilterType::Memcmp(memcmp) => memcmp.bytes_match(data),
+            _ => false,
+        })
+    };
+
+    let data = |market: &Pubkey, owner: &Pubkey| {
+        open_orders_account_data(market, owner, (0, 0), (0, 0), &[])
+    };
+
+    assert!(matches(&data(&market, &owner)));
+    assert!(!matches(&data(&Pubkey::new_unique(), &owner)));
+    assert!(!matches(&data(&market, &Pubkey::new_unique())));
+    assert!(!matches(&data(&market, &owner)[..100]));
+}
+
+#[tokio::test]
+async fn test_find_for_market_and_owner_goes_through_the_provider() {
+    let program_id = Pubkey::new_unique();
+    let market: Pubkey = MARKET_ID.parse().unwrap();
+    let owner = Pubkey::new_unique();
+    let (ours, other_market, other_owner) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let data = |market: &Pubkey, owner: &Pubkey| {
+        open_orders_account_data(market, owner, (0, 0), (0, 0), &[])
+    };
+    let mock = MockRpc::new()
+        .with_account(ours, program_id, data(&market, &owner))
+        .with_account(
+            other_market,
+            program_id,
+            data(&Pubkey::new_unique(), &owner),
+        )
+        .with_account(
+            other_owner,
+            program_id,
+            data(&market, &Pubkey::new_unique()),
+        );
+
+    let found = OpenOrders::find_for_market_and_owner(
+        &Rpc::with_provider(mock.clone()),
+        program_id,
+        market,
+        owner,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].0, ours);
+    assert_eq!(found[0].1.market, market);
+    assert_eq!(found[0].1.owner, owner);
+    assert_eq!(mock.requests(), 1);
+}
+
+#[test]
+fn test_is_initialized_open_orders_account_checks_owner_and_flags() {
+    let program_id = Pubkey::new_unique();
+    let account = |owner: &Pubkey, data: Vec<u8>| Account {
+        lamports: 1,
+        data,
+        owner: *owner,
+        executable: false,
+        rent_epoch: 0,
+    };
+    let data = open_orders_account_data(
+        &MARKET_ID.parse().unwrap(),
+        &Pubkey::new_unique(),
+        (0, 0),
+        (0, 0),
+        &[],
+    );
+    assert!(is_initialized_open_orders_account(
+        &account(&program_id, data.clone()),
+        &program_id
+    ));
+
+    // Owned by another program.
+    assert!(!is_initialized_open_orders_account(
+        &account(&Pubkey::new_unique(), data.clone()),
+        &program_id
+    ));
+
+    // Only the Initialized flag, only the OpenOrders flag, and no flags at all.
+    for flags in [1u64, 4, 0] {
+        let mut data = data.clone();
+        data[5..13].copy_from_slice(&flags.to_le_bytes());
+        assert!(!is_initialized_open_orders_account(
+            &account(&program_id, data),
+            &program_id
+        ));
+    }
+
+    // Too short to hold the account flags.
+    assert!(!is_initialized_open_orders_account(
+        &account(&program_id, data[..8].to_vec()),
+        &program_id
+    ));
+}
@@ -0,0 +1,226 @@
+#![cfg(feature = "v2")]
+
+use anchor_lang::{AccountDeserialize, Discriminator};
+use openbook::pubkey::Pubkey;
+use openbook::v2::market::{
+    CreateMarketArgs, CreateMarketError, CreatedMarket, FeeRates, MarketInfo,
+};
+use openbookdex_v2::pubkey_option::NonZeroPubkeyOption;
+use openbookdex_v2::state::{Market, OracleConfigParams};
+use spl_associated_token_account::get_associated_token_address;
+
+fn recorded_market() -> Market {
+    let mut data = vec![0u8; 8 + std::mem::size_of::<Market>()];
+    data[..8].copy_from_slice(&Market::discriminator());
+    Market::try_deserialize(&mut &data[..]).unwrap()
+}
+
+#[test]
+fn test_configured_oracle() {
+    let mut market = recorded_market();
+    market.base_lot_size = 1_000;
+    market.quote_lot_size = 1;
+    market.oracle_config = OracleConfigParams {
+        conf_filter: 0.1,
+        max_staleness_slots: Some(100),
+    }
+    .to_oracle_config();
+
+    market.oracle_a = NonZeroPubkeyOption::from(None);
+    market.oracle_b = NonZeroPubkeyOption::from(Some(Pubkey::new_unique()));
+    let market_info = MarketInfo::from_market(&market);
+    assert!(market_info.configured_oracle().is_none());
+
+    let oracle = Pubkey::new_unique();
+    market.oracle_a = NonZeroPubkeyOption::from(Some(oracle));
+    let market_info = MarketInfo::from_market(&market);
+    assert_eq!(Option::<Pubkey>::from(market_info.oracle_a), Some(oracle));
+    let oracle_config = market_info.configured_oracle().unwrap();
+    assert!((oracle_config.conf_filter - 0.1).abs() < 1e-6);
+    assert_eq!(oracle_config.max_staleness_slots, 100);
+    assert_eq!(market_info.base_lot_size, 1_000);
+}
+
+#[test]
+fn test_breakeven_spread_bps() {
+    let mut market = recorded_market();
+    market.maker_fee = 200;
+    market.taker_fee = 400;
+
+    let market_info = MarketInfo::from_market(&market);
+    assert_eq!(
+        market_info.fee_rates(),
+        FeeRates {
+            maker: 0.0002,
+            taker: 0.0004,
+        }
+    );
+    assert!((market_info.breakeven_spread_bps() - 4.0).abs() < 1e-9);
+
+    market.maker_fee = -100;
+    let market_info = MarketInfo::from_market(&market);
+    assert!((market_info.breakeven_spread_bps() + 2.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_market_info_json_roundtrip() {
+    let mut market = recorded_market();
+    market.base_mint = Pubkey::new_unique();
+    market.fees_accrued = u128::MAX;
+    market.oracle_a = NonZeroPubkeyOption::from(Some(Pubkey::new_unique()));
+    market.oracle_config = OracleConfigParams {
+        conf_filter: 0.1,
+        max_staleness_slots: Some(100),
+    }
+    .to_oracle_config();
+
+    let market_info = MarketInfo::from_market(&market);
+    let json = market_info.to_json().unwrap();
+    assert!(json.contains(&market.base_mint.to_string()));
+
+    let restored = MarketInfo::from_json(&json).unwrap();
+    assert_eq!(restored.base_mint, market.base_mint);
+    assert_eq!(restored.fees_accrued, u128::MAX);
+    assert!(restored.oracle_a == market.oracle_a);
+    assert!(restored.oracle_b == Pubkey::default());
+    assert_eq!(restored.oracle_config.max_staleness_slots, 100);
+}
+
+#[test]
+fn test_peg_offset_to_lots() {
+    let mut market = recorded_market();
+    market.base_decimals = 9;
+    market.quote_decimals = 6;
+    market.base_lot_size = 1_000_000;
+    market.quote_lot_size = 1;
+
+    // One price lot is 0.001 quote per base.
+    let market_info = MarketInfo::from_market(&market);
+    assert_eq!(market_info.peg_offset_to_lots(0.0), 0);
+    assert_eq!(market_info.peg_offset_to_lots(0.05), 50);
+    assert_eq!(market_info.peg_offset_to_lots(-0.05), -50);
+    // Offsets are rounded to the nearest lot.
+    assert_eq!(market_info.peg_offset_to_lots(-0.0516), -52);
+
+    market.quote_lot_size = 10;
+    let market_info = MarketInfo::from_market(&market);
+    assert_eq!(market_info.peg_offset_to_lots(-0.05), -5);
+}
+
+#[test]
+fn test_base_lots_from_quote() {
+    let mut market = recorded_market();
+    market.base_decimals = 9;
+    market.quote_decimals = 6;
+    market.base_lot_size = 1_000_000;
+    market.quote_lot_size = 1;
+
+    let market_info = MarketInfo::from_market(&market);
+    assert_eq!(market_info.base_lots_from_quote(10.5, 2.0), 5_250);
+    // Fractions of a quote unit still buy base lots, and the rest rounds down.
+    assert_eq!(market_info.base_lots_from_quote(0.5, 2.0), 250);
+    assert_eq!(market_info.base_lots_from_quote(0.0019, 2.0), 0);
+}
+
+#[test]
+fn test_ensure_closable_by() {
+    let admin = Pubkey::new_unique();
+    let mut market = recorded_market();
+
+    let market_info = MarketInfo::from_market(&market);
+    assert!(market_info.ensure_closable_by(&admin).is_err());
+
+    market.close_market_admin = NonZeroPubkeyOption::from(Some(admin));
+    let market_info = MarketInfo::from_market(&market);
+    assert!(market_info.ensure_closable_by(&admin).is_ok());
+    assert!(market_info
+        .ensure_closable_by(&Pubkey::new_unique())
+        .is_err());
+
+    market.base_deposit_total = 1;
+    let market_info = MarketInfo::from_market(&market);
+    assert!(market_info.ensure_closable_by(&admin).is_err());
+}
+
+#[test]
+fn test_create_market_args_builder() {
+    let (base_mint, quote_mint, admin) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let builder = CreateMarketArgs::builder()
+        .name("JLP/USDC")
+        .base_mint(base_mint)
+        .quote_mint(quote_mint)
+        .base_lot_size(1_000)
+        .quote_lot_size(1)
+        .maker_fee(-200)
+        .taker_fee(400)
+        .collect_fee_admin(admin);
+
+    let args = builder.clone().oracle_a(None).build().unwrap();
+    assert_eq!(args.name, "JLP/USDC");
+    assert_eq!((args.maker_fee, args.taker_fee), (-200, 400));
+    assert_eq!(args.oracle_a, None);
+    assert_eq!(args.close_market_admin, None);
+    assert_eq!(args.time_expiry, 0);
+
+    assert_eq!(
+        CreateMarketArgs::builder().build().unwrap_err(),
+        CreateMarketError::MissingField("base mint")
+    );
+    assert_eq!(
+        builder.clone().quote_lot_size(0).build().unwrap_err(),
+        CreateMarketError::NonPositiveLotSize {
+            field: "quote lot size",
+            value: 0
+        }
+    );
+    assert!(matches!(
+        builder.clone().maker_fee(-500).build().unwrap_err(),
+        CreateMarketError::FeesOutOfRange { .. }
+    ));
+    assert!(matches!(
+        builder.clone().taker_fee(-1).build().unwrap_err(),
+        CreateMarketError::FeesOutOfRange { .. }
+    ));
+    assert_eq!(
+        builder.clone().quote_mint(base_mint).build().unwrap_err(),
+        CreateMarketError::SameMints(base_mint)
+    );
+    assert!(matches!(
+        builder.name("A market name too long").build().unwrap_err(),
+        CreateMarketError::NameTooLong(_)
+    ));
+}
+
+#[test]
+fn test_created_market_derive() {
+    let (market, bids, asks, event_heap) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let (base_mint, quote_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    let created = CreatedMarket::derive(market, bids, asks, event_heap, &base_mint, &quote_mint);
+
+    let (market_authority, _) =
+        Pubkey::find_program_address(&[b"Market", market.as_ref()], &openbookdex_v2::id());
+    assert_eq!(created.market, market);
+    assert_eq!(created.market_authority, market_authority);
+    assert_eq!(
+        created.base_vault,
+        get_associated_token_address(&market_authority, &base_mint)
+    );
+    assert_eq!(
+        created.quote_vault,
+        get_associated_token_address(&market_authority, &quote_mint)
+    );
+    assert_eq!(
+        (created.bids, created.asks, created.event_heap),
+        (bids, asks, event_heap)
+    );
+}
@@ -0,0 +1,441 @@
+#![cfg(feature = "v2")]
+
+#[allow(dead_code)]
+#[path = "common/mock_rpc.rs"]
+mod mock_rpc;
+
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, Discriminator};
+use mock_rpc::MockRpc;
+use openbook::error::OpenBookError;
+use openbook::keypair::Keypair;
+use openbook::metrics::CounterMetrics;
+use openbook::pubkey::Pubkey;
+use openbook::rpc::Rpc;
+use openbook::rpc_client::RpcClient;
+use openbook::v2::account_fetcher::RpcAccountFetcher;
+use openbook::v2::context::MarketContext;
+use openbook::v2::market::{CreateMarketArgs, MarketInfo};
+use openbook::v2::ob_client::{
+    decode_placed_order_ids, ensure_open_orders_account_empty, ClientSnapshot, OBClient,
+    OpenOrderNode, OpenOrderState,
+};
+use openbookdex_v2::pubkey_option::NonZeroPubkeyOption;
+use openbookdex_v2::state::{
+    BookSide, EventHeap, Market, OpenOrdersAccount, PlaceOrderType, SelfTradeBehavior, Side,
+};
+use openbookdex_v2::{PlaceMultipleOrdersArgs, PlaceOrderPeggedArgs};
+use solana_sdk::rent::Rent;
+use solana_sdk::{system_instruction, system_program};
+use std::sync::Arc;
+
+fn recorded_open_orders_account() -> OpenOrdersAccount {
+    let mut data = vec![0u8; 8 + std::mem::size_of::<OpenOrdersAccount>()];
+    data[..8].copy_from_slice(&OpenOrdersAccount::discriminator());
+    OpenOrdersAccount::try_deserialize(&mut &data[..]).unwrap()
+}
+
+fn recorded_market() -> Market {
+    let mut data = vec![0u8; 8 + std::mem::size_of::<Market>()];
+    data[..8].copy_from_slice(&Market::discriminator());
+    let mut market = Market::try_deserialize(&mut &data[..]).unwrap();
+    market.base_decimals = 9;
+    market.quote_decimals = 6;
+    market.base_lot_size = 1_000_000;
+    market.quote_lot_size = 1;
+    market
+}
+
+/// Builds a client on `market` whose requests are answered by `mock`, for tests that must not
+/// hit the network.
+fn offline_client(market: Market, mock: &MockRpc) -> OBClient {
+    let market_id = Pubkey::new_unique();
+    OBClient {
+        owner: Arc::new(Keypair::new()),
+        rpc_client: Rpc::with_provider(mock.clone()),
+        quote_ata: Pubkey::new_unique(),
+        base_ata: Pubkey::new_unique(),
+        index_account: Pubkey::new_unique(),
+        market_id,
+        account_fetcher: Arc::new(RpcAccountFetcher {
+            rpc: RpcClient::new("http://127.0.0.1:1".to_string()),
+        }),
+        open_orders_account: Pubkey::new_unique(),
+        open_orders: vec![],
+        market_info: MarketInfo::from_market(&market),
+        context: MarketContext {
+            address: market_id,
+            market,
+        },
+        oo_state: Default::default(),
+        ata_balances: Default::default(),
+    }
+}
+
+/// Encodes a zero-copy account of the program the way it's stored on chain.
+fn account_data<T: Discriminator>(account: &T) -> Vec<u8> {
+    let mut data = T::discriminator().to_vec();
+    // The zero-copy accounts of the program are plain old data.
+    data.extend_from_slice(unsafe {
+        std::slice::from_raw_parts(account as *const T as *const u8, std::mem::size_of::<T>())
+    });
+    data
+}
+
+/// Returns the data of an empty book side.
+fn empty_book_side_data() -> Vec<u8> {
+    let mut data = vec![0u8; 8 + std::mem::size_of::<BookSide>()];
+    data[..8].copy_from_slice(&BookSide::discriminator());
+    data
+}
+
+fn encode_order_ids(order_ids: &[Option<u128>]) -> Vec<u8> {
+    let mut data = (order_ids.len() as u32).to_le_bytes().to_vec();
+    for order_id in order_ids {
+        match order_id {
+            Some(order_id) => {
+                data.push(1);
+                data.extend_from_slice(&order_id.to_le_bytes());
+            }
+            None => data.push(0),
+        }
+    }
+    data
+}
+
+#[test]
+fn test_decode_placed_order_ids() {
+    let order_ids = vec![Some(7 << 64 | 3), None, Some(u128::MAX)];
+    assert_eq!(
+        decode_placed_order_ids(&encode_order_ids(&order_ids)),
+        order_ids
+    );
+    assert!(decode_placed_order_ids(&encode_order_ids(&[])).is_empty());
+}
+
+#[test]
+fn test_decode_placed_order_ids_with_stripped_zeros() {
+    // The runtime strips the trailing zeros of the return data.
+    let order_ids = vec![Some(42), Some(1 << 80), None];
+    let mut data = encode_order_ids(&order_ids);
+    while data.last() == Some(&0) {
+        data.pop();
+    }
+    assert!(data.len() < 4 + 17 + 17 + 1);
+
+    assert_eq!(decode_placed_order_ids(&data), order_ids);
+}
+
+#[test]
+fn test_decode_placed_order_ids_clamps_the_length() {
+    // A corrupted length prefix can't claim more entries than the return data can hold.
+    let mut data = u32::MAX.to_le_bytes().to_vec();
+    data.push(1);
+    data.extend_from_slice(&42u128.to_le_bytes());
+
+    let order_ids = decode_placed_order_ids(&data);
+    assert_eq!(order_ids.len(), 1 + (1_024 - 4 - 17));
+    assert_eq!(order_ids[0], Some(42));
+    assert!(order_ids[1..].iter().all(Option::is_none));
+}
+
+#[test]
+fn test_ensure_open_orders_account_empty() {
+    let account = recorded_open_orders_account();
+    assert!(ensure_open_orders_account_empty(&account).is_ok());
+
+    let mut with_orders = recorded_open_orders_account();
+    with_orders.position.asks_base_lots = 3;
+    assert!(ensure_open_orders_account_empty(&with_orders).is_err());
+
+    let mut with_balance = recorded_open_orders_account();
+    with_balance.position.quote_free_native = 1;
+    assert!(ensure_open_orders_account_empty(&with_balance).is_err());
+
+    let mut with_fees = recorded_open_orders_account();
+    with_fees.position.locked_maker_fees = 1;
+    assert!(ensure_open_orders_account_empty(&with_fees).is_err());
+}
+
+#[test]
+fn test_client_snapshot_roundtrip() {
+    let snapshot = ClientSnapshot {
+        market_id: Pubkey::new_unique(),
+        open_orders_account: Pubkey::new_unique(),
+        open_orders: vec![OpenOrderNode {
+            is_buy: true,
+            price: 1.99,
+            amount: 3.5,
+            order_id: 42,
+            timestamp: 1_700_000_000,
+            slot: 2,
+        }],
+        oo_state: OpenOrderState {
+            bids_base_in_oos: 3.5,
+            quote_free_in_oos: 10.0,
+            ..OpenOrderState::default()
+        },
+        taken_at: 1_700_000_100,
+    };
+
+    for extension in ["json", "bin"] {
+        let path = std::env::temp_dir().join(format!(
+            "openbook-snapshot-{}.{}",
+            Pubkey::new_unique(),
+            extension
+        ));
+        snapshot.save(&path).unwrap();
+        let restored = ClientSnapshot::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        let restored = restored.unwrap();
+
+        assert_eq!(restored.market_id, snapshot.market_id);
+        assert_eq!(restored.open_orders_account, snapshot.open_orders_account);
+        assert_eq!(restored.taken_at, snapshot.taken_at);
+        assert_eq!(restored.open_orders.len(), 1);
+        assert_eq!(restored.open_orders[0].order_id, 42);
+        assert_eq!(restored.open_orders[0].price, 1.99);
+        assert_eq!(restored.oo_state.bids_base_in_oos, 3.5);
+        assert_eq!(restored.oo_state.quote_free_in_oos, 10.0);
+    }
+
+    let path =
+        std::env::temp_dir().join(format!("openbook-snapshot-{}.json", Pubkey::new_unique()));
+    assert!(ClientSnapshot::load(&path).is_err());
+}
+
+#[tokio::test]
+async fn test_place_pegged_order_requires_an_oracle() {
+    let mut market = recorded_market();
+    market.oracle_a = NonZeroPubkeyOption::from(None);
+    let mock = MockRpc::new();
+    let ob_client = offline_client(market, &mock);
+    let args = PlaceOrderPeggedArgs {
+        side: Side::Bid,
+        price_offset_lots: 0,
+        peg_limit: -1,
+        max_base_lots: 0,
+        max_quote_lots_including_fees: i64::MAX,
+        client_order_id: 0,
+        order_type: PlaceOrderType::PostOnly,
+        expiry_timestamp: 0,
+        self_trade_behavior: SelfTradeBehavior::AbortTransaction,
+        limit: 12,
+    };
+
+    let err = ob_client
+        .place_pegged_order(Side::Bid, -0.05, 1_000_000_000, args)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("has no oracle"));
+    assert!(mock.sent().is_empty());
+}
+
+#[tokio::test]
+async fn test_trading_reports_metrics() {
+    let mock = MockRpc::new();
+    let metrics = Arc::new(CounterMetrics::new());
+    let mut ob_client = offline_client(recorded_market(), &mock);
+    ob_client.rpc_client = Rpc::with_provider(mock.clone()).with_metrics(metrics.clone());
+    let order = |is_buy, order_id| OpenOrderNode {
+        is_buy,
+        price: 1.0,
+        amount: 1.0,
+        order_id,
+        timestamp: 0,
+        slot: 0,
+    };
+    ob_client.open_orders = vec![order(true, 1), order(false, 2)];
+    let args = PlaceMultipleOrdersArgs {
+        price_lots: 1_000,
+        max_quote_lots_including_fees: 1_000,
+        expiry_timestamp: 0,
+    };
+
+    assert!(ob_client.settle_funds().await.unwrap().0);
+    assert!(ob_client.cancel_all_orders().await.unwrap().0);
+    assert!(
+        ob_client
+            .cancel_all_and_place_orders(vec![args.clone()], vec![args])
+            .await
+            .unwrap()
+            .0
+    );
+
+    let counts = metrics.counts();
+    assert_eq!(mock.sent().len(), 3);
+    assert_eq!(counts.settles, 1);
+    // The two loaded orders, canceled twice.
+    assert_eq!(counts.orders_canceled, 4);
+    assert_eq!(counts.orders_placed, 2);
+}
+
+#[tokio::test]
+async fn test_refresh_reloads_the_market() {
+    let mut market = recorded_market();
+    market.bids = Pubkey::new_unique();
+    market.asks = Pubkey::new_unique();
+    let mock = MockRpc::new();
+    let mut ob_client = offline_client(market, &mock);
+    // Without an open orders account, the balances aren't reloaded.
+    ob_client.open_orders_account = Pubkey::default();
+    ob_client.open_orders = vec![OpenOrderNode {
+        is_buy: true,
+        price: 1.0,
+        amount: 1.0,
+        order_id: 1,
+        timestamp: 0,
+        slot: 0,
+    }];
+
+    // A missing account fails the refresh and leaves the client as it was.
+    assert!(ob_client.refresh().await.is_err());
+    assert_eq!(ob_client.open_orders.len(), 1);
+    assert_eq!(mock.requests(), 1);
+
+    let mut reloaded = market;
+    reloaded.quote_lot_size = 10;
+    reloaded.taker_fee = 400;
+    let mock = mock
+        .with_account(
+            ob_client.market_id,
+            Pubkey::new_unique(),
+            account_data(&reloaded),
+        )
+        .with_account(market.bids, Pubkey::new_unique(), empty_book_side_data())
+        .with_account(market.asks, Pubkey::new_unique(), empty_book_side_data());
+
+    ob_client.refresh().await.unwrap();
+
+    // The market, bids and asks come in a single request.
+    assert_eq!(mock.requests(), 2);
+    assert_eq!(ob_client.market_info.quote_lot_size, 10);
+    assert_eq!(ob_client.market_info.taker_fee, 400);
+    assert_eq!(ob_client.context.market.quote_lot_size, 10);
+    assert_eq!(ob_client.context.address, ob_client.market_id);
+    assert!(ob_client.open_orders.is_empty());
+}
+
+#[tokio::test]
+async fn test_create_market_allocates_and_signs_with_the_new_accounts() {
+    let (base_mint, quote_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mock = MockRpc::new()
+        .with_account(base_mint, spl_token::id(), vec![0; 82])
+        .with_account(quote_mint, spl_token::id(), vec![0; 82]);
+    let ob_client = offline_client(recorded_market(), &mock);
+    let args = CreateMarketArgs::builder()
+        .name("SOL/USDC")
+        .base_mint(base_mint)
+        .quote_mint(quote_mint)
+        .base_lot_size(1_000_000)
+        .quote_lot_size(1)
+        .collect_fee_admin(ob_client.owner())
+        .build()
+        .unwrap();
+
+    let (confirmed, _, created) = ob_client.create_market(args).await.unwrap();
+    assert!(confirmed);
+
+    let sent = mock.sent();
+    assert_eq!(sent.len(), 1);
+    let message = &sent[0].message;
+    assert!(sent[0].verify().is_ok());
+    let mut signers =
+        message.account_keys[..message.header.num_required_signatures as usize].to_vec();
+    signers.sort();
+    let mut expected_signers = vec![
+        ob_client.owner(),
+        created.market,
+        created.bids,
+        created.asks,
+        created.event_heap,
+    ];
+    expected_signers.sort();
+    assert_eq!(signers, expected_signers);
+
+    // The books and event heap are allocated for the program before the market is created.
+    let book_side_space = 8 + std::mem::size_of::<BookSide>();
+    let event_heap_space = 8 + std::mem::size_of::<EventHeap>();
+    let allocations = [
+        (created.bids, book_side_space),
+        (created.asks, book_side_space),
+        (created.event_heap, event_heap_space),
+    ];
+    assert_eq!(message.instructions.len(), allocations.len() + 1);
+    for (instruction, (account, space)) in message.instructions.iter().zip(allocations) {
+        let expected = system_instruction::create_account(
+            &ob_client.owner(),
+            &account,
+            Rent::default().minimum_balance(space),
+            space as u64,
+            &openbookdex_v2::id(),
+        );
+        assert_eq!(
+            message.account_keys[instruction.program_id_index as usize],
+            system_program::id()
+        );
+        assert_eq!(
+            message.account_keys[instruction.accounts[1] as usize],
+            account
+        );
+        assert_eq!(instruction.data, expected.data);
+    }
+    let create = &message.instructions[3];
+    assert_eq!(
+        message.account_keys[create.program_id_index as usize],
+        openbookdex_v2::id()
+    );
+    assert_eq!(
+        message.account_keys[create.accounts[0] as usize],
+        created.market
+    );
+}
+
+#[tokio::test]
+async fn test_swap_order_is_immediate_or_cancel() {
+    let mock = MockRpc::new();
+    let ob_client = offline_client(recorded_market(), &mock);
+    let sent_order = |index: usize| {
+        let message = mock.sent()[index].message.clone();
+        let data = &message.instructions.last().unwrap().data;
+        openbookdex_v2::instruction::PlaceOrder::try_from_slice(&data[8..])
+            .unwrap()
+            .args
+    };
+
+    // 12.5 quote tokens, fractions included, at up to 2 quote per base.
+    assert!(
+        ob_client
+            .place_swap_order(Side::Bid, 12.5, 2.0)
+            .await
+            .unwrap()
+            .0
+    );
+    let bid = sent_order(0);
+    assert!(matches!(bid.side, Side::Bid));
+    assert!(matches!(bid.order_type, PlaceOrderType::ImmediateOrCancel));
+    assert_eq!(bid.expiry_timestamp, 0);
+    assert_eq!(bid.price_lots, 2_000);
+    assert_eq!(bid.max_quote_lots_including_fees, 12_500_000);
+    assert_eq!(bid.max_base_lots, i64::MAX);
+
+    assert!(
+        ob_client
+            .place_swap_order(Side::Ask, 0.5, 1.5)
+            .await
+            .unwrap()
+            .0
+    );
+    let ask = sent_order(1);
+    assert!(matches!(ask.order_type, PlaceOrderType::ImmediateOrCancel));
+    assert_eq!(ask.price_lots, 1_500);
+    assert_eq!(ask.max_base_lots, 500);
+
+    // Less than a base lot can't be sold.
+    assert!(matches!(
+        ob_client
+            .place_swap_order(Side::Ask, 0.000_000_1, 1.9)
+            .await,
+        Err(OpenBookError::OrderTooSmall(_))
+    ));
+    assert_eq!(mock.sent().len(), 2);
+}